@@ -0,0 +1,59 @@
+//! Benchmarks of the GTV encoding and hashing paths on large transactions, queries and arrays.
+//!
+//! Run with `cargo bench --bench encoding`.
+
+use std::collections::BTreeMap;
+
+use bigdecimal::BigDecimal;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use postchain_client::{
+    encoding::gtv,
+    utils::{operation::{Operation, Params}, transaction::Transaction},
+};
+
+/// Builds an operation argument mixing every kind of value
+fn argument(index: usize) -> Params {
+    let mut dict = BTreeMap::new();
+    dict.insert("id".to_string(), Params::Integer(index as i64));
+    dict.insert("name".to_string(), Params::Text(format!("account-{}", index)));
+    dict.insert("owner".to_string(), Params::ByteArray(vec![index as u8; 33]));
+    dict.insert("balance".to_string(), Params::Decimal(BigDecimal::new((index as i64 * 1_000_003).into(), 4)));
+    dict.insert("tags".to_string(), Params::Array((0..4).map(|tag| Params::Text(format!("tag-{}", tag))).collect()));
+    Params::Dict(dict)
+}
+
+fn large_transaction(operation_count: usize) -> Transaction<'static> {
+    let operations = (0..operation_count)
+        .map(|index| Operation::from_list("register_account", vec![argument(index), Params::Boolean(index % 2 == 0)]))
+        .collect();
+    Transaction::new(vec![7; 32], Some(operations), Some(vec![vec![2; 33], vec![3; 33]]), None)
+}
+
+fn bench_encoding(c: &mut Criterion) {
+    let value = Params::Array((0..1000).map(argument).collect());
+    c.bench_function("encode_value 1000 dicts", |b| b.iter(|| gtv::encode_value(black_box(&value)).unwrap()));
+
+    let tx = large_transaction(1000);
+    c.bench_function("encode_tx 1000 operations", |b| b.iter(|| black_box(&tx).to_bytes().unwrap()));
+    c.bench_function("tx_rid 1000 operations", |b| b.iter(|| black_box(&tx).tx_rid().unwrap()));
+
+    let mut args: Vec<(&str, Params)> = (0..100).map(|index| ("account", argument(index))).collect();
+    c.bench_function("encode query 100 arguments", |b| b.iter(|| gtv::encode("get_accounts", Some(black_box(&mut args))).unwrap()));
+}
+
+/// Builds a transaction whose only argument is an array of `elements`, so its RID is
+/// dominated by the Merkle hash of that array
+fn array_transaction(elements: Vec<Params>) -> Transaction<'static> {
+    Transaction::new(vec![7; 32], Some(vec![Operation::from_list("store", vec![Params::Array(elements)])]), None, None)
+}
+
+fn bench_hashing(c: &mut Criterion) {
+    let integers = array_transaction((0..100_000).map(Params::Integer).collect());
+    c.bench_function("tx_rid 100k integers", |b| b.iter(|| black_box(&integers).tx_rid().unwrap()));
+
+    let dicts = array_transaction((0..100_000).map(argument).collect());
+    c.bench_function("tx_rid 100k dicts", |b| b.iter(|| black_box(&dicts).tx_rid().unwrap()));
+}
+
+criterion_group!(benches, bench_encoding, bench_hashing);
+criterion_main!(benches);
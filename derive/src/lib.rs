@@ -3,7 +3,12 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput, Data, Fields};
 
-#[proc_macro_derive(StructMetadata)]
+/// Records the name and type of every field, and the declaration order of the fields,
+/// used when converting the struct into `Params`.
+///
+/// A field marked `#[params(bytes)]` is recorded as a byte array field, so a hex
+/// string or a list of numbers in it becomes a `Params::ByteArray`.
+#[proc_macro_derive(StructMetadata, attributes(params))]
 pub fn derive_struct_metadata(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -18,13 +23,44 @@ pub fn derive_struct_metadata(input: TokenStream) -> TokenStream {
         panic!("StructMetadata can only be derived for structs");
     };
 
-    let field_collector = fields.iter().map(|field| {
+    let mut errors = Vec::new();
+
+    let field_collector: Vec<_> = fields.iter().map(|field| {
         let field_name = &field.ident;
         let field_type = &field.ty;
-        quote! {
-            fields.insert(stringify!(#field_name).to_string(), stringify!(#field_type).to_string());
+        let mut bytes = false;
+
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("params")) {
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("bytes") {
+                    bytes = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported params attribute, expected `bytes`"))
+                }
+            });
+
+            if let Err(error) = parsed {
+                errors.push(error.to_compile_error());
+            }
         }
-    });
+
+        if bytes {
+            quote! {
+                fields.insert(stringify!(#field_name).to_string(), concat!("#[params(bytes)] ", stringify!(#field_type)).to_string());
+            }
+        } else {
+            quote! {
+                fields.insert(stringify!(#field_name).to_string(), stringify!(#field_type).to_string());
+            }
+        }
+    }).collect();
+
+    if !errors.is_empty() {
+        return TokenStream::from(quote! { #(#errors)* });
+    }
+
+    let field_names = fields.iter().map(|field| &field.ident);
 
     let expanded = quote! {
         impl StructMetadata for #name {
@@ -33,6 +69,10 @@ pub fn derive_struct_metadata(input: TokenStream) -> TokenStream {
                 #(#field_collector)*
                 fields
             }
+
+            fn field_names() -> Vec<String> {
+                vec![#(stringify!(#field_names).to_string()),*]
+            }
         }
     };
 
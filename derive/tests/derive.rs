@@ -4,6 +4,7 @@ use num_bigint::BigInt;
 
 pub trait StructMetadata {
     fn field_names_and_types() -> std::collections::BTreeMap<String, String>;
+    fn field_names() -> Vec<String>;
 }
 
 #[allow(dead_code)]
@@ -29,4 +30,20 @@ fn test_struct_metadata() {
     assert_eq!(fields.get("bigdecimal"), Some(&"BigDecimal".to_string()));
     assert_eq!(fields.get("bigint"), Some(&"BigInt".to_string()));
     assert_eq!(fields.get("nested_struct"), Some(&"TestStruct2".to_string()));
-}
\ No newline at end of file
+    assert_eq!(TestStruct::field_names(), ["text", "int", "bigdecimal", "bigint", "nested_struct"]);
+}
+
+#[allow(dead_code)]
+#[derive(StructMetadata)]
+struct TestBytesStruct {
+    #[params(bytes)]
+    pubkey: String,
+    data: Vec<u8>,
+}
+
+#[test]
+fn test_struct_metadata_bytes_attribute() {
+    let fields = TestBytesStruct::field_names_and_types();
+    assert_eq!(fields.get("pubkey"), Some(&"#[params(bytes)] String".to_string()));
+    assert_eq!(fields.get("data"), Some(&"Vec < u8 >".to_string()));
+}
@@ -35,7 +35,7 @@ async fn get_all_books(brid: &String, rc: &RestClient<'_>) {
     if let Ok(val) = resp {
         if let RestResponse::Bytes(val1) = val {
             if let Ok(d) = gtv::decode(&val1) {
-                let vec: Vec<Params> = d.into();
+                let vec: Vec<Params> = d.try_into().unwrap_or_default();
                 for v in vec {
                     let result: Result<Book, _> = v.to_struct();
                     if let Ok(book) = result {
@@ -72,11 +72,7 @@ async fn create_new_books(brid: &String, rc: &RestClient<'_>) {
 
     let brid_vec = hex::decode(brid.clone()).unwrap();
 
-    let tx = Transaction{
-        blockchain_rid: brid_vec,
-        operations: Some(operations),
-        ..Default::default()
-    };
+    let tx = Transaction::new(brid_vec, Some(operations), None, None);
 
     let resp = rc.send_transaction(&tx).await;
     println!("{:?}", resp);
@@ -105,11 +101,7 @@ async fn create_book_review(brid: &String, rc: &RestClient<'_>) {
 
     let brid_vec = hex::decode(brid.clone()).unwrap();
 
-    let mut tx = Transaction{
-        blockchain_rid: brid_vec,
-        operations: Some(operations),
-        ..Default::default()
-    };
+    let mut tx = Transaction::new(brid_vec, Some(operations), None, None);
 
     let result = tx.sign_from_raw_priv_key(&PRIV_KEY);
 
@@ -96,11 +96,7 @@ async fn send_unsign_transaction(rc: &RestClient<'_>, brid: &str) {
             ])
     ];
 
-    let tx = Transaction{
-        blockchain_rid: hex::decode(brid).unwrap(),
-        operations: Some(operations),
-        ..Default::default()
-    };
+    let tx = Transaction::new(hex::decode(brid).unwrap(), Some(operations), None, None);
 
     let result = rc.send_transaction(&tx).await;
 
@@ -121,11 +117,7 @@ async fn send_sign_transaction(rc: &RestClient<'_>, brid: &str, privkey: &str) {
             ])
     ];
 
-    let mut tx = Transaction{
-        blockchain_rid: hex::decode(brid).unwrap(),
-        operations: Some(operations),
-        ..Default::default()
-    };
+    let mut tx = Transaction::new(hex::decode(brid).unwrap(), Some(operations), None, None);
 
     if let Err(error) = tx.sign_from_raw_priv_key(privkey) {
         println!("TX sign error {:?}", error);
@@ -151,11 +143,7 @@ async fn send_multi_sign_transaction(rc: &RestClient<'_>, brid: &str, privkeys:
             ])
     ];
 
-    let mut tx = Transaction{
-        blockchain_rid: hex::decode(brid).unwrap(),
-        operations: Some(operations),
-        ..Default::default()
-    };
+    let mut tx = Transaction::new(hex::decode(brid).unwrap(), Some(operations), None, None);
 
     if let Err(error) = tx.multi_sign_from_raw_priv_keys(&privkeys) {
         println!("TX multi sign error {:?}", error);
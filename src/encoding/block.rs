@@ -0,0 +1,389 @@
+//! Typed decoding of the blocks returned by the node's block endpoints.
+//!
+//! With GTV requested, `GET /blocks/{blockchain_rid}` and `GET /blocks/{blockchain_rid}/height/{height}`
+//! return blocks as dictionaries:
+//!
+//! * `rid`, `prevBlockRID`, `height` and `timestamp` describe the block
+//! * `header` holds the GTV-encoded header the block RID is computed from
+//! * `transactions` lists dictionaries with the `rid`, `hash` and, when requested, `data` of each transaction
+//! * `witness` holds the signatures of the block, as length-prefixed subject ids and signatures
+//!
+//! # Example
+//! ```
+//! # use postchain_client::{dict, gtv_array, encoding::{block::Block, gtv}, utils::operation::Params};
+//! let header = gtv_array![vec![1u8; 32], vec![0u8; 32], vec![2u8; 32], 1736929874000i64, 0, Params::Null, dict!{}];
+//! let response = gtv::encode_value(&dict!{
+//!     "rid" => vec![3u8; 32],
+//!     "prevBlockRID" => vec![0u8; 32],
+//!     "header" => gtv::encode_value(&header).unwrap(),
+//!     "height" => 0,
+//!     "timestamp" => 1736929874000i64,
+//!     "transactions" => Params::Array(vec![]),
+//!     "witness" => vec![0u8, 0, 0, 0]
+//! }).unwrap();
+//!
+//! let block = Block::decode(&response).unwrap();
+//! assert_eq!(block.header.blockchain_rid, vec![1u8; 32]);
+//! assert!(block.witness.signatures.is_empty());
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::encoding::gtv;
+use crate::utils::operation::Params;
+
+/// Errors that can occur when decoding a block.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlockError {
+    /// The bytes are not valid GTV
+    Decode(Box<asn1::ParseError>),
+    /// A field is missing or of the wrong kind
+    Field {
+        /// Path of the field, such as `transactions[1].rid` or `header.height`
+        path: String,
+        /// Kind of value the field should hold
+        expected: &'static str,
+    },
+    /// The witness does not follow the length-prefixed signature layout
+    Witness(String),
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockError::Decode(error) => write!(f, "Invalid GTV: {}", error),
+            BlockError::Field { path, expected } => write!(f, "{}: expected {}", path, expected),
+            BlockError::Witness(message) => write!(f, "Invalid witness: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
+
+impl From<asn1::ParseError> for BlockError {
+    fn from(error: asn1::ParseError) -> Self {
+        BlockError::Decode(Box::new(error))
+    }
+}
+
+/// A block, as returned by the block endpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /// RID of the block, the hash of its header
+    pub rid: Vec<u8>,
+    /// RID of the previous block, all zeros for the first block
+    pub previous_block_rid: Vec<u8>,
+    /// The decoded header
+    pub header: BlockHeader,
+    /// The header as encoded by the node, needed to check `rid` and the witness
+    pub raw_header: Vec<u8>,
+    /// Height of the block, starting at 0
+    pub height: u64,
+    /// Time the block was built, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Transactions of the block, in block order
+    pub transactions: Vec<BlockTransaction>,
+    /// Signatures of the signers that approved the block
+    pub witness: BlockWitness,
+}
+
+/// The header of a block, from which its RID is computed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    /// RID of the blockchain the block belongs to
+    pub blockchain_rid: Vec<u8>,
+    /// RID of the previous block
+    pub previous_block_rid: Vec<u8>,
+    /// Merkle root hash of the transactions of the block
+    pub merkle_root_hash: Vec<u8>,
+    /// Time the block was built, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Height of the block
+    pub height: u64,
+    /// Blocks of other blockchains this block depends on
+    pub dependencies: Vec<BlockDependency>,
+    /// Extra header data, such as the configuration hash
+    pub extra: BTreeMap<String, Params>,
+}
+
+/// A block of another blockchain that a block depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockDependency {
+    /// RID of the other blockchain
+    pub blockchain_rid: Vec<u8>,
+    /// RID of the block of the other blockchain
+    pub block_rid: Vec<u8>,
+}
+
+/// A transaction listed in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTransaction {
+    /// RID of the transaction
+    pub rid: Vec<u8>,
+    /// Hash of the signed transaction
+    pub hash: Vec<u8>,
+    /// The encoded transaction, present when the endpoint was asked to include transactions
+    pub data: Option<Vec<u8>>,
+}
+
+/// The signatures approving a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockWitness {
+    /// Signatures in the order the node stored them
+    pub signatures: Vec<WitnessSignature>,
+}
+
+/// One signature of a block witness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitnessSignature {
+    /// Public key of the signer
+    pub subject_id: Vec<u8>,
+    /// Signature of the block RID
+    pub data: Vec<u8>,
+}
+
+/// Decodes a list of blocks, as returned by `GET /blocks/{blockchain_rid}`.
+///
+/// # Arguments
+/// * `data` - The encoded GTV array of blocks
+///
+/// # Errors
+/// `BlockError` if `data` is not a GTV array or any block is malformed, with the block index
+/// in the path
+pub fn decode_blocks(data: &[u8]) -> Result<Vec<Block>, BlockError> {
+    let value = gtv::decode(data)?;
+    let blocks = value.as_array().ok_or_else(|| field_error("", "an array of blocks"))?;
+    blocks.iter().enumerate()
+        .map(|(index, block)| Block::from_params_at(block, &format!("[{}]", index)))
+        .collect()
+}
+
+impl Block {
+    /// Decodes a block, as returned by `GET /blocks/{blockchain_rid}/height/{height}`.
+    ///
+    /// # Arguments
+    /// * `data` - The encoded GTV dictionary of the block
+    ///
+    /// # Errors
+    /// `BlockError` if `data` is not valid GTV or a field is missing or malformed
+    pub fn decode(data: &[u8]) -> Result<Block, BlockError> {
+        Block::from_params(&gtv::decode(data)?)
+    }
+
+    /// Reads a block from its already decoded dictionary.
+    ///
+    /// # Errors
+    /// `BlockError` if a field is missing or malformed
+    pub fn from_params(value: &Params) -> Result<Block, BlockError> {
+        Block::from_params_at(value, "")
+    }
+
+    fn from_params_at(value: &Params, path: &str) -> Result<Block, BlockError> {
+        let raw_header = bytes(value, path, "header")?;
+        let header = BlockHeader::decode(&raw_header).map_err(|error| match error {
+            BlockError::Field { path: field, expected } => field_error(&join(path, &format!("header{}", field)), expected),
+            BlockError::Decode(_) => field_error(&join(path, "header"), "an encoded block header"),
+            error => error,
+        })?;
+        let transactions = item(value, path, "transactions")?.as_array()
+            .ok_or_else(|| field_error(&join(path, "transactions"), "an array"))?
+            .iter().enumerate()
+            .map(|(index, tx)| BlockTransaction::from_params_at(tx, &format!("{}[{}]", join(path, "transactions"), index)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Block {
+            rid: bytes(value, path, "rid")?,
+            previous_block_rid: bytes(value, path, "prevBlockRID")?,
+            header,
+            raw_header,
+            height: unsigned(value, path, "height")?,
+            timestamp: unsigned(value, path, "timestamp")?,
+            transactions,
+            witness: BlockWitness::decode(&bytes(value, path, "witness")?)?,
+        })
+    }
+}
+
+impl BlockHeader {
+    /// Decodes a header from the `header` bytes of a block.
+    ///
+    /// The header is a GTV array of the blockchain RID, previous block RID, Merkle root hash,
+    /// timestamp, height, dependencies (null or an array of `[blockchain_rid, block_rid]`)
+    /// and a dictionary of extra data.
+    ///
+    /// # Errors
+    /// `BlockError` if `data` is not valid GTV or an element is missing or malformed
+    pub fn decode(data: &[u8]) -> Result<BlockHeader, BlockError> {
+        let value = gtv::decode(data)?;
+        let fields = value.as_array().ok_or_else(|| field_error("", "an array"))?;
+        let at = |index: usize| fields.get(index).ok_or_else(|| field_error(&format!("[{}]", index), "a header field"));
+        let as_bytes = |index: usize| at(index)?.as_bytes().map(<[u8]>::to_vec)
+            .ok_or_else(|| field_error(&format!("[{}]", index), "a byte array"));
+        let as_unsigned = |index: usize| at(index)?.as_i64().and_then(|value| u64::try_from(value).ok())
+            .ok_or_else(|| field_error(&format!("[{}]", index), "a non-negative integer"));
+
+        let dependencies = match at(5)? {
+            Params::Null => Vec::new(),
+            Params::Array(dependencies) => dependencies.iter().enumerate()
+                .map(|(index, dependency)| match dependency.as_array() {
+                    Some([Params::ByteArray(blockchain_rid), Params::ByteArray(block_rid)]) => Ok(BlockDependency {
+                        blockchain_rid: blockchain_rid.clone(),
+                        block_rid: block_rid.clone(),
+                    }),
+                    _ => Err(field_error(&format!("[5][{}]", index), "a [blockchain_rid, block_rid] pair")),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => return Err(field_error("[5]", "null or an array of dependencies")),
+        };
+
+        Ok(BlockHeader {
+            blockchain_rid: as_bytes(0)?,
+            previous_block_rid: as_bytes(1)?,
+            merkle_root_hash: as_bytes(2)?,
+            timestamp: as_unsigned(3)?,
+            height: as_unsigned(4)?,
+            dependencies,
+            extra: at(6)?.as_dict().cloned().ok_or_else(|| field_error("[6]", "a dictionary"))?,
+        })
+    }
+}
+
+impl BlockTransaction {
+    fn from_params_at(value: &Params, path: &str) -> Result<BlockTransaction, BlockError> {
+        let data = match value.get("data") {
+            None | Some(Params::Null) => None,
+            Some(_) => Some(bytes(value, path, "data")?),
+        };
+        Ok(BlockTransaction { rid: bytes(value, path, "rid")?, hash: bytes(value, path, "hash")?, data })
+    }
+}
+
+impl BlockWitness {
+    /// Decodes the `witness` bytes of a block.
+    ///
+    /// The witness is a 4-byte big-endian signature count followed, for each signature,
+    /// by the 4-byte length and bytes of the subject id, then of the signature.
+    ///
+    /// # Errors
+    /// `BlockError::Witness` if the bytes end early or are followed by extra bytes
+    pub fn decode(data: &[u8]) -> Result<BlockWitness, BlockError> {
+        let mut rest = data;
+        let count = read_u32(&mut rest)?;
+        let signatures = (0..count)
+            .map(|_| Ok(WitnessSignature { subject_id: read_chunk(&mut rest)?, data: read_chunk(&mut rest)? }))
+            .collect::<Result<_, BlockError>>()?;
+        if !rest.is_empty() {
+            return Err(BlockError::Witness(format!("{} trailing bytes", rest.len())));
+        }
+        Ok(BlockWitness { signatures })
+    }
+}
+
+fn read_u32(rest: &mut &[u8]) -> Result<u32, BlockError> {
+    let (length, tail) = rest.split_first_chunk::<4>()
+        .ok_or_else(|| BlockError::Witness("unexpected end of data".to_string()))?;
+    *rest = tail;
+    Ok(u32::from_be_bytes(*length))
+}
+
+fn read_chunk(rest: &mut &[u8]) -> Result<Vec<u8>, BlockError> {
+    let length = read_u32(rest)? as usize;
+    if rest.len() < length {
+        return Err(BlockError::Witness("unexpected end of data".to_string()));
+    }
+    let (chunk, tail) = rest.split_at(length);
+    *rest = tail;
+    Ok(chunk.to_vec())
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) }
+}
+
+fn field_error(path: &str, expected: &'static str) -> BlockError {
+    BlockError::Field { path: path.to_string(), expected }
+}
+
+fn item<'a>(value: &'a Params, path: &str, key: &str) -> Result<&'a Params, BlockError> {
+    value.get(key).ok_or_else(|| field_error(&join(path, key), "a value"))
+}
+
+fn bytes(value: &Params, path: &str, key: &str) -> Result<Vec<u8>, BlockError> {
+    item(value, path, key)?.as_bytes().map(<[u8]>::to_vec).ok_or_else(|| field_error(&join(path, key), "a byte array"))
+}
+
+fn unsigned(value: &Params, path: &str, key: &str) -> Result<u64, BlockError> {
+    item(value, path, key)?.as_i64().and_then(|value| u64::try_from(value).ok())
+        .ok_or_else(|| field_error(&join(path, key), "a non-negative integer"))
+}
+
+#[cfg(test)]
+fn sample_block(height: i64, witness: Vec<u8>) -> Params {
+    let header = crate::gtv_array![
+        vec![1u8; 32], vec![0u8; 32], vec![2u8; 32], 1736929874000i64, height,
+        crate::gtv_array![crate::gtv_array![vec![4u8; 32], vec![5u8; 32]]],
+        crate::dict!{"config_hash" => vec![6u8; 32]}
+    ];
+    crate::dict!{
+        "rid" => vec![3u8; 32],
+        "prevBlockRID" => vec![0u8; 32],
+        "header" => gtv::encode_value(&header).unwrap(),
+        "height" => height,
+        "timestamp" => 1736929874000i64,
+        "transactions" => crate::gtv_array![
+            crate::dict!{"rid" => vec![7u8; 32], "hash" => vec![8u8; 32], "data" => vec![0xa5u8, 0x02, 0x30, 0x00]},
+            crate::dict!{"rid" => vec![9u8; 32], "hash" => vec![10u8; 32]}
+        ],
+        "witness" => witness
+    }
+}
+
+#[test]
+fn test_block_decode() {
+    let witness = [&[0, 0, 0, 1, 0, 0, 0, 2, 0xaa, 0xbb, 0, 0, 0, 3][..], &[1, 2, 3]].concat();
+    let block = Block::decode(&gtv::encode_value(&sample_block(42, witness)).unwrap()).unwrap();
+
+    assert_eq!(block.rid, vec![3u8; 32]);
+    assert_eq!(block.height, 42);
+    assert_eq!(block.timestamp, 1736929874000);
+    assert_eq!(block.header, BlockHeader {
+        blockchain_rid: vec![1u8; 32],
+        previous_block_rid: vec![0u8; 32],
+        merkle_root_hash: vec![2u8; 32],
+        timestamp: 1736929874000,
+        height: 42,
+        dependencies: vec![BlockDependency { blockchain_rid: vec![4u8; 32], block_rid: vec![5u8; 32] }],
+        extra: BTreeMap::from([("config_hash".to_string(), Params::ByteArray(vec![6u8; 32]))]),
+    });
+    assert_eq!(gtv::decode(&block.raw_header).unwrap().get_index(4), Some(&Params::Integer(42)));
+    assert_eq!(block.transactions[0].data, Some(vec![0xa5, 0x02, 0x30, 0x00]));
+    assert_eq!(block.transactions[1], BlockTransaction { rid: vec![9u8; 32], hash: vec![10u8; 32], data: None });
+    assert_eq!(block.witness.signatures, vec![WitnessSignature { subject_id: vec![0xaa, 0xbb], data: vec![1, 2, 3] }]);
+
+    let list = Params::Array(vec![sample_block(0, vec![0; 4]), sample_block(1, vec![0; 4])]);
+    let blocks = decode_blocks(&gtv::encode_value(&list).unwrap()).unwrap();
+    assert_eq!(blocks.iter().map(|block| block.height).collect::<Vec<_>>(), vec![0, 1]);
+}
+
+#[test]
+fn test_block_decode_errors() {
+    let mut block = sample_block(1, vec![0; 4]);
+    if let Params::Dict(fields) = &mut block {
+        fields.insert("height".to_string(), Params::Integer(-1));
+    }
+    assert_eq!(Block::from_params(&block).unwrap_err().to_string(), "height: expected a non-negative integer");
+
+    let list = Params::Array(vec![sample_block(0, vec![0; 4]), crate::dict!{"rid" => 1}]);
+    assert_eq!(decode_blocks(&gtv::encode_value(&list).unwrap()).unwrap_err().to_string(), "[1].header: expected a value");
+
+    let mut block = sample_block(1, vec![0; 4]);
+    if let Params::Dict(fields) = &mut block {
+        fields.insert("header".to_string(), Params::ByteArray(gtv::encode_value(&crate::gtv_array![vec![1u8; 32]]).unwrap()));
+    }
+    assert_eq!(Block::from_params(&block).unwrap_err().to_string(), "header[5]: expected a header field");
+
+    assert_eq!(BlockWitness::decode(&[0, 0, 0, 1, 0, 0, 0, 5, 1]), Err(BlockError::Witness("unexpected end of data".to_string())));
+    assert_eq!(BlockWitness::decode(&[0, 0, 0, 0, 1]), Err(BlockError::Witness("1 trailing bytes".to_string())));
+    assert!(matches!(Block::decode(&[0xa0]), Err(BlockError::Decode(_))));
+}
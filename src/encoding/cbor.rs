@@ -0,0 +1,210 @@
+//! CBOR conversion of GTV values, available with the `cbor` feature.
+//!
+//! Values map to their natural CBOR counterparts, so CBOR-based systems such as IoT
+//! devices or COSE signatures can consume them directly:
+//!
+//! * `Null`, `Boolean`, `Text`, `ByteArray`, `Array` and `Dict` map to the CBOR types of the same kind
+//! * `Integer` and `BigInteger` map to CBOR integers, or to bignums (tags 2 and 3) beyond 64 bits
+//! * `Decimal` maps to a decimal fraction (tag 4)
+//!
+//! # Example
+//! ```
+//! # use postchain_client::{dict, utils::operation::Params};
+//! let value = dict!{"id" => 7, "name" => "sensor"};
+//! let cbor = value.to_cbor();
+//!
+//! assert_eq!(Params::from_cbor(&cbor).unwrap(), value);
+//! ```
+
+use bigdecimal::BigDecimal;
+use ciborium::value::{Integer, Value};
+use num_bigint::{BigInt, Sign};
+
+use crate::utils::operation::Params;
+
+/// CBOR tag of a positive bignum
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+/// CBOR tag of a negative bignum
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+/// CBOR tag of a decimal fraction
+const TAG_DECIMAL_FRACTION: u64 = 4;
+
+/// Errors that can occur when converting CBOR into `Params`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CborError {
+    /// The bytes are not valid CBOR
+    Decode(String),
+    /// The CBOR value has no GTV counterpart, e.g. a map with non-text keys
+    Unsupported(String),
+}
+
+impl std::fmt::Display for CborError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CborError::Decode(error) => write!(f, "Invalid CBOR: {}", error),
+            CborError::Unsupported(error) => write!(f, "Unsupported CBOR value: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for CborError {}
+
+impl Params {
+    /// Encodes the value as CBOR.
+    ///
+    /// # Returns
+    /// CBOR-encoded bytes
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&to_cbor_value(self), &mut bytes)
+            .expect("Writing CBOR into a Vec cannot fail");
+        bytes
+    }
+
+    /// Decodes a value from CBOR.
+    ///
+    /// Floats become decimals using their shortest exact decimal form.
+    ///
+    /// # Arguments
+    /// * `data` - CBOR-encoded bytes
+    ///
+    /// # Returns
+    /// The decoded value, or an error if the bytes are not CBOR or hold a value without a GTV counterpart
+    pub fn from_cbor(data: &[u8]) -> Result<Params, CborError> {
+        let value: Value = ciborium::from_reader(data)
+            .map_err(|error| CborError::Decode(error.to_string()))?;
+        from_cbor_value(value)
+    }
+}
+
+/// Converts a big integer into a CBOR integer, or a bignum if it does not fit.
+fn bigint_to_cbor(value: &BigInt) -> Value {
+    if let Some(integer) = i128::try_from(value).ok().and_then(|value| Integer::try_from(value).ok()) {
+        return Value::Integer(integer);
+    }
+
+    match value.sign() {
+        Sign::Minus => {
+            let magnitude: BigInt = -value - 1;
+            Value::Tag(TAG_NEGATIVE_BIGNUM, Box::new(Value::Bytes(magnitude.to_bytes_be().1)))
+        },
+        _ => Value::Tag(TAG_POSITIVE_BIGNUM, Box::new(Value::Bytes(value.to_bytes_be().1)))
+    }
+}
+
+fn to_cbor_value(value: &Params) -> Value {
+    match value {
+        Params::Null => Value::Null,
+        Params::Boolean(val) => Value::Bool(*val),
+        Params::Integer(val) => Value::Integer((*val).into()),
+        Params::BigInteger(val) => bigint_to_cbor(val),
+        Params::Decimal(val) => {
+            let (mantissa, scale) = val.as_bigint_and_exponent();
+            Value::Tag(TAG_DECIMAL_FRACTION, Box::new(Value::Array(vec![
+                Value::Integer((-scale).into()),
+                bigint_to_cbor(&mantissa)
+            ])))
+        },
+        Params::Text(val) => Value::Text(val.clone()),
+        Params::ByteArray(val) => Value::Bytes(val.clone()),
+        Params::Array(array) => Value::Array(array.iter().map(to_cbor_value).collect()),
+        Params::Dict(dict) => Value::Map(dict.iter()
+            .map(|(key, value)| (Value::Text(key.clone()), to_cbor_value(value)))
+            .collect()),
+    }
+}
+
+/// Reads a CBOR integer or bignum.
+fn bigint_from_cbor(value: Value) -> Result<BigInt, CborError> {
+    match value {
+        Value::Integer(integer) => Ok(BigInt::from(i128::from(integer))),
+        Value::Tag(TAG_POSITIVE_BIGNUM, inner) => match *inner {
+            Value::Bytes(bytes) => Ok(BigInt::from_bytes_be(Sign::Plus, &bytes)),
+            _ => Err(CborError::Unsupported("positive bignum without a byte string".to_string()))
+        },
+        Value::Tag(TAG_NEGATIVE_BIGNUM, inner) => match *inner {
+            Value::Bytes(bytes) => Ok(-BigInt::from_bytes_be(Sign::Plus, &bytes) - 1),
+            _ => Err(CborError::Unsupported("negative bignum without a byte string".to_string()))
+        },
+        other => Err(CborError::Unsupported(format!("expected an integer, found {:?}", other)))
+    }
+}
+
+/// Narrows a big integer to `Params::Integer` when it fits into an `i64`.
+fn integer_params(value: BigInt) -> Params {
+    match i64::try_from(&value) {
+        Ok(value) => Params::Integer(value),
+        Err(_) => Params::BigInteger(value)
+    }
+}
+
+fn from_cbor_value(value: Value) -> Result<Params, CborError> {
+    match value {
+        Value::Null => Ok(Params::Null),
+        Value::Bool(val) => Ok(Params::Boolean(val)),
+        Value::Text(val) => Ok(Params::Text(val)),
+        Value::Bytes(val) => Ok(Params::ByteArray(val)),
+        Value::Float(val) => Params::decimal_from_f64(val)
+            .ok_or_else(|| CborError::Unsupported(format!("float {} has no decimal value", val))),
+        Value::Array(array) => array.into_iter().map(from_cbor_value).collect::<Result<_, _>>().map(Params::Array),
+        Value::Map(map) => map.into_iter()
+            .map(|(key, value)| match key {
+                Value::Text(key) => Ok((key, from_cbor_value(value)?)),
+                other => Err(CborError::Unsupported(format!("map key {:?} is not text", other)))
+            })
+            .collect::<Result<_, _>>()
+            .map(Params::Dict),
+        Value::Tag(TAG_DECIMAL_FRACTION, inner) => match *inner {
+            Value::Array(parts) if parts.len() == 2 => {
+                let mut parts = parts.into_iter();
+                let exponent = bigint_from_cbor(parts.next().unwrap_or(Value::Null))?;
+                let mantissa = bigint_from_cbor(parts.next().unwrap_or(Value::Null))?;
+                let scale = i64::try_from(-exponent)
+                    .map_err(|_| CborError::Unsupported("decimal exponent out of range".to_string()))?;
+                Ok(Params::Decimal(BigDecimal::new(mantissa, scale)))
+            },
+            _ => Err(CborError::Unsupported("decimal fraction is not a two-element array".to_string()))
+        },
+        Value::Tag(TAG_POSITIVE_BIGNUM | TAG_NEGATIVE_BIGNUM, _) | Value::Integer(_) => bigint_from_cbor(value).map(integer_params),
+        Value::Tag(tag, _) => Err(CborError::Unsupported(format!("tag {}", tag))),
+        other => Err(CborError::Unsupported(format!("{:?}", other)))
+    }
+}
+
+#[test]
+fn test_cbor_roundtrip() {
+    use std::str::FromStr;
+
+    let value = crate::dict!{
+        "null" => Params::Null,
+        "flag" => true,
+        "count" => -42,
+        "huge" => BigInt::from(1) << 100u32,
+        "negative_huge" => -(BigInt::from(1) << 100u32),
+        "price" => BigDecimal::from_str("-12.3450").unwrap(),
+        "name" => "sensor",
+        "raw" => crate::bytes!([1, 2, 3]),
+        "list" => crate::gtv_array![1, "1"]
+    };
+
+    assert_eq!(Params::from_cbor(&value.to_cbor()).unwrap(), value);
+
+    // 64-bit values beyond i64 stay native CBOR integers and come back as BigInteger
+    let wide = Params::BigInteger(BigInt::from(u64::MAX));
+    assert_eq!(wide.to_cbor(), vec![0x1B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(Params::from_cbor(&wide.to_cbor()).unwrap(), wide);
+}
+
+#[test]
+fn test_cbor_interop() {
+    // {"a": 1.5, "b": [h'01']} written by another encoder
+    let cbor = [0xA2, 0x61, 0x61, 0xF9, 0x3E, 0x00, 0x61, 0x62, 0x81, 0x41, 0x01];
+    assert_eq!(Params::from_cbor(&cbor).unwrap(), crate::dict!{
+        "a" => BigDecimal::new(BigInt::from(15), 1),
+        "b" => crate::gtv_array![crate::bytes!([1])]
+    });
+
+    // {1: 2} has a non-text key
+    assert!(matches!(Params::from_cbor(&[0xA1, 0x01, 0x02]), Err(CborError::Unsupported(_))));
+    assert!(matches!(Params::from_cbor(&[0xFF]), Err(CborError::Decode(_))));
+}
@@ -15,37 +15,92 @@
 //! # Examples
 //! 
 //! ```rust
+//! # use postchain_client::{encoding::gtv::{decode, encode_value}, utils::operation::Params};
 //! // Encoding a simple value
 //! let value = Params::Text("hello".to_string());
-//! let encoded = encode_value(&value);
+//! let encoded = encode_value(&value).unwrap();
 //! 
 //! // Decoding a value
 //! let decoded = decode(&encoded).unwrap();
 //! ```
+//! 
+//! # Low-level API
+//! 
+//! `Choice`, `GTVParams`, `write_explicit_element`, `write_array`, `write_dict`, `write_dict_entry`,
+//! `read_params`, `read_sequence_array` and `read_sequence_dict` work directly on the `asn1` crate's
+//! writer and parser, for structures `Params` does not model, such as proofs or blocks. They are
+//! part of the public API under semver: they only change in a breaking release, which includes an
+//! upgrade of the `asn1` dependency since its types appear in their signatures.
+//! 
+//! ```rust
+//! # use postchain_client::{encoding::gtv::{self, Choice, GTVParams}, utils::operation::Params};
+//! // A custom container `[hash, {name: value}]` written without building a `Params` tree first
+//! let hash = [7u8; 32];
+//! let encoded = asn1::write(|writer| gtv::write_array(writer, &|writer| {
+//!   writer.write_element(&Choice::OCTETSTRING(&hash))?;
+//!   gtv::write_dict(writer, &|writer| gtv::write_dict_entry(writer, "height", &Params::Integer(42)))
+//! })).unwrap();
+//! 
+//! let (decoded_hash, fields) = asn1::parse_single::<Choice>(&encoded).map(|choice| match choice {
+//!   Choice::ARRAY(seq) => seq.parse(|parser| {
+//!     let hash = parser.read_element::<Choice>()?;
+//!     let fields = match gtv::read_params(parser)? {
+//!       Params::Dict(fields) => fields,
+//!       _ => unreachable!(),
+//!     };
+//!     Ok::<_, asn1::ParseError>((hash, fields))
+//!   }),
+//!   _ => unreachable!(),
+//! }).unwrap().unwrap();
+//! assert!(matches!(decoded_hash, Choice::OCTETSTRING(bytes) if bytes == hash));
+//! assert_eq!(fields["height"], Params::Integer(42));
+//! assert_eq!(gtv::decode(&encoded).unwrap(), Params::Array(vec![hash.to_vec().into(), Params::Dict(fields)]));
+//! ```
 
-use crate::utils::{operation::{Operation, Params}, transaction::Transaction};
+#![allow(clippy::result_large_err)] // `asn1::ParseError` is large; it is the error type the asn1 crate gives us.
 
-use asn1::{Asn1Read, Asn1Readable, Asn1Write, ParseError};
-use std::collections::BTreeMap;
+use crate::utils::{operation::{parse_path, DiffEntry, Operation, Params, PathSegment}, shared_params::SharedParams};
+#[cfg(feature = "std")]
+use crate::utils::{params_deserializer::{from_params, DeserializeError}, transaction::Transaction};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
+use alloc::{collections::BTreeMap, format, string::{String, ToString}, vec::Vec};
+use asn1::{Asn1Read, Asn1Write, ParseError};
+use base64::{Engine as _, engine::general_purpose};
+
+/// A single GTV element, as read from or written to the `asn1` crate's parser and writer
+/// 
+/// Each variant is the explicitly tagged ASN.1 element of one GTV type. Arrays and dictionaries
+/// are left as unparsed sequences, read them with `read_sequence_array` and `read_sequence_dict`.
+/// Write arrays and dictionaries with `write_array` and `write_dict`, since writing an
+/// `asn1::Sequence` copies already encoded content.
 #[derive(Asn1Read, Asn1Write, Debug, Clone)]
 pub enum Choice<'a> {
+    /// `[0] NULL`
     #[explicit(0)]
     NULL(()),
+    /// `[1] OCTET STRING`, a byte array
     #[explicit(1)]
     OCTETSTRING(&'a [u8]),
+    /// `[2] UTF8String`, a text or a decimal
     #[explicit(2)]
     UTF8STRING(asn1::Utf8String<'a>),
+    /// `[3] INTEGER`, a 64-bit integer or a boolean
     #[explicit(3)]
     INTEGER(i64),
+    /// `[4] SEQUENCE OF SEQUENCE { UTF8String, GTV }`, a dictionary
     #[explicit(4)]
     DICT(asn1::Sequence<'a>),
+    /// `[5] SEQUENCE OF GTV`, an array
     #[explicit(5)]
     ARRAY(asn1::Sequence<'a>),
+    /// `[6] INTEGER`, an arbitrary precision integer
     #[explicit(6)]
     BIGINTEGER(asn1::BigInt<'a>),
 }
 
+/// The explicit tag number of each GTV type
 #[derive(Debug)]
 pub enum GTVType {
     Null = 0,
@@ -57,63 +112,116 @@ pub enum GTVType {
     BigInteger = 6,
 }
 
+/// Default maximum nesting depth of arrays and dictionaries accepted by `decode` and `gtv_hash`
+/// 
+/// Bounds the recursion on untrusted input, well above what Rell values need
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
+/// Errors that can occur while encoding to GTV
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The output buffer could not be allocated
+    Allocation,
+    /// The operation at this index has no name
+    MissingOperationName(usize),
+}
+
+impl core::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EncodeError::Allocation => write!(f, "Failed to allocate the GTV output buffer"),
+            EncodeError::MissingOperationName(index) => write!(f, "Operation at index {} has no name", index),
+        }
+    }
+}
+
+impl core::error::Error for EncodeError {}
+
+impl From<asn1::WriteError> for EncodeError {
+    fn from(_: asn1::WriteError) -> Self {
+        EncodeError::Allocation
+    }
+}
+
+/// Values that write themselves as a GTV element to an `asn1::Writer`
 pub trait GTVParams: Clone {
+    /// Writes the value as a single GTV element
     fn to_writer(&self, writer: &mut asn1::Writer) -> asn1::WriteResult;
 }
 
+/// Writes `val` wrapped in the context-specific explicit tag `tag`
+/// 
+/// # Arguments
+/// 
+/// * `writer` - The ASN.1 writer to write to
+/// * `val` - The element to wrap
+/// * `tag` - The explicit tag number, one of the `GTVType` values for GTV elements
 pub fn write_explicit_element<T: asn1::Asn1Writable>(writer: &mut asn1::Writer, val: &T, tag: u32)
   -> asn1::WriteResult {
   let tag = asn1::explicit_tag(tag);
   writer.write_tlv(tag, |dest| asn1::Writer::new(dest).write_element(val))
 }
 
-impl<'a> GTVParams for Params {
+/// Writes a GTV array whose items `write_items` writes
+/// 
+/// # Arguments
+/// 
+/// * `writer` - The ASN.1 writer to write to
+/// * `write_items` - Writes each item as a GTV element, for example with `GTVParams::to_writer`
+pub fn write_array(writer: &mut asn1::Writer, write_items: &dyn Fn(&mut asn1::Writer) -> asn1::WriteResult)
+  -> asn1::WriteResult {
+  write_explicit_element(writer, &asn1::SequenceWriter::new(write_items), GTVType::Array as u32)
+}
+
+/// Writes a GTV dictionary whose entries `write_entries` writes
+/// 
+/// GTV expects the entries sorted by key without duplicates, which the caller ensures
+/// 
+/// # Arguments
+/// 
+/// * `writer` - The ASN.1 writer to write to
+/// * `write_entries` - Writes each entry with `write_dict_entry`
+pub fn write_dict(writer: &mut asn1::Writer, write_entries: &dyn Fn(&mut asn1::Writer) -> asn1::WriteResult)
+  -> asn1::WriteResult {
+  write_explicit_element(writer, &asn1::SequenceWriter::new(write_entries), GTVType::Dict as u32)
+}
+
+/// Writes one entry of a GTV dictionary written with `write_dict`
+/// 
+/// # Arguments
+/// 
+/// * `writer` - The ASN.1 writer of the dictionary content
+/// * `key` - The entry key
+/// * `value` - The entry value
+pub fn write_dict_entry<T: GTVParams>(writer: &mut asn1::Writer, key: &str, value: &T) -> asn1::WriteResult {
+  writer.write_element(&asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
+    writer.write_element(&asn1::Utf8String::new(key))?;
+    value.to_writer(writer)
+  }))
+}
+
+impl GTVParams for Params {
     fn to_writer(&self, writer: &mut asn1::Writer) -> asn1::WriteResult {
         match self {
-            Params::Array(val) => {
-                write_explicit_element(writer,
-                    &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-                        for v in val {
-                            v.to_writer(writer)?;
-                        }
-                        Ok(())
-                    }),
-                    5,
-                )
-            }
-            Params::Dict(val) => {
-                write_explicit_element(writer,
-                    &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-                        for v in val {
-                            writer.write_element(&asn1::SequenceWriter::new(
-                                &|writer: &mut asn1::Writer| {
-                                    writer.write_element(&asn1::Utf8String::new(v.0))?;
-                                    v.1.to_writer(writer)?;
-                                    Ok(())
-                                },
-                            ))?;
-                        }
-                        Ok(())
-                    }),
-                    4,
-                )
-            }
+            Params::Array(val) => write_array(writer, &|writer| val.iter().try_for_each(|v| v.to_writer(writer))),
+            Params::Dict(val) => write_dict(writer, &|writer| {
+                val.iter().try_for_each(|(key, value)| write_dict_entry(writer, key, value))
+            }),
             Params::Integer(val) => writer.write_element(&Choice::INTEGER(*val)),
             Params::Boolean(val) => writer.write_element(&Choice::INTEGER(*val as i64)),
             Params::Decimal(val) => {
-                let decimal_to_string = val.to_string();
+                let decimal_to_string = format_decimal(val);
                 writer.write_element(&Choice::UTF8STRING(asn1::Utf8String::new(&decimal_to_string)))
             }
             Params::Text(val) => writer.write_element(&Choice::UTF8STRING(asn1::Utf8String::new(val))),
             Params::ByteArray(val) => writer.write_element(&Choice::OCTETSTRING(val)),
             Params::BigInteger(val) => {
-                let (sign, bytes) = val.to_bytes_be();
-                let bigint_to_vec_u8 = if sign == num_bigint::Sign::Minus {
-                    val.to_signed_bytes_be()
-                } else {
-                    bytes
-                };
-                writer.write_element(&Choice::BIGINTEGER(asn1::BigInt::new(&bigint_to_vec_u8).unwrap()))
+                // Minimal two's complement, as DER requires, so positive values with the
+                // top bit set keep their sign
+                let bytes = val.to_signed_bytes_be();
+                writer.write_tlv(asn1::explicit_tag(6), |dest| {
+                    asn1::Writer::new(dest).write_tlv(asn1::Tag::primitive(0x02), |dest| dest.push_slice(&bytes))
+                })
             }
             _ => writer.write_element(&Choice::NULL(())),
         }
@@ -129,70 +237,47 @@ impl<'a> GTVParams for Params {
 /// # Returns
 /// 
 /// * `Vec<u8>` - Encoded transaction as a byte vector
-pub fn encode_tx<'a>(tx: &Transaction<'a>) -> Vec<u8> {
-  asn1::write(|writer| {
-    write_explicit_element(writer,
-      &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-          
-          write_explicit_element(writer,
-            &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-
-              // Blockchain RID
-              writer.write_element(&Choice::OCTETSTRING(
-                &tx.blockchain_rid))?;
-
-              // Operations and args
-              write_explicit_element(writer,
-                &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
- 
-                  if let Some(operations) = &tx.operations {
-                    for operation in operations {
-                      encode_tx_body(writer, operation)?;
-                    }      
-                  }
-
-                  Ok(())
-              }), 5)?;
-
-
-              // Signers pubkeys
-              write_explicit_element(writer,
-                &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-                
-                  if let Some(signers) = &tx.signers {
-                    for sig in signers {
-                      writer.write_element(&Choice::OCTETSTRING(&sig))?;
-                    }
-                  }
-
-                  Ok(())
-              }), 5)?;
-
-              Ok(())
-          }), 5)?;
-
-          // Signatures
-          write_explicit_element(writer,
-            &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-             
-              if let Some(signatures) = &tx.signatures {
-                for sig in signatures {
-                  writer.write_element(&Choice::OCTETSTRING(&sig))?;
-                }
-              }
-
-              Ok(())
-          }), 5)?;
-
-        Ok(())
-      }),
-      5, )?;
-    Ok(())
-  }).unwrap()
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::MissingOperationName` - An operation has no name
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+#[cfg(feature = "std")]
+pub fn encode_tx(tx: &Transaction<'_>) -> Result<Vec<u8>, EncodeError> {
+  check_operation_names(tx.operations.iter().flatten())?;
+
+  let body = SizedTxBody::new(tx);
+  let signatures = tx.signatures.as_deref().unwrap_or_default();
+  let content_len = body.len() + tlv_len(tlv_len(byte_arrays_len(signatures)));
+  write_to_vec(tlv_len(tlv_len(content_len)), |out| {
+    write_sequence_header(out, 0xa5, content_len)?;
+    body.write(out)?;
+    write_byte_arrays(out, signatures)
+  })
+}
+
+/// Checks that every operation has a name, which the encoding requires
+/// 
+/// # Arguments
+/// 
+/// * `operations` - The operations to check
+/// 
+/// # Returns
+/// 
+/// * `Result<(), EncodeError>` - `EncodeError::MissingOperationName` with the index of the first unnamed operation
+pub(crate) fn check_operation_names<'a, 'b: 'a>(operations: impl Iterator<Item = &'a Operation<'b>>) -> Result<(), EncodeError> {
+  for (index, operation) in operations.enumerate() {
+    if operation.operation_name.is_none() {
+      return Err(EncodeError::MissingOperationName(index));
+    }
+  }
+  Ok(())
 }
 
 /// Encodes a query and its arguments into GTV format
 /// 
+/// Same as `encode_query`, which borrows the arguments from any slice
+/// 
 /// # Arguments
 /// 
 /// * `query_type` - The type of query to encode
@@ -201,88 +286,84 @@ pub fn encode_tx<'a>(tx: &Transaction<'a>) -> Vec<u8> {
 /// # Returns
 /// 
 /// * `Vec<u8>` - Encoded query as a byte vector
-pub fn encode<'a>(
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode(
     query_type: &str,
-    query_args: Option<&'a mut Vec<(&str, Params)>>,
-) -> Vec<u8> {
-    asn1::write(|writer| {
-        write_explicit_element(writer,
-            &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-                writer.write_element(&Choice::UTF8STRING(asn1::Utf8String::new(query_type)))?;
-                encode_body(writer, &query_args)?;
-                Ok(())
-            }),
-            5,
-        )?;
-        Ok(())
-    })
-    .unwrap()
+    query_args: Option<&mut Vec<(&str, Params)>>,
+) -> Result<Vec<u8>, EncodeError> {
+    encode_query(query_type, query_args.as_deref().map_or(&[][..], Vec::as_slice))
 }
 
-/// Encodes the body of a transaction operation
+/// Encodes a query and its arguments into GTV format, as `[query_type, {name: value, ...}]`
 /// 
 /// # Arguments
 /// 
-/// * `writer` - The ASN.1 writer to write to
-/// * `operation` - The operation to encode
+/// * `query_type` - The type of query to encode
+/// * `query_args` - Query arguments as (name, value) pairs, encoded in the given order
 /// 
 /// # Returns
 /// 
-/// * `asn1::WriteResult` - Result of the write operation
-fn encode_tx_body<'a>(writer: &mut asn1::Writer, operation: &Operation<'a>) -> asn1::WriteResult {
-  write_explicit_element(writer, &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-    // Operation name
-    write_explicit_element(writer,&asn1::Utf8String::new(operation.operation_name.as_ref().unwrap()), 2)?;
-    // Operation args
-    write_explicit_element(writer, &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-      if let Some(operation_args) = &operation.list {
-        for arg in operation_args {
-          arg.to_writer(writer)?;
-        }
-      } else if let Some(operation_args) = &operation.dict {
-        write_explicit_element(writer, &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-          for (_key, val) in operation_args {
-            val.to_writer(writer)?;
-          }
-          Ok(())
-        }), 5)?;
-      }
-      Ok(())
-    }), 5)?;
-    Ok(())
-  }), 5)
+/// * `Vec<u8>` - Encoded query as a byte vector
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+/// 
+/// # Example
+/// 
+/// ```
+/// # use postchain_client::{encoding::gtv, utils::operation::Params};
+/// let args = [("account_id", Params::Integer(7))];
+/// assert_eq!(gtv::encode_query("get_account", &args).unwrap(), gtv::encode("get_account", Some(&mut args.to_vec())).unwrap());
+/// ```
+pub fn encode_query<K: AsRef<str>>(query_type: &str, query_args: &[(K, Params)]) -> Result<Vec<u8>, EncodeError> {
+  let entries = || query_args.iter().map(|(key, value)| (key.as_ref(), value));
+  let mut layout = Layout::default();
+  let args_len = collect_entries(entries(), &mut layout);
+  let content_len = tlv_len(tlv_len(query_type.len())) + tlv_len(tlv_len(args_len));
+  write_to_vec(tlv_len(tlv_len(content_len)), |out| {
+    write_sequence_header(out, 0xa5, content_len)?;
+    write_primitive(out, 0xa2, 0x0c, query_type.as_bytes())?;
+    write_entries(entries(), args_len, &layout, &mut Cursor::default(), out)
+  })
 }
 
-/// Encodes the body of a query
+/// Encodes a query without arguments, as `[query_type, {}]`
+/// 
+/// The node always expects the arguments dictionary, so it is written even when empty
 /// 
 /// # Arguments
 /// 
-/// * `writer` - The ASN.1 writer to write to
-/// * `query_args` - Optional query arguments to encode
+/// * `query_type` - The type of query to encode
 /// 
 /// # Returns
 /// 
-/// * `asn1::WriteResult` - Result of the write operation
-fn encode_body<'a>(writer: &mut asn1::Writer,
-  query_args: &Option<&'a mut Vec<(&str, Params)>>)
-  -> asn1::WriteResult {
-  write_explicit_element(writer,
-      &asn1::SequenceWriter::new(&|writer: &mut asn1::Writer| {
-          if let Some(q_args) = &query_args {
-              for (q_type, q_args) in q_args.iter() {
-                  writer.write_element(&asn1::SequenceWriter::new(
-                      &|writer: &mut asn1::Writer| {
-                          writer.write_element(&asn1::Utf8String::new(q_type))?;
-                          q_args.to_writer(writer)?;
-                          Ok(())
-                      },
-                  ))?;
-              }
-          }
-          Ok(())
-      }),
-      4,
-  )
+/// * `Vec<u8>` - Encoded query as a byte vector
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_query_without_args(query_type: &str) -> Result<Vec<u8>, EncodeError> {
+  encode_query::<&str>(query_type, &[])
+}
+
+/// Builds the single struct argument of an operation with named arguments
+/// 
+/// Named arguments fill a Rell struct, which GTX takes as one GTV dict argument
+/// 
+/// # Arguments
+/// 
+/// * `operation_args` - The named arguments of the operation
+/// 
+/// # Returns
+/// 
+/// * `Params` - A `Params::Dict` with the arguments
+#[cfg(feature = "std")]
+fn dict_argument(operation_args: &[(Cow<'_, str>, Params)]) -> Params {
+  Params::Dict(operation_args.iter().map(|(key, value)| (key.to_string(), value.clone())).collect())
 }
 
 /// Decodes a simple GTV value from a Choice enum
@@ -315,80 +396,1608 @@ fn decode_simple(choice: Choice) -> Params {
   }
 }
 
-/// Decodes a sequence of values into an array
-/// 
-/// # Arguments
-/// 
-/// * `parser` - The ASN.1 parser to read from
-/// * `vec_array` - Vector to store the decoded values
-fn decode_sequence_array<'a>(parser: &mut asn1::Parser<'a>, vec_array: &mut Vec<Params>) {
-  while let Ok(val) = Choice::parse(parser) {
-    let op_val = match val {
-        Choice::ARRAY(seq) => {
-          let res: Result<Params, ParseError> = seq.parse(|parser| {
-            let mut vect_array_new: Vec<Params> = Vec::new();
-            decode_sequence_array(parser, &mut vect_array_new);
-            Ok(Params::Array(vect_array_new))
-          });
-          res.unwrap()
+/// Reads the next GTV element of a parser
+/// 
+/// An `INTEGER` too wide for `i64`, which the node never writes but other encoders might, is
+/// returned as `Choice::BIGINTEGER` so it decodes to `Params::BigInteger` instead of failing
+fn read_choice<'a>(parser: &mut asn1::Parser<'a>) -> Result<Choice<'a>, ParseError> {
+  let tlv = parser.read_element::<asn1::Tlv>()?;
+  if tlv.tag() != asn1::explicit_tag(GTVType::Integer as u32) {
+    return asn1::parse_single::<Choice>(tlv.full_data());
+  }
+  let value = asn1::parse_single::<asn1::BigInt>(tlv.data())?;
+  if value.as_bytes().len() > 8 {
+    return Ok(Choice::BIGINTEGER(value));
+  }
+  Ok(Choice::INTEGER(asn1::parse_single::<i64>(tlv.data())?))
+}
+
+/// Tracks the nesting depth while decoding
+#[derive(Clone, Copy)]
+struct Depth {
+  current: usize,
+  max: usize,
+}
+
+impl Depth {
+  fn new(max: usize) -> Self {
+    Depth { current: 0, max }
+  }
+
+  /// Enters an array or dictionary, failing with `ParseErrorKind::InvalidSize` past the maximum
+  fn enter(self) -> Result<Self, ParseError> {
+    if self.current >= self.max {
+      return Err(ParseError::new(asn1::ParseErrorKind::InvalidSize { min: 0, max: self.max, actual: self.current + 1 }));
+    }
+    Ok(Depth { current: self.current + 1, max: self.max })
+  }
+}
+
+/// Reads a single GTV value, including nested arrays and dictionaries, from a parser
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// * `depth` - Nesting depth of the value
+/// 
+/// # Returns
+/// 
+/// * `Result<Params, ParseError>` - The value or an error if the element is not valid GTV
+fn read_value(parser: &mut asn1::Parser<'_>, depth: Depth) -> Result<Params, ParseError> {
+  match read_choice(parser)? {
+    Choice::ARRAY(seq) => {
+      let depth = depth.enter()?;
+      seq.parse(|parser| {
+        let mut vect_array_new: Vec<Params> = Vec::new();
+        decode_sequence_array(parser, &mut vect_array_new, depth)?;
+        Ok(Params::Array(vect_array_new))
+      })
+    }
+    Choice::DICT(seq) => {
+      let depth = depth.enter()?;
+      seq.parse(|parser| {
+        let mut btree_map_new: BTreeMap<String, Params> = BTreeMap::new();
+        decode_sequence_dict(parser, &mut btree_map_new, depth)?;
+        Ok(Params::Dict(btree_map_new))
+      })
+    }
+    val => Ok(decode_simple(val)),
+  }
+}
+
+/// Decodes a sequence of values into an array
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// * `vec_array` - Vector to store the decoded values
+/// * `depth` - Nesting depth of the array
+/// 
+/// # Returns
+/// 
+/// * `Result<(), ParseError>` - An error if any element is not valid GTV
+fn decode_sequence_array(parser: &mut asn1::Parser<'_>, vec_array: &mut Vec<Params>, depth: Depth) -> Result<(), ParseError> {
+  while !parser.is_empty() {
+    vec_array.push(read_value(parser, depth)?);
+  }
+  Ok(())
+}
+
+/// Decodes a sequence of key-value pairs into a dictionary
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// * `btreemap` - BTreeMap to store the decoded key-value pairs
+/// * `depth` - Nesting depth of the dictionary
+/// 
+/// # Returns
+/// 
+/// * `Result<(), ParseError>` - An error if any entry is not a UTF-8 key followed by a valid GTV value
+fn decode_sequence_dict(parser: &mut asn1::Parser<'_>, btreemap: &mut BTreeMap<String, Params>, depth: Depth) -> Result<(), ParseError> {
+  while !parser.is_empty() {
+    let (key, value) = parser.read_element::<asn1::Sequence>()?.parse(|parser| {
+      let key = parser.read_element::<asn1::Utf8String>()?;
+      Ok::<_, ParseError>((key.as_str(), read_value(parser, depth)?))
+    })?;
+    btreemap.insert(key.to_string(), value);
+  }
+  Ok(())
+}
+
+/// Reads the next GTV element of a parser as a value, including nested arrays and dictionaries
+/// 
+/// Nesting is limited to `DEFAULT_MAX_DEPTH` levels below the element
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// 
+/// # Returns
+/// 
+/// * `Result<Params, ParseError>` - The value or an error if the element is not valid GTV
+pub fn read_params(parser: &mut asn1::Parser<'_>) -> Result<Params, ParseError> {
+  read_value(parser, Depth::new(DEFAULT_MAX_DEPTH))
+}
+
+/// Reads the remaining elements of a parser as the items of a GTV array
+/// 
+/// Use it on the content of a `Choice::ARRAY`, as in `seq.parse(read_sequence_array)`
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser of the array content
+/// 
+/// # Returns
+/// 
+/// * `Result<Vec<Params>, ParseError>` - The items or an error if any is not valid GTV
+pub fn read_sequence_array(parser: &mut asn1::Parser<'_>) -> Result<Vec<Params>, ParseError> {
+  let mut items = Vec::new();
+  decode_sequence_array(parser, &mut items, Depth::new(DEFAULT_MAX_DEPTH))?;
+  Ok(items)
+}
+
+/// Reads the remaining elements of a parser as the entries of a GTV dictionary
+/// 
+/// Use it on the content of a `Choice::DICT`, as in `seq.parse(read_sequence_dict)`
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser of the dictionary content
+/// 
+/// # Returns
+/// 
+/// * `Result<BTreeMap<String, Params>, ParseError>` - The entries or an error if any is not
+///   a UTF-8 key followed by a valid GTV value
+pub fn read_sequence_dict(parser: &mut asn1::Parser<'_>) -> Result<BTreeMap<String, Params>, ParseError> {
+  let mut entries = BTreeMap::new();
+  decode_sequence_dict(parser, &mut entries, Depth::new(DEFAULT_MAX_DEPTH))?;
+  Ok(entries)
+}
+
+/// Decodes a byte slice into a GTV value
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded GTV data
+/// 
+/// # Returns
+/// 
+/// * `Result<Params, ParseError>` - The decoded value or an error if decoding fails
+/// 
+/// # Errors
+/// 
+/// Returns an error instead of panicking for any malformed input, such as truncated data,
+/// unknown tags, invalid UTF-8 texts, dictionary keys that are not texts, or trailing bytes.
+/// Arrays and dictionaries nested deeper than `DEFAULT_MAX_DEPTH` are rejected, see `decode_with_max_depth`
+pub fn decode(data: &[u8]) -> Result<Params, ParseError> {
+  decode_with_max_depth(data, DEFAULT_MAX_DEPTH)
+}
+
+/// Decodes a byte slice into a GTV value, rejecting arrays and dictionaries nested deeper than `max_depth`
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded GTV data
+/// * `max_depth` - Maximum nesting depth; a scalar has depth 0 and `[[]]` has depth 2
+/// 
+/// # Returns
+/// 
+/// * `Result<Params, ParseError>` - The decoded value or an error if decoding fails
+/// 
+/// # Errors
+/// 
+/// Too deep input fails with `ParseErrorKind::InvalidSize`, where `max` is the limit
+pub fn decode_with_max_depth(data: &[u8], max_depth: usize) -> Result<Params, ParseError> {
+  asn1::parse(data, |parser| read_value(parser, Depth::new(max_depth)))
+}
+
+/// Decodes the first GTV value of a byte slice and returns the bytes after it
+/// 
+/// Unlike `decode`, which rejects trailing bytes, this tolerates padding after the value and
+/// reads concatenated values one at a time
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice starting with an encoded GTV value
+/// 
+/// # Returns
+/// 
+/// * `Result<(Params, &[u8]), ParseError>` - The value and the remaining bytes, or an error if
+///   the data does not start with a complete, valid GTV value
+/// 
+/// # Example
+/// 
+/// ```
+/// # use postchain_client::{encoding::gtv, utils::operation::Params};
+/// let mut data = [gtv::encode_value(&Params::Integer(1)).unwrap(), gtv::encode_value(&Params::Null).unwrap()].concat();
+/// data.extend_from_slice(&[0, 0]);
+/// 
+/// let (first, rest) = gtv::decode_prefix(&data).unwrap();
+/// let (second, rest) = gtv::decode_prefix(rest).unwrap();
+/// assert_eq!((first, second, rest), (Params::Integer(1), Params::Null, &[0u8, 0][..]));
+/// ```
+pub fn decode_prefix(data: &[u8]) -> Result<(Params, &[u8]), ParseError> {
+  let (element, rest) = asn1::strip_tlv(data)?;
+  Ok((decode(element.full_data())?, rest))
+}
+
+/// Decodes a byte slice into a GTV value, accepting only the canonical encoding of that value
+///
+/// Use this when the input is hashed or signed: re-encoding a value accepted here reproduces
+/// `data` exactly, so its hash matches the hash of the original bytes.
+///
+/// # Arguments
+///
+/// * `data` - Byte slice containing the encoded GTV data
+///
+/// # Returns
+///
+/// * `Result<Params, ParseError>` - The decoded value or an error if decoding fails
+///
+/// # Errors
+///
+/// Fails like `decode`, and with `ParseErrorKind::InvalidValue` for input that decodes but is not
+/// canonical, such as dictionaries with unordered or duplicate keys. Non-minimal lengths and integers
+/// are already rejected by `decode`
+pub fn decode_canonical(data: &[u8]) -> Result<Params, ParseError> {
+  let value = decode(data)?;
+  match encode_value(&value) {
+    Ok(encoded) if encoded == data => Ok(value),
+    _ => Err(ParseError::new(asn1::ParseErrorKind::InvalidValue)),
+  }
+}
+
+/// Errors that can occur in `decode_as`
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq)]
+pub enum DecodeAsError {
+  /// The bytes are not valid GTV
+  Decode(ParseError),
+  /// The decoded value does not match the target type
+  Deserialize(DeserializeError),
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for DecodeAsError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      DecodeAsError::Decode(error) => write!(f, "Invalid GTV: {}", error),
+      DecodeAsError::Deserialize(error) => write!(f, "Unexpected value at {}", error),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for DecodeAsError {}
+
+/// Decodes a byte slice and deserializes the GTV value into `T`
+///
+/// Reads the value directly, without going through JSON; see `utils::params_deserializer` for how
+/// GTV types map to Rust types.
+///
+/// # Arguments
+///
+/// * `data` - Byte slice containing the encoded GTV data, such as a query response
+///
+/// # Returns
+///
+/// * `Result<T, DecodeAsError>` - The deserialized value or the reason it could not be read
+///
+/// # Example
+/// ```
+/// use postchain_client::{dict, encoding::gtv};
+///
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct NodeInfo {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let response = gtv::encode_value(&dict!{"host" => "localhost", "port" => 7740}).unwrap();
+/// let node: NodeInfo = gtv::decode_as(&response).unwrap();
+/// assert_eq!(node, NodeInfo { host: "localhost".to_string(), port: 7740 });
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_as<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<T, DecodeAsError> {
+  let value = decode(data).map_err(DecodeAsError::Decode)?;
+  from_params(&value).map_err(DecodeAsError::Deserialize)
+}
+
+/// A decoded GTV value whose texts, byte arrays and dictionary keys borrow from the encoded bytes
+/// 
+/// Returned by `decode_borrowed`. Convert with `Params::from` when an owned value is needed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamsRef<'a> {
+  /// Represents a null value
+  Null,
+  /// Represents a 64-bit signed integer, which also encodes booleans
+  Integer(i64),
+  /// Represents an arbitrary-precision integer
+  BigInteger(num_bigint::BigInt),
+  /// Represents a UTF-8 string, which also encodes decimals
+  Text(&'a str),
+  /// Represents a raw byte array
+  ByteArray(&'a [u8]),
+  /// Represents an ordered collection of values
+  Array(Vec<ParamsRef<'a>>),
+  /// Represents a key-value mapping where keys are strings
+  Dict(BTreeMap<&'a str, ParamsRef<'a>>),
+}
+
+impl ParamsRef<'_> {
+  /// Returns the value of a dictionary entry
+  /// 
+  /// # Arguments
+  /// 
+  /// * `key` - The key to look up
+  /// 
+  /// # Returns
+  /// 
+  /// * `Option<&ParamsRef>` - The value, or `None` if this is not a dictionary or the key is missing
+  pub fn get(&self, key: &str) -> Option<&Self> {
+    match self {
+      ParamsRef::Dict(dict) => dict.get(key),
+      _ => None,
+    }
+  }
+}
+
+impl From<&ParamsRef<'_>> for Params {
+  fn from(value: &ParamsRef<'_>) -> Self {
+    match value {
+      ParamsRef::Null => Params::Null,
+      ParamsRef::Integer(val) => Params::Integer(*val),
+      ParamsRef::BigInteger(val) => Params::BigInteger(val.clone()),
+      ParamsRef::Text(val) => Params::Text(val.to_string()),
+      ParamsRef::ByteArray(val) => Params::ByteArray(val.to_vec()),
+      ParamsRef::Array(val) => Params::Array(val.iter().map(Params::from).collect()),
+      ParamsRef::Dict(val) => Params::Dict(val.iter().map(|(key, value)| (key.to_string(), Params::from(value))).collect()),
+    }
+  }
+}
+
+impl From<ParamsRef<'_>> for Params {
+  fn from(value: ParamsRef<'_>) -> Self {
+    Params::from(&value)
+  }
+}
+
+/// Reads a single GTV value from a parser, borrowing texts and byte arrays
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// * `depth` - Nesting depth of the value
+/// 
+/// # Returns
+/// 
+/// * `Result<ParamsRef, ParseError>` - The value or an error if the element is not valid GTV
+fn read_value_borrowed<'a>(parser: &mut asn1::Parser<'a>, depth: Depth) -> Result<ParamsRef<'a>, ParseError> {
+  match read_choice(parser)? {
+    Choice::ARRAY(seq) => {
+      let depth = depth.enter()?;
+      seq.parse(|parser| {
+        let mut values = Vec::new();
+        while !parser.is_empty() {
+          values.push(read_value_borrowed(parser, depth)?);
+        }
+        Ok(ParamsRef::Array(values))
+      })
+    }
+    Choice::DICT(seq) => {
+      let depth = depth.enter()?;
+      seq.parse(|parser| {
+        let mut entries = BTreeMap::new();
+        while !parser.is_empty() {
+          let (key, value) = parser.read_element::<asn1::Sequence<'a>>()?.parse(|parser| {
+            let key = parser.read_element::<asn1::Utf8String<'a>>()?.as_str();
+            Ok::<_, ParseError>((key, read_value_borrowed(parser, depth)?))
+          })?;
+          entries.insert(key, value);
+        }
+        Ok(ParamsRef::Dict(entries))
+      })
+    }
+    Choice::INTEGER(val) => Ok(ParamsRef::Integer(val)),
+    Choice::BIGINTEGER(val) => Ok(ParamsRef::BigInteger(num_bigint::BigInt::from_signed_bytes_be(val.as_bytes()))),
+    Choice::OCTETSTRING(val) => Ok(ParamsRef::ByteArray(val)),
+    Choice::UTF8STRING(val) => Ok(ParamsRef::Text(val.as_str())),
+    Choice::NULL(()) => Ok(ParamsRef::Null),
+  }
+}
+
+/// Decodes a byte slice into a GTV value without copying texts, byte arrays or keys
+/// 
+/// Cheaper than `decode` when many values are decoded and only read, since only arrays,
+/// dictionaries and big integers allocate.
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded GTV data
+/// 
+/// # Returns
+/// 
+/// * `Result<ParamsRef, ParseError>` - The decoded value, borrowing from `data`, or an error if decoding fails
+/// 
+/// # Errors
+/// 
+/// Fails on the same input as `decode`, including nesting deeper than `DEFAULT_MAX_DEPTH`
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use postchain_client::{dict, encoding::gtv::{decode_borrowed, encode_value, ParamsRef}, utils::operation::Params};
+/// let encoded = encode_value(&dict!{"name" => "alice"}).unwrap();
+/// let value = decode_borrowed(&encoded).unwrap();
+/// 
+/// assert_eq!(value.get("name"), Some(&ParamsRef::Text("alice")));
+/// assert_eq!(Params::from(value), dict!{"name" => "alice"});
+/// ```
+pub fn decode_borrowed(data: &[u8]) -> Result<ParamsRef<'_>, ParseError> {
+  asn1::parse(data, |parser| read_value_borrowed(parser, Depth::new(DEFAULT_MAX_DEPTH)))
+}
+
+/// Decodes only the value at `path` in an encoded GTV value
+/// 
+/// Walks the encoding and skips the elements off the path without decoding them, so one
+/// field can be read from a large query response cheaply
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded GTV data
+/// * `path` - Path to the value, in the syntax of `Params::get_path` such as `accounts[2].balance`
+/// 
+/// # Returns
+/// 
+/// * `Result<Option<Params>, ParseError>` - The value, `None` if the path is malformed or does
+///   not exist, or an error if the elements read on the way are not valid GTV
+/// 
+/// # Example
+/// 
+/// ```
+/// # use postchain_client::{dict, gtv_array, encoding::gtv, utils::operation::Params};
+/// let response = gtv::encode_value(&dict!{"accounts" => gtv_array![dict!{"balance" => 10}, dict!{"balance" => 20}]}).unwrap();
+/// 
+/// assert_eq!(gtv::extract(&response, "accounts[1].balance").unwrap(), Some(Params::Integer(20)));
+/// assert_eq!(gtv::extract(&response, "accounts[2]").unwrap(), None);
+/// ```
+pub fn extract(data: &[u8], path: &str) -> Result<Option<Params>, ParseError> {
+  let Some(path) = parse_path(path) else {
+    return Ok(None);
+  };
+  asn1::parse(data, |parser| extract_at(parser, &path, Depth::new(DEFAULT_MAX_DEPTH)))
+}
+
+/// Reads the next element of a parser and follows `path` into it, skipping the other elements
+fn extract_at(parser: &mut asn1::Parser<'_>, path: &[PathSegment<'_>], depth: Depth) -> Result<Option<Params>, ParseError> {
+  let Some((segment, rest)) = path.split_first() else {
+    return read_value(parser, depth).map(Some);
+  };
+  match (read_choice(parser)?, segment) {
+    (Choice::ARRAY(seq), PathSegment::Index(index)) => {
+      let depth = depth.enter()?;
+      seq.parse(|parser| {
+        let mut found = None;
+        let mut current = 0;
+        while !parser.is_empty() {
+          if current == *index {
+            found = extract_at(parser, rest, depth)?;
+          } else {
+            parser.read_element::<asn1::Tlv>()?;
+          }
+          current += 1;
+        }
+        Ok(found)
+      })
+    }
+    (Choice::DICT(seq), PathSegment::Key(key)) => {
+      let depth = depth.enter()?;
+      seq.parse(|parser| {
+        let mut found = None;
+        while !parser.is_empty() {
+          let entry = parser.read_element::<asn1::Sequence>()?;
+          if found.is_some() {
+            continue;
+          }
+          found = entry.parse(|parser| {
+            if parser.read_element::<asn1::Utf8String>()?.as_str() == *key {
+              extract_at(parser, rest, depth)
+            } else {
+              parser.read_element::<asn1::Tlv>()?;
+              Ok(None)
+            }
+          })?;
+        }
+        Ok(found)
+      })
+    }
+    _ => Ok(None),
+  }
+}
+
+/// Decodes a transaction from a byte slice
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded transaction
+/// 
+/// # Returns
+/// 
+/// * `Result<Params, ParseError>` - The decoded transaction or an error if decoding fails
+pub fn decode_tx(data: &[u8]) -> Result<Params, ParseError> {
+  decode(data)
+}
+
+/// Decodes a transaction from its GTV encoding into a `Transaction`
+/// 
+/// Operation names borrow from `data`. Argument names of dictionary operations are not
+/// part of the encoding, so every operation is decoded with list arguments.
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded transaction, as produced by `encode_tx`
+/// 
+/// # Returns
+/// 
+/// * `Result<Transaction, ParseError>` - The decoded transaction or an error if decoding fails
+#[cfg(feature = "std")]
+pub fn decode_transaction(data: &[u8]) -> Result<Transaction<'_>, ParseError> {
+  asn1::parse_single::<asn1::Explicit<asn1::Sequence, 5>>(data)?.into_inner().parse(|parser| {
+    let (blockchain_rid, operations, signers) = parser
+      .read_element::<asn1::Explicit<asn1::Sequence, 5>>()?.into_inner().parse(|parser| {
+        let blockchain_rid = parser.read_element::<asn1::Explicit<&[u8], 1>>()?.into_inner().to_vec();
+
+        let operations = parser.read_element::<asn1::Explicit<asn1::Sequence, 5>>()?.into_inner().parse(|parser| {
+          let mut operations = Vec::new();
+          while !parser.is_empty() {
+            operations.push(read_operation(parser)?);
+          }
+          Ok::<_, ParseError>(operations)
+        })?;
+
+        let signers = read_byte_arrays(parser)?;
+
+        Ok::<_, ParseError>((blockchain_rid, operations, signers))
+      })?;
+
+    let signatures = read_byte_arrays(parser)?;
+
+    Ok(Transaction::new(
+      blockchain_rid,
+      Some(operations),
+      if signers.is_empty() { None } else { Some(signers) },
+      if signatures.is_empty() { None } else { Some(signatures) }))
+  })
+}
+
+/// Encodes a single operation into a byte vector, as it appears in a transaction
+/// 
+/// # Arguments
+/// 
+/// * `operation` - The operation to encode
+/// 
+/// # Returns
+/// 
+/// * `Vec<u8>` - Encoded operation as a byte vector
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::MissingOperationName` - The operation has no name
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_operation(operation: &Operation) -> Result<Vec<u8>, EncodeError> {
+  check_operation_names(core::iter::once(operation))?;
+  let mut layout = Layout::default();
+  let operation = SizedOperation::new(operation, &mut layout);
+  write_to_vec(operation.len(), |out| operation.write(&layout, &mut Cursor::default(), out))
+}
+
+/// Decodes a single operation encoded by `encode_operation`
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing the encoded operation
+/// 
+/// # Returns
+/// 
+/// * `Result<Operation, ParseError>` - The decoded operation, with list arguments, or an error if decoding fails
+pub fn decode_operation(data: &[u8]) -> Result<Operation<'_>, ParseError> {
+  asn1::parse(data, read_operation)
+}
+
+/// Reads an operation `[name, [args...]]` from a parser
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// 
+/// # Returns
+/// 
+/// * `Result<Operation, ParseError>` - The operation or an error if the element is not an operation
+fn read_operation<'a>(parser: &mut asn1::Parser<'a>) -> Result<Operation<'a>, ParseError> {
+  parser.read_element::<asn1::Explicit<asn1::Sequence<'a>, 5>>()?.into_inner().parse(|parser| {
+    let name = parser.read_element::<asn1::Explicit<asn1::Utf8String<'a>, 2>>()?.into_inner().as_str();
+    let args = parser.read_element::<asn1::Explicit<asn1::Sequence, 5>>()?.into_inner().parse(|parser| {
+      let mut args = Vec::new();
+      // The arguments sit inside the operation and its argument list
+      decode_sequence_array(parser, &mut args, Depth { current: 2, max: DEFAULT_MAX_DEPTH })?;
+      Ok::<_, ParseError>(args)
+    })?;
+    Ok(Operation::from_list(name, args))
+  })
+}
+
+/// Reads an array of byte arrays, such as the signers or signatures of a transaction
+/// 
+/// # Arguments
+/// 
+/// * `parser` - The ASN.1 parser to read from
+/// 
+/// # Returns
+/// 
+/// * `Result<Vec<Vec<u8>>, ParseError>` - The byte arrays or an error if decoding fails
+#[cfg(feature = "std")]
+fn read_byte_arrays(parser: &mut asn1::Parser) -> Result<Vec<Vec<u8>>, ParseError> {
+  parser.read_element::<asn1::Explicit<asn1::Sequence, 5>>()?.into_inner().parse(|parser| {
+    let mut values = Vec::new();
+    while !parser.is_empty() {
+      values.push(parser.read_element::<asn1::Explicit<&[u8], 1>>()?.into_inner().to_vec());
+    }
+    Ok(values)
+  })
+}
+
+/// Encodes a single GTV value into a byte vector
+/// 
+/// # Arguments
+/// 
+/// * `value` - The value to encode
+/// 
+/// # Returns
+/// 
+/// * `Vec<u8>` - The encoded value as a byte vector
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_value(value: &Params) -> Result<Vec<u8>, EncodeError> {
+  let value = SizedValue::new(value);
+  write_to_vec(value.len, |out| value.write(out))
+}
+
+/// Encodes a shared value into a byte vector, reading its texts, byte arrays and
+/// collections in place
+/// 
+/// Produces the same bytes as `encode_value(&Params::from(value))` without copying the value.
+/// 
+/// # Arguments
+/// 
+/// * `value` - The value to encode
+/// 
+/// # Returns
+/// 
+/// * `Vec<u8>` - The encoded value as a byte vector
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_shared_value(value: &SharedParams) -> Result<Vec<u8>, EncodeError> {
+  let value = SizedValue::new(value);
+  write_to_vec(value.len, |out| value.write(out))
+}
+
+/// Formats a decimal the way Rell writes a `decimal` to GTV, as the text a decimal is encoded as
+/// 
+/// The text is in plain notation without an exponent or trailing fractional zeros, and without a
+/// decimal point for whole numbers, so `1.50`, `15E-1` and `1.5` all give `1.5` and `1.5E+3`
+/// gives `1500`. Decimals that differ only in scale thus encode and hash identically, as they do
+/// on the node. Read decoded texts back with `Params::to_decimal`
+/// 
+/// # Arguments
+/// 
+/// * `value` - The decimal to format
+/// 
+/// # Returns
+/// 
+/// * `String` - The canonical text of the decimal
+pub fn format_decimal(value: &bigdecimal::BigDecimal) -> String {
+  value.normalized().to_plain_string()
+}
+
+/// Encodes a GTV value and returns it as a hexadecimal string
+/// 
+/// # Arguments
+/// 
+/// * `value` - The value to encode
+/// 
+/// # Returns
+/// 
+/// * `String` - Hexadecimal representation of the encoded value
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_value_hex_encode(value: &Params) -> Result<String, EncodeError> {
+  encode_value(value).map(hex::encode)
+}
+
+/// Letter case of hexadecimal digits above 9
+///
+/// Node endpoints print lowercase hex, while some explorers and signers expect uppercase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HexCase {
+  /// `a` to `f`, as `hex::encode` produces
+  #[default]
+  Lower,
+  /// `A` to `F`
+  Upper,
+}
+
+/// Appends the hexadecimal form of `bytes` to `out`
+///
+/// Lets logging and other hot paths reuse one `String` instead of allocating per call.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to format
+/// * `case` - Letter case of the digits
+/// * `out` - The string to append to
+///
+/// # Examples
+///
+/// ```
+/// # use postchain_client::encoding::gtv::{encode_hex_into, HexCase};
+/// let mut line = String::from("rid=");
+/// encode_hex_into(&[0xab, 0x01], HexCase::Upper, &mut line);
+/// assert_eq!(line, "rid=AB01");
+/// ```
+pub fn encode_hex_into(bytes: &[u8], case: HexCase, out: &mut String) {
+  let digits: &[u8; 16] = match case {
+    HexCase::Lower => b"0123456789abcdef",
+    HexCase::Upper => b"0123456789ABCDEF",
+  };
+  out.reserve(bytes.len() * 2);
+  for byte in bytes {
+    out.push(digits[(byte >> 4) as usize] as char);
+    out.push(digits[(byte & 0x0f) as usize] as char);
+  }
+}
+
+/// Encodes a GTV value and returns it as a hexadecimal string in the given case
+///
+/// # Arguments
+///
+/// * `value` - The value to encode
+/// * `case` - Letter case of the digits
+///
+/// # Returns
+///
+/// * `String` - Hexadecimal representation of the encoded value
+///
+/// # Errors
+///
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_value_hex(value: &Params, case: HexCase) -> Result<String, EncodeError> {
+  let mut out = String::new();
+  encode_value_hex_into(value, case, &mut out)?;
+  Ok(out)
+}
+
+/// Appends the hexadecimal form of a value's GTV encoding to `out`
+///
+/// # Arguments
+///
+/// * `value` - The value to encode
+/// * `case` - Letter case of the digits
+/// * `out` - The string to append to
+///
+/// # Errors
+///
+/// * `EncodeError::Allocation` - The encoding buffer could not be allocated
+pub fn encode_value_hex_into(value: &Params, case: HexCase, out: &mut String) -> Result<(), EncodeError> {
+  encode_hex_into(&encode_value(value)?, case, out);
+  Ok(())
+}
+
+/// Encodes a GTV value and returns it as a base64 string, with the standard alphabet and padding
+/// 
+/// # Arguments
+/// 
+/// * `value` - The value to encode
+/// 
+/// # Returns
+/// 
+/// * `String` - Base64 representation of the encoded value
+/// 
+/// # Errors
+/// 
+/// * `EncodeError::Allocation` - The output buffer could not be allocated
+pub fn encode_value_base64(value: &Params) -> Result<String, EncodeError> {
+  encode_value(value).map(|encoded| general_purpose::STANDARD.encode(encoded))
+}
+
+/// Errors that can occur in `decode_value_base64`
+#[derive(Debug, PartialEq)]
+pub enum DecodeBase64Error {
+  /// The text is not valid base64
+  Base64(base64::DecodeError),
+  /// The decoded bytes are not valid GTV
+  Decode(ParseError),
+}
+
+impl core::fmt::Display for DecodeBase64Error {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      DecodeBase64Error::Base64(error) => write!(f, "Invalid base64: {}", error),
+      DecodeBase64Error::Decode(error) => write!(f, "Invalid GTV: {}", error),
+    }
+  }
+}
+
+impl core::error::Error for DecodeBase64Error {}
+
+/// Decodes a GTV value from a base64 string, as produced by `encode_value_base64`
+/// 
+/// # Arguments
+/// 
+/// * `data` - Base64 text with the standard alphabet and padding
+/// 
+/// # Returns
+/// 
+/// * `Result<Params, DecodeBase64Error>` - The decoded value or the reason it could not be read
+/// 
+/// # Example
+/// 
+/// ```
+/// # use postchain_client::{encoding::gtv, utils::operation::Params};
+/// let encoded = gtv::encode_value_base64(&Params::Text("hello".to_string())).unwrap();
+/// assert_eq!(encoded, "ogcMBWhlbGxv");
+/// assert_eq!(gtv::decode_value_base64(&encoded).unwrap(), Params::Text("hello".to_string()));
+/// ```
+pub fn decode_value_base64(data: &str) -> Result<Params, DecodeBase64Error> {
+  let bytes = general_purpose::STANDARD.decode(data).map_err(DecodeBase64Error::Base64)?;
+  decode(&bytes).map_err(DecodeBase64Error::Decode)
+}
+
+/// Returns the length of a DER element whose content is `content_len` bytes long
+pub(crate) fn tlv_len(content_len: usize) -> usize {
+  let length_len = if content_len < 0x80 {
+    1
+  } else {
+    1 + (usize::BITS - content_len.leading_zeros()).div_ceil(8) as usize
+  };
+  1 + length_len + content_len
+}
+
+/// Returns the content length of a DER INTEGER holding `value`
+pub(crate) fn integer_content_len(value: i64) -> usize {
+  let significant_bits = if value < 0 { 64 - value.leading_ones() } else { 64 - value.leading_zeros() };
+  (significant_bits as usize + 1).div_ceil(8)
+}
+
+impl Params {
+  /// Computes the length of the GTV encoding of the value without encoding it.
+  ///
+  /// Useful to pack operations into transactions up to the node's size limit.
+  ///
+  /// # Returns
+  ///
+  /// * `usize` - The number of bytes `encode_value` produces for the value
+  pub fn encoded_len(&self) -> usize {
+    let inner = match self {
+      Params::Null => tlv_len(0),
+      Params::Boolean(val) => tlv_len(integer_content_len(*val as i64)),
+      Params::Integer(val) => tlv_len(integer_content_len(*val)),
+      Params::BigInteger(val) => tlv_len(val.to_signed_bytes_be().len()),
+      Params::Decimal(val) => tlv_len(format_decimal(val).len()),
+      Params::Text(val) => tlv_len(val.len()),
+      Params::ByteArray(val) => tlv_len(val.len()),
+      Params::Array(val) => tlv_len(val.iter().map(Params::encoded_len).sum()),
+      Params::Dict(val) => tlv_len(val.iter()
+        .map(|(key, value)| tlv_len(tlv_len(key.len()) + value.encoded_len()))
+        .sum()),
+    };
+    tlv_len(inner)
+  }
+}
+
+/// A value tree the encoder and the Merkle hasher walk, so `SharedParams` is encoded in
+/// place instead of being converted to `Params` first
+pub(crate) trait GtvValue: Sized {
+  /// Type of dictionary keys
+  type Key: AsRef<str>;
+
+  /// Returns the GTV element the value encodes as
+  fn element(&self) -> Element<'_, Self>;
+}
+
+/// A GTV element borrowed from a `GtvValue`, with booleans already turned into integers
+pub(crate) enum Element<'a, V: GtvValue> {
+  Null,
+  Integer(i64),
+  BigInteger(&'a num_bigint::BigInt),
+  Decimal(&'a bigdecimal::BigDecimal),
+  Text(&'a str),
+  ByteArray(&'a [u8]),
+  Array(&'a [V]),
+  Dict(&'a BTreeMap<V::Key, V>),
+}
+
+impl GtvValue for Params {
+  type Key = String;
+
+  fn element(&self) -> Element<'_, Self> {
+    match self {
+      Params::Null => Element::Null,
+      Params::Boolean(val) => Element::Integer(*val as i64),
+      Params::Integer(val) => Element::Integer(*val),
+      Params::BigInteger(val) => Element::BigInteger(val),
+      Params::Decimal(val) => Element::Decimal(val),
+      Params::Text(val) => Element::Text(val),
+      Params::ByteArray(val) => Element::ByteArray(val),
+      Params::Array(val) => Element::Array(val),
+      Params::Dict(val) => Element::Dict(val),
+    }
+  }
+}
+
+impl GtvValue for SharedParams {
+  type Key = alloc::sync::Arc<str>;
+
+  fn element(&self) -> Element<'_, Self> {
+    match self {
+      SharedParams::Null => Element::Null,
+      SharedParams::Boolean(val) => Element::Integer(*val as i64),
+      SharedParams::Integer(val) => Element::Integer(*val),
+      SharedParams::BigInteger(val) => Element::BigInteger(val),
+      SharedParams::Decimal(val) => Element::Decimal(val),
+      SharedParams::Text(val) => Element::Text(val),
+      SharedParams::ByteArray(val) => Element::ByteArray(val),
+      SharedParams::Array(val) => Element::Array(val),
+      SharedParams::Dict(val) => Element::Dict(val),
+    }
+  }
+}
+
+/// Lengths and formatted decimals of a value, collected by `collect_layout` in the order
+/// `write_value` needs them, so nothing is measured or formatted twice
+#[derive(Default)]
+struct Layout {
+  /// Content length of every array and dictionary, and encoded length of every dictionary value
+  sizes: Vec<usize>,
+  /// Text of every decimal
+  decimals: Vec<String>,
+}
+
+/// Position of `write_value` in a `Layout`
+#[derive(Default)]
+struct Cursor {
+  size: usize,
+  decimal: usize,
+}
+
+/// Computes the encoded length of `value` and records what `write_value` needs in `layout`
+fn collect_layout<V: GtvValue>(value: &V, layout: &mut Layout) -> usize {
+  let content_len = match value.element() {
+    Element::Null => 0,
+    Element::Integer(val) => integer_content_len(val),
+    Element::BigInteger(val) => val.to_signed_bytes_be().len(),
+    Element::Decimal(val) => {
+      let text = format_decimal(val);
+      let len = text.len();
+      layout.decimals.push(text);
+      len
+    }
+    Element::Text(val) => val.len(),
+    Element::ByteArray(val) => val.len(),
+    Element::Array(val) => {
+      let index = layout.sizes.len();
+      layout.sizes.push(0);
+      layout.sizes[index] = val.iter().map(|value| collect_layout(value, layout)).sum();
+      layout.sizes[index]
+    }
+    Element::Dict(val) => {
+      let index = layout.sizes.len();
+      layout.sizes.push(0);
+      layout.sizes[index] = collect_entries(val.iter().map(|(key, value)| (key.as_ref(), value)), layout);
+      layout.sizes[index]
+    }
+  };
+  tlv_len(tlv_len(content_len))
+}
+
+/// Collects the layout of dictionary entries, recording the encoded length of each value
+/// first since its entry header needs it, and returns the content length of the dictionary
+fn collect_entries<'a, V: GtvValue + 'a>(entries: impl Iterator<Item = (&'a str, &'a V)>, layout: &mut Layout) -> usize {
+  entries
+    .map(|(key, value)| {
+      let index = layout.sizes.len();
+      layout.sizes.push(0);
+      layout.sizes[index] = collect_layout(value, layout);
+      tlv_len(tlv_len(key.len()) + layout.sizes[index])
+    })
+    .sum()
+}
+
+/// Destination of the GTV writers, so encoding needs no `std::io`
+pub(crate) trait Sink {
+  /// Error of a failed write
+  type Error;
+
+  /// Appends `bytes` to the destination
+  fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl Sink for Vec<u8> {
+  type Error = core::convert::Infallible;
+
+  fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+    self.extend_from_slice(bytes);
+    Ok(())
+  }
+}
+
+impl Sink for sha2::Sha256 {
+  type Error = core::convert::Infallible;
+
+  fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+    sha2::Digest::update(self, bytes);
+    Ok(())
+  }
+}
+
+/// Adapts an `io::Write` sink, such as a file or socket, to the GTV writers
+#[cfg(feature = "std")]
+struct IoSink<'a, W>(&'a mut W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Sink for IoSink<'_, W> {
+  type Error = std::io::Error;
+
+  fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+    self.0.write_all(bytes)
+  }
+}
+
+/// Writes the tag and DER length of an element with `content_len` bytes of content
+pub(crate) fn write_header<S: Sink>(out: &mut S, tag: u8, content_len: usize) -> Result<(), S::Error> {
+  if content_len < 0x80 {
+    return out.write_all(&[tag, content_len as u8]);
+  }
+  let bytes = (content_len as u64).to_be_bytes();
+  let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+  out.write_all(&[tag, 0x80 | (bytes.len() - skip) as u8])?;
+  out.write_all(&bytes[skip..])
+}
+
+/// Writes an explicitly tagged primitive element
+pub(crate) fn write_primitive<S: Sink>(out: &mut S, explicit_tag: u8, tag: u8, content: &[u8]) -> Result<(), S::Error> {
+  write_header(out, explicit_tag, tlv_len(content.len()))?;
+  write_header(out, tag, content.len())?;
+  out.write_all(content)
+}
+
+/// Writes the headers of an explicitly tagged sequence with `content_len` bytes of content
+pub(crate) fn write_sequence_header<S: Sink>(out: &mut S, explicit_tag: u8, content_len: usize) -> Result<(), S::Error> {
+  write_header(out, explicit_tag, tlv_len(content_len))?;
+  write_header(out, 0x30, content_len)
+}
+
+/// Writes the encoding of `value`, taking lengths and decimals from `layout` as collected by `collect_layout`
+fn write_value<V: GtvValue, S: Sink>(value: &V, layout: &Layout, cursor: &mut Cursor, out: &mut S) -> Result<(), S::Error> {
+  match value.element() {
+    Element::Null => write_primitive(out, 0xa0, 0x05, &[]),
+    Element::Integer(val) => write_primitive(out, 0xa3, 0x02, &val.to_be_bytes()[8 - integer_content_len(val)..]),
+    Element::BigInteger(val) => write_primitive(out, 0xa6, 0x02, &val.to_signed_bytes_be()),
+    Element::Decimal(_) => {
+      cursor.decimal += 1;
+      write_primitive(out, 0xa2, 0x0c, layout.decimals[cursor.decimal - 1].as_bytes())
+    }
+    Element::Text(val) => write_primitive(out, 0xa2, 0x0c, val.as_bytes()),
+    Element::ByteArray(val) => write_primitive(out, 0xa1, 0x04, val),
+    Element::Array(val) => {
+      write_sequence_header(out, 0xa5, layout.sizes[cursor.size])?;
+      cursor.size += 1;
+      for value in val {
+        write_value(value, layout, cursor, out)?;
+      }
+      Ok(())
+    }
+    Element::Dict(val) => {
+      let content_len = layout.sizes[cursor.size];
+      cursor.size += 1;
+      write_entries(val.iter().map(|(key, value)| (key.as_ref(), value)), content_len, layout, cursor, out)
+    }
+  }
+}
+
+/// Writes a dictionary from entries whose layout `collect_entries` collected
+fn write_entries<'a, V: GtvValue + 'a, S: Sink>(
+  entries: impl Iterator<Item = (&'a str, &'a V)>,
+  content_len: usize,
+  layout: &Layout,
+  cursor: &mut Cursor,
+  out: &mut S,
+) -> Result<(), S::Error> {
+  write_sequence_header(out, 0xa4, content_len)?;
+  for (key, value) in entries {
+    let value_len = layout.sizes[cursor.size];
+    cursor.size += 1;
+    write_header(out, 0x30, tlv_len(key.len()) + value_len)?;
+    write_header(out, 0x0c, key.len())?;
+    out.write_all(key.as_bytes())?;
+    write_value(value, layout, cursor, out)?;
+  }
+  Ok(())
+}
+
+/// A value with its `Layout`, ready to be written
+struct SizedValue<'a, V> {
+  value: &'a V,
+  layout: Layout,
+  len: usize,
+}
+
+impl<'a, V: GtvValue> SizedValue<'a, V> {
+  fn new(value: &'a V) -> Self {
+    let mut layout = Layout::default();
+    let len = collect_layout(value, &mut layout);
+    SizedValue { value, layout, len }
+  }
+
+  fn write<S: Sink>(&self, out: &mut S) -> Result<(), S::Error> {
+    write_value(self.value, &self.layout, &mut Cursor::default(), out)
+  }
+}
+
+/// The arguments of an operation, where named arguments form the single dictionary argument GTX expects
+enum Arguments<'a> {
+  List(&'a [Params]),
+  SharedList(&'a [SharedParams]),
+  /// Entries sorted by name, with the content length of the dictionary
+  Dict(BTreeMap<&'a str, &'a Params>, usize),
+}
+
+/// An operation `[name, [args...]]` whose layout has been collected
+struct SizedOperation<'a> {
+  name: &'a str,
+  args: Arguments<'a>,
+  args_len: usize,
+}
+
+impl<'a> SizedOperation<'a> {
+  /// Callers check the operation name first with `check_operation_names`
+  fn new(operation: &'a Operation<'_>, layout: &mut Layout) -> Self {
+    let (args, args_len) = if let Some(list) = &operation.list {
+      (Arguments::List(list), list.iter().map(|value| collect_layout(value, layout)).sum())
+    } else if let Some(list) = &operation.shared_list {
+      (Arguments::SharedList(list), list.iter().map(|value| collect_layout(value, layout)).sum())
+    } else if let Some(dict) = &operation.dict {
+      // Later entries win, as in `dict_argument`
+      let entries: BTreeMap<&str, &Params> = dict.iter().map(|(key, value)| (key.as_ref(), value)).collect();
+      let content_len = collect_entries(entries.iter().map(|(key, value)| (*key, *value)), layout);
+      (Arguments::Dict(entries, content_len), tlv_len(tlv_len(content_len)))
+    } else {
+      (Arguments::List(&[]), 0)
+    };
+    SizedOperation { name: operation.operation_name.as_deref().unwrap_or_default(), args, args_len }
+  }
+
+  fn content_len(&self) -> usize {
+    tlv_len(tlv_len(self.name.len())) + tlv_len(tlv_len(self.args_len))
+  }
+
+  fn len(&self) -> usize {
+    tlv_len(tlv_len(self.content_len()))
+  }
+
+  fn write<S: Sink>(&self, layout: &Layout, cursor: &mut Cursor, out: &mut S) -> Result<(), S::Error> {
+    write_sequence_header(out, 0xa5, self.content_len())?;
+    write_primitive(out, 0xa2, 0x0c, self.name.as_bytes())?;
+    write_sequence_header(out, 0xa5, self.args_len)?;
+    match &self.args {
+      Arguments::List(values) => values.iter().try_for_each(|value| write_value(value, layout, cursor, out)),
+      Arguments::SharedList(values) => values.iter().try_for_each(|value| write_value(value, layout, cursor, out)),
+      Arguments::Dict(entries, content_len) =>
+        write_entries(entries.iter().map(|(key, value)| (*key, *value)), *content_len, layout, cursor, out),
+    }
+  }
+}
+
+/// Returns the content length of an array of byte arrays, such as signers or signatures
+#[cfg(feature = "std")]
+fn byte_arrays_len(values: &[Vec<u8>]) -> usize {
+  values.iter().map(|value| tlv_len(tlv_len(value.len()))).sum()
+}
+
+/// Writes an array of byte arrays
+#[cfg(feature = "std")]
+fn write_byte_arrays<S: Sink>(out: &mut S, values: &[Vec<u8>]) -> Result<(), S::Error> {
+  write_sequence_header(out, 0xa5, byte_arrays_len(values))?;
+  values.iter().try_for_each(|value| write_primitive(out, 0xa1, 0x04, value))
+}
+
+/// The transaction body `[blockchain_rid, [operations...], [signers...]]`, ready to be written
+#[cfg(feature = "std")]
+struct SizedTxBody<'a> {
+  blockchain_rid: &'a [u8],
+  operations: Vec<SizedOperation<'a>>,
+  operations_len: usize,
+  signers: &'a [Vec<u8>],
+  layout: Layout,
+}
+
+#[cfg(feature = "std")]
+impl<'a> SizedTxBody<'a> {
+  fn new(tx: &'a Transaction<'_>) -> Self {
+    let mut layout = Layout::default();
+    let operations: Vec<_> = tx.operations.iter().flatten()
+      .map(|operation| SizedOperation::new(operation, &mut layout))
+      .collect();
+    let operations_len = operations.iter().map(SizedOperation::len).sum();
+    SizedTxBody {
+      blockchain_rid: &tx.blockchain_rid,
+      operations,
+      operations_len,
+      signers: tx.signers.as_deref().unwrap_or_default(),
+      layout,
+    }
+  }
+
+  fn content_len(&self) -> usize {
+    tlv_len(tlv_len(self.blockchain_rid.len())) + tlv_len(tlv_len(self.operations_len)) + tlv_len(tlv_len(byte_arrays_len(self.signers)))
+  }
+
+  fn len(&self) -> usize {
+    tlv_len(tlv_len(self.content_len()))
+  }
+
+  fn write<S: Sink>(&self, out: &mut S) -> Result<(), S::Error> {
+    let mut cursor = Cursor::default();
+    write_sequence_header(out, 0xa5, self.content_len())?;
+    write_primitive(out, 0xa1, 0x04, self.blockchain_rid)?;
+    write_sequence_header(out, 0xa5, self.operations_len)?;
+    self.operations.iter().try_for_each(|operation| operation.write(&self.layout, &mut cursor, out))?;
+    write_byte_arrays(out, self.signers)
+  }
+}
+
+/// Writes `len` bytes with `write` into a vector allocated once up front
+fn write_to_vec(len: usize, write: impl FnOnce(&mut Vec<u8>) -> Result<(), core::convert::Infallible>) -> Result<Vec<u8>, EncodeError> {
+  let mut buffer = Vec::new();
+  buffer.try_reserve_exact(len).map_err(|_| EncodeError::Allocation)?;
+  write(&mut buffer).expect("writing to a Vec does not fail");
+  debug_assert_eq!(buffer.len(), len);
+  Ok(buffer)
+}
+
+/// Appends the GTV encoding of a value to a buffer
+/// 
+/// Produces the same bytes as `encode_value`, but lets hot paths reuse one buffer
+/// across encodings: clear it between values instead of allocating a new one.
+/// 
+/// # Arguments
+/// 
+/// * `value` - The value to encode
+/// * `buffer` - The buffer to append to
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use postchain_client::{encoding::gtv::{encode_value, encode_value_into}, utils::operation::Params};
+/// let mut buffer = Vec::new();
+/// for value in [Params::Integer(1), Params::Text("two".to_string())] {
+///     buffer.clear();
+///     encode_value_into(&value, &mut buffer);
+///     assert_eq!(buffer, encode_value(&value).unwrap());
+/// }
+/// ```
+pub fn encode_value_into(value: &Params, buffer: &mut Vec<u8>) {
+  let value = SizedValue::new(value);
+  buffer.reserve(value.len);
+  value.write(buffer).expect("writing to a Vec does not fail");
+}
+
+/// Writes the GTV encoding of a value to an `io::Write` sink, such as a file or socket
+/// 
+/// Produces the same bytes as `encode_value` without building them in memory first.
+/// Wrap unbuffered sinks in `std::io::BufWriter`, since the encoding is written in small pieces.
+/// 
+/// # Arguments
+/// 
+/// * `value` - The value to encode
+/// * `writer` - The sink to write to
+/// 
+/// # Errors
+/// 
+/// Returns the first error of the sink
+#[cfg(feature = "std")]
+pub fn encode_value_to_writer<W: std::io::Write>(value: &Params, writer: &mut W) -> std::io::Result<()> {
+  SizedValue::new(value).write(&mut IoSink(writer))
+}
+
+/// Writes the GTV encoding of a `Params` or `SharedParams` value, for the Merkle hasher
+pub(crate) fn write_encoded<V: GtvValue, S: Sink>(value: &V, writer: &mut S) -> Result<(), S::Error> {
+  SizedValue::new(value).write(writer)
+}
+
+/// Number of content bytes `explain` shows per primitive element
+const EXPLAIN_BYTES_LIMIT: usize = 16;
+
+/// Number of characters `explain` shows per text
+const EXPLAIN_TEXT_LIMIT: usize = 64;
+
+/// Returns the GTV name of a tag byte, or the ASN.1 name of a universal tag used inside one
+fn tag_name(tag: u8) -> Option<&'static str> {
+  Some(match tag {
+    0xa0 => "NULL",
+    0xa1 => "BYTE_ARRAY",
+    0xa2 => "STRING",
+    0xa3 => "INTEGER",
+    0xa4 => "DICT",
+    0xa5 => "ARRAY",
+    0xa6 => "BIG_INTEGER",
+    0x02 => "INTEGER",
+    0x04 => "OCTET STRING",
+    0x05 => "NULL",
+    0x0c => "UTF8String",
+    0x30 => "SEQUENCE",
+    _ => return None,
+  })
+}
+
+/// Describes the content of a primitive element
+fn describe_primitive(tag: u8, content: &[u8]) -> String {
+  match tag {
+    0x02 => num_bigint::BigInt::from_signed_bytes_be(content).to_string(),
+    0x05 if content.is_empty() => "null".to_string(),
+    0x0c => match core::str::from_utf8(content) {
+      Ok(text) if text.chars().count() > EXPLAIN_TEXT_LIMIT => {
+        let head: String = text.chars().take(EXPLAIN_TEXT_LIMIT).collect();
+        format!("{:?}... ({} chars)", head, text.chars().count())
+      }
+      Ok(text) => format!("{:?}", text),
+      Err(_) => "invalid UTF-8".to_string(),
+    },
+    _ => format!("{} bytes", content.len()),
+  }
+}
+
+/// Appends a line per element of `data` to `out`, recursing into constructed elements
+/// 
+/// # Returns
+/// 
+/// * `Result<(), String>` - An error describing the first element that could not be read
+fn explain_elements(data: &[u8], base: usize, indent: usize, out: &mut String) -> Result<(), String> {
+  use core::fmt::Write;
+
+  let mut offset = 0;
+  while offset < data.len() {
+    let tag = data[offset];
+    let (length, header_len) = match data.get(offset + 1) {
+      None => return Err(format!("{:04x}: missing length", base + offset + 1)),
+      Some(&first) if first < 0x80 => (first as usize, 2),
+      Some(&first) => {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+          return Err(format!("{:04x}: unsupported length form {:02x}", base + offset + 1, first));
+        }
+        let bytes = data.get(offset + 2..offset + 2 + count)
+          .ok_or_else(|| format!("{:04x}: truncated length", base + offset + 1))?;
+        (bytes.iter().fold(0usize, |acc, byte| (acc << 8) | *byte as usize), 2 + count)
+      }
+    };
+    let content_start = offset + header_len;
+    let remaining = data.len() - content_start;
+    if length > remaining {
+      return Err(format!("{:04x}: length {} exceeds the {} remaining bytes", base + offset, length, remaining));
+    }
+    let content = &data[content_start..content_start + length];
+    let name = tag_name(tag).map_or_else(|| format!("unknown tag {:02x}", tag), str::to_string);
+    let header = hex::encode(&data[offset..content_start]);
+
+    if tag & 0x20 != 0 {
+      let _ = writeln!(out, "{:04x}  {:indent$}{}  {} ({} bytes)", base + offset, "", header, name, length, indent = indent * 2);
+      explain_elements(content, base + content_start, indent + 1, out)?;
+    } else {
+      let mut bytes = header;
+      if !content.is_empty() {
+        bytes.push(' ');
+        bytes.push_str(&hex::encode(&content[..content.len().min(EXPLAIN_BYTES_LIMIT)]));
+      }
+      if content.len() > EXPLAIN_BYTES_LIMIT {
+        bytes.push_str("...");
+      }
+      let _ = writeln!(out, "{:04x}  {:indent$}{}  {} {}", base + offset, "", bytes, name, describe_primitive(tag, content), indent = indent * 2);
+    }
+    offset = content_start + length;
+  }
+  Ok(())
+}
+
+/// Renders GTV bytes as an annotated dump, one line per element with its offset, tag,
+/// length, raw bytes and decoded value
+/// 
+/// Meant for comparing encodings with other clients. Malformed input is rendered up to
+/// the first element that cannot be read, followed by an error line, and the result of
+/// `decode` is reported when it fails.
+/// 
+/// # Arguments
+/// 
+/// * `data` - Byte slice containing GTV data
+/// 
+/// # Returns
+/// 
+/// * `String` - The annotated dump
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use postchain_client::{encoding::gtv::{encode_value, explain}, utils::operation::Params};
+/// let encoded = encode_value(&Params::Array(vec![Params::Text("foo".to_string())])).unwrap();
+/// assert_eq!(explain(&encoded), "\
+/// 0000  a509  ARRAY (9 bytes)
+/// 0002    3007  SEQUENCE (7 bytes)
+/// 0004      a205  STRING (5 bytes)
+/// 0006        0c03 666f6f  UTF8String \"foo\"
+/// ");
+/// ```
+pub fn explain(data: &[u8]) -> String {
+  let mut out = String::new();
+  if let Err(error) = explain_elements(data, 0, 0, &mut out) {
+    out.push_str(&format!("error at {}\n", error));
+  } else if let Err(error) = decode(data) {
+    out.push_str(&format!("invalid GTV: {}\n", error));
+  }
+  out
+}
+
+/// Where and why GTV bytes could not be decoded, as returned by `decode_diagnosed`
+#[derive(Debug, PartialEq)]
+pub struct DecodeDiagnostic {
+  /// Offset of the first byte that does not fit, or the length of the data when it ends early
+  pub offset: usize,
+  /// What the decoder expected at `offset`, such as `UTF8String (0c)`
+  pub expected: String,
+  /// The byte found at `offset`, or `None` when the data ends there
+  pub found: Option<u8>,
+  /// The error `decode` reported
+  pub error: ParseError,
+}
+
+impl core::fmt::Display for DecodeDiagnostic {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "at byte {}: expected {}, found ", self.offset, self.expected)?;
+    match self.found {
+      Some(byte) => write!(f, "{:02x}", byte)?,
+      None => write!(f, "end of data")?,
+    }
+    write!(f, " ({})", self.error)
+  }
+}
+
+impl core::error::Error for DecodeDiagnostic {}
+
+/// The first place a `Locator` found that does not follow the GTV grammar
+struct Fault {
+  offset: usize,
+  expected: String,
+  found: Option<u8>,
+}
+
+/// Walks GTV bytes element by element, keeping track of offsets, to find where they break
+struct Locator<'a> {
+  data: &'a [u8],
+}
+
+impl Locator<'_> {
+  fn fault(&self, offset: usize, expected: impl Into<String>) -> Fault {
+    Fault { offset, expected: expected.into(), found: self.data.get(offset).copied() }
+  }
+
+  /// Reads the header of an element with tag `tag` at `offset` that must end by `end`
+  /// 
+  /// # Returns
+  /// 
+  /// * `Result<(usize, usize), Fault>` - The start and end offsets of the content
+  fn header(&self, offset: usize, end: usize, tag: u8) -> Result<(usize, usize), Fault> {
+    let expected = tag_name(tag).map_or_else(|| format!("tag {:02x}", tag), |name| format!("{} ({:02x})", name, tag));
+    if offset >= end || self.data[offset] != tag {
+      return Err(Fault { offset, expected, found: self.data[..end].get(offset).copied() });
+    }
+    let (length, content_start) = match self.data[..end].get(offset + 1) {
+      None => return Err(self.fault(end, "length")),
+      Some(&first) if first < 0x80 => (first as usize, offset + 2),
+      Some(&first) => {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 8 {
+          return Err(self.fault(offset + 1, "definite length"));
         }
-        Choice::DICT(seq) => {
-          let res: Result<Params, ParseError> = seq.parse(|parser| {
-            let mut btree_map_new: BTreeMap<String, Params> = BTreeMap::new();
-            decode_sequence_dict(parser, &mut btree_map_new);
-            Ok(Params::Dict(btree_map_new))
-          });
-          res.unwrap()
+        let bytes = self.data[..end].get(offset + 2..offset + 2 + count).ok_or_else(|| self.fault(end, "length"))?;
+        if bytes[0] == 0 || (count == 1 && bytes[0] < 0x80) {
+          return Err(self.fault(offset + 1, "minimal length"));
         }
-        _ =>
-          decode_simple(val)
+        let length = bytes.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+        (usize::try_from(length).unwrap_or(usize::MAX), offset + 2 + count)
+      }
     };
-    vec_array.push(op_val);
+    if length > end - content_start {
+      return Err(Fault { offset: end, expected: format!("{} content bytes", length), found: None });
+    }
+    Ok((content_start, content_start + length))
   }
-}
 
-/// Decodes a sequence of key-value pairs into a dictionary
-/// 
-/// # Arguments
-/// 
-/// * `parser` - The ASN.1 parser to read from
-/// * `btreemap` - BTreeMap to store the decoded key-value pairs
-fn decode_sequence_dict<'a>(parser: &mut asn1::Parser<'a>, btreemap: &mut BTreeMap<String, Params>) {
-  while let Ok(seq) = parser.read_element::<asn1::Sequence>() {
-      let res: Result<(&'a str, Params), ParseError> = seq.parse(|parser| {
-        let key = parser.read_element::<asn1::Utf8String>()?;
-        let val = Choice::parse(parser).unwrap();
-
-        let op_val = match val {
-          Choice::DICT(seq) => {
-            let res: Result<Params, ParseError> = seq.parse(|parser| {
-              let mut btree_map_new: BTreeMap<String, Params> = BTreeMap::new();
-              decode_sequence_dict(parser, &mut btree_map_new);
-              Ok(Params::Dict(btree_map_new))
-            });
-            res.unwrap()
-          }
-          Choice::ARRAY(seq) => {
-            let res: Result<Params, ParseError> = seq.parse(|parser| {
-              let mut vect_array_new: Vec<Params> = Vec::new();
-              decode_sequence_array(parser, &mut vect_array_new);
-              Ok(Params::Array(vect_array_new))
-            });
-            res.unwrap()
-          },
-          _ => 
-            decode_simple(val)      
-        };
+  /// Checks that a primitive element follows at `offset`, with `check` validating its content
+  fn primitive(&self, offset: usize, end: usize, tag: u8, check: impl Fn(&[u8], usize) -> Result<(), Fault>)
+    -> Result<usize, Fault> {
+    let (start, content_end) = self.header(offset, end, tag)?;
+    check(&self.data[start..content_end], start)?;
+    Ok(content_end)
+  }
 
-        Ok((key.as_str(), op_val))
-      });
+  fn text(&self, offset: usize, end: usize) -> Result<usize, Fault> {
+    self.primitive(offset, end, 0x0c, |content, start| match core::str::from_utf8(content) {
+      Ok(_) => Ok(()),
+      Err(error) => Err(self.fault(start + error.valid_up_to(), "UTF-8")),
+    })
+  }
+
+  fn integer(&self, offset: usize, end: usize) -> Result<usize, Fault> {
+    self.primitive(offset, end, 0x02, |content, start| match content {
+      [] => Err(self.fault(start, "integer content")),
+      [0x00, next, ..] if *next < 0x80 => Err(self.fault(start, "minimal integer")),
+      [0xff, next, ..] if *next >= 0x80 => Err(self.fault(start, "minimal integer")),
+      _ => Ok(()),
+    })
+  }
 
-      let (key, value) = res.unwrap();
-      btreemap.insert(key.to_string(), value);
+  /// Checks the GTV element at `offset` and returns the offset after it
+  fn value(&self, offset: usize, end: usize, depth: usize) -> Result<usize, Fault> {
+    let tag = self.data[..end].get(offset).copied();
+    if !matches!(tag, Some(0xa0..=0xa6)) {
+      return Err(Fault { offset, expected: "GTV element (a0-a6)".to_string(), found: tag });
+    }
+    let tag = tag.unwrap_or_default();
+    let (start, content_end) = self.header(offset, end, tag)?;
+    let inner_end = match tag {
+      0xa0 => self.primitive(start, content_end, 0x05, |content, start| {
+        if content.is_empty() { Ok(()) } else { Err(self.fault(start, "empty NULL")) }
+      })?,
+      0xa1 => self.primitive(start, content_end, 0x04, |_, _| Ok(()))?,
+      0xa2 => self.text(start, content_end)?,
+      0xa3 | 0xa6 => self.integer(start, content_end)?,
+      _ => {
+        if depth >= DEFAULT_MAX_DEPTH {
+          return Err(self.fault(offset, format!("nesting within {} levels", DEFAULT_MAX_DEPTH)));
+        }
+        let (mut position, sequence_end) = self.header(start, content_end, 0x30)?;
+        while position < sequence_end {
+          position = if tag == 0xa5 {
+            self.value(position, sequence_end, depth + 1)?
+          } else {
+            let (entry_start, entry_end) = self.header(position, sequence_end, 0x30)?;
+            let value_start = self.text(entry_start, entry_end)?;
+            let value_end = self.value(value_start, entry_end, depth + 1)?;
+            if value_end != entry_end {
+              return Err(self.fault(value_end, "end of dictionary entry"));
+            }
+            entry_end
+          };
+        }
+        sequence_end
+      }
+    };
+    if inner_end != content_end {
+      return Err(self.fault(inner_end, "end of element"));
+    }
+    Ok(content_end)
   }
 }
 
-/// Decodes a byte slice into a GTV value
+/// Decodes a byte slice into a GTV value, reporting where malformed input breaks
+/// 
+/// Decodes like `decode`; on failure the bytes are walked again to find the offset of the
+/// first byte that does not fit, with the tag expected there and the byte found instead
 /// 
 /// # Arguments
 /// 
@@ -396,80 +2005,206 @@ fn decode_sequence_dict<'a>(parser: &mut asn1::Parser<'a>, btreemap: &mut BTreeM
 /// 
 /// # Returns
 /// 
-/// * `Result<Params, ParseError>` - The decoded value or an error if decoding fails
-pub fn decode<'a>(data: &'a [u8]) -> Result<Params, ParseError> {
-  let tag = asn1::Tag::from_bytes(data).unwrap();
-  let tag_num = tag.0.as_u8().unwrap() & 0x1f;
-
-  if vec![0, 1, 2, 3, 6].contains(&tag_num) {
-    asn1::parse(data, |d| {
-        let res_choice = Choice::parse(d);
-        match res_choice {
-            Ok(val) => Ok(decode_simple(val)),
-            Err(error) => Err(error),
-        }
-    })
-  } else {
-    if tag_num == 4 {
-      let result = asn1::parse_single::<asn1::Explicit<asn1::Sequence, 4>>(data).unwrap();
-      result.into_inner().parse(|parser| {
-        let mut btree_map_new: BTreeMap<String, Params> = BTreeMap::new();
-        decode_sequence_dict(parser, &mut btree_map_new);
-        Ok(Params::Dict(btree_map_new))
-      })
-    } else if tag_num == 5 {
-      let result = asn1::parse_single::<asn1::Explicit<asn1::Sequence, 5>>(data).unwrap();
-      result.into_inner().parse(|parser|{
-        let mut vect_array_new: Vec<Params> = Vec::new();
-        decode_sequence_array(parser, &mut vect_array_new);
-        Ok(Params::Array(vect_array_new))
-      })
-    } else {
-      Ok(Params::Null)
+/// * `Result<Params, DecodeDiagnostic>` - The decoded value or where and why decoding failed
+/// 
+/// # Example
+/// 
+/// ```
+/// # use postchain_client::encoding::gtv;
+/// // An array whose only item is a text with an OCTET STRING inside
+/// let error = gtv::decode_diagnosed(&hex::decode("a5073005a20304016f").unwrap()).unwrap_err();
+/// assert_eq!((error.offset, error.expected.as_str(), error.found), (6, "UTF8String (0c)", Some(0x04)));
+/// ```
+pub fn decode_diagnosed(data: &[u8]) -> Result<Params, DecodeDiagnostic> {
+  decode(data).map_err(|error| {
+    let locator = Locator { data };
+    let fault = match locator.value(0, data.len(), 0) {
+      Err(fault) => fault,
+      Ok(end) if end < data.len() => locator.fault(end, "end of data"),
+      Ok(_) => locator.fault(0, "valid GTV"),
+    };
+    DecodeDiagnostic { offset: fault.offset, expected: fault.expected, found: fault.found, error }
+  })
+}
+
+/// Errors that can occur in `diff`
+#[derive(Debug, PartialEq)]
+pub enum DiffError {
+  /// The first blob is not valid GTV
+  A(DecodeDiagnostic),
+  /// The second blob is not valid GTV
+  B(DecodeDiagnostic),
+}
+
+impl core::fmt::Display for DiffError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      DiffError::A(error) => write!(f, "Invalid first GTV blob {}", error),
+      DiffError::B(error) => write!(f, "Invalid second GTV blob {}", error),
     }
   }
 }
 
-/// Decodes a transaction from a byte slice
+impl core::error::Error for DiffError {}
+
+/// Decodes two GTV blobs and reports their structural differences
+/// 
+/// Meant for debugging encoding mismatches against other clients. Differences are reported
+/// as by `Params::diff`, with `a` as the old and `b` as the new value. Blobs that decode to
+/// the same value but differ in bytes, such as dictionary entries out of order, give no
+/// differences; `decode_canonical` and `explain` show those
 /// 
 /// # Arguments
 /// 
-/// * `data` - Byte slice containing the encoded transaction
+/// * `a` - The first encoded value, such as this client's output
+/// * `b` - The second encoded value, such as the Kotlin client's output
 /// 
 /// # Returns
 /// 
-/// * `Result<Params, ParseError>` - The decoded transaction or an error if decoding fails
-pub fn decode_tx<'a>(data: &'a [u8]) -> Result<Params, ParseError> {
-  decode(data)
+/// * `Result<Vec<DiffEntry>, DiffError>` - The differences in path order, or where a blob is malformed
+/// 
+/// # Example
+/// 
+/// ```
+/// # use postchain_client::{dict, encoding::gtv};
+/// let ours = gtv::encode_value(&dict!{"amount" => 10, "memo" => "a"}).unwrap();
+/// let theirs = gtv::encode_value(&dict!{"amount" => 11, "fee" => 1, "memo" => "a"}).unwrap();
+/// 
+/// let report: Vec<String> = gtv::diff(&ours, &theirs).unwrap().iter().map(ToString::to_string).collect();
+/// assert_eq!(report, ["~ amount: Integer(10) -> Integer(11)", "+ fee: Integer(1)"]);
+/// ```
+pub fn diff(a: &[u8], b: &[u8]) -> Result<Vec<DiffEntry>, DiffError> {
+  let a = decode_diagnosed(a).map_err(DiffError::A)?;
+  let b = decode_diagnosed(b).map_err(DiffError::B)?;
+  Ok(a.diff(&b))
 }
 
-/// Encodes a single GTV value into a byte vector
+/// Key of the JSON object that tags a byte array in tagged GTV JSON
+pub(crate) const JSON_BYTES_TAG: &str = "$bytes";
+
+/// Key of the JSON object that tags a big integer in tagged GTV JSON
+pub(crate) const JSON_BIG_INTEGER_TAG: &str = "$bigint";
+
+/// Converts a value to tagged GTV JSON, which keeps every GTV type distinct
+/// 
+/// Tagged GTV JSON is this crate's own format, not the JSON mapping of Postchain or its
+/// other clients, which do not read the `$bytes` and `$bigint` tags or the `$` escapes.
+/// Use it to store or exchange values between programs built on this crate.
+/// 
+/// Unlike `Params::to_json_value`, which is meant for display, the result converts back
+/// to the same GTV value with `from_tagged_json`:
+/// 
+/// * null, integers, texts and arrays map to their JSON counterparts
+/// * byte arrays become `{"$bytes": "<lowercase hex>"}`
+/// * big integers become `{"$bigint": "<decimal digits>"}`, so JavaScript does not round them
+/// * dictionaries become objects with sorted keys; keys starting with `$` get one more `$`
+/// * booleans and decimals become integers and texts, as in the binary encoding
 /// 
 /// # Arguments
 /// 
-/// * `value` - The value to encode
+/// * `value` - The value to convert
 /// 
 /// # Returns
 /// 
-/// * `Vec<u8>` - The encoded value as a byte vector
-pub fn encode_value(value: &Params) -> Vec<u8> {
-  asn1::write(|writer| {
-      value.to_writer(writer)?;
-      Ok(())
-  }).unwrap()
+/// * `serde_json::Value` - The tagged JSON representation
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use postchain_client::{dict, encoding::gtv::{from_tagged_json, to_tagged_json}, utils::operation::Params};
+/// let value = dict!{"key" => vec![0xabu8, 0x01], "$id" => 1};
+/// let json = to_tagged_json(&value);
+/// 
+/// assert_eq!(json.to_string(), r#"{"$$id":1,"key":{"$bytes":"ab01"}}"#);
+/// assert_eq!(from_tagged_json(&json), Ok(value));
+/// ```
+pub fn to_tagged_json(value: &Params) -> serde_json::Value {
+  use serde_json::Value;
+
+  match value {
+    Params::Null => Value::Null,
+    Params::Boolean(val) => Value::from(*val as i64),
+    Params::Integer(val) => Value::from(*val),
+    Params::BigInteger(val) => serde_json::json!({ JSON_BIG_INTEGER_TAG: val.to_string() }),
+    Params::Decimal(val) => Value::String(val.to_string()),
+    Params::Text(val) => Value::String(val.clone()),
+    Params::ByteArray(val) => serde_json::json!({ JSON_BYTES_TAG: hex::encode(val) }),
+    Params::Array(val) => Value::Array(val.iter().map(to_tagged_json).collect()),
+    Params::Dict(val) => Value::Object(val.iter()
+      .map(|(key, value)| {
+        let key = if key.starts_with('$') { format!("${}", key) } else { key.clone() };
+        (key, to_tagged_json(value))
+      })
+      .collect()),
+  }
 }
 
-/// Encodes a GTV value and returns it as a hexadecimal string
+/// Converts tagged GTV JSON, as produced by `to_tagged_json`, to a value
+/// 
+/// JSON booleans are accepted as the integers 0 and 1, and integers beyond the 64-bit range
+/// as big integers.
 /// 
 /// # Arguments
 /// 
-/// * `value` - The value to encode
+/// * `json` - The tagged JSON representation
 /// 
 /// # Returns
 /// 
-/// * `String` - Hexadecimal representation of the encoded value
-pub fn encode_value_hex_encode(value: &Params) -> String {
-  hex::encode(encode_value(value))
+/// * `Result<Params, String>` - The value, or an error naming the offending path
+/// 
+/// # Errors
+/// 
+/// Fails on fractional numbers, which GTV cannot represent, on tags with invalid hex or
+/// digits, and on object keys starting with a single `$` that are not tags
+pub fn from_tagged_json(json: &serde_json::Value) -> Result<Params, String> {
+  from_tagged_json_at(json, "$")
+}
+
+fn from_tagged_json_at(json: &serde_json::Value, path: &str) -> Result<Params, String> {
+  use serde_json::Value;
+
+  match json {
+    Value::Null => Ok(Params::Null),
+    Value::Bool(val) => Ok(Params::Integer(*val as i64)),
+    Value::Number(number) => match number.as_i64() {
+      Some(val) => Ok(Params::Integer(val)),
+      None => num_bigint::BigInt::parse_bytes(number.to_string().as_bytes(), 10)
+        .map(Params::BigInteger)
+        .ok_or_else(|| format!("{}: {} is not an integer", path, number)),
+    },
+    Value::String(val) => Ok(Params::Text(val.clone())),
+    Value::Array(values) => values.iter().enumerate()
+      .map(|(index, value)| from_tagged_json_at(value, &format!("{}[{}]", path, index)))
+      .collect::<Result<_, _>>()
+      .map(Params::Array),
+    Value::Object(object) => {
+      if let (1, Some(value)) = (object.len(), object.get(JSON_BYTES_TAG)) {
+        return value.as_str()
+          .and_then(|hex_str| hex::decode(hex_str).ok())
+          .map(Params::ByteArray)
+          .ok_or_else(|| format!("{}: {} must be a hex string", path, JSON_BYTES_TAG));
+      }
+      if let (1, Some(value)) = (object.len(), object.get(JSON_BIG_INTEGER_TAG)) {
+        return value.as_str()
+          .filter(|digits| !digits.is_empty() && digits.strip_prefix('-').unwrap_or(digits).bytes().all(|b| b.is_ascii_digit()))
+          .and_then(|digits| num_bigint::BigInt::parse_bytes(digits.as_bytes(), 10))
+          .map(Params::BigInteger)
+          .ok_or_else(|| format!("{}: {} must be a string of decimal digits", path, JSON_BIG_INTEGER_TAG));
+      }
+      object.iter()
+        .map(|(key, value)| {
+          let key = match key.strip_prefix('$') {
+            Some(escaped) if escaped.starts_with('$') => escaped.to_string(),
+            Some(_) => return Err(format!("{}: unknown tag {:?}", path, key)),
+            None => key.clone(),
+          };
+          let value = from_tagged_json_at(value, &format!("{}.{}", path, key))?;
+          Ok((key, value))
+        })
+        .collect::<Result<_, _>>()
+        .map(Params::Dict)
+    }
+  }
 }
 
 /// Converts a transaction into a GTV representation for visualization
@@ -481,6 +2216,7 @@ pub fn encode_value_hex_encode(value: &Params) -> String {
 /// # Returns
 /// 
 /// * `Params` - GTV representation of the transaction
+#[cfg(feature = "std")]
 pub fn to_draw_gtx<'a>(tx: &'a Transaction<'a>) -> Params {
   let mut signers: Vec<Params> = vec![];
   let mut operations:Vec<Params> = vec![];
@@ -491,21 +2227,21 @@ pub fn to_draw_gtx<'a>(tx: &'a Transaction<'a>) -> Params {
     }
   }
 
-  for op in &tx.operations.clone().unwrap() {
+  for op in tx.operations.iter().flatten() {
     let mut op_args: Vec<Params> = vec![];
 
     if let Some(op_list) = &op.list {
       for arg in op_list {
         op_args.push(arg.clone());
       }
+    } else if let Some(op_list) = &op.shared_list {
+      op_args.extend(op_list.iter().map(Params::from));
     } else if let Some(op_dict) = &op.dict {
-      for (_key, value) in op_dict {
-        op_args.push(value.clone());
-      }
+      op_args.push(dict_argument(op_dict));
     }
 
     operations.push(Params::Array(vec![
-      Params::Text(op.operation_name.unwrap().to_string()),
+      Params::Text(op.operation_name.as_deref().unwrap_or_default().to_string()),
       Params::Array(op_args)
     ]));
   }
@@ -524,14 +2260,16 @@ pub fn to_draw_gtx<'a>(tx: &'a Transaction<'a>) -> Params {
 /// 
 /// * `query_args` - Optional query arguments to test
 /// * `expected_value` - Expected hexadecimal string after encoding
-fn assert_roundtrips<'a>(
-  query_args: Option<&'a mut Vec<(&str, Params)>>,
+fn assert_roundtrips(
+  query_args: Option<&mut Vec<(&str, Params)>>,
   expected_value: &str) {
-    let result = asn1::write(|writer| {
-      encode_body(writer, &query_args)?;
-      Ok(())
-    });
-    assert_eq!(hex::encode(result.unwrap()), expected_value);
+    let args = query_args.as_deref().map_or(&[][..], Vec::as_slice);
+    let entries = || args.iter().map(|(key, value)| (*key, value));
+    let mut layout = Layout::default();
+    let content_len = collect_entries(entries(), &mut layout);
+    let mut result = Vec::new();
+    write_entries(entries(), content_len, &layout, &mut Cursor::default(), &mut result).unwrap();
+    assert_eq!(hex::encode(result), expected_value);
 }
 
 #[allow(dead_code)]
@@ -542,11 +2280,11 @@ fn assert_roundtrips<'a>(
 /// * `value` - Value to test
 /// * `expected_decode` - Expected decoded value
 /// * `expected_value` - Expected hexadecimal string after encoding
-fn assert_roundtrips_value<'a>(
+fn assert_roundtrips_value(
   value: &Params,
   expected_decode: &Params,
   expected_value: &str) {
-    let encode_result = encode_value(&value);
+    let encode_result = encode_value(value).unwrap();
     assert_eq!(expected_value, hex::encode(encode_result.clone()));
 
     let decode_result = decode(&encode_result).unwrap();
@@ -821,7 +2559,7 @@ fn gtv_test_simple_integer() {
 
 #[test]
 fn gtv_test_simple_big_integer() {
-  assert_roundtrips_simple(Params::BigInteger(num_bigint::BigInt::from(1234567890123456789 as i128)), "a60a0208112210f47de98115");
+  assert_roundtrips_simple(Params::BigInteger(num_bigint::BigInt::from(1234567890123456789_i128)), "a60a0208112210f47de98115");
 }
 
 #[test]
@@ -877,7 +2615,7 @@ fn gtv_test_simple_null_decode() {
 #[test]
 fn gtv_test_simple_big_integer_decode() {
   assert_roundtrips_simple_decode("a60a0208112210f47de98115", 
-    Params::BigInteger(num_bigint::BigInt::from(1234567890123456789 as i128)));
+    Params::BigInteger(num_bigint::BigInt::from(1234567890123456789_i128)));
 }
 
 #[test]
@@ -958,7 +2696,7 @@ fn gtv_test_sequence_complex_mix_dict_array_decode() {
   data_btreemap.insert("dict".to_string(), Params::Dict(dict_in));
   data_btreemap.insert("array".to_string(), Params::Array(vec![
     Params::Text("test array".to_string()),
-    Params::BigInteger(num_bigint::BigInt::from(123456 as i128)),
+    Params::BigInteger(num_bigint::BigInt::from(123456_i128)),
     Params::Array(vec![
       Params::Text("test array 2".to_string())
     ])
@@ -970,4 +2708,762 @@ fn gtv_test_sequence_complex_mix_dict_array_decode() {
     data.to_writer(writer)?; Ok(()) }).unwrap();
 
   assert_eq!(data, decode(result.as_slice()).unwrap());
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_transaction_roundtrip() {
+  let operations = vec![
+    Operation::from_list("set_name", vec![Params::Text("foo".to_string()), Params::Integer(-1)]),
+    Operation::from_list("nop", vec![Params::ByteArray(vec![1, 2, 3])]),
+  ];
+  let tx = Transaction::new(vec![0xab; 32], Some(operations), Some(vec![vec![2; 33]]), Some(vec![vec![3; 64]]));
+
+  let encoded = encode_tx(&tx).unwrap();
+  let decoded = decode_transaction(&encoded).unwrap();
+
+  assert_eq!(encode_tx(&decoded), Ok(encoded.clone()));
+  assert_eq!(decoded.tx_rid().unwrap(), tx.tx_rid().unwrap());
+  assert_eq!(decoded.operations.as_ref().unwrap()[0].name(), Some("set_name"));
+
+  let unsigned = Transaction::new(vec![0xab; 32], Some(vec![]), None, None);
+  let encoded_unsigned = encode_tx(&unsigned).unwrap();
+  let decoded = decode_transaction(&encoded_unsigned).unwrap();
+  assert!(decoded.signers.is_none() && decoded.signatures.is_none());
+
+  assert!(decode_transaction(&encoded[..encoded.len() - 1]).is_err());
+}
+
+#[test]
+fn test_operation_roundtrip() {
+  let operation = Operation::from_list("set_name", vec![Params::Array(vec![Params::Null, Params::Integer(7)])]);
+  let encoded = encode_operation(&operation).unwrap();
+  let decoded = decode_operation(&encoded).unwrap();
+
+  assert_eq!(decoded.name(), Some("set_name"));
+  assert_eq!(encode_operation(&decoded), Ok(encoded));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn gtv_test_operation_with_named_arguments() {
+  let named = Operation::from_dict("setEntityViaStruct", vec![
+    ("string1", Params::Text("foo".to_string())),
+    ("int", Params::Integer(1)),
+  ]);
+  let mut dict = BTreeMap::new();
+  dict.insert("int".to_string(), Params::Integer(1));
+  dict.insert("string1".to_string(), Params::Text("foo".to_string()));
+  let positional = Operation::from_list("setEntityViaStruct", vec![Params::Dict(dict)]);
+
+  assert_eq!(encode_operation(&named), encode_operation(&positional));
+  assert_eq!(decode_operation(&encode_operation(&named).unwrap()).unwrap(), positional);
+
+  let tx = Transaction::new(vec![0; 32], Some(vec![named]), None, None);
+  let encoded = encode_tx(&tx).unwrap();
+  assert_eq!(decode_transaction(&encoded).unwrap().tx_rid().unwrap(), tx.tx_rid().unwrap());
+}
+
+#[test]
+fn gtv_test_encoded_len() {
+  use std::str::FromStr;
+
+  let mut dict = BTreeMap::new();
+  dict.insert("key".to_string(), Params::Array(vec![Params::Null, Params::Boolean(true)]));
+  dict.insert("x".repeat(200), Params::ByteArray(vec![1; 70000]));
+
+  let values = vec![
+    Params::Null,
+    Params::Boolean(false),
+    Params::Integer(0), Params::Integer(127), Params::Integer(128), Params::Integer(-128), Params::Integer(-129),
+    Params::Integer(i64::MAX), Params::Integer(i64::MIN),
+    Params::BigInteger(num_bigint::BigInt::from(i128::MAX)), Params::BigInteger(num_bigint::BigInt::from(i128::MIN)),
+    Params::BigInteger(num_bigint::BigInt::from(u64::MAX)), Params::BigInteger(num_bigint::BigInt::from(0)),
+    Params::Decimal(bigdecimal::BigDecimal::from_str("-12.345").unwrap()),
+    Params::Text("Trái Tim".to_string()), Params::Text("a".repeat(127)), Params::Text("a".repeat(300)),
+    Params::ByteArray(vec![]), Params::ByteArray(vec![0; 256]),
+    Params::Array(vec![]), Params::Dict(BTreeMap::new()),
+    Params::Dict(dict),
+  ];
+
+  for value in values {
+    assert_eq!(value.encoded_len(), encode_value(&value).unwrap().len(), "{:?}", value.kind());
+  }
+}
+
+#[test]
+fn gtv_test_encode_errors() {
+  for value in [num_bigint::BigInt::from(u64::MAX), num_bigint::BigInt::from(1u64 << 63), num_bigint::BigInt::from(1000000000000i64), num_bigint::BigInt::from(128)] {
+    let encoded = encode_value(&Params::BigInteger(value.clone())).unwrap();
+    assert_eq!(decode(&encoded).unwrap(), Params::BigInteger(value));
+  }
+  assert_eq!(encode_value_hex_encode(&Params::BigInteger(num_bigint::BigInt::from(u64::MAX))),
+    Ok("a60b020900ffffffffffffffff".to_string()));
+
+  let unnamed = Operation { list: Some(vec![Params::Null]), ..Default::default() };
+  assert_eq!(encode_operation(&unnamed), Err(EncodeError::MissingOperationName(0)));
+
+  #[cfg(feature = "std")]
+  {
+    let tx = Transaction::new(vec![0; 32], Some(vec![Operation::nop(), unnamed]), None, None);
+    assert_eq!(encode_tx(&tx), Err(EncodeError::MissingOperationName(1)));
+  }
+}
+
+#[test]
+fn gtv_test_decode_malformed_input() {
+  let corpus = [
+    // Empty input and lone tags
+    "", "a0", "a5", "30",
+    // Length longer than the data
+    "a0050500", "a5ff", "a584ffffffff",
+    // Unknown GTV tag and a non-GTV universal tag
+    "a7020500", "0500",
+    // Null with content
+    "a003050100",
+    // Invalid UTF-8 text
+    "a2040c02c328",
+    // Non-minimal integer
+    "a30402020001",
+    // Trailing bytes
+    "a0020500ff", "a0020500a0020500",
+    // Array containing garbage, a truncated element and a bare integer
+    "a503300100", "a5073005a0030500", "a50530030201ff",
+    // Dictionary entry with a non-text key, a missing value and an extra element
+    "a40b30093007040161a0020500", "a407300530030c0161", "a40f300d300b0c0161a0020500a0020500",
+    // Dictionary containing a bare value instead of an entry
+    "a4063004a0020500",
+    // Explicit tag with the wrong inner type
+    "a1030c0161", "a6030c0161",
+  ];
+
+  for input in corpus {
+    let data = hex::decode(input).unwrap();
+    assert!(decode(&data).is_err(), "{} decoded", input);
+    #[cfg(feature = "std")]
+    assert!(decode_transaction(&data).is_err(), "{} decoded as a transaction", input);
+    assert!(decode_operation(&data).is_err(), "{} decoded as an operation", input);
+  }
+
+  let mut dict = BTreeMap::new();
+  dict.insert("key".to_string(), Params::Array(vec![Params::Text("value".to_string()), Params::Integer(-5)]));
+  let valid = encode_value(&Params::Array(vec![Params::Dict(dict), Params::ByteArray(vec![1, 2, 3])])).unwrap();
+  for len in 0..valid.len() {
+    assert!(decode(&valid[..len]).is_err(), "prefix of length {} decoded", len);
+  }
+  assert!(decode(&valid).is_ok());
+
+  let mut dict = BTreeMap::new();
+  dict.insert("a".to_string(), Params::Null);
+  assert_eq!(decode(&hex::decode("a40b300930070c0161a0020500").unwrap()).unwrap(), Params::Dict(dict));
+}
+
+#[test]
+fn gtv_test_decode_max_depth() {
+  fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if content.len() < 0x80 {
+      out.push(content.len() as u8);
+    } else {
+      let len = (content.len() as u32).to_be_bytes();
+      let len = &len[len.iter().position(|b| *b != 0).unwrap()..];
+      out.push(0x80 | len.len() as u8);
+      out.extend_from_slice(len);
+    }
+    out.extend_from_slice(content);
+    out
+  }
+
+  let nested_arrays = |depth: usize| {
+    (0..depth).fold(hex::decode("a0020500").unwrap(), |inner, _| tlv(0xa5, &tlv(0x30, &inner)))
+  };
+
+  assert!(decode(&nested_arrays(DEFAULT_MAX_DEPTH)).is_ok());
+  let error = decode(&nested_arrays(DEFAULT_MAX_DEPTH + 1)).unwrap_err();
+  assert_eq!(error.kind(), asn1::ParseErrorKind::InvalidSize { min: 0, max: DEFAULT_MAX_DEPTH, actual: DEFAULT_MAX_DEPTH + 1 });
+
+  // Far deeper than the stack could handle without the limit
+  assert!(decode(&nested_arrays(10_000)).is_err());
+
+  assert_eq!(decode_with_max_depth(&nested_arrays(2), 2).unwrap(), Params::Array(vec![Params::Array(vec![Params::Null])]));
+  assert!(decode_with_max_depth(&nested_arrays(3), 2).is_err());
+  assert_eq!(decode_with_max_depth(&hex::decode("a0020500").unwrap(), 0).unwrap(), Params::Null);
+
+  let nested_dict = tlv(0xa4, &tlv(0x30, &tlv(0x30, &[hex::decode("0c0161").unwrap(), nested_arrays(1)].concat())));
+  assert!(decode_with_max_depth(&nested_dict, 2).is_ok());
+  assert!(decode_with_max_depth(&nested_dict, 1).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn gtv_test_decode_as() {
+  #[derive(Debug, PartialEq, serde::Deserialize)]
+  struct Entry {
+    key: Vec<u8>,
+    values: Vec<Option<i64>>,
+  }
+
+  let data = encode_value(&crate::gtv_array![crate::dict!{"key" => vec![1u8], "values" => crate::gtv_array![1, Params::Null]}]).unwrap();
+  assert_eq!(decode_as::<Vec<Entry>>(&data), Ok(vec![Entry { key: vec![1], values: vec![Some(1), None] }]));
+
+  let error = decode_as::<Vec<Entry>>(&encode_value(&crate::gtv_array![crate::dict!{"key" => vec![1u8]}]).unwrap()).unwrap_err();
+  assert_eq!(error.to_string(), "Unexpected value at [0]: missing field `values`");
+
+  let error = decode_as::<Vec<Entry>>(&data[..data.len() - 1]).unwrap_err();
+  assert!(matches!(error, DecodeAsError::Decode(_)));
+  assert!(error.to_string().starts_with("Invalid GTV: "));
+}
+
+#[test]
+fn gtv_test_decode_canonical() {
+  let canonical = encode_value(&crate::dict!{"a" => 1, "b" => 2, "c" => crate::gtv_array![num_bigint::BigInt::from(-1), "x"]}).unwrap();
+  assert_eq!(decode_canonical(&canonical).unwrap(), decode(&canonical).unwrap());
+
+  let entry_a = "30080c0161a303020101";
+  let entry_b = "30080c0162a303020102";
+  assert!(decode_canonical(&hex::decode(format!("a4163014{}{}", entry_a, entry_b)).unwrap()).is_ok());
+  let non_canonical = [
+    format!("a4163014{}{}", entry_b, entry_a),
+    format!("a4163014{}{}", entry_a, entry_a),
+  ];
+  for data in non_canonical {
+    let data = hex::decode(data).unwrap();
+    assert!(decode(&data).is_ok());
+    assert_eq!(decode_canonical(&data).unwrap_err().kind(), asn1::ParseErrorKind::InvalidValue);
+  }
+
+  // Non-minimal integers and lengths never decode
+  for data in ["a30402020001", "a381030201010500"] {
+    assert!(decode(&hex::decode(data).unwrap()).is_err());
+    assert!(decode_canonical(&hex::decode(data).unwrap()).is_err());
+  }
+}
+
+#[test]
+fn gtv_test_decode_borrowed() {
+  use std::str::FromStr;
+
+  let mut dict = BTreeMap::new();
+  dict.insert("text".to_string(), Params::Text("Trái Tim".to_string()));
+  dict.insert("bytes".to_string(), Params::ByteArray(vec![1, 2, 3]));
+  dict.insert("nested".to_string(), Params::Array(vec![
+    Params::Null, Params::Integer(-7), Params::BigInteger(num_bigint::BigInt::from(u64::MAX)),
+    Params::BigInteger(num_bigint::BigInt::from(i128::MIN)), Params::Dict(BTreeMap::new()), Params::Array(vec![]),
+  ]));
+  let value = Params::Dict(dict);
+  let encoded = encode_value(&value).unwrap();
+
+  let borrowed = decode_borrowed(&encoded).unwrap();
+  assert_eq!(Params::from(&borrowed), decode(&encoded).unwrap());
+  assert_eq!(Params::from(borrowed.clone()), value);
+
+  let Some(ParamsRef::Text(text)) = borrowed.get("text") else { panic!("Expected text") };
+  assert!(encoded.as_ptr_range().contains(&text.as_ptr()));
+  let Some(ParamsRef::ByteArray(bytes)) = borrowed.get("bytes") else { panic!("Expected byte array") };
+  assert!(encoded.as_ptr_range().contains(&bytes.as_ptr()));
+  assert_eq!(borrowed.get("missing"), None);
+
+  let decimal = encode_value(&Params::Decimal(bigdecimal::BigDecimal::from_str("1.5").unwrap())).unwrap();
+  assert_eq!(decode_borrowed(&decimal).unwrap(), ParamsRef::Text("1.5"));
+
+  for input in ["", "a7020500", "a0020500ff", "a2040c02c328", "a40b30093007040161a0020500"] {
+    assert!(decode_borrowed(&hex::decode(input).unwrap()).is_err(), "{} decoded", input);
+  }
+}
+
+#[test]
+fn gtv_test_encode_value_into() {
+  use std::str::FromStr;
+
+  let mut dict = BTreeMap::new();
+  dict.insert("array".to_string(), Params::Array(vec![
+    Params::Array(vec![]), Params::Dict(BTreeMap::new()), Params::Boolean(true), Params::Integer(i64::MIN),
+  ]));
+  dict.insert("x".repeat(300), Params::ByteArray(vec![7; 70000]));
+  dict.insert("nested".to_string(), Params::Dict(dict.clone()));
+
+  let values = vec![
+    Params::Null, Params::Boolean(false), Params::Integer(0), Params::Integer(128), Params::Integer(-129), Params::Integer(i64::MAX),
+    Params::BigInteger(num_bigint::BigInt::from(u64::MAX)), Params::BigInteger(num_bigint::BigInt::from(i128::MIN)),
+    Params::Decimal(bigdecimal::BigDecimal::from_str("-12.345").unwrap()),
+    Params::Text("Trái Tim".to_string()), Params::Text("a".repeat(200)), Params::ByteArray(vec![]),
+    Params::Array(vec![Params::Text("a".repeat(127)), Params::Array(vec![Params::Null])]),
+    Params::Dict(dict),
+  ];
+
+  let mut buffer = vec![0xff];
+  for value in values {
+    buffer.truncate(1);
+    encode_value_into(&value, &mut buffer);
+    assert_eq!(buffer[1..], encode_value(&value).unwrap(), "{:?}", value.kind());
+
+    #[cfg(feature = "std")]
+    {
+      let mut written = Vec::new();
+      encode_value_to_writer(&value, &mut written).unwrap();
+      assert_eq!(written, buffer[1..]);
+    }
+  }
+
+  #[cfg(feature = "std")]
+  {
+    let mut sink = [0u8; 4];
+    assert!(encode_value_to_writer(&Params::Text("too long".to_string()), &mut sink.as_mut_slice()).is_err());
+  }
+}
+
+#[test]
+fn gtv_test_explain() {
+  let mut dict = BTreeMap::new();
+  dict.insert("id".to_string(), Params::Integer(-1));
+  dict.insert("key".to_string(), Params::ByteArray(vec![0xab; 20]));
+  dict.insert("big".to_string(), Params::BigInteger(num_bigint::BigInt::from(u64::MAX)));
+  let encoded = encode_value(&Params::Array(vec![Params::Null, Params::Dict(dict)])).unwrap();
+
+  assert_eq!(explain(&encoded), "\
+0000  a548  ARRAY (72 bytes)
+0002    3046  SEQUENCE (70 bytes)
+0004      a002  NULL (2 bytes)
+0006        0500  NULL null
+0008      a440  DICT (64 bytes)
+000a        303e  SEQUENCE (62 bytes)
+000c          3012  SEQUENCE (18 bytes)
+000e            0c03 626967  UTF8String \"big\"
+0013            a60b  BIG_INTEGER (11 bytes)
+0015              0209 00ffffffffffffffff  INTEGER 18446744073709551615
+0020          3009  SEQUENCE (9 bytes)
+0022            0c02 6964  UTF8String \"id\"
+0026            a303  INTEGER (3 bytes)
+0028              0201 ff  INTEGER -1
+002b          301d  SEQUENCE (29 bytes)
+002d            0c03 6b6579  UTF8String \"key\"
+0032            a116  BYTE_ARRAY (22 bytes)
+0034              0414 abababababababababababababababab...  OCTET STRING 20 bytes
+");
+
+  assert_eq!(explain(&hex::decode("a5053003a30502").unwrap()), "\
+0000  a505  ARRAY (5 bytes)
+0002    3003  SEQUENCE (3 bytes)
+error at 0004: length 5 exceeds the 1 remaining bytes
+");
+  assert!(explain(&hex::decode("a7020500").unwrap()).starts_with("\
+0000  a702  unknown tag a7 (2 bytes)
+0002    0500  NULL null
+invalid GTV: "));
+
+  for input in ["", "a5", "a584ffffffff", "a58103300100", "a2040c02c328", "0c0161ff"] {
+    let output = explain(&hex::decode(input).unwrap());
+    assert!(output.contains("error") || output.contains("invalid GTV"), "{}: {}", input, output);
+  }
+}
+
+#[test]
+fn gtv_test_tagged_json() {
+  use std::str::FromStr;
+
+  let mut dict = BTreeMap::new();
+  dict.insert("$type".to_string(), Params::Text("$bytes".to_string()));
+  dict.insert("$$money".to_string(), Params::BigInteger(num_bigint::BigInt::from_str("-123456789012345678901234567890").unwrap()));
+  dict.insert("bytes".to_string(), Params::ByteArray(vec![0, 0xff]));
+  dict.insert("list".to_string(), Params::Array(vec![Params::Null, Params::Integer(i64::MIN), Params::Dict(BTreeMap::new())]));
+  let value = Params::Dict(dict);
+
+  let json = to_tagged_json(&value);
+  assert_eq!(json, serde_json::json!({
+    "$$type": "$bytes",
+    "$$$money": {"$bigint": "-123456789012345678901234567890"},
+    "bytes": {"$bytes": "00ff"},
+    "list": [null, i64::MIN, {}]
+  }));
+  assert_eq!(from_tagged_json(&json), Ok(value.clone()));
+  assert_eq!(from_tagged_json(&serde_json::from_str(&json.to_string()).unwrap()), Ok(value));
+
+  // The binary encoding has no booleans or decimals either
+  assert_eq!(to_tagged_json(&Params::Boolean(true)), serde_json::json!(1));
+  assert_eq!(to_tagged_json(&Params::Decimal(bigdecimal::BigDecimal::from_str("1.50").unwrap())), serde_json::json!("1.50"));
+  assert_eq!(from_tagged_json(&serde_json::json!(false)), Ok(Params::Integer(0)));
+
+  let big: serde_json::Value = serde_json::from_str("18446744073709551616").unwrap();
+  assert_eq!(from_tagged_json(&big), Ok(Params::BigInteger(num_bigint::BigInt::from(u64::MAX) + 1)));
+
+  assert_eq!(from_tagged_json(&serde_json::json!({"a": [1, 1.5]})), Err("$.a[1]: 1.5 is not an integer".to_string()));
+  assert_eq!(from_tagged_json(&serde_json::json!({"$bytes": "xyz"})), Err("$: $bytes must be a hex string".to_string()));
+  assert_eq!(from_tagged_json(&serde_json::json!({"$bigint": "1e5"})), Err("$: $bigint must be a string of decimal digits".to_string()));
+  assert_eq!(from_tagged_json(&serde_json::json!({"$other": 1})), Err("$: unknown tag \"$other\"".to_string()));
+  assert_eq!(from_tagged_json(&serde_json::json!({"$bytes": "00", "x": 1})), Err("$: unknown tag \"$bytes\"".to_string()));
+}
+
+
+/// Generates GTV values in their decoded form, including the edge cases handwritten
+/// vectors tend to miss: empty collections, extreme and huge integers, and unicode
+#[cfg(test)]
+pub(crate) fn arb_params() -> impl proptest::strategy::Strategy<Value = Params> {
+  use proptest::{collection, prelude::*};
+
+  let integer = prop_oneof![
+    any::<i64>(),
+    Just(0i64), Just(-1), Just(i64::MIN), Just(i64::MAX), Just(127), Just(128), Just(-128), Just(-129),
+  ];
+  let big_integer = (any::<bool>(), collection::vec(any::<u8>(), 0..48)).prop_map(|(negative, bytes)| {
+    let sign = if negative { num_bigint::Sign::Minus } else { num_bigint::Sign::Plus };
+    num_bigint::BigInt::from_bytes_be(sign, &bytes)
+  });
+  let leaf = prop_oneof![
+    Just(Params::Null),
+    integer.prop_map(Params::Integer),
+    big_integer.prop_map(Params::BigInteger),
+    any::<String>().prop_map(Params::Text),
+    collection::vec(any::<u8>(), 0..200).prop_map(Params::ByteArray),
+  ];
+  leaf.prop_recursive(4, 64, 6, |inner| prop_oneof![
+    collection::vec(inner.clone(), 0..6).prop_map(Params::Array),
+    collection::btree_map(any::<String>(), inner, 0..6).prop_map(Params::Dict),
+  ])
+}
+
+/// Converts booleans and decimals to the integers and texts they are encoded as
+#[cfg(test)]
+fn normalized(value: &Params) -> Params {
+  match value {
+    Params::Boolean(val) => Params::Integer(*val as i64),
+    Params::Decimal(val) => Params::Text(format_decimal(val)),
+    Params::Array(val) => Params::Array(val.iter().map(normalized).collect()),
+    Params::Dict(val) => Params::Dict(val.iter().map(|(key, value)| (key.clone(), normalized(value))).collect()),
+    val => val.clone(),
+  }
+}
+
+#[cfg(test)]
+proptest::proptest! {
+  #[test]
+  fn gtv_prop_roundtrip(value in arb_params()) {
+    let encoded = encode_value(&value).unwrap();
+    proptest::prop_assert_eq!(&decode(&encoded).unwrap(), &value);
+    proptest::prop_assert_eq!(&decode_canonical(&encoded).unwrap(), &value);
+    proptest::prop_assert_eq!(&Params::from(decode_borrowed(&encoded).unwrap()), &value);
+    #[cfg(feature = "std")]
+    proptest::prop_assert_eq!(&crate::encoding::gtv_stream::GtvReader::new(encoded.as_slice()).read_value().unwrap(), &Some(value.clone()));
+
+    let mut buffer = Vec::new();
+    encode_value_into(&value, &mut buffer);
+    proptest::prop_assert_eq!(&buffer, &encoded);
+    proptest::prop_assert_eq!(value.encoded_len(), encoded.len());
+    proptest::prop_assert_eq!(&encode_shared_value(&SharedParams::from(value.clone())).unwrap(), &encoded);
+
+    proptest::prop_assert_eq!(&from_tagged_json(&to_tagged_json(&value)).unwrap(), &value);
+    #[cfg(feature = "std")]
+    proptest::prop_assert_eq!(&crate::encoding::gtvml::from_xml(&crate::encoding::gtvml::to_xml(&value)).unwrap(), &value);
+  }
+
+  #[test]
+  fn gtv_prop_writers_agree(value in arb_params(), args in proptest::collection::btree_map(".*", arb_params(), 0..4)) {
+    let encoded = encode_value(&value).unwrap();
+    proptest::prop_assert_eq!(&asn1::write(|writer| value.to_writer(writer)).unwrap(), &encoded);
+
+    let query = encode_query("query", &args.iter().map(|(key, value)| (key.as_str(), value.clone())).collect::<Vec<_>>()).unwrap();
+    proptest::prop_assert_eq!(query, encode_value(&crate::gtv_array!["query", Params::Dict(args)]).unwrap());
+  }
+
+  #[test]
+  fn gtv_prop_decode_diagnosed_locates(value in arb_params(), index: proptest::sample::Index, byte: u8, cut: bool) {
+    let mut encoded = encode_value(&value).unwrap();
+    let index = index.index(encoded.len());
+    if cut {
+      encoded.truncate(index);
+    } else {
+      encoded[index] = byte;
+    }
+    if let Err(error) = decode_diagnosed(&encoded) {
+      proptest::prop_assert_ne!(error.expected, "valid GTV");
+    }
+  }
+
+  #[test]
+  fn gtv_prop_hash_stable(value in arb_params()) {
+    use crate::utils::hasher::gtv_hash;
+
+    let hash = gtv_hash(&value).unwrap();
+    proptest::prop_assert_eq!(gtv_hash(&value).unwrap(), hash);
+    proptest::prop_assert_eq!(gtv_hash(&decode(&encode_value(&value).unwrap()).unwrap()).unwrap(), hash);
+    proptest::prop_assert_eq!(crate::utils::hasher::gtv_hash_shared(&SharedParams::from(value.clone())).unwrap(), hash);
+  }
+
+  #[test]
+  fn gtv_prop_booleans_and_decimals(flag: bool, units: i64, scale in -20i64..20, wrap: bool) {
+    let value = crate::gtv_array![flag, Params::Decimal(bigdecimal::BigDecimal::new(units.into(), scale))];
+    let value = if wrap { crate::dict!{"value" => value} } else { value };
+
+    let encoded = encode_value(&value).unwrap();
+    proptest::prop_assert_eq!(&decode(&encoded).unwrap(), &normalized(&value));
+    proptest::prop_assert_eq!(encoded, encode_value(&normalized(&value)).unwrap());
+    proptest::prop_assert_eq!(
+      crate::utils::hasher::gtv_hash(&value).unwrap(),
+      crate::utils::hasher::gtv_hash(&normalized(&value)).unwrap()
+    );
+  }
+}
+
+#[test]
+fn gtv_test_low_level_api() {
+  let value = Params::Array(vec![
+    Params::ByteArray(vec![1, 2]),
+    Params::Dict(BTreeMap::from([("a".to_string(), Params::Integer(1)), ("b".to_string(), Params::Null)])),
+  ]);
+  let encoded = asn1::write(|writer| write_array(writer, &|writer| {
+    writer.write_element(&Choice::OCTETSTRING(&[1, 2]))?;
+    write_dict(writer, &|writer| {
+      write_dict_entry(writer, "a", &Params::Integer(1))?;
+      write_dict_entry(writer, "b", &Params::Null)
+    })
+  })).unwrap();
+  assert_eq!(encoded, encode_value(&value).unwrap());
+
+  let (bytes, dict) = match asn1::parse_single::<Choice>(&encoded).unwrap() {
+    Choice::ARRAY(seq) => seq.parse(|parser| {
+      let bytes = read_params(parser)?;
+      let dict = match parser.read_element::<Choice>()? {
+        Choice::DICT(seq) => seq.parse(read_sequence_dict)?,
+        _ => panic!("expected a dict"),
+      };
+      Ok::<_, ParseError>((bytes, dict))
+    }).unwrap(),
+    _ => panic!("expected an array"),
+  };
+  assert_eq!(Params::Array(vec![bytes, Params::Dict(dict)]), value);
+
+  match asn1::parse_single::<Choice>(&encoded).unwrap() {
+    Choice::ARRAY(seq) => assert_eq!(seq.parse(read_sequence_array).unwrap(), value.as_array().unwrap()),
+    _ => panic!("expected an array"),
+  }
+}
+
+#[test]
+fn gtv_test_extract() {
+  let value = Params::Dict(BTreeMap::from([
+    ("accounts".to_string(), Params::Array(vec![
+      Params::Dict(BTreeMap::from([("balance".to_string(), Params::Integer(10))])),
+      Params::Dict(BTreeMap::from([("balance".to_string(), Params::Integer(20)), ("name".to_string(), Params::Text("b".to_string()))])),
+    ])),
+    ("total".to_string(), Params::BigInteger(num_bigint::BigInt::from(30))),
+  ]));
+  let encoded = encode_value(&value).unwrap();
+
+  for path in ["", "accounts", "accounts[0]", "accounts[1].balance", "accounts[1].name", "total", "accounts[5]", "missing", "total[0]", "accounts.x", "a..b", "accounts[x]"] {
+    assert_eq!(extract(&encoded, path).unwrap(), value.get_path(path).cloned(), "{}", path);
+  }
+
+  // Siblings are skipped, not decoded: a bad UTF-8 text off the path goes unnoticed
+  let mut corrupted = encode_value(&Params::Array(vec![Params::Text("ab".to_string()), Params::Integer(1)])).unwrap();
+  corrupted[8] = 0xff;
+  assert!(decode(&corrupted).is_err());
+  assert_eq!(extract(&corrupted, "[1]").unwrap(), Some(Params::Integer(1)));
+  assert!(extract(&corrupted, "[0]").is_err());
+  assert!(extract(&encoded[..encoded.len() - 1], "total").is_err());
+}
+
+#[test]
+fn gtv_test_integer_boundaries() {
+  use num_bigint::BigInt;
+
+  // Minimal two's complement as java.math.BigInteger.toByteArray() gives it, which the node expects
+  for (value, content) in [
+    (BigInt::from(0), "00"),
+    (BigInt::from(-1), "ff"),
+    (BigInt::from(127), "7f"),
+    (BigInt::from(128), "0080"),
+    (BigInt::from(-128), "80"),
+    (BigInt::from(-129), "ff7f"),
+    (BigInt::from(i64::MAX), "7fffffffffffffff"),
+    (BigInt::from(i64::MIN), "8000000000000000"),
+    (BigInt::from(i64::MAX) + 1u8, "008000000000000000"),
+    (BigInt::from(i64::MIN) - 1u8, "ff7fffffffffffffff"),
+    (BigInt::from(u64::MAX), "00ffffffffffffffff"),
+  ] {
+    let encoded = format!("a6{:02x}02{:02x}{}", content.len() / 2 + 2, content.len() / 2, content);
+    assert_eq!(hex::encode(encode_value(&Params::BigInteger(value.clone())).unwrap()), encoded, "{}", value);
+    assert_eq!(decode(&hex::decode(&encoded).unwrap()).unwrap(), Params::BigInteger(value));
+  }
+
+  for (value, encoded) in [(i64::MAX, "a30a02087fffffffffffffff"), (i64::MIN, "a30a02088000000000000000"), (0, "a303020100")] {
+    assert_eq!(hex::encode(encode_value(&Params::Integer(value)).unwrap()), encoded);
+    assert_eq!(decode(&hex::decode(encoded).unwrap()).unwrap(), Params::Integer(value));
+  }
+
+  // An INTEGER wider than i64 decodes as a big integer instead of failing, in every decoder
+  let wide = hex::decode("a50f300da30b0209008000000000000000").unwrap();
+  let expected = BigInt::from(i64::MAX) + 1u8;
+  assert_eq!(decode(&wide).unwrap(), Params::Array(vec![Params::BigInteger(expected.clone())]));
+  assert_eq!(Params::from(decode_borrowed(&wide).unwrap()), Params::Array(vec![Params::BigInteger(expected.clone())]));
+  assert_eq!(extract(&wide, "[0]").unwrap(), Some(Params::BigInteger(expected)));
+  // It is not how the node encodes the value, so canonical decoding rejects it
+  assert!(decode_canonical(&wide).is_err());
+  // Non-minimal integers are still invalid DER
+  assert!(decode(&hex::decode("a30402020000").unwrap()).is_err());
+  assert!(decode(&hex::decode("a30b0209007fffffffffffffff").unwrap()).is_err());
+}
+
+#[test]
+fn gtv_test_decimal_format() {
+  use std::str::FromStr;
+
+  for (decimal, text) in [
+    ("0", "0"),
+    ("0.000", "0"),
+    ("-0.0", "0"),
+    ("1.50", "1.5"),
+    ("15E-1", "1.5"),
+    ("1.5E+3", "1500"),
+    ("100", "100"),
+    ("-12.3400", "-12.34"),
+    ("0.00000001234", "0.00000001234"),
+    ("1.2340E-8", "0.00000001234"),
+    ("123456789012345678901234567890.000000000000000000001", "123456789012345678901234567890.000000000000000000001"),
+  ] {
+    let value = bigdecimal::BigDecimal::from_str(decimal).unwrap();
+    assert_eq!(format_decimal(&value), text, "{}", decimal);
+
+    let encoded = encode_value(&Params::Decimal(value.clone())).unwrap();
+    assert_eq!(encoded, encode_value(&Params::Text(text.to_string())).unwrap());
+    assert_eq!(Params::Decimal(value.clone()).encoded_len(), encoded.len());
+    assert_eq!(decode(&encoded).unwrap().to_decimal(), Some(value.clone()));
+    assert_eq!(
+      crate::utils::hasher::gtv_hash(&Params::Decimal(value)).unwrap(),
+      crate::utils::hasher::gtv_hash(&Params::Text(text.to_string())).unwrap()
+    );
+  }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn gtv_test_empty_argument_vectors() {
+  // Byte-level vectors of the empty cases, kept fixed so encoder changes cannot drift from them
+  let operation = |operation: &Operation| hex::encode(encode_operation(operation).unwrap());
+  assert_eq!(operation(&Operation::nop()), "a50d300ba2050c036e6f70a5023000");
+  assert_eq!(operation(&Operation::without_args("op")), "a50c300aa2040c026f70a5023000");
+  assert_eq!(operation(&Operation { operation_name: Some("op".into()), ..Default::default() }), "a50c300aa2040c026f70a5023000");
+  // One empty struct argument, not zero arguments
+  assert_eq!(operation(&Operation::from_dict::<_, &str>("op", vec![])), "a510300ea2040c026f70a5063004a4023000");
+
+  let query = hex::encode(encode_query_without_args("q").unwrap());
+  assert_eq!(query, "a50b3009a2030c0171a4023000");
+  assert_eq!(hex::encode(encode("q", None).unwrap()), query);
+  assert_eq!(hex::encode(encode("q", Some(&mut vec![])).unwrap()), query);
+  assert_eq!(
+    hex::encode(encode_query("q", &[("a", Params::Array(vec![])), ("d", Params::Dict(BTreeMap::new()))]).unwrap()),
+    "a51d301ba2030c0171a414301230070c0161a502300030070c0164a4023000"
+  );
+
+  let expected = format!("a5363034a52e302ca1220420{}a5023000a5023000a5023000", "00".repeat(32));
+  assert_eq!(hex::encode(encode_tx(&Transaction::new(vec![0; 32], None, None, None)).unwrap()), expected);
+  assert_eq!(hex::encode(encode_tx(&Transaction::new(vec![0; 32], Some(vec![]), Some(vec![]), Some(vec![]))).unwrap()), expected);
+  assert_eq!(
+    Transaction::new(vec![0; 32], None, None, None).tx_rid().unwrap(),
+    crate::utils::hasher::gtv_hash(&to_draw_gtx(&Transaction::new(vec![0; 32], Some(vec![]), None, None))).unwrap()
+  );
+}
+
+#[test]
+fn gtv_test_base64() {
+  let value = Params::Array(vec![Params::Integer(1), Params::ByteArray(vec![0xfb, 0xff])]);
+  let encoded = encode_value_base64(&value).unwrap();
+  assert_eq!(general_purpose::STANDARD.decode(&encoded).unwrap(), encode_value(&value).unwrap());
+  assert_eq!(encoded, "pQ0wC6MDAgEBoQQEAvv/");
+  assert_eq!(decode_value_base64(&encoded).unwrap(), value);
+
+  assert!(matches!(decode_value_base64("pQ0wC6MDAgEBoQQEAvv_"), Err(DecodeBase64Error::Base64(_))));
+  assert!(matches!(decode_value_base64("pQ0wC6MDAgEB"), Err(DecodeBase64Error::Decode(_))));
+  assert!(decode_value_base64("%").unwrap_err().to_string().starts_with("Invalid base64: "));
+}
+
+#[test]
+fn gtv_test_decode_diagnosed() {
+  let value = Params::Array(vec![Params::Text("ab".to_string()), Params::Dict(BTreeMap::from([("k".to_string(), Params::Integer(1))]))]);
+  let encoded = encode_value(&value).unwrap();
+  assert_eq!(hex::encode(&encoded), "a5163014a2040c026162a40c300a30080c016ba303020101");
+  assert_eq!(decode_diagnosed(&encoded).unwrap(), value);
+
+  let diagnose = |bytes: &[u8]| {
+    let error = decode_diagnosed(bytes).unwrap_err();
+    (error.offset, error.expected, error.found)
+  };
+  let with = |offset: usize, byte: u8| {
+    let mut bytes = encoded.clone();
+    bytes[offset] = byte;
+    bytes
+  };
+  let expected = |offset: usize, expected: &str, found: Option<u8>| (offset, expected.to_string(), found);
+  assert_eq!(diagnose(&[]), expected(0, "GTV element (a0-a6)", None));
+  assert_eq!(diagnose(&with(0, 0xa7)), expected(0, "GTV element (a0-a6)", Some(0xa7)));
+  assert_eq!(diagnose(&with(6, 0x04)), expected(6, "UTF8String (0c)", Some(0x04)));
+  assert_eq!(diagnose(&with(8, 0xff)), expected(8, "UTF-8", Some(0xff)));
+  assert_eq!(diagnose(&with(14, 0x31)), expected(14, "SEQUENCE (30)", Some(0x31)));
+  assert_eq!(diagnose(&with(16, 0x04)), expected(16, "UTF8String (0c)", Some(0x04)));
+  assert_eq!(diagnose(&with(19, 0xa9)), expected(19, "GTV element (a0-a6)", Some(0xa9)));
+  assert_eq!(diagnose(&encoded[..23]), expected(23, "22 content bytes", None));
+  assert_eq!(diagnose(&[encoded.as_slice(), &[0]].concat()), expected(24, "end of data", Some(0)));
+  assert_eq!(diagnose(&hex::decode("a30402020001").unwrap()), expected(4, "minimal integer", Some(0)));
+  assert_eq!(diagnose(&hex::decode("a003050100").unwrap()), expected(4, "empty NULL", Some(0)));
+
+  let error = decode_diagnosed(&with(6, 0x04)).unwrap_err();
+  assert!(error.to_string().starts_with("at byte 6: expected UTF8String (0c), found 04 ("), "{}", error);
+}
+
+#[test]
+fn gtv_test_decode_prefix() {
+  let values = [Params::Text("a".to_string()), Params::Array(vec![Params::Integer(2)]), Params::Null];
+  let data: Vec<u8> = values.iter().flat_map(|value| encode_value(value).unwrap()).collect();
+
+  let mut rest = data.as_slice();
+  let mut decoded = Vec::new();
+  while !rest.is_empty() {
+    let (value, remaining) = decode_prefix(rest).unwrap();
+    decoded.push(value);
+    rest = remaining;
+  }
+  assert_eq!(decoded, values);
+
+  assert!(decode(&data).is_err());
+  assert!(decode_prefix(&[]).is_err());
+  assert!(decode_prefix(&data[..2]).is_err());
+  // A complete element that is not valid GTV is still an error
+  assert!(decode_prefix(&hex::decode("a20304016100").unwrap()).is_err());
+}
+
+#[test]
+fn gtv_test_diff() {
+  use crate::utils::operation::DiffKind;
+
+  let a = encode_value(&Params::Array(vec![Params::Text("x".to_string()), Params::ByteArray(vec![1])])).unwrap();
+  let b = encode_value(&Params::Array(vec![Params::Text("x".to_string()), Params::Text("01".to_string()), Params::Null])).unwrap();
+  assert_eq!(diff(&a, &b).unwrap(), [
+    DiffEntry { path: "[1]".to_string(), kind: DiffKind::Changed { old: Params::ByteArray(vec![1]), new: Params::Text("01".to_string()) } },
+    DiffEntry { path: "[2]".to_string(), kind: DiffKind::Added(Params::Null) },
+  ]);
+  assert_eq!(diff(&a, &a).unwrap(), []);
+  // The same dictionary with its entries out of order
+  let sorted = hex::decode("a414301230070c0161a002050030070c0162a0020500").unwrap();
+  let unsorted = hex::decode("a414301230070c0162a002050030070c0161a0020500").unwrap();
+  assert_eq!(diff(&sorted, &unsorted).unwrap(), []);
+  assert!(decode_canonical(&unsorted).is_err());
+
+  assert!(matches!(diff(&a[1..], &b), Err(DiffError::A(_))));
+  assert!(matches!(diff(&a, &b[..3]), Err(DiffError::B(error)) if error.offset == 3));
+}
+
+#[test]
+fn gtv_test_hex_case() {
+  let bytes: Vec<u8> = (0..=255).collect();
+  let mut out = String::new();
+  encode_hex_into(&bytes, HexCase::Lower, &mut out);
+  assert_eq!(out, hex::encode(&bytes));
+  out.clear();
+  encode_hex_into(&bytes, HexCase::Upper, &mut out);
+  assert_eq!(out, hex::encode_upper(&bytes));
+
+  let value = Params::Array(vec![Params::ByteArray(vec![0xab, 0xcd])]);
+  assert_eq!(encode_value_hex(&value, HexCase::Lower).unwrap(), encode_value_hex_encode(&value).unwrap());
+  assert_eq!(encode_value_hex(&value, HexCase::Upper).unwrap(), "A5083006A1040402ABCD");
+  let mut line = String::from("value=");
+  encode_value_hex_into(&value, HexCase::Lower, &mut line).unwrap();
+  assert_eq!(line, "value=a5083006a1040402abcd");
+}
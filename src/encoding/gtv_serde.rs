@@ -0,0 +1,737 @@
+//! Streaming transcoding between GTV bytes and JSON, available with the `transcode` feature.
+//!
+//! `GtvDeserializer` reads GTV bytes as a serde data source and `GtvSerializer` writes GTV bytes
+//! from any serde data, so `serde_transcode` converts between GTV and another format element by
+//! element, without building a `Params` tree. The JSON shape is the tagged GTV JSON of
+//! `gtv::to_tagged_json` and `gtv::from_tagged_json`, a format of this crate that other
+//! Postchain clients do not read:
+//!
+//! * null, integers, texts and arrays map to their JSON counterparts
+//! * byte arrays are `{"$bytes": "<hex>"}` and big integers `{"$bigint": "<digits>"}`
+//! * dictionaries are objects, with one more `$` before keys starting with `$`
+//!
+//! # Example
+//! ```
+//! # use postchain_client::{dict, encoding::{gtv, gtv_serde}};
+//! let response = gtv::encode_value(&dict!{"id" => 7, "owner" => vec![0xabu8, 0xcd]}).unwrap();
+//!
+//! let mut json = Vec::new();
+//! gtv_serde::gtv_to_tagged_json(&response, &mut json).unwrap();
+//! assert_eq!(String::from_utf8(json.clone()).unwrap(), r#"{"id":7,"owner":{"$bytes":"abcd"}}"#);
+//!
+//! assert_eq!(gtv_serde::tagged_json_to_gtv(&json).unwrap(), response);
+//! ```
+
+use std::fmt;
+
+use num_bigint::BigInt;
+use serde::de::{self, value::{BorrowedStrDeserializer, MapDeserializer}, IntoDeserializer};
+use serde::ser::{self, Serialize};
+
+use crate::encoding::gtv::{
+    integer_content_len, tlv_len, write_header, write_primitive, write_sequence_header,
+    DEFAULT_MAX_DEPTH, JSON_BIG_INTEGER_TAG, JSON_BYTES_TAG,
+};
+
+/// Key under which `serde_json` passes numbers when its `arbitrary_precision` feature is on
+const JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// Error of a GTV transcoding, with a message naming what could not be converted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscodeError(String);
+
+impl fmt::Display for TranscodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TranscodeError {}
+
+impl de::Error for TranscodeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        TranscodeError(msg.to_string())
+    }
+}
+
+impl ser::Error for TranscodeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        TranscodeError(msg.to_string())
+    }
+}
+
+impl From<asn1::ParseError> for TranscodeError {
+    fn from(error: asn1::ParseError) -> Self {
+        TranscodeError(format!("Invalid GTV: {}", error))
+    }
+}
+
+impl From<serde_json::Error> for TranscodeError {
+    fn from(error: serde_json::Error) -> Self {
+        TranscodeError(error.to_string())
+    }
+}
+
+/// Streams an encoded GTV value into `writer` as tagged GTV JSON.
+///
+/// # Arguments
+/// * `data` - The encoded value, such as a query response
+/// * `writer` - Destination of the compact JSON text
+///
+/// # Errors
+/// `TranscodeError` if `data` is not a single valid GTV value or writing fails
+pub fn gtv_to_tagged_json<W: std::io::Write>(data: &[u8], writer: W) -> Result<(), TranscodeError> {
+    let mut deserializer = GtvDeserializer::from_slice(data);
+    serde_transcode::transcode(&mut deserializer, &mut serde_json::Serializer::new(writer))?;
+    deserializer.end()
+}
+
+/// Encodes tagged GTV JSON as GTV bytes, streaming from the JSON text.
+///
+/// # Arguments
+/// * `json` - The JSON text, as produced by `gtv_to_tagged_json` or `gtv::to_tagged_json`
+///
+/// # Returns
+/// The encoded value, with dictionary keys sorted as GTV requires
+///
+/// # Errors
+/// `TranscodeError` for invalid JSON, fractional numbers, malformed tags, and object keys
+/// starting with a single `$` that are not tags
+pub fn tagged_json_to_gtv(json: &[u8]) -> Result<Vec<u8>, TranscodeError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(json);
+    let encoded = serde_transcode::transcode(&mut deserializer, GtvSerializer)?;
+    deserializer.end()?;
+    Ok(encoded)
+}
+
+/// A serde `Deserializer` reading GTV bytes in the shape of tagged GTV JSON.
+pub struct GtvDeserializer<'de> {
+    input: &'de [u8],
+    depth: usize,
+}
+
+impl<'de> GtvDeserializer<'de> {
+    /// Creates a deserializer reading the value at the start of `input`.
+    pub fn from_slice(input: &'de [u8]) -> Self {
+        GtvDeserializer { input, depth: 0 }
+    }
+
+    /// Checks that the whole input was read.
+    ///
+    /// # Errors
+    /// `TranscodeError` if bytes are left after the value
+    pub fn end(&self) -> Result<(), TranscodeError> {
+        if self.input.is_empty() {
+            Ok(())
+        } else {
+            Err(TranscodeError(format!("{} trailing bytes after the GTV value", self.input.len())))
+        }
+    }
+
+    /// Removes the next element from the input
+    fn next_element(&mut self) -> Result<asn1::Tlv<'de>, TranscodeError> {
+        let (element, rest) = asn1::strip_tlv(self.input)?;
+        self.input = rest;
+        Ok(element)
+    }
+
+    /// Reads the content of the `SEQUENCE` inside an explicitly tagged array or dictionary
+    fn sequence_content(&self, data: &'de [u8]) -> Result<GtvDeserializer<'de>, TranscodeError> {
+        if self.depth >= DEFAULT_MAX_DEPTH {
+            return Err(TranscodeError(format!("GTV nested deeper than {} levels", DEFAULT_MAX_DEPTH)));
+        }
+        let sequence = single_element(data)?;
+        if sequence.full_data()[0] != 0x30 {
+            return Err(TranscodeError("Invalid GTV: expected a SEQUENCE".to_string()));
+        }
+        Ok(GtvDeserializer { input: sequence.data(), depth: self.depth + 1 })
+    }
+}
+
+/// Parses `data` as exactly one element
+fn single_element(data: &[u8]) -> Result<asn1::Tlv<'_>, TranscodeError> {
+    let (element, rest) = asn1::strip_tlv(data)?;
+    if !rest.is_empty() {
+        return Err(TranscodeError("Invalid GTV: trailing bytes inside an element".to_string()));
+    }
+    Ok(element)
+}
+
+/// Visits a one-entry map `{tag: text}`, the JSON form of byte arrays and big integers
+fn visit_tagged<'de, V: de::Visitor<'de>>(visitor: V, tag: &'static str, text: String) -> Result<V::Value, TranscodeError> {
+    visitor.visit_map(MapDeserializer::new(std::iter::once((tag, text))))
+}
+
+impl<'de> de::Deserializer<'de> for &mut GtvDeserializer<'de> {
+    type Error = TranscodeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let element = self.next_element()?;
+        let data = element.data();
+        match element.tag() {
+            tag if tag == asn1::explicit_tag(0) => {
+                asn1::parse_single::<()>(data)?;
+                visitor.visit_unit()
+            }
+            tag if tag == asn1::explicit_tag(1) => {
+                visit_tagged(visitor, JSON_BYTES_TAG, hex::encode(asn1::parse_single::<&[u8]>(data)?))
+            }
+            tag if tag == asn1::explicit_tag(2) => {
+                visitor.visit_borrowed_str(asn1::parse_single::<asn1::Utf8String>(data)?.as_str())
+            }
+            tag if tag == asn1::explicit_tag(3) => {
+                let value = asn1::parse_single::<asn1::BigInt>(data)?;
+                if value.as_bytes().len() > 8 {
+                    return visit_tagged(visitor, JSON_BIG_INTEGER_TAG, BigInt::from_signed_bytes_be(value.as_bytes()).to_string());
+                }
+                visitor.visit_i64(asn1::parse_single::<i64>(data)?)
+            }
+            tag if tag == asn1::explicit_tag(4) => {
+                let mut content = self.sequence_content(data)?;
+                let value = visitor.visit_map(DictAccess { deserializer: &mut content, value: None })?;
+                content.end()?;
+                Ok(value)
+            }
+            tag if tag == asn1::explicit_tag(5) => {
+                let mut content = self.sequence_content(data)?;
+                let value = visitor.visit_seq(ArrayAccess { deserializer: &mut content })?;
+                content.end()?;
+                Ok(value)
+            }
+            tag if tag == asn1::explicit_tag(6) => {
+                let value = asn1::parse_single::<asn1::BigInt>(data)?;
+                visit_tagged(visitor, JSON_BIG_INTEGER_TAG, BigInt::from_signed_bytes_be(value.as_bytes()).to_string())
+            }
+            tag => Err(TranscodeError(format!("Invalid GTV: unexpected tag {:?}", tag))),
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.input.first() == Some(&0xa0) {
+            self.next_element()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Reads the items of a GTV array
+struct ArrayAccess<'a, 'de> {
+    deserializer: &'a mut GtvDeserializer<'de>,
+}
+
+impl<'de> de::SeqAccess<'de> for ArrayAccess<'_, 'de> {
+    type Error = TranscodeError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        if self.deserializer.input.is_empty() {
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+}
+
+/// Reads the entries of a GTV dictionary, escaping keys that start with `$`
+struct DictAccess<'a, 'de> {
+    deserializer: &'a mut GtvDeserializer<'de>,
+    /// The value of the entry whose key was just read
+    value: Option<&'de [u8]>,
+}
+
+impl<'de> de::MapAccess<'de> for DictAccess<'_, 'de> {
+    type Error = TranscodeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        if self.deserializer.input.is_empty() {
+            return Ok(None);
+        }
+        let entry = self.deserializer.next_element()?;
+        if entry.full_data()[0] != 0x30 {
+            return Err(TranscodeError("Invalid GTV: expected a dictionary entry".to_string()));
+        }
+        let (key, value) = asn1::strip_tlv(entry.data())?;
+        let key = asn1::parse_single::<asn1::Utf8String>(key.full_data())?.as_str();
+        self.value = Some(value);
+        if key.starts_with('$') {
+            seed.deserialize(format!("${}", key).into_deserializer()).map(Some)
+        } else {
+            seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let input = self.value.take().ok_or_else(|| TranscodeError("Value requested before its key".to_string()))?;
+        let mut value = GtvDeserializer { input, depth: self.deserializer.depth };
+        let result = seed.deserialize(&mut value)?;
+        value.end()?;
+        Ok(result)
+    }
+}
+
+/// A serde `Serializer` writing GTV bytes, reading maps as tagged GTV JSON objects.
+///
+/// Booleans become the integers 0 and 1, integers beyond the 64-bit range big integers, and
+/// options their value or null. Maps become dictionaries with sorted keys, where a later
+/// duplicate key wins, unless they are a `$bytes` or `$bigint` tag. Floating point numbers
+/// are rejected, since GTV cannot represent them.
+pub struct GtvSerializer;
+
+/// Writes a primitive element into a new buffer
+fn primitive(explicit_tag: u8, tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(tlv_len(tlv_len(content.len())));
+    write_primitive(&mut out, explicit_tag, tag, content).expect("writing to a Vec does not fail");
+    out
+}
+
+fn integer(value: i64) -> Vec<u8> {
+    primitive(0xa3, 0x02, &value.to_be_bytes()[8 - integer_content_len(value)..])
+}
+
+fn big_integer(value: &BigInt) -> Vec<u8> {
+    match i64::try_from(value) {
+        Ok(value) => integer(value),
+        Err(_) => primitive(0xa6, 0x02, &value.to_signed_bytes_be()),
+    }
+}
+
+/// Wraps encoded items in an array or dictionary element
+fn sequence(explicit_tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(tlv_len(tlv_len(content.len())));
+    write_sequence_header(&mut out, explicit_tag, content.len()).expect("writing to a Vec does not fail");
+    out.extend_from_slice(content);
+    out
+}
+
+/// Encodes sorted dictionary entries
+fn dict(entries: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut content = Vec::new();
+    for (key, value) in entries {
+        let entry_len = tlv_len(key.len()) + value.len();
+        write_header(&mut content, 0x30, entry_len).expect("writing to a Vec does not fail");
+        write_header(&mut content, 0x0c, key.len()).expect("writing to a Vec does not fail");
+        content.extend_from_slice(key.as_bytes());
+        content.extend_from_slice(value);
+    }
+    sequence(0xa4, &content)
+}
+
+/// Parses the digits of a JSON number or a `$bigint` tag
+fn parse_integer(digits: &str) -> Result<BigInt, TranscodeError> {
+    let valid = !digits.is_empty() && digits.strip_prefix('-').unwrap_or(digits).bytes().all(|b| b.is_ascii_digit());
+    valid.then(|| BigInt::parse_bytes(digits.as_bytes(), 10)).flatten()
+        .ok_or_else(|| TranscodeError(format!("{} is not an integer", digits)))
+}
+
+/// Reads a map key or a tag value, which must be a string
+fn capture_string<T: ?Sized + Serialize>(value: &T, what: &str) -> Result<String, TranscodeError> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Err(TranscodeError(format!("{} must be a string, found {}", what, other))),
+    }
+}
+
+impl ser::Serializer for GtvSerializer {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+    type SerializeSeq = ArrayEncoder;
+    type SerializeTuple = ArrayEncoder;
+    type SerializeTupleStruct = ArrayEncoder;
+    type SerializeTupleVariant = VariantEncoder<ArrayEncoder>;
+    type SerializeMap = DictEncoder;
+    type SerializeStruct = DictEncoder;
+    type SerializeStructVariant = VariantEncoder<DictEncoder>;
+
+    fn serialize_bool(self, v: bool) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v as i64))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Vec<u8>, TranscodeError> {
+        Ok(big_integer(&v.into()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Vec<u8>, TranscodeError> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Vec<u8>, TranscodeError> {
+        Ok(big_integer(&v.into()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Vec<u8>, TranscodeError> {
+        Ok(big_integer(&v.into()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Vec<u8>, TranscodeError> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Vec<u8>, TranscodeError> {
+        Err(TranscodeError(format!("{} is not an integer", v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Vec<u8>, TranscodeError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Vec<u8>, TranscodeError> {
+        Ok(primitive(0xa2, 0x0c, v.as_bytes()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Vec<u8>, TranscodeError> {
+        Ok(primitive(0xa1, 0x04, v))
+    }
+
+    fn serialize_none(self) -> Result<Vec<u8>, TranscodeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Vec<u8>, TranscodeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Vec<u8>, TranscodeError> {
+        Ok(primitive(0xa0, 0x05, &[]))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Vec<u8>, TranscodeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Vec<u8>, TranscodeError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Vec<u8>, TranscodeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Vec<u8>, TranscodeError> {
+        Ok(dict(&[(variant.to_string(), value.serialize(GtvSerializer)?)]))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ArrayEncoder, TranscodeError> {
+        Ok(ArrayEncoder { content: Vec::new() })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ArrayEncoder, TranscodeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ArrayEncoder, TranscodeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantEncoder<ArrayEncoder>, TranscodeError> {
+        Ok(VariantEncoder { variant, inner: self.serialize_seq(Some(len))? })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<DictEncoder, TranscodeError> {
+        Ok(DictEncoder { entries: Vec::new(), key: None, tagged: None, tagged_json: true })
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<DictEncoder, TranscodeError> {
+        // `serde_json::Number` serializes as this struct when the exact digits are kept
+        let tagged = (name == JSON_NUMBER_TOKEN).then_some(Tagged::Pending(JSON_NUMBER_TOKEN));
+        Ok(DictEncoder { entries: Vec::new(), key: None, tagged, tagged_json: false })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<VariantEncoder<DictEncoder>, TranscodeError> {
+        Ok(VariantEncoder { variant, inner: self.serialize_struct(name, len)? })
+    }
+}
+
+/// Collects the items of an array
+pub struct ArrayEncoder {
+    content: Vec<u8>,
+}
+
+impl ser::SerializeSeq for ArrayEncoder {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TranscodeError> {
+        self.content.extend_from_slice(&value.serialize(GtvSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<u8>, TranscodeError> {
+        Ok(sequence(0xa5, &self.content))
+    }
+}
+
+impl ser::SerializeTuple for ArrayEncoder {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TranscodeError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, TranscodeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ArrayEncoder {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TranscodeError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, TranscodeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// A tag object being read: `$bytes`, `$bigint` or a `serde_json` number
+enum Tagged {
+    Pending(&'static str),
+    Done(Vec<u8>),
+}
+
+/// Collects the entries of a dictionary, sorting them on `end`
+pub struct DictEncoder {
+    entries: Vec<(String, Vec<u8>)>,
+    key: Option<String>,
+    tagged: Option<Tagged>,
+    /// Whether keys follow tagged GTV JSON, with tags and `$` escapes, as map keys do
+    tagged_json: bool,
+}
+
+impl DictEncoder {
+    fn add_entry(&mut self, key: String, value: Vec<u8>) -> Result<(), TranscodeError> {
+        if self.tagged.is_some() {
+            return Err(TranscodeError(format!("Unexpected key {:?} after a tag", key)));
+        }
+        self.entries.push((key, value));
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for DictEncoder {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), TranscodeError> {
+        let key = capture_string(key, "Dictionary key")?;
+        if !self.tagged_json {
+            self.key = Some(key);
+            return Ok(());
+        }
+        let tag = [JSON_BYTES_TAG, JSON_BIG_INTEGER_TAG, JSON_NUMBER_TOKEN].into_iter().find(|tag| *tag == key);
+        match (tag, key.strip_prefix('$')) {
+            (Some(tag), _) if self.entries.is_empty() && self.tagged.is_none() => self.tagged = Some(Tagged::Pending(tag)),
+            (_, Some(escaped)) if escaped.starts_with('$') => self.key = Some(escaped.to_string()),
+            (_, Some(_)) => return Err(TranscodeError(format!("Unknown tag {:?}", key))),
+            (_, None) => self.key = Some(key),
+        }
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TranscodeError> {
+        if let Some(Tagged::Pending(tag)) = self.tagged {
+            let text = capture_string(value, tag)?;
+            let encoded = match tag {
+                JSON_BYTES_TAG => hex::decode(&text).map(|bytes| primitive(0xa1, 0x04, &bytes))
+                    .map_err(|_| TranscodeError(format!("{} must be a hex string", JSON_BYTES_TAG)))?,
+                // Like `gtv::from_tagged_json`, a `$bigint` tag stays a big integer even in the 64-bit range
+                JSON_BIG_INTEGER_TAG => primitive(0xa6, 0x02, &parse_integer(&text)?.to_signed_bytes_be()),
+                _ => big_integer(&parse_integer(&text)?),
+            };
+            self.tagged = Some(Tagged::Done(encoded));
+            return Ok(());
+        }
+        let key = self.key.take().ok_or_else(|| TranscodeError("Value serialized before its key".to_string()))?;
+        let value = value.serialize(GtvSerializer)?;
+        self.add_entry(key, value)
+    }
+
+    fn end(mut self) -> Result<Vec<u8>, TranscodeError> {
+        if let Some(Tagged::Done(encoded)) = self.tagged {
+            return Ok(encoded);
+        }
+        // A stable sort keeps duplicates in order, so the last of them is kept
+        self.entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut entries: Vec<(String, Vec<u8>)> = Vec::with_capacity(self.entries.len());
+        for (key, value) in self.entries {
+            match entries.last_mut() {
+                Some(last) if last.0 == key => last.1 = value,
+                _ => entries.push((key, value)),
+            }
+        }
+        Ok(dict(&entries))
+    }
+}
+
+impl ser::SerializeStruct for DictEncoder {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), TranscodeError> {
+        if let Some(Tagged::Pending(_)) = self.tagged {
+            return ser::SerializeMap::serialize_value(self, value);
+        }
+        let value = value.serialize(GtvSerializer)?;
+        self.add_entry(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, TranscodeError> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Wraps the content of a tuple or struct variant in a one-entry dictionary `{variant: content}`
+pub struct VariantEncoder<E> {
+    variant: &'static str,
+    inner: E,
+}
+
+impl ser::SerializeTupleVariant for VariantEncoder<ArrayEncoder> {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), TranscodeError> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, TranscodeError> {
+        Ok(dict(&[(self.variant.to_string(), ser::SerializeSeq::end(self.inner)?)]))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantEncoder<DictEncoder> {
+    type Ok = Vec<u8>;
+    type Error = TranscodeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), TranscodeError> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, TranscodeError> {
+        Ok(dict(&[(self.variant.to_string(), ser::SerializeMap::end(self.inner)?)]))
+    }
+}
+
+#[test]
+fn test_transcode_vectors() {
+    let cases: &[(&str, &str)] = &[
+        ("a0020500", "null"),
+        ("a303020101", "1"),
+        ("a30402020080", "128"),
+        ("a3060204deadbeef", "-559038737"),
+        ("a60b0209010000000000000000", r#"{"$bigint":"18446744073709551616"}"#),
+        ("a6030201ff", r#"{"$bigint":"-1"}"#),
+        ("a1040402abcd", r#"{"$bytes":"abcd"}"#),
+        ("a2050c03616263", r#""abc""#),
+        ("a50b3009a303020101a0020500", "[1,null]"),
+        ("a40c300a30080c022461a0020500", r#"{"$$a":null}"#),
+    ];
+    for (gtv, json) in cases {
+        let gtv = hex::decode(gtv).unwrap();
+        let mut transcoded = Vec::new();
+        gtv_to_tagged_json(&gtv, &mut transcoded).unwrap();
+        assert_eq!(String::from_utf8(transcoded).unwrap(), *json);
+        assert_eq!(tagged_json_to_gtv(json.as_bytes()).unwrap(), gtv, "{}", json);
+    }
+}
+
+#[test]
+fn test_transcode_json_input() {
+    use crate::encoding::gtv;
+    use crate::utils::operation::Params;
+
+    // Keys are sorted, the last duplicate wins and booleans become integers
+    let encoded = tagged_json_to_gtv(br#"{"b": true, "a": 1, "b": [false]}"#).unwrap();
+    assert_eq!(gtv::decode(&encoded).unwrap(), crate::dict!{"a" => 1, "b" => crate::gtv_array![0]});
+
+    // Numbers beyond the 64-bit range become big integers
+    let encoded = tagged_json_to_gtv(b"-9223372036854775809").unwrap();
+    assert_eq!(gtv::decode(&encoded).unwrap(), Params::BigInteger(BigInt::from(i64::MIN) - 1u8));
+
+    // `serde_json` values serialize through the same tagged form
+    let value = serde_json::json!({"n": 5, "raw": {"$bytes": "0102"}});
+    assert_eq!(ser::Serialize::serialize(&value, GtvSerializer).unwrap(), tagged_json_to_gtv(value.to_string().as_bytes()).unwrap());
+}
+
+#[test]
+fn test_transcode_errors() {
+    assert_eq!(tagged_json_to_gtv(b"1.5").unwrap_err().to_string(), "1.5 is not an integer at line 1 column 3");
+    assert_eq!(tagged_json_to_gtv(br#"{"$other": 1}"#).unwrap_err().to_string(), "Unknown tag \"$other\" at line 1 column 9");
+    assert_eq!(tagged_json_to_gtv(br#"{"$bytes": "0g"}"#).unwrap_err().to_string(), "$bytes must be a hex string at line 1 column 16");
+    assert_eq!(tagged_json_to_gtv(br#"{"$bigint": "12", "x": 1}"#).unwrap_err().to_string(), "Unexpected key \"x\" after a tag at line 1 column 25");
+    assert!(tagged_json_to_gtv(b"[1] 2").is_err());
+
+    let mut out = Vec::new();
+    assert_eq!(gtv_to_tagged_json(&hex::decode("a0020500a0020500").unwrap(), &mut out).unwrap_err().to_string(), "4 trailing bytes after the GTV value");
+    assert!(gtv_to_tagged_json(&hex::decode("a5043002a303").unwrap(), &mut Vec::new()).is_err());
+    assert!(gtv_to_tagged_json(&hex::decode("a7020500").unwrap(), &mut Vec::new()).is_err());
+
+    let nested = (0..=DEFAULT_MAX_DEPTH).fold(crate::utils::operation::Params::Null, |inner, _| crate::gtv_array![inner]);
+    let encoded = crate::encoding::gtv::encode_value(&nested).unwrap();
+    assert!(gtv_to_tagged_json(&encoded, &mut Vec::new()).unwrap_err().to_string().contains("nested deeper"));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_transcode_prop_matches_to_tagged_json(value in crate::encoding::gtv::arb_params()) {
+        use crate::encoding::gtv;
+
+        let encoded = gtv::encode_value(&value).unwrap();
+        let mut json = Vec::new();
+        gtv_to_tagged_json(&encoded, &mut json).unwrap();
+        proptest::prop_assert_eq!(&json, &serde_json::to_vec(&gtv::to_tagged_json(&value)).unwrap());
+        proptest::prop_assert_eq!(&tagged_json_to_gtv(&json).unwrap(), &encoded);
+    }
+}
@@ -0,0 +1,481 @@
+//! Incremental GTV decoding
+//!
+//! `GtvReader` pulls GTV bytes from any `std::io::Read` and reports the structure as
+//! `GtvEvent`s, holding only the value being read and the path to it in memory. This lets
+//! callers process query results of hundreds of megabytes element by element, and
+//! materialize only the sub-values they need with `GtvReader::read_value`.
+//!
+//! For async sources, run the reader on a blocking task, e.g. with `tokio::task::spawn_blocking`.
+//!
+//! # Example
+//! ```
+//! use postchain_client::encoding::{gtv::encode_value, gtv_stream::{GtvEvent, GtvReader}};
+//! use postchain_client::{gtv_array, utils::operation::Params};
+//!
+//! let encoded = encode_value(&gtv_array![1, 2, 3]).unwrap();
+//! let mut reader = GtvReader::new(encoded.as_slice());
+//!
+//! assert_eq!(reader.next_event().unwrap(), Some(GtvEvent::StartArray));
+//! let mut sum = 0;
+//! while let Some(Params::Integer(value)) = reader.read_value().unwrap() {
+//!     sum += value;
+//! }
+//! assert_eq!(sum, 6);
+//! assert_eq!(reader.next_event().unwrap(), None);
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use asn1::{ParseError, ParseErrorKind};
+
+use crate::encoding::gtv::{decode, DEFAULT_MAX_DEPTH};
+use crate::utils::operation::Params;
+
+const TAG_NULL: u8 = 0xa0;
+const TAG_BYTE_ARRAY: u8 = 0xa1;
+const TAG_STRING: u8 = 0xa2;
+const TAG_INTEGER: u8 = 0xa3;
+const TAG_DICT: u8 = 0xa4;
+const TAG_ARRAY: u8 = 0xa5;
+const TAG_BIG_INTEGER: u8 = 0xa6;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_UTF8_STRING: u8 = 0x0c;
+
+/// A step in the structure of a GTV value
+#[derive(Clone, Debug, PartialEq)]
+pub enum GtvEvent {
+    /// An array starts; its elements follow until `EndArray`
+    StartArray,
+    /// The current array ends
+    EndArray,
+    /// A dictionary starts; its entries follow as a `Key` and a value each, until `EndDict`
+    StartDict,
+    /// The key of the next dictionary entry; its value follows
+    Key(String),
+    /// The current dictionary ends
+    EndDict,
+    /// A value that is neither an array nor a dictionary
+    Value(Params),
+}
+
+/// Errors that can occur while reading a GTV stream
+#[derive(Debug)]
+pub enum StreamError {
+    /// Reading from the source failed, including when it ends before the value is complete
+    Io(std::io::Error),
+    /// The bytes are not valid GTV
+    Parse(Box<ParseError>),
+    /// `read_value` was called where a dictionary key comes next
+    KeyPending,
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "Failed to read GTV stream: {}", error),
+            StreamError::Parse(error) => write!(f, "Invalid GTV stream: {}", error),
+            StreamError::KeyPending => write!(f, "Expected a dictionary key, not a value"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<std::io::Error> for StreamError {
+    fn from(error: std::io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(error: ParseError) -> Self {
+        StreamError::Parse(Box::new(error))
+    }
+}
+
+fn parse_error(kind: ParseErrorKind) -> StreamError {
+    StreamError::Parse(Box::new(ParseError::new(kind)))
+}
+
+fn unexpected_tag(tag: u8) -> StreamError {
+    match asn1::Tag::from_bytes(&[tag]) {
+        Ok((actual, _)) => parse_error(ParseErrorKind::UnexpectedTag { actual }),
+        Err(error) => StreamError::from(error),
+    }
+}
+
+/// A container the reader is inside of, with the stream position where it ends
+#[derive(Clone, Copy, Debug)]
+enum Frame {
+    Array { end: u64 },
+    Dict { end: u64 },
+    /// A dictionary entry whose key has been read
+    Entry { end: u64 },
+}
+
+impl Frame {
+    fn end(&self) -> u64 {
+        match self {
+            Frame::Array { end } | Frame::Dict { end } | Frame::Entry { end } => *end,
+        }
+    }
+}
+
+/// Pull decoder that reads one GTV value from a byte stream
+///
+/// Call `next_event` to walk the structure, or `read_value` to decode the next complete
+/// value. Arrays and dictionaries nested deeper than `DEFAULT_MAX_DEPTH` are rejected
+/// unless the reader is created with `GtvReader::with_max_depth`.
+pub struct GtvReader<R> {
+    reader: R,
+    position: u64,
+    stack: Vec<Frame>,
+    max_depth: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: Read> GtvReader<R> {
+    /// Creates a reader over a byte source
+    ///
+    /// # Arguments
+    /// * `reader` - Source of the GTV encoding; wrap unbuffered sources in `std::io::BufReader`
+    pub fn new(reader: R) -> Self {
+        Self::with_max_depth(reader, DEFAULT_MAX_DEPTH)
+    }
+
+    /// Creates a reader that rejects arrays and dictionaries nested deeper than `max_depth`
+    ///
+    /// # Arguments
+    /// * `reader` - Source of the GTV encoding
+    /// * `max_depth` - Maximum nesting depth; a scalar has depth 0 and `[[]]` has depth 2
+    pub fn with_max_depth(reader: R, max_depth: usize) -> Self {
+        GtvReader { reader, position: 0, stack: Vec::new(), max_depth, started: false, finished: false }
+    }
+
+    /// Returns the number of bytes consumed from the source so far
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the number of arrays and dictionaries the reader is currently inside of
+    pub fn depth(&self) -> usize {
+        self.stack.iter().filter(|frame| !matches!(frame, Frame::Entry { .. })).count()
+    }
+
+    /// Returns the underlying source
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next event
+    ///
+    /// # Returns
+    /// The next event, or `None` once the value is complete
+    ///
+    /// # Errors
+    /// Fails if the source fails or ends early, the bytes are not valid GTV, the value is
+    /// nested too deep, or bytes follow the value
+    pub fn next_event(&mut self) -> Result<Option<GtvEvent>, StreamError> {
+        if self.finished {
+            return Ok(None);
+        }
+        if self.started && self.stack.is_empty() {
+            return self.finish().map(|_| None);
+        }
+        self.started = true;
+
+        match self.stack.last().copied() {
+            Some(Frame::Array { end }) if self.position == end => {
+                self.stack.pop();
+                self.complete_value()?;
+                Ok(Some(GtvEvent::EndArray))
+            }
+            Some(Frame::Dict { end }) if self.position == end => {
+                self.stack.pop();
+                self.complete_value()?;
+                Ok(Some(GtvEvent::EndDict))
+            }
+            Some(Frame::Dict { .. }) => self.read_key().map(Some),
+            Some(Frame::Entry { end }) if self.position == end => Err(parse_error(ParseErrorKind::ShortData { needed: 1 })),
+            _ => self.read_element().map(Some),
+        }
+    }
+
+    /// Reads the next complete value, including all elements of an array or dictionary
+    ///
+    /// Must be called where a value comes next: before the first event, inside an array,
+    /// or after a `Key` event.
+    ///
+    /// # Returns
+    /// The value, or `None` if the current array or dictionary ends instead, in which case
+    /// its end event is consumed
+    ///
+    /// # Errors
+    /// `StreamError::KeyPending` when called where a dictionary key comes next, otherwise
+    /// the errors of `next_event`. Trailing bytes are reported with the top-level value.
+    pub fn read_value(&mut self) -> Result<Option<Params>, StreamError> {
+        if let Some(Frame::Dict { end }) = self.stack.last() {
+            if self.position != *end {
+                return Err(StreamError::KeyPending);
+            }
+        }
+
+        let value = match self.next_event()? {
+            None | Some(GtvEvent::EndArray) | Some(GtvEvent::EndDict) => return Ok(None),
+            Some(GtvEvent::Value(value)) => value,
+            Some(GtvEvent::StartArray) => {
+                let mut values = Vec::new();
+                while let Some(value) = self.read_value()? {
+                    values.push(value);
+                }
+                Params::Array(values)
+            }
+            Some(GtvEvent::StartDict) => {
+                let mut entries = BTreeMap::new();
+                while let Some(GtvEvent::Key(key)) = self.next_event()? {
+                    let value = self.read_value()?.ok_or(parse_error(ParseErrorKind::ShortData { needed: 1 }))?;
+                    entries.insert(key, value);
+                }
+                Params::Dict(entries)
+            }
+            Some(GtvEvent::Key(_)) => unreachable!("keys are only read inside dictionaries"),
+        };
+        if self.stack.is_empty() {
+            self.finish()?;
+        }
+        Ok(Some(value))
+    }
+
+    /// Reads an element where a value is expected
+    fn read_element(&mut self) -> Result<GtvEvent, StreamError> {
+        let tag = self.read_byte()?;
+        let (length, length_bytes) = self.read_length()?;
+        let end = self.position + length;
+        self.check_end(end)?;
+
+        match tag {
+            TAG_ARRAY | TAG_DICT => {
+                if self.depth() >= self.max_depth {
+                    let actual = self.depth() + 1;
+                    return Err(parse_error(ParseErrorKind::InvalidSize { min: 0, max: self.max_depth, actual }));
+                }
+                self.expect_tag(TAG_SEQUENCE)?;
+                let (inner_length, _) = self.read_length()?;
+                if self.position + inner_length != end {
+                    return Err(parse_error(ParseErrorKind::InvalidLength));
+                }
+                if tag == TAG_ARRAY {
+                    self.stack.push(Frame::Array { end });
+                    Ok(GtvEvent::StartArray)
+                } else {
+                    self.stack.push(Frame::Dict { end });
+                    Ok(GtvEvent::StartDict)
+                }
+            }
+            TAG_NULL | TAG_BYTE_ARRAY | TAG_STRING | TAG_INTEGER | TAG_BIG_INTEGER => {
+                let mut element = vec![tag];
+                element.extend_from_slice(&length_bytes);
+                self.read_content(length, &mut element)?;
+                let value = decode(&element)?;
+                self.complete_value()?;
+                Ok(GtvEvent::Value(value))
+            }
+            _ => Err(unexpected_tag(tag)),
+        }
+    }
+
+    /// Reads the header and key of a dictionary entry
+    fn read_key(&mut self) -> Result<GtvEvent, StreamError> {
+        self.expect_tag(TAG_SEQUENCE)?;
+        let (length, _) = self.read_length()?;
+        let end = self.position + length;
+        self.check_end(end)?;
+
+        self.expect_tag(TAG_UTF8_STRING)?;
+        let (key_length, _) = self.read_length()?;
+        self.check_end(self.position + key_length)?;
+        let mut key = Vec::new();
+        self.read_content(key_length, &mut key)?;
+        let key = String::from_utf8(key).map_err(|_| parse_error(ParseErrorKind::InvalidValue))?;
+
+        self.stack.push(Frame::Entry { end });
+        Ok(GtvEvent::Key(key))
+    }
+
+    /// Closes the dictionary entry that a value completes
+    fn complete_value(&mut self) -> Result<(), StreamError> {
+        if let Some(Frame::Entry { end }) = self.stack.last().copied() {
+            if self.position != end {
+                return Err(parse_error(ParseErrorKind::ExtraData));
+            }
+            self.stack.pop();
+        }
+        Ok(())
+    }
+
+    /// Checks that nothing follows the value
+    fn finish(&mut self) -> Result<(), StreamError> {
+        self.finished = true;
+        let mut byte = [0u8];
+        if self.reader.read(&mut byte)? != 0 {
+            return Err(parse_error(ParseErrorKind::ExtraData));
+        }
+        Ok(())
+    }
+
+    /// Checks that an element ending at `end` fits in its container
+    fn check_end(&self, end: u64) -> Result<(), StreamError> {
+        match self.stack.last() {
+            Some(frame) if end > frame.end() => Err(parse_error(ParseErrorKind::InvalidLength)),
+            _ => Ok(()),
+        }
+    }
+
+    fn expect_tag(&mut self, expected: u8) -> Result<(), StreamError> {
+        let tag = self.read_byte()?;
+        if tag != expected {
+            return Err(unexpected_tag(tag));
+        }
+        Ok(())
+    }
+
+    /// Reads a DER length, returning it with its encoded bytes
+    fn read_length(&mut self) -> Result<(u64, Vec<u8>), StreamError> {
+        let first = self.read_byte()?;
+        if first < 0x80 {
+            return Ok((first as u64, vec![first]));
+        }
+
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+            return Err(parse_error(ParseErrorKind::InvalidLength));
+        }
+        let mut bytes = vec![first];
+        let mut length = 0u64;
+        for _ in 0..count {
+            let byte = self.read_byte()?;
+            bytes.push(byte);
+            length = (length << 8) | byte as u64;
+        }
+        // DER requires the shortest form
+        if bytes[1] == 0 || length < 0x80 {
+            return Err(parse_error(ParseErrorKind::InvalidLength));
+        }
+        Ok((length, bytes))
+    }
+
+    fn read_byte(&mut self) -> Result<u8, StreamError> {
+        let mut byte = [0u8];
+        self.reader.read_exact(&mut byte)?;
+        self.position += 1;
+        Ok(byte[0])
+    }
+
+    /// Appends `length` bytes to `buffer`, growing it as data arrives rather than up front
+    fn read_content(&mut self, length: u64, buffer: &mut Vec<u8>) -> Result<(), StreamError> {
+        let read = self.reader.by_ref().take(length).read_to_end(buffer)? as u64;
+        self.position += read;
+        if read != length {
+            return Err(StreamError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for GtvReader<R> {
+    type Item = Result<GtvEvent, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.next_event();
+        if event.is_err() {
+            self.finished = true;
+        }
+        event.transpose()
+    }
+}
+
+#[test]
+fn test_gtv_reader() {
+    use crate::encoding::gtv::encode_value;
+    use crate::{dict, gtv_array};
+
+    let value = dict!{
+        "name" => "foo",
+        "items" => gtv_array![1, gtv_array![], dict!{}, vec![1u8, 2], Params::Null],
+        "nested" => dict!{"big" => num_bigint::BigInt::from(u64::MAX)}
+    };
+    let encoded = encode_value(&value).unwrap();
+
+    let events: Vec<GtvEvent> = GtvReader::new(encoded.as_slice()).collect::<Result<_, _>>().unwrap();
+    assert_eq!(events, vec![
+        GtvEvent::StartDict,
+        GtvEvent::Key("items".to_string()),
+        GtvEvent::StartArray,
+        GtvEvent::Value(Params::Integer(1)),
+        GtvEvent::StartArray,
+        GtvEvent::EndArray,
+        GtvEvent::StartDict,
+        GtvEvent::EndDict,
+        GtvEvent::Value(Params::ByteArray(vec![1, 2])),
+        GtvEvent::Value(Params::Null),
+        GtvEvent::EndArray,
+        GtvEvent::Key("name".to_string()),
+        GtvEvent::Value(Params::Text("foo".to_string())),
+        GtvEvent::Key("nested".to_string()),
+        GtvEvent::StartDict,
+        GtvEvent::Key("big".to_string()),
+        GtvEvent::Value(Params::BigInteger(num_bigint::BigInt::from(u64::MAX))),
+        GtvEvent::EndDict,
+        GtvEvent::EndDict,
+    ]);
+
+    let mut reader = GtvReader::new(encoded.as_slice());
+    assert_eq!(reader.read_value().unwrap(), Some(value.clone()));
+    assert_eq!(reader.read_value().unwrap(), None);
+    assert_eq!(reader.position(), encoded.len() as u64);
+
+    // Materialize one entry at a time
+    let mut reader = GtvReader::new(encoded.as_slice());
+    assert_eq!(reader.next_event().unwrap(), Some(GtvEvent::StartDict));
+    assert!(matches!(reader.read_value(), Err(StreamError::KeyPending)));
+    let mut entries = BTreeMap::new();
+    while let Some(GtvEvent::Key(key)) = reader.next_event().unwrap() {
+        assert_eq!(reader.depth(), 1);
+        entries.insert(key, reader.read_value().unwrap().unwrap());
+    }
+    assert_eq!(Params::Dict(entries), value);
+
+    let scalar = encode_value(&Params::Integer(-5)).unwrap();
+    let mut reader = GtvReader::new(scalar.as_slice());
+    assert_eq!(reader.next_event().unwrap(), Some(GtvEvent::Value(Params::Integer(-5))));
+    assert_eq!(reader.next_event().unwrap(), None);
+}
+
+#[test]
+fn test_gtv_reader_malformed_input() {
+    use crate::encoding::gtv::encode_value;
+
+    let corpus = [
+        "", "a5", "a584ffffffff", "a7020500", "a0020500ff", "a503300100", "a50530030201ff",
+        "a40b30093007040161a0020500", "a407300530030c0161", "a40f300d300b0c0161a0020500a0020500",
+        "a4063004a0020500", "a2040c02c328", "a5033001", "a50430023000", "a58103300100",
+    ];
+    for input in corpus {
+        let data = hex::decode(input).unwrap();
+        let result: Result<Vec<_>, _> = GtvReader::new(data.as_slice()).collect();
+        assert!(result.is_err(), "{} was read", input);
+        assert!(GtvReader::new(data.as_slice()).read_value().is_err(), "{} was read as a value", input);
+    }
+
+    let valid = encode_value(&Params::Array(vec![Params::Text("value".to_string()), Params::Array(vec![Params::Integer(7)])])).unwrap();
+    for len in 0..valid.len() {
+        let result: Result<Vec<_>, _> = GtvReader::new(&valid[..len]).collect();
+        assert!(result.is_err(), "prefix of length {} was read", len);
+    }
+
+    let nested = encode_value(&Params::Array(vec![Params::Array(vec![Params::Array(vec![])])])).unwrap();
+    assert!(GtvReader::with_max_depth(nested.as_slice(), 3).read_value().is_ok());
+    assert!(matches!(GtvReader::with_max_depth(nested.as_slice(), 2).read_value(), Err(StreamError::Parse(_))));
+}
@@ -0,0 +1,493 @@
+//! GtvML, the XML representation of GTV used in blockchain configuration files
+//!
+//! Supported elements are `<null/>`, `<int>`, `<bigint>`, `<string>`, `<bytea>` (hex),
+//! `<array>` and `<dict>` with `<entry key="...">` children. Template `<param>` elements
+//! are not supported.
+//!
+//! # Example
+//! ```
+//! use postchain_client::{dict, encoding::gtvml, utils::operation::Params};
+//!
+//! let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+//! <dict>
+//!     <entry key="blockstrategy">
+//!         <dict>
+//!             <entry key="maxblocktime"><int>2000</int></entry>
+//!         </dict>
+//!     </entry>
+//!     <entry key="signers">
+//!         <array>
+//!             <bytea>0350FE40766BC0CE8D08B3F5B810E49A8352FDD458606BD5FAFE5ACDCDC8FF3F57</bytea>
+//!         </array>
+//!     </entry>
+//! </dict>"#;
+//!
+//! let config = gtvml::from_xml(xml).unwrap();
+//! assert_eq!(config.get("blockstrategy"), Some(&dict!{"maxblocktime" => 2000}));
+//! assert_eq!(gtvml::from_xml(&gtvml::to_xml(&config)), Ok(config));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use num_bigint::BigInt;
+
+use crate::encoding::gtv::{format_decimal, DEFAULT_MAX_DEPTH};
+use crate::utils::operation::Params;
+
+/// Indentation of nested elements in `to_xml`
+const INDENT: &str = "    ";
+
+/// Converts GtvML to a value
+///
+/// # Arguments
+/// * `xml` - The GtvML document; the XML declaration and comments are optional
+///
+/// # Returns
+/// The value of the root element
+///
+/// # Errors
+/// Fails with the line and column of the problem for malformed XML, unknown elements,
+/// invalid numbers or hex, dictionary children other than `<entry key="...">`, and
+/// nesting deeper than `DEFAULT_MAX_DEPTH`
+pub fn from_xml(xml: &str) -> Result<Params, String> {
+    let mut parser = XmlParser { input: xml, pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.parse_element(0)?;
+    parser.skip_misc()?;
+    if parser.pos != xml.len() {
+        return Err(parser.error("content after the root element"));
+    }
+    to_params(&root, 0)
+}
+
+/// Converts a value to a GtvML document
+///
+/// Byte arrays are written as uppercase hex. Booleans and decimals are written as
+/// `<int>` and `<string>`, as in the binary encoding.
+///
+/// # Arguments
+/// * `value` - The value to convert
+///
+/// # Returns
+/// The document, starting with an XML declaration
+pub fn to_xml(value: &Params) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+    write_element(value, 0, &mut out);
+    out
+}
+
+fn write_element(value: &Params, indent: usize, out: &mut String) {
+    let pad = INDENT.repeat(indent);
+    let _ = match value {
+        Params::Null => writeln!(out, "{}<null/>", pad),
+        Params::Boolean(val) => writeln!(out, "{}<int>{}</int>", pad, *val as i64),
+        Params::Integer(val) => writeln!(out, "{}<int>{}</int>", pad, val),
+        Params::BigInteger(val) => writeln!(out, "{}<bigint>{}</bigint>", pad, val),
+        Params::Decimal(val) => writeln!(out, "{}<string>{}</string>", pad, format_decimal(val)),
+        Params::Text(val) => writeln!(out, "{}<string>{}</string>", pad, escape(val, false)),
+        Params::ByteArray(val) => writeln!(out, "{}<bytea>{}</bytea>", pad, hex::encode_upper(val)),
+        Params::Array(val) if val.is_empty() => writeln!(out, "{}<array/>", pad),
+        Params::Dict(val) if val.is_empty() => writeln!(out, "{}<dict/>", pad),
+        Params::Array(val) => {
+            let _ = writeln!(out, "{}<array>", pad);
+            for item in val {
+                write_element(item, indent + 1, out);
+            }
+            writeln!(out, "{}</array>", pad)
+        }
+        Params::Dict(val) => {
+            let _ = writeln!(out, "{}<dict>", pad);
+            for (key, item) in val {
+                let _ = writeln!(out, "{}{}<entry key=\"{}\">", pad, INDENT, escape(key, true));
+                write_element(item, indent + 2, out);
+                let _ = writeln!(out, "{}{}</entry>", pad, INDENT);
+            }
+            writeln!(out, "{}</dict>", pad)
+        }
+    };
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// An XML element, with the position of its start tag for error messages
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+    line: usize,
+    column: usize,
+}
+
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn error(&self, message: impl std::fmt::Display) -> String {
+        format!("<{}> at {}:{}: {}", self.name, self.line, self.column, message)
+    }
+
+    fn text(&self) -> Result<String, String> {
+        let mut text = String::new();
+        for child in &self.children {
+            match child {
+                Node::Text(val) => text.push_str(val),
+                Node::Element(element) => return Err(self.error(format!("unexpected <{}>", element.name))),
+            }
+        }
+        Ok(text)
+    }
+
+    fn elements(&self) -> Result<Vec<&Element>, String> {
+        self.children.iter()
+            .filter_map(|child| match child {
+                Node::Element(element) => Some(Ok(element)),
+                Node::Text(text) if text.trim().is_empty() => None,
+                Node::Text(text) => Some(Err(self.error(format!("unexpected text {:?}", text.trim())))),
+            })
+            .collect()
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+fn to_params(element: &Element, depth: usize) -> Result<Params, String> {
+    match element.name.as_str() {
+        "null" => match element.elements()?.first() {
+            None => Ok(Params::Null),
+            Some(child) => Err(element.error(format!("unexpected <{}>", child.name))),
+        },
+        "string" => element.text().map(Params::Text),
+        "int" => {
+            let text = element.text()?;
+            text.trim().parse::<i64>().map(Params::Integer).map_err(|_| element.error(format!("invalid integer {:?}", text)))
+        }
+        "bigint" => {
+            let text = element.text()?;
+            let digits = text.trim();
+            let is_integer = !digits.is_empty() && digits.strip_prefix('-').unwrap_or(digits).bytes().all(|b| b.is_ascii_digit());
+            BigInt::parse_bytes(digits.as_bytes(), 10)
+                .filter(|_| is_integer)
+                .map(Params::BigInteger)
+                .ok_or_else(|| element.error(format!("invalid integer {:?}", text)))
+        }
+        "bytea" => {
+            let text = element.text()?;
+            hex::decode(text.trim()).map(Params::ByteArray).map_err(|_| element.error(format!("invalid hex {:?}", text)))
+        }
+        "array" | "dict" if depth >= DEFAULT_MAX_DEPTH => Err(element.error("nested too deep")),
+        "array" => element.elements()?.into_iter()
+            .map(|child| to_params(child, depth + 1))
+            .collect::<Result<_, _>>()
+            .map(Params::Array),
+        "dict" => {
+            let mut entries = BTreeMap::new();
+            for entry in element.elements()? {
+                if entry.name != "entry" {
+                    return Err(entry.error("expected <entry>"));
+                }
+                let key = entry.attribute("key").ok_or_else(|| entry.error("missing key attribute"))?;
+                let value = match entry.elements()?.as_slice() {
+                    [value] => to_params(value, depth + 1)?,
+                    _ => return Err(entry.error("expected exactly one value")),
+                };
+                if entries.insert(key.to_string(), value).is_some() {
+                    return Err(entry.error(format!("duplicate key {:?}", key)));
+                }
+            }
+            Ok(Params::Dict(entries))
+        }
+        "param" => Err(element.error("template parameters are not supported")),
+        _ => Err(element.error("unknown element")),
+    }
+}
+
+/// Minimal XML reader for the subset GtvML uses: elements, attributes, text, entity and
+/// character references, CDATA sections, comments and processing instructions
+struct XmlParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl XmlParser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn position(&self) -> (usize, usize) {
+        let before = &self.input[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rfind('\n').map_or(before, |newline| &before[newline + 1..]).chars().count() + 1;
+        (line, column)
+    }
+
+    fn error(&self, message: &str) -> String {
+        let (line, column) = self.position();
+        format!("{}:{}: {}", line, column, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips past `terminator`, failing if it never comes
+    fn skip_past(&mut self, terminator: &str, what: &str) -> Result<(), String> {
+        match self.rest().find(terminator) {
+            Some(index) => {
+                self.pos += index + terminator.len();
+                Ok(())
+            }
+            None => Err(self.error(&format!("unterminated {}", what))),
+        }
+    }
+
+    /// Skips whitespace, comments, processing instructions and the XML declaration
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>", "processing instruction")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->", "comment")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        if !self.rest().starts_with(expected) {
+            return Err(self.error(&format!("expected {:?}", expected)));
+        }
+        self.pos += expected.len();
+        Ok(())
+    }
+
+    fn read_name(&mut self) -> Result<String, String> {
+        let len = self.rest()
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    fn parse_element(&mut self, depth: usize) -> Result<Element, String> {
+        // Each GTV level takes at most two elements, a container and an entry
+        if depth > 2 * DEFAULT_MAX_DEPTH + 1 {
+            return Err(self.error("nested too deep"));
+        }
+        let (line, column) = self.position();
+        self.expect("<")?;
+        let name = self.read_name()?;
+        let mut element = Element { name, attributes: Vec::new(), children: Vec::new(), line, column };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.read_name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error("expected a quoted attribute value")),
+            };
+            self.pos += 1;
+            let end = self.rest().find(quote).ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = self.unescape(&self.rest()[..end])?;
+            self.pos += end + 1;
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error(&format!("missing </{}>", element.name)));
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.read_name()?;
+                if name != element.name {
+                    return Err(self.error(&format!("expected </{}>, found </{}>", element.name, name)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "comment")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").ok_or_else(|| self.error("unterminated CDATA section"))?;
+                element.children.push(Node::Text(cdata[..end].to_string()));
+                self.pos += "<![CDATA[".len() + end + "]]>".len();
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>", "processing instruction")?;
+            } else if rest.starts_with('<') {
+                element.children.push(Node::Element(self.parse_element(depth + 1)?));
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let text = self.unescape(&rest[..end])?;
+                element.children.push(Node::Text(text));
+                self.pos += end;
+            }
+        }
+    }
+
+    /// Replaces entity and character references
+    fn unescape(&self, text: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('&') {
+            out.push_str(&rest[..start]);
+            let end = rest[start..].find(';').ok_or_else(|| self.error("unterminated entity reference"))?;
+            let entity = &rest[start + 1..start + end];
+            let c = match entity {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => entity.strip_prefix("#x").map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error(&format!("unknown entity &{};", entity)))?,
+            };
+            out.push(c);
+            rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+#[test]
+fn test_gtvml_roundtrip() {
+    use crate::{dict, gtv_array};
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<!-- Chain configuration -->
+<dict>
+    <entry key="blockstrategy">
+        <dict>
+            <entry key="name">
+                <string>net.postchain.base.BaseBlockBuildingStrategy</string>
+            </entry>
+            <entry key='maxblocktime'><int> 2000 </int></entry>
+        </dict>
+    </entry>
+    <entry key="gtx">
+        <dict>
+            <entry key="modules">
+                <array>
+                    <string>net.postchain.rell.module.RellPostchainModuleFactory</string>
+                    <string><![CDATA[a < b & "c"]]></string>
+                </array>
+            </entry>
+        </dict>
+    </entry>
+    <entry key="signers">
+        <array>
+            <bytea>0350fe40766bc0ce8d08b3f5b810e49a8352fdd458606bd5fafe5acdcdc8ff3f57</bytea>
+        </array>
+    </entry>
+    <entry key="supply"><bigint>-123456789012345678901234567890</bigint></entry>
+    <entry key="empty"><array/></entry>
+    <entry key="none"><null/></entry>
+    <entry key="text &amp; &#x54;&#105;m"><string> spaced &lt;text&gt; </string></entry>
+    <entry key="blank"><string/></entry>
+</dict>
+"#;
+
+    let expected = dict!{
+        "blockstrategy" => dict!{
+            "name" => "net.postchain.base.BaseBlockBuildingStrategy",
+            "maxblocktime" => 2000
+        },
+        "gtx" => dict!{
+            "modules" => gtv_array!["net.postchain.rell.module.RellPostchainModuleFactory", "a < b & \"c\""]
+        },
+        "signers" => gtv_array![hex::decode("0350fe40766bc0ce8d08b3f5b810e49a8352fdd458606bd5fafe5acdcdc8ff3f57").unwrap()],
+        "supply" => "-123456789012345678901234567890".parse::<BigInt>().unwrap(),
+        "empty" => gtv_array![],
+        "none" => Params::Null,
+        "text & Tim" => " spaced <text> ",
+        "blank" => ""
+    };
+
+    let config = from_xml(xml).unwrap();
+    assert_eq!(config, expected);
+    assert_eq!(from_xml(&to_xml(&config)), Ok(config));
+
+    assert_eq!(to_xml(&dict!{"a\"" => gtv_array![vec![0xabu8], Params::Null, dict!{}], "b" => "<&>"}), "\
+<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>
+<dict>
+    <entry key=\"a&quot;\">
+        <array>
+            <bytea>AB</bytea>
+            <null/>
+            <dict/>
+        </array>
+    </entry>
+    <entry key=\"b\">
+        <string>&lt;&amp;&gt;</string>
+    </entry>
+</dict>
+");
+}
+
+#[test]
+fn test_gtvml_errors() {
+    let cases = [
+        ("", "1:1: expected \"<\""),
+        ("<int>12</int> <int>1</int>", "1:15: content after the root element"),
+        ("<dict>\n  <entry key=\"a\"><int>1</int></dict>", "2:36: expected </entry>, found </dict>"),
+        ("<int>1.5</int>", "<int> at 1:1: invalid integer \"1.5\""),
+        ("<bigint>1e5</bigint>", "<bigint> at 1:1: invalid integer \"1e5\""),
+        ("<bytea>xyz</bytea>", "<bytea> at 1:1: invalid hex \"xyz\""),
+        ("<float>1</float>", "<float> at 1:1: unknown element"),
+        ("<dict><int>1</int></dict>", "<int> at 1:7: expected <entry>"),
+        ("<dict><entry><int>1</int></entry></dict>", "<entry> at 1:7: missing key attribute"),
+        ("<dict><entry key=\"a\"/></dict>", "<entry> at 1:7: expected exactly one value"),
+        ("<dict><entry key=\"a\"><null/></entry><entry key=\"a\"><null/></entry></dict>", "<entry> at 1:37: duplicate key \"a\""),
+        ("<array>text</array>", "<array> at 1:1: unexpected text \"text\""),
+        ("<string>a<int>1</int></string>", "<string> at 1:1: unexpected <int>"),
+        ("<string>&nbsp;</string>", "1:9: unknown entity &nbsp;"),
+        ("<param key=\"x\" type=\"int\"/>", "<param> at 1:1: template parameters are not supported"),
+        ("<array>", "1:8: missing </array>"),
+    ];
+    for (xml, error) in cases {
+        assert_eq!(from_xml(xml), Err(error.to_string()), "{}", xml);
+    }
+
+    let deep = format!("{}{}", "<array>".repeat(DEFAULT_MAX_DEPTH + 1), "</array>".repeat(DEFAULT_MAX_DEPTH + 1));
+    assert!(from_xml(&deep).unwrap_err().ends_with("nested too deep"));
+    let very_deep = "<array>".repeat(100_000);
+    assert!(from_xml(&very_deep).unwrap_err().ends_with("nested too deep"));
+}
@@ -1 +1,13 @@
-pub mod gtv;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub mod block;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod gtv;
+#[cfg(feature = "transcode")]
+pub mod gtv_serde;
+#[cfg(feature = "std")]
+pub mod gtv_stream;
+#[cfg(feature = "std")]
+pub mod gtvml;
+#[cfg(feature = "std")]
+pub mod vectors;
@@ -0,0 +1,147 @@
+//! GTV test vectors: values with their expected GTV bytes and hash.
+//!
+//! Vectors are JSON arrays of objects with a `name`, a `value` in tagged GTV JSON (see
+//! `gtv::to_tagged_json`), and the expected `gtv` encoding and `hash` in hex. Either expectation may
+//! be left out. An optional `source` notes where the expectations come from and is not checked.
+//! The regression vectors in `tests/vectors/gtv.json` are self-generated from this crate's own
+//! tests and output, not from another client, so passing them says nothing about compatibility.
+//! Vectors from other clients can be run the same way once their values are written as tagged
+//! GTV JSON.
+//!
+//! # Example
+//! ```
+//! # use postchain_client::encoding::vectors;
+//! let failures = vectors::run_vectors(r#"[
+//!     {"name": "text", "value": "a", "gtv": "a2030c0161"},
+//!     {"name": "wrong", "value": 1, "gtv": "a303020102"}
+//! ]"#).unwrap();
+//!
+//! assert_eq!(failures.len(), 1);
+//! assert_eq!(failures[0].name, "wrong");
+//! ```
+
+use std::fmt;
+
+use crate::encoding::gtv;
+use crate::utils::hasher::gtv_hash;
+use crate::utils::operation::Params;
+
+/// A value with the encoding and hash it is expected to have.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestVector {
+    /// Name identifying the vector in failures
+    pub name: String,
+    /// The value
+    pub value: Params,
+    /// The expected GTV encoding, if given
+    pub gtv: Option<Vec<u8>>,
+    /// The expected GTV hash, if given
+    pub hash: Option<[u8; 32]>,
+}
+
+/// A vector whose value does not encode, decode or hash as expected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorFailure {
+    /// Name of the vector
+    pub name: String,
+    /// What differed, with the expected and actual hex
+    pub message: String,
+}
+
+impl fmt::Display for VectorFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.message)
+    }
+}
+
+/// Parses vectors from their JSON form.
+///
+/// # Arguments
+/// * `json` - A JSON array of vector objects
+///
+/// # Errors
+/// A message naming the vector if the JSON is invalid, a value is not tagged GTV JSON,
+/// or an expectation is not valid hex
+pub fn parse_vectors(json: &str) -> Result<Vec<TestVector>, String> {
+    let json: serde_json::Value = serde_json::from_str(json).map_err(|error| error.to_string())?;
+    let entries = json.as_array().ok_or("Vectors must be a JSON array")?;
+    entries.iter().enumerate().map(|(index, entry)| {
+        let name = entry.get("name").and_then(serde_json::Value::as_str)
+            .map(String::from)
+            .unwrap_or_else(|| format!("#{}", index));
+        let hex_field = |key: &str| entry.get(key)
+            .map(|hex_str| hex_str.as_str().and_then(|hex_str| hex::decode(hex_str).ok())
+                .ok_or_else(|| format!("{}: {} must be a hex string", name, key)))
+            .transpose();
+
+        let value = entry.get("value").ok_or_else(|| format!("{}: missing value", name))?;
+        let value = gtv::from_tagged_json(value).map_err(|error| format!("{}: {}", name, error))?;
+        let hash = hex_field("hash")?
+            .map(|hash| <[u8; 32]>::try_from(hash).map_err(|_| format!("{}: hash must be 32 bytes", name)))
+            .transpose()?;
+        Ok(TestVector { gtv: hex_field("gtv")?, hash, value, name })
+    }).collect()
+}
+
+/// Parses and checks vectors, returning the ones that fail.
+///
+/// # Arguments
+/// * `json` - A JSON array of vector objects
+///
+/// # Returns
+/// The failures, empty if every vector matches
+///
+/// # Errors
+/// A message if the vectors cannot be parsed, as for `parse_vectors`
+pub fn run_vectors(json: &str) -> Result<Vec<VectorFailure>, String> {
+    Ok(parse_vectors(json)?.iter().filter_map(|vector| vector.check().err()).collect())
+}
+
+impl TestVector {
+    /// Checks that the value encodes to the expected bytes, that the bytes decode back to
+    /// the value, and that the value hashes to the expected hash.
+    ///
+    /// # Errors
+    /// A `VectorFailure` describing the first mismatch
+    pub fn check(&self) -> Result<(), VectorFailure> {
+        let fail = |message: String| VectorFailure { name: self.name.clone(), message };
+
+        if let Some(expected) = &self.gtv {
+            let encoded = gtv::encode_value(&self.value).map_err(|error| fail(format!("encoding failed: {:?}", error)))?;
+            if &encoded != expected {
+                return Err(fail(format!("encoded {}, expected {}", hex::encode(encoded), hex::encode(expected))));
+            }
+            match gtv::decode(expected) {
+                Ok(decoded) if decoded == self.value => {}
+                Ok(decoded) => return Err(fail(format!("decoded {:?}, expected {:?}", decoded, self.value))),
+                Err(error) => return Err(fail(format!("decoding failed: {}", error))),
+            }
+        }
+        if let Some(expected) = &self.hash {
+            let hash = gtv_hash(&self.value).map_err(|error| fail(format!("hashing failed: {:?}", error)))?;
+            if &hash != expected {
+                return Err(fail(format!("hashed {}, expected {}", hex::encode(hash), hex::encode(expected))));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_regression_vectors() {
+    let failures = run_vectors(include_str!("../../tests/vectors/gtv.json")).unwrap();
+    assert!(failures.is_empty(), "{}", failures.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"));
+}
+
+#[test]
+fn test_vectors_errors() {
+    assert_eq!(parse_vectors("{}"), Err("Vectors must be a JSON array".to_string()));
+    assert_eq!(parse_vectors(r#"[{"gtv": "00"}]"#), Err("#0: missing value".to_string()));
+    assert_eq!(parse_vectors(r#"[{"name": "x", "value": 1.5}]"#), Err("x: $: 1.5 is not an integer".to_string()));
+    assert_eq!(parse_vectors(r#"[{"name": "x", "value": 1, "gtv": "0g"}]"#), Err("x: gtv must be a hex string".to_string()));
+    assert_eq!(parse_vectors(r#"[{"name": "x", "value": 1, "hash": "00"}]"#), Err("x: hash must be 32 bytes".to_string()));
+
+    let failures = run_vectors(&format!(r#"[{{"name": "x", "value": 1, "hash": "{}"}}]"#, "00".repeat(32))).unwrap();
+    assert!(failures[0].to_string().starts_with("x: hashed "));
+}
+
@@ -1,4 +1,16 @@
+// Tests link `std` for the harness anyway, so they keep its prelude without the feature
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod encoding;
+#[cfg(feature = "std")]
 pub mod transport;
 pub mod utils;
-pub use postchain_client_derive::StructMetadata;
\ No newline at end of file
+pub use postchain_client_derive::StructMetadata;
+
+/// Paths used by the exported macros, so they also expand in `no_std` crates
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{collections::BTreeMap, string::ToString, vec, vec::Vec};
+}
@@ -10,12 +10,16 @@ extern crate serde_json;
 extern crate url;
 
 use reqwest::{header::CONTENT_TYPE, Client};
+use rand::Rng;
 use url::Url;
 
 use serde_json::Value;
-use std::{error::Error, time::Duration};
+use std::{collections::HashMap, error::Error, sync::{Arc, Mutex, OnceLock}, time::Duration};
+#[cfg(unix)]
+use std::path::PathBuf;
+use tokio::sync::{oneshot, Semaphore};
 
-use crate::utils::transaction::{Transaction, TransactionStatus};
+use crate::utils::transaction::{Transaction, TransactionError, TransactionStatus, TxStatusDetail};
 
 /// A REST client for interacting with Postchain blockchain nodes.
 /// 
@@ -28,16 +32,200 @@ use crate::utils::transaction::{Transaction, TransactionStatus};
 pub struct RestClient<'a> {
     /// List of node URLs to connect to
     pub node_url: Vec<&'a str>,
+    /// Weighted node entries; when not empty they are used instead of `node_url`
+    pub nodes: Vec<NodeEntry>,
     /// Request timeout in seconds
     pub request_time_out: u64,
     /// Number of attempts to poll for transaction status
     pub poll_attemps: u64,
     /// Interval between poll attempts in seconds
-    pub poll_attemp_interval_time: u64
+    pub poll_attemp_interval_time: u64,
+    /// Use HTTP/2 without negotiation (the nodes must speak HTTP/2 cleartext or TLS ALPN)
+    pub http2_prior_knowledge: bool,
+    /// Interval in seconds between HTTP/2 keep-alive pings, `None` disables them
+    pub http2_keep_alive_interval: Option<u64>,
+    /// Timeout in seconds to wait for a keep-alive ping acknowledgement before closing the connection
+    pub http2_keep_alive_timeout: Option<u64>,
+    /// Whether HTTP/2 keep-alive pings are also sent when there are no open streams
+    pub http2_keep_alive_while_idle: bool,
+    /// How redirects issued by nodes are handled
+    pub redirect_policy: RedirectPolicy,
+    /// How connections to the nodes are established
+    pub connector: Connector,
+    /// Share one outgoing request between identical queries issued concurrently, off by default
+    pub coalesce_queries: bool,
+    /// HTTP client shared by all requests, built from the settings above on first use;
+    /// changing those settings afterwards has no effect on this client
+    #[doc(hidden)]
+    pub http_client: HttpClientCache,
+    /// Queries currently in flight, used when `coalesce_queries` is enabled
+    #[doc(hidden)]
+    pub in_flight_queries: InFlightQueries,
+    /// Maximum number of simultaneous in-flight requests, `None` means unlimited; `Some(0)` is treated as `Some(1)`
+    pub max_concurrent_requests: Option<usize>,
+    /// Semaphore enforcing `max_concurrent_requests`, created on first use
+    #[doc(hidden)]
+    pub request_limiter: RequestLimiter,
+    /// Fail every network call immediately with `TypeError::Offline`
+    pub offline: bool,
+    /// Largest encoded transaction `send_transaction` will submit, in bytes; `None` disables the check
+    pub max_transaction_size: Option<usize>
 }
 
+/// Default value of `RestClient::max_transaction_size`, the default limit of a Postchain node (25 MiB).
+pub const DEFAULT_MAX_TRANSACTION_SIZE: usize = 25 * 1024 * 1024;
+
+/// Client-wide limit on simultaneous in-flight requests.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct RequestLimiter(OnceLock<Semaphore>);
+
+/// Key identifying an identical query: endpoint prefix, blockchain RID, URL parameters and encoded body.
+type QueryKey = (String, String, Vec<(String, String)>, Vec<u8>);
+
+/// Waiters for the result of a query that is already in flight.
+type QueryWaiters = Vec<oneshot::Sender<Result<RestResponse, RestError>>>;
+
+/// Registry of in-flight queries, shared by all tasks using the same client.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct InFlightQueries(Mutex<HashMap<QueryKey, QueryWaiters>>);
+
+/// Removes an in-flight query entry when the task performing it finishes or is cancelled.
+///
+/// If the task is cancelled, the waiters' senders are dropped and each waiter
+/// falls back to sending the query itself.
+struct InFlightGuard<'c> {
+    queries: &'c InFlightQueries,
+    key: Option<QueryKey>
+}
+
+impl InFlightGuard<'_> {
+    /// Removes the entry and hands the result to every waiter.
+    fn complete(mut self, result: &Result<RestResponse, RestError>) {
+        for waiter in self.take_waiters() {
+            let _ = waiter.send(result.clone());
+        }
+    }
+
+    fn take_waiters(&mut self) -> QueryWaiters {
+        match self.key.take() {
+            Some(key) => self.queries.0.lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .remove(&key)
+                .unwrap_or_default(),
+            None => Vec::new()
+        }
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.take_waiters();
+    }
+}
+
+/// Policy applied when a node answers with an HTTP redirect.
+///
+/// A redirect that is not followed is returned as a `RestError` carrying the 3xx status code.
+#[derive(Clone)]
+pub enum RedirectPolicy {
+    /// Never follow redirects
+    None,
+    /// Follow at most the given number of redirects
+    Limited(usize),
+    /// Decide for each redirect; the closure receives the redirect target and the
+    /// URLs visited so far, and returns whether the redirect should be followed
+    Custom(RedirectFn)
+}
+
+/// Closure deciding whether a redirect is followed, see `RedirectPolicy::Custom`.
+pub type RedirectFn = Arc<dyn Fn(&Url, &[Url]) -> bool + Send + Sync>;
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Limited(10)
+    }
+}
+
+impl std::fmt::Debug for RedirectPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedirectPolicy::None => write!(f, "None"),
+            RedirectPolicy::Limited(max) => write!(f, "Limited({})", max),
+            RedirectPolicy::Custom(_) => write!(f, "Custom(..)")
+        }
+    }
+}
+
+impl RedirectPolicy {
+    /// Converts the policy into the equivalent `reqwest` redirect policy.
+    fn to_reqwest(&self) -> reqwest::redirect::Policy {
+        match self {
+            RedirectPolicy::None => reqwest::redirect::Policy::none(),
+            RedirectPolicy::Limited(max) => reqwest::redirect::Policy::limited(*max),
+            RedirectPolicy::Custom(follow) => {
+                let follow = follow.clone();
+                reqwest::redirect::Policy::custom(move |attempt| {
+                    if follow(attempt.url(), attempt.previous()) {
+                        attempt.follow()
+                    } else {
+                        attempt.stop()
+                    }
+                })
+            }
+        }
+    }
+}
+
+/// Transport used to reach the nodes.
+#[derive(Clone, Default)]
+pub enum Connector {
+    /// Regular TCP connections to the host of each node URL
+    #[default]
+    Tcp,
+    /// Send every request over the Unix domain socket at this path; the host of the
+    /// node URL is only used for the `Host` header
+    #[cfg(unix)]
+    UnixSocket(PathBuf),
+    /// Adjust the underlying `reqwest` client builder, e.g. to install a tunnel
+    /// with `ClientBuilder::connector_layer`
+    Custom(ConnectorFn)
+}
+
+/// Hook configuring the `reqwest` client builder, see `Connector::Custom`.
+pub type ConnectorFn = Arc<dyn Fn(reqwest::ClientBuilder) -> reqwest::ClientBuilder + Send + Sync>;
+
+impl std::fmt::Debug for Connector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Connector::Tcp => write!(f, "Tcp"),
+            #[cfg(unix)]
+            Connector::UnixSocket(path) => write!(f, "UnixSocket({:?})", path),
+            Connector::Custom(_) => write!(f, "Custom(..)")
+        }
+    }
+}
+
+impl Connector {
+    /// Applies the connector to a `reqwest` client builder.
+    fn apply(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        match self {
+            Connector::Tcp => builder,
+            #[cfg(unix)]
+            Connector::UnixSocket(path) => builder.unix_socket(path.clone()),
+            Connector::Custom(configure) => configure(builder)
+        }
+    }
+}
+
+/// Lazily built HTTP client reused across requests so that connections
+/// (and their keep-alive pings) outlive a single call.
+#[derive(Debug, Default)]
+pub struct HttpClientCache(OnceLock<Client>);
+
 /// Response types that can be returned from REST API calls.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum RestResponse {
     /// Plain text response
     String(String),
@@ -47,8 +235,58 @@ pub enum RestResponse {
     Bytes(Vec<u8>),
 }
 
+/// A node reachable through one or more addresses, selected according to its weight.
+///
+/// For each request the entries are ordered by a weighted random draw, so an entry
+/// with weight 3 is tried first three times as often as one with weight 1. The
+/// remaining entries are used for failover. The addresses of an entry (e.g. its IPv4
+/// and IPv6 URLs) are tried in the order given. Entries with weight 0 are only used
+/// when all other entries fail.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeEntry {
+    /// Base URLs of the node, e.g. `http://10.0.0.1:7740` and `http://[fd00::1]:7740`
+    pub addresses: Vec<String>,
+    /// Relative share of the traffic sent to this node first
+    pub weight: u32
+}
+
+impl NodeEntry {
+    /// Creates a node entry.
+    ///
+    /// # Arguments
+    /// * `addresses` - Base URLs of the node
+    /// * `weight` - Relative share of the traffic
+    pub fn new<S: Into<String>>(addresses: impl IntoIterator<Item = S>, weight: u32) -> Self {
+        Self {
+            addresses: addresses.into_iter().map(Into::into).collect(),
+            weight
+        }
+    }
+}
+
+/// Consensus status of one peer of a blockchain, as reported by a node.
+///
+/// Fields the node does not report are left as `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PeerStatus {
+    /// Position of the peer in the blockchain's signer list
+    pub index: usize,
+    /// Consensus state of the peer, e.g. `WaitBlock` or `HaveBlock`
+    pub state: Option<String>,
+    /// Height of the block the peer is working on
+    pub height: Option<u64>,
+    /// Serial number of the status message
+    pub serial: Option<u64>,
+    /// Consensus round
+    pub round: Option<u64>,
+    /// Whether the peer is revolting against the current primary
+    pub revolting: Option<bool>,
+    /// Hex-encoded RID of the block the peer holds, if any
+    pub block_rid: Option<String>
+}
+
 /// HTTP methods supported by the REST client.
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum RestRequestMethod {
     /// HTTP GET method
     GET,
@@ -56,28 +294,57 @@ pub enum RestRequestMethod {
     POST,
 }
 
+/// Request body for `RestClient::raw_request`.
+#[derive(Clone, Debug)]
+pub enum RequestBody {
+    /// JSON body, sent with `Content-Type: application/json`
+    Json(Value),
+    /// Binary body, e.g. a GTV encoded value
+    Bytes(Vec<u8>),
+}
+
 impl<'a> Default for RestClient<'a> {
     fn default() -> Self {
-        return RestClient {
+        RestClient {
             node_url: vec!["http://localhost:7740"],
+            nodes: Vec::new(),
             request_time_out: 30,
             poll_attemps: 5,
-            poll_attemp_interval_time: 5
-        };
+            poll_attemp_interval_time: 5,
+            http2_prior_knowledge: false,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: None,
+            http2_keep_alive_while_idle: false,
+            redirect_policy: RedirectPolicy::default(),
+            connector: Connector::default(),
+            coalesce_queries: false,
+            http_client: HttpClientCache::default(),
+            in_flight_queries: InFlightQueries::default(),
+            max_concurrent_requests: None,
+            request_limiter: RequestLimiter::default(),
+            offline: false,
+            max_transaction_size: Some(DEFAULT_MAX_TRANSACTION_SIZE)
+        }
     }
 }
 
 /// Types of errors that can occur during REST operations
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum TypeError {
     /// Error from the reqwest client
     FromReqClient,
     /// Error from the REST API
     FromRestApi,
+    /// The client is in offline mode and no request was sent
+    Offline,
+    /// The transaction failed client-side validation and was not sent
+    InvalidTransaction,
+    /// The query could not be GTV-encoded and was not sent
+    Encode,
 }
 
 /// Error type for REST operations
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RestError {
     /// HTTP status code if available
     pub status_code: Option<String>,
@@ -93,12 +360,12 @@ impl Error for RestError {}
 
 impl Default for RestError {
     fn default() -> Self {
-        return RestError {
+        RestError {
             status_code: None,
             error_str: None,
             error_json: None,
             type_error: TypeError::FromRestApi,
-        };
+        }
     }
 }
 
@@ -120,6 +387,28 @@ impl std::fmt::Display for RestError {
 }
 
 impl<'a> RestClient<'a> {
+    /// Switches offline mode on or off.
+    ///
+    /// In offline mode every network call returns a `RestError` with
+    /// `TypeError::Offline` immediately, without any DNS lookup or connection attempt.
+    ///
+    /// # Arguments
+    /// * `offline` - Whether the client is offline
+    ///
+    /// # Returns
+    /// * `Self` - The updated client
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::transport::client::RestClient;
+    /// let client = RestClient::default().offline(true);
+    /// assert!(client.offline);
+    /// ```
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
     /// Retrieves a list of node URLs from the blockchain directory.
     ///
     /// # Arguments
@@ -130,6 +419,7 @@ impl<'a> RestClient<'a> {
     ///
     /// # Example
     /// ```no_run
+    /// # use postchain_client::transport::client::RestClient;
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = RestClient::default();
     /// let nodes = client.get_nodes_from_directory("blockchain_rid").await?;
@@ -140,7 +430,7 @@ impl<'a> RestClient<'a> {
         let directory_brid = self.get_blockchain_rid(0).await?;
 
         let path_segments = &["query", &directory_brid];
-        let mut query_params = vec![
+        let query_params = vec![
             ("type", "cm_get_blockchain_api_urls"),
             ("blockchain_rid", brid),
         ];
@@ -151,7 +441,7 @@ impl<'a> RestClient<'a> {
             .postchain_rest_api(
                 RestRequestMethod::GET,
                 Some(path_segments),
-                Some(&mut query_params),
+                Some(&query_params),
                 query_body_json,
                 query_body_raw
             )
@@ -207,6 +497,101 @@ impl<'a> RestClient<'a> {
         }
     }
 
+    // Node statuses
+    // GET /node/{blockchain_rid}/statuses
+    /// Retrieves the consensus status of every peer of a blockchain, as seen by the node.
+    ///
+    /// # Arguments
+    /// * `blockchain_rid` - Blockchain RID
+    ///
+    /// # Returns
+    /// * `Result<Vec<PeerStatus>, RestError>` - Peer statuses ordered by signer index, or error
+    pub async fn get_peer_statuses(&self, blockchain_rid: &str) -> Result<Vec<PeerStatus>, RestError> {
+        let resp = self.postchain_rest_api(RestRequestMethod::GET,
+            Some(&["node", blockchain_rid, "statuses"]),
+            None,
+            None,
+            None).await?;
+
+        match resp {
+            RestResponse::Json(Value::Array(values)) => Ok(values.iter()
+                .enumerate()
+                .map(|(index, value)| parse_peer_status(index, value))
+                .collect()),
+            _ => Err(RestError {
+                error_str: Some("Unexpected response for node statuses".to_string()),
+                type_error: TypeError::FromRestApi,
+                ..Default::default()
+            })
+        }
+    }
+
+    // Node status
+    // GET /node/{blockchain_rid}/my_status
+    /// Retrieves the consensus status of the node the request is sent to.
+    ///
+    /// # Arguments
+    /// * `blockchain_rid` - Blockchain RID
+    ///
+    /// # Returns
+    /// * `Result<PeerStatus, RestError>` - Status of the node, or error
+    pub async fn get_node_status(&self, blockchain_rid: &str) -> Result<PeerStatus, RestError> {
+        let resp = self.postchain_rest_api(RestRequestMethod::GET,
+            Some(&["node", blockchain_rid, "my_status"]),
+            None,
+            None,
+            None).await?;
+
+        match resp {
+            RestResponse::Json(value) => Ok(parse_peer_status(0, &value)),
+            _ => Err(RestError {
+                error_str: Some("Unexpected response for node status".to_string()),
+                type_error: TypeError::FromRestApi,
+                ..Default::default()
+            })
+        }
+    }
+
+    /// Sends a request to any node endpoint, including ones this client does not wrap.
+    ///
+    /// The request goes through the same node failover, redirect policy, concurrency
+    /// limit and response decoding as the other methods.
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method to use
+    /// * `path_segments` - URL path segments, e.g. `&["blocks", brid]`
+    /// * `query` - Optional URL query parameters
+    /// * `body` - Optional request body; required for `POST`
+    ///
+    /// # Returns
+    /// * `Result<RestResponse, RestError>` - Decoded response or error
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use postchain_client::transport::client::{RestClient, RestRequestMethod};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = RestClient::default();
+    /// let query = vec![("limit", "10")];
+    /// let blocks = client.raw_request(RestRequestMethod::GET, &["blocks", "<BLOCKCHAIN_RID>"], Some(&query), None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn raw_request(
+        &self,
+        method: RestRequestMethod,
+        path_segments: &[&str],
+        query: Option<&'a Vec<(&'a str, &'a str)>>,
+        body: Option<RequestBody>
+    ) -> Result<RestResponse, RestError> {
+        let (query_body_json, query_body_raw) = match body {
+            Some(RequestBody::Json(val)) => (Some(val), None),
+            Some(RequestBody::Bytes(bytes)) => (None, Some(bytes)),
+            None => (None, None)
+        };
+
+        self.postchain_rest_api(method, Some(path_segments), query, query_body_json, query_body_raw).await
+    }
+
     /// Prints error information and determines if the error should be ignored.
     ///
     /// # Arguments
@@ -238,7 +623,7 @@ impl<'a> RestClient<'a> {
     ///
     /// # Arguments
     /// * `node_urls` - New list of node URLs to use
-    pub fn update_node_urls(&mut self, node_urls: &'a Vec<String>) {
+    pub fn update_node_urls(&mut self, node_urls: &'a [String]) {
         self.node_url = node_urls.iter().map(String::as_str).collect();
     }
 
@@ -266,11 +651,59 @@ impl<'a> RestClient<'a> {
     /// # Returns
     /// * `Result<TransactionStatus, RestError>` - Transaction status or error
     pub async fn get_transaction_status_with_poll(&self, blockchain_rid: &str, tx_rid: &str, attempts: u64) -> Result<TransactionStatus, RestError> {
+        let detail = self.poll_transaction_status(blockchain_rid, tx_rid, attempts).await?;
+        Ok(detail.status)
+    }
+
+    // Transaction info
+    // GET /transactions/{blockchain_rid}/{transaction_rid}
+    /// Gets the detailed status of a transaction, polling until it leaves the waiting state.
+    ///
+    /// When the transaction is confirmed, the block height, block RID and timestamp of the
+    /// confirming block are fetched from the node's transaction info endpoint. When it is
+    /// rejected, the reject reason reported by the node is included.
+    ///
+    /// # Arguments
+    /// * `blockchain_rid` - Blockchain RID
+    /// * `tx_rid` - Transaction RID
+    ///
+    /// # Returns
+    /// * `Result<TxStatusDetail, RestError>` - Detailed transaction status or error
+    pub async fn get_transaction_status_detail(&self, blockchain_rid: &str, tx_rid: &str) -> Result<TxStatusDetail, RestError> {
+        let mut detail = self.poll_transaction_status(blockchain_rid, tx_rid, 0).await?;
+
+        if detail.status != TransactionStatus::CONFIRMED {
+            return Ok(detail);
+        }
+
+        let resp = self.postchain_rest_api(RestRequestMethod::GET,
+            Some(&["transactions", blockchain_rid, tx_rid]),
+            None,
+            None,
+            None).await?;
+
+        if let RestResponse::Json(val) = resp {
+            fill_block_info(&mut detail, &val);
+        }
+
+        Ok(detail)
+    }
+
+    /// Polls the transaction status endpoint until the transaction is no longer waiting.
+    ///
+    /// # Arguments
+    /// * `blockchain_rid` - Blockchain RID
+    /// * `tx_rid` - Transaction RID
+    /// * `attempts` - Number of polling attempts made so far
+    ///
+    /// # Returns
+    /// * `Result<TxStatusDetail, RestError>` - Status (and reject reason, if any) or error
+    async fn poll_transaction_status(&self, blockchain_rid: &str, tx_rid: &str, attempts: u64) -> Result<TxStatusDetail, RestError> {
         tracing::info!("Waiting for transaction status of blockchain RID: {} with tx: {} | attempt: {}", blockchain_rid, tx_rid, attempts);
 
         if attempts >= self.poll_attemps {
             tracing::warn!("Transaction status still in waiting status after {} attempts", attempts);
-            return Ok(TransactionStatus::WAITING);
+            return Ok(TxStatusDetail::new(TransactionStatus::WAITING));
         }
 
         let resp = self.postchain_rest_api(RestRequestMethod::GET,
@@ -278,35 +711,29 @@ impl<'a> RestClient<'a> {
             None,
             None,
             None).await?;
-        match resp {
-            RestResponse::Json(val) => {
-                let status: serde_json::Map<String, Value> = serde_json::from_value(val).unwrap();
-                if let Some(status_value) = status.get("status") {
-                    let status_value = status_value.as_str();
-                    match status_value {
-                        Some("waiting") => {
-                            // Waiting for transaction rejected or confirmed!!!
-                            // Interval time = 5 secs on each attempt
-                            // Break after 5 attempts
-                            tokio::time::sleep(Duration::from_secs(self.poll_attemp_interval_time)).await;
-                            return Box::pin(self.get_transaction_status_with_poll(blockchain_rid, tx_rid, attempts + 1)).await;
-                        },
-                        Some("confirmed") => {
-                            tracing::info!("Transaction confirmed!");
-                            return Ok(TransactionStatus::CONFIRMED)
-                        },
-                        Some("rejected") => {
-                            tracing::warn!("Transaction rejected!");
-                            return Ok(TransactionStatus::REJECTED)
-                        },
-                        _ => return Ok(TransactionStatus::UNKNOWN)
-                    };
-                }
-                Ok(TransactionStatus::UNKNOWN)
-            }
-            _ => {
-                Ok(TransactionStatus::UNKNOWN)
-            }
+
+        let detail = match resp {
+            RestResponse::Json(val) => parse_status(&val),
+            _ => TxStatusDetail::new(TransactionStatus::UNKNOWN)
+        };
+
+        match detail.status {
+            TransactionStatus::WAITING => {
+                // Waiting for transaction rejected or confirmed!!!
+                // Interval time = 5 secs on each attempt
+                // Break after 5 attempts
+                tokio::time::sleep(Duration::from_secs(self.poll_attemp_interval_time)).await;
+                Box::pin(self.poll_transaction_status(blockchain_rid, tx_rid, attempts + 1)).await
+            },
+            TransactionStatus::CONFIRMED => {
+                tracing::info!("Transaction confirmed!");
+                Ok(detail)
+            },
+            TransactionStatus::REJECTED => {
+                tracing::warn!("Transaction rejected!");
+                Ok(detail)
+            },
+            TransactionStatus::UNKNOWN => Ok(detail)
         }
     }
 
@@ -314,13 +741,56 @@ impl<'a> RestClient<'a> {
     // POST /tx/{blockchainRid}
     /// Sends a transaction to the blockchain.
     ///
+    /// The transaction is rejected without contacting the node when it is malformed
+    /// (see `Transaction::validate`), it changed after being signed (see
+    /// `Transaction::verify_signatures`) or its encoded size exceeds `max_transaction_size`.
+    ///
     /// # Arguments
     /// * `tx` - Transaction to send
     ///
     /// # Returns
     /// * `Result<RestResponse, RestError>` - Response from the blockchain or error
     pub async fn send_transaction(&self, tx: &Transaction<'a>) -> Result<RestResponse, RestError> {
-        let txe = tx.gvt_hex_encoded();
+        if let Err(error) = tx.validate().and_then(|_| tx.verify_signatures()) {
+            let rest_error = RestError {
+                error_str: Some(error.to_string()),
+                type_error: TypeError::InvalidTransaction,
+                ..Default::default()
+            };
+
+            tracing::error!("{:?}", rest_error);
+
+            return Err(rest_error);
+        }
+
+        let encoded_tx = tx.to_bytes().map_err(|error| {
+            let rest_error = RestError {
+                error_str: Some(error.to_string()),
+                type_error: TypeError::InvalidTransaction,
+                ..Default::default()
+            };
+
+            tracing::error!("{:?}", rest_error);
+
+            rest_error
+        })?;
+
+        if let Some(max_size) = self.max_transaction_size {
+            if encoded_tx.len() > max_size {
+                let rest_error = RestError {
+                    error_str: Some(format!("Transaction is {} bytes, which exceeds the maximum transaction size of {} bytes",
+                        encoded_tx.len(), max_size)),
+                    type_error: TypeError::InvalidTransaction,
+                    ..Default::default()
+                };
+
+                tracing::error!("{:?}", rest_error);
+
+                return Err(rest_error);
+            }
+        }
+
+        let txe = hex::encode(encoded_tx);
 
         let resq_body: serde_json::Map<String, Value> =
             vec![("tx".to_string(), serde_json::json!(txe))]
@@ -342,6 +812,40 @@ impl<'a> RestClient<'a> {
             .await
     }
 
+    /// Sends a batch of transactions and waits for all of them to reach a final status.
+    ///
+    /// Transactions are submitted one after the other in the given order. The status
+    /// of every accepted transaction is then polled concurrently, using `poll_attemps`
+    /// and `poll_attemp_interval_time` like `get_transaction_status_with_poll`.
+    ///
+    /// # Arguments
+    /// * `txs` - Transactions to send
+    ///
+    /// # Returns
+    /// One result per transaction, in the same order as `txs`: the last seen status,
+    /// or the error that occurred while submitting or tracking that transaction
+    pub async fn send_transactions(&self, txs: &[Transaction<'a>]) -> Vec<Result<TxStatusDetail, RestError>> {
+        let mut submitted = Vec::with_capacity(txs.len());
+
+        for tx in txs {
+            let submission = match self.send_transaction(tx).await {
+                Ok(_) => tx.tx_rid_hex().map_err(|error| RestError {
+                    error_str: Some(TransactionError::from(error).to_string()),
+                    type_error: TypeError::InvalidTransaction,
+                    ..Default::default()
+                }),
+                Err(error) => Err(error)
+            };
+
+            submitted.push(submission.map(|tx_rid| (hex::encode(&tx.blockchain_rid), tx_rid)));
+        }
+
+        futures_util::future::join_all(submitted.into_iter().map(|submission| async move {
+            let (blockchain_rid, tx_rid) = submission?;
+            self.poll_transaction_status(&blockchain_rid, &tx_rid, 0).await
+        })).await
+    }
+
     // Make a query with GTV encoded response
     // POST /query_gtv/{blockchainRid}
     /// Executes a query on the blockchain.
@@ -365,23 +869,81 @@ impl<'a> RestClient<'a> {
     ) -> Result<RestResponse, RestError> {
         let query_prefix_str = query_prefix.unwrap_or("query_gtv");
 
-        let mut query_args_converted: Option<Vec<(&str, crate::utils::operation::Params)>> = query_args.map(|args| {
-            args.iter()
-                .map(|(key, params)| (key.as_ref(), params.clone()))
-                .collect()
-        });
-
-        let encode_str = crate::encoding::gtv::encode(query_type, query_args_converted.as_mut().map(|v| v.as_mut()));      
+        let encode_str = crate::encoding::gtv::encode_query(query_type, query_args.as_deref().map_or(&[][..], Vec::as_slice))
+            .map_err(|error| RestError {
+                error_str: Some(error.to_string()),
+                type_error: TypeError::Encode,
+                ..Default::default()
+            })?;
         
         tracing::info!("Querying {} to {}", query_type, brid); 
 
-        self.postchain_rest_api(
+        if !self.coalesce_queries {
+            return self.postchain_rest_api(
+                RestRequestMethod::POST,
+                Some(&[query_prefix_str, brid]),
+                query_params.as_deref(),
+                None,
+                Some(encode_str)
+            ).await;
+        }
+
+        let key: QueryKey = (
+            query_prefix_str.to_string(),
+            brid.to_string(),
+            query_params.as_deref().map(|qp| qp.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()).unwrap_or_default(),
+            encode_str.clone()
+        );
+
+        let waiter = {
+            let mut in_flight = self.in_flight_queries.0.lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            match in_flight.get_mut(&key) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    in_flight.insert(key.clone(), Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = waiter {
+            tracing::info!("Query {} to {} is already in flight; waiting for its result", query_type, brid);
+
+            if let Ok(result) = receiver.await {
+                return result;
+            }
+
+            // The task sending the query was cancelled; send it ourselves
+            return self.postchain_rest_api(
+                RestRequestMethod::POST,
+                Some(&[query_prefix_str, brid]),
+                query_params.as_deref(),
+                None,
+                Some(encode_str)
+            ).await;
+        }
+
+        let guard = InFlightGuard { queries: &self.in_flight_queries, key: Some(key) };
+
+        let result = self.postchain_rest_api(
             RestRequestMethod::POST,
             Some(&[query_prefix_str, brid]),
             query_params.as_deref(),
             None,
             Some(encode_str)
-        ).await
+        ).await;
+
+        guard.complete(&result);
+
+        result
     }
 
     /// Makes a REST API request to a Postchain node.
@@ -403,16 +965,38 @@ impl<'a> RestClient<'a> {
         query_body_json: Option<Value>,
         query_body_raw: Option<Vec<u8>>
     ) -> Result<RestResponse, RestError> {
+        if self.offline {
+            return Err(RestError {
+                error_str: Some("Client is in offline mode".to_string()),
+                type_error: TypeError::Offline,
+                ..Default::default()
+            });
+        }
+
+        let node_urls: Vec<&str> = if self.nodes.is_empty() {
+            self.node_url.clone()
+        } else {
+            weighted_order(&self.nodes, &mut rand::thread_rng())
+        };
+
+        if node_urls.is_empty() {
+            return Err(RestError {
+                error_str: Some("No node URL configured".to_string()),
+                type_error: TypeError::FromReqClient,
+                ..Default::default()
+            });
+        }
+
         let mut node_index: usize = 0;
         loop {
             let result = self.postchain_rest_api_with_poll(method,
                 path_segments, query_params,
-                query_body_json.clone(), query_body_raw.clone(), node_index).await;
+                query_body_json.clone(), query_body_raw.clone(), node_urls[node_index]).await;
 
             if let Err(ref error) = result {
                 node_index += 1;
 
-                if node_index >= self.node_url.len() || error.status_code.is_some() {
+                if node_index >= node_urls.len() || error.status_code.is_some() {
                     return result;
                 }
                 tracing::info!("The API endpoint can't be reached; will try another one!");
@@ -422,6 +1006,50 @@ impl<'a> RestClient<'a> {
         }
     }
 
+    /// Returns the underlying HTTP client, building it from the connection settings on first use.
+    ///
+    /// Changing the connection settings after the first request has no effect.
+    ///
+    /// # Returns
+    /// * `Result<Client, RestError>` - HTTP client or error
+    fn http_client(&self) -> Result<Client, RestError> {
+        if let Some(client) = self.http_client.0.get() {
+            return Ok(client.clone());
+        }
+
+        let mut builder = Client::builder()
+            .redirect(self.redirect_policy.to_reqwest())
+            .http2_keep_alive_while_idle(self.http2_keep_alive_while_idle);
+
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(Duration::from_secs(interval));
+        }
+
+        if let Some(timeout) = self.http2_keep_alive_timeout {
+            builder = builder.http2_keep_alive_timeout(Duration::from_secs(timeout));
+        }
+
+        builder = self.connector.apply(builder);
+
+        let client = builder.build().map_err(|error| {
+            let rest_error = RestError {
+                error_str: Some(error.to_string()),
+                type_error: TypeError::FromReqClient,
+                ..Default::default()
+            };
+
+            tracing::error!("{:?}", rest_error);
+
+            rest_error
+        })?;
+
+        Ok(self.http_client.0.get_or_init(|| client).clone())
+    }
+
     /// Makes a REST API request with retry logic for failed nodes.
     ///
     /// # Arguments
@@ -430,7 +1058,7 @@ impl<'a> RestClient<'a> {
     /// * `query_params` - Query parameters
     /// * `query_body_json` - JSON request body
     /// * `query_body_raw` - Raw request body
-    /// * `node_index` - Index of the node to try
+    /// * `node_url` - Base URL of the node to try
     ///
     /// # Returns
     /// * `Result<RestResponse, RestError>` - API response or error
@@ -441,10 +1069,20 @@ impl<'a> RestClient<'a> {
         query_params: Option<&'a Vec<(&'a str, &'a str)>>,
         query_body_json: Option<Value>,
         query_body_raw: Option<Vec<u8>>,
-        node_index: usize,
+        node_url: &str,
     ) -> Result<RestResponse, RestError> {
 
-        let mut url = Url::parse(&self.node_url[node_index]).unwrap();
+        let mut url = Url::parse(node_url).map_err(|error| {
+            let rest_error = RestError {
+                error_str: Some(format!("Invalid node URL {}: {}", node_url, error)),
+                type_error: TypeError::FromReqClient,
+                ..Default::default()
+            };
+
+            tracing::error!("{:?}", rest_error);
+
+            rest_error
+        })?;
 
         tracing::info!("Requesting on API endpoint: {}", url);
 
@@ -479,7 +1117,20 @@ impl<'a> RestClient<'a> {
             });
         }
 
-        let rest_client = Client::new();
+        let rest_client = self.http_client()?;
+
+        // Held until the response body has been read
+        let _permit = match self.max_concurrent_requests {
+            Some(max) => {
+                let semaphore = self.request_limiter.0.get_or_init(|| Semaphore::new(max.max(1)));
+                Some(semaphore.acquire().await.map_err(|error| RestError {
+                    error_str: Some(error.to_string()),
+                    type_error: TypeError::FromReqClient,
+                    ..Default::default()
+                })?)
+            }
+            None => None
+        };
 
         let req_result = match method {
             RestRequestMethod::GET => {
@@ -499,7 +1150,7 @@ impl<'a> RestClient<'a> {
                         .send()
                         .await
                 } else {
-                    let r_body = reqwest::Body::from(query_body_raw.unwrap());
+                    let r_body = reqwest::Body::from(query_body_raw.unwrap_or_default());
                     rest_client
                         .post(url.clone())
                         .timeout(Duration::from_secs(self.request_time_out))
@@ -512,24 +1163,46 @@ impl<'a> RestClient<'a> {
 
         let req_result_match = match req_result {
             Ok(resp) => {
-                let http_status_code = resp.status().to_string();
-                let http_resp_header = resp.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap();
-                let json_resp = http_resp_header.contains("application/json");
-                let octet_stream_resp = http_resp_header.contains("application/octet-stream");
+                let http_status = resp.status();
+                let http_status_code = http_status.to_string();
+                let content_type = resp.headers().get(CONTENT_TYPE)
+                    .and_then(|val| val.to_str().ok())
+                    .map(String::from);
+
+                let body = match resp.bytes().await {
+                    Ok(bytes) => bytes.to_vec(),
+                    Err(error) => {
+                        let rest_error = RestError {
+                            status_code: Some(http_status_code),
+                            error_str: Some(error.to_string()),
+                            type_error: TypeError::FromReqClient,
+                            ..Default::default()
+                        };
+
+                        tracing::error!("{:?}", rest_error);
+
+                        return Err(rest_error);
+                    }
+                };
 
-                if http_status_code.starts_with('4') || http_status_code.starts_with('5') {
+                let rest_resp = decode_body(content_type.as_deref(), body);
+
+                if http_status.is_informational() || http_status.is_redirection()
+                    || http_status.is_client_error() || http_status.is_server_error() {
                     let mut err = RestError {
                         status_code: Some(http_status_code),
                         type_error: TypeError::FromRestApi,
                         ..Default::default()
                     };
 
-                    if json_resp {
-                        let error_json = resp.json().await.unwrap();
-                        err.error_json = Some(error_json);
-                    } else {
-                        let error_str = resp.text().await.unwrap();
-                        err.error_str = Some(error_str);
+                    match rest_resp {
+                        RestResponse::Json(val) => err.error_json = Some(val),
+                        RestResponse::String(val) => err.error_str = Some(val),
+                        RestResponse::Bytes(val) => err.error_str = Some(hex::encode(val))
+                    }
+
+                    if http_status.is_redirection() && err.error_str.is_none() {
+                        err.error_str = Some(format!("Unexpected redirect response from {}", url));
                     }
 
                     tracing::error!("{:?}", err);
@@ -537,19 +1210,6 @@ impl<'a> RestClient<'a> {
                     return Err(err);
                 }
 
-                let rest_resp: RestResponse;
-
-                if json_resp {
-                    let val = resp.json().await.unwrap();
-                    rest_resp = RestResponse::Json(val);
-                } else if octet_stream_resp {
-                    let bytes = resp.bytes().await.unwrap();
-                    rest_resp = RestResponse::Bytes(bytes.to_vec());
-                } else {
-                    let val = resp.text().await.unwrap();
-                    rest_resp = RestResponse::String(val);
-                }
-
                 Ok(rest_resp)
             }
             Err(error) => {
@@ -566,4 +1226,689 @@ impl<'a> RestClient<'a> {
 
         req_result_match
     }
-}
\ No newline at end of file
+}
+
+/// Orders the addresses of weighted node entries for one request.
+///
+/// Uses weighted random sampling without replacement: each entry draws the key
+/// `u^(1/weight)` with `u` uniform in `(0, 1]`, and entries are sorted by descending key.
+///
+/// # Arguments
+/// * `nodes` - Node entries
+/// * `rng` - Random number generator
+///
+/// # Returns
+/// * `Vec<&str>` - Addresses of all entries, in the order they should be tried
+fn weighted_order<'n, R: Rng>(nodes: &'n [NodeEntry], rng: &mut R) -> Vec<&'n str> {
+    let mut keyed: Vec<(f64, &NodeEntry)> = nodes.iter()
+        .map(|node| {
+            let key = if node.weight == 0 {
+                -1.0
+            } else {
+                let u: f64 = 1.0 - rng.gen::<f64>();
+                u.powf(1.0 / node.weight as f64)
+            };
+            (key, node)
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    keyed.into_iter()
+        .flat_map(|(_, node)| node.addresses.iter().map(String::as_str))
+        .collect()
+}
+
+/// Parses one entry of a node status response.
+///
+/// Nodes return each status either as a JSON object or as a string holding a JSON object.
+///
+/// # Arguments
+/// * `index` - Position of the peer in the status list
+/// * `value` - JSON status entry
+///
+/// # Returns
+/// * `PeerStatus` - Parsed status; unknown or missing fields are `None`
+fn parse_peer_status(index: usize, value: &Value) -> PeerStatus {
+    let parsed;
+    let value = match value {
+        Value::String(text) => {
+            parsed = serde_json::from_str(text).unwrap_or(Value::Null);
+            &parsed
+        }
+        _ => value
+    };
+
+    PeerStatus {
+        index,
+        state: value.get("state").and_then(Value::as_str).map(String::from),
+        height: value.get("height").and_then(Value::as_u64),
+        serial: value.get("serial").and_then(Value::as_u64),
+        round: value.get("round").and_then(Value::as_u64),
+        revolting: value.get("revolting").and_then(Value::as_bool),
+        block_rid: value.get("blockRid").and_then(Value::as_str).map(String::from)
+    }
+}
+
+/// Decodes a response body according to its `Content-Type` header.
+///
+/// When the header is missing or not one the client knows, the body is sniffed:
+/// GTV (ASN.1 tagged) or non UTF-8 bodies become `Bytes`, bodies that parse as a
+/// JSON object or array become `Json`, and anything else is returned as `String`.
+/// A body that does not match its declared content type is sniffed as well.
+///
+/// # Arguments
+/// * `content_type` - Value of the `Content-Type` header, if any
+/// * `body` - Raw response body
+///
+/// # Returns
+/// * `RestResponse` - Decoded response
+fn decode_body(content_type: Option<&str>, body: Vec<u8>) -> RestResponse {
+    let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+
+    if content_type.contains("application/octet-stream") {
+        return RestResponse::Bytes(body);
+    }
+
+    if content_type.contains("json") {
+        if let Ok(val) = serde_json::from_slice(&body) {
+            return RestResponse::Json(val);
+        }
+    } else if content_type.starts_with("text/") {
+        return match String::from_utf8(body) {
+            Ok(val) => RestResponse::String(val),
+            Err(error) => RestResponse::Bytes(error.into_bytes())
+        };
+    }
+
+    sniff_body(body)
+}
+
+/// Guesses the kind of a response body from its content.
+///
+/// # Arguments
+/// * `body` - Raw response body
+///
+/// # Returns
+/// * `RestResponse` - `Bytes`, `Json` or `String` response
+fn sniff_body(body: Vec<u8>) -> RestResponse {
+    // GTV values are DER encoded with an explicit context tag (0xa0 ..= 0xa6)
+    if matches!(body.first(), Some(0xa0..=0xa6)) {
+        return RestResponse::Bytes(body);
+    }
+
+    let text = match String::from_utf8(body) {
+        Ok(text) => text,
+        Err(error) => return RestResponse::Bytes(error.into_bytes())
+    };
+
+    let trimmed = text.trim_start();
+
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        if let Ok(val) = serde_json::from_str(trimmed) {
+            return RestResponse::Json(val);
+        }
+    }
+
+    RestResponse::String(text)
+}
+
+/// Parses the JSON body of `GET /tx/{blockchain_rid}/{transaction_rid}/status`.
+///
+/// # Arguments
+/// * `val` - JSON body returned by the node, e.g. `{"status": "rejected", "rejectReason": "..."}`
+///
+/// # Returns
+/// * `TxStatusDetail` - Status and reject reason; block info is left empty
+fn parse_status(val: &Value) -> TxStatusDetail {
+    let status = match val.get("status").and_then(Value::as_str) {
+        Some("waiting") => TransactionStatus::WAITING,
+        Some("confirmed") => TransactionStatus::CONFIRMED,
+        Some("rejected") => TransactionStatus::REJECTED,
+        _ => TransactionStatus::UNKNOWN
+    };
+
+    TxStatusDetail {
+        reject_reason: val.get("rejectReason").and_then(Value::as_str).map(String::from),
+        ..TxStatusDetail::new(status)
+    }
+}
+
+/// Copies the confirming block info from the JSON body of
+/// `GET /transactions/{blockchain_rid}/{transaction_rid}` into `detail`.
+///
+/// # Arguments
+/// * `detail` - Status detail to update
+/// * `val` - JSON body returned by the node
+fn fill_block_info(detail: &mut TxStatusDetail, val: &Value) {
+    detail.block_height = val.get("blockHeight").and_then(Value::as_u64);
+    detail.block_rid = val.get("blockRID").and_then(Value::as_str).map(String::from);
+    detail.timestamp = val.get("timestamp").and_then(Value::as_u64);
+}
+
+#[test]
+fn test_parse_status() {
+    let detail = parse_status(&serde_json::json!({"status": "confirmed"}));
+    assert_eq!(detail, TxStatusDetail::new(TransactionStatus::CONFIRMED));
+
+    let detail = parse_status(&serde_json::json!({"status": "rejected", "rejectReason": "Operation not found"}));
+    assert_eq!(detail.status, TransactionStatus::REJECTED);
+    assert_eq!(detail.reject_reason.as_deref(), Some("Operation not found"));
+
+    let detail = parse_status(&serde_json::json!([]));
+    assert_eq!(detail.status, TransactionStatus::UNKNOWN);
+}
+
+#[test]
+fn test_fill_block_info() {
+    let mut detail = TxStatusDetail::new(TransactionStatus::CONFIRMED);
+    fill_block_info(&mut detail, &serde_json::json!({
+        "blockRID": "d3c1ba1e4c8a4b9e3f0e27d06c0cf7f9a1ba4e5d8e4d6d6b6e2c0a3f6a1d9b1c",
+        "blockHeight": 42,
+        "timestamp": 1736929874000_u64,
+        "txRID": "ab"
+    }));
+
+    assert_eq!(detail.block_height, Some(42));
+    assert_eq!(detail.block_rid.as_deref(), Some("d3c1ba1e4c8a4b9e3f0e27d06c0cf7f9a1ba4e5d8e4d6d6b6e2c0a3f6a1d9b1c"));
+    assert_eq!(detail.timestamp, Some(1736929874000));
+}
+
+#[test]
+fn test_decode_body_with_content_type() {
+    assert!(matches!(decode_body(Some("application/json; charset=utf-8"), b"{\"a\": 1}".to_vec()), RestResponse::Json(_)));
+    assert!(matches!(decode_body(Some("application/octet-stream"), b"{}".to_vec()), RestResponse::Bytes(_)));
+    assert!(matches!(decode_body(Some("text/plain"), b"{}".to_vec()), RestResponse::String(_)));
+    assert!(matches!(decode_body(Some("text/plain"), vec![0xff, 0xfe]), RestResponse::Bytes(_)));
+    // Declared JSON that does not parse falls back to sniffing
+    assert!(matches!(decode_body(Some("application/json"), b"not json".to_vec()), RestResponse::String(_)));
+}
+
+#[test]
+fn test_decode_body_without_content_type() {
+    assert!(matches!(decode_body(None, b" [1, 2, 3]".to_vec()), RestResponse::Json(_)));
+    assert!(matches!(decode_body(None, vec![0xa5, 0x02, 0x30, 0x00]), RestResponse::Bytes(_)));
+    assert!(matches!(decode_body(None, vec![0x00, 0xff]), RestResponse::Bytes(_)));
+    assert!(matches!(decode_body(None, Vec::new()), RestResponse::String(s) if s.is_empty()));
+
+    match decode_body(Some("application/x-unknown"), b"FA189BEBA886669C".to_vec()) {
+        RestResponse::String(s) => assert_eq!(s, "FA189BEBA886669C"),
+        other => panic!("Unexpected response: {:?}", other)
+    }
+}
+
+#[test]
+fn test_redirect_policy_debug() {
+    assert_eq!(format!("{:?}", RedirectPolicy::default()), "Limited(10)");
+    assert_eq!(format!("{:?}", RedirectPolicy::Custom(Arc::new(|url, _| url.scheme() == "https"))), "Custom(..)");
+}
+
+#[test]
+fn test_in_flight_guard_hands_result_to_waiters() {
+    let queries = InFlightQueries::default();
+    let key: QueryKey = ("query_gtv".to_string(), "brid".to_string(), Vec::new(), vec![0xa5]);
+    let (sender, mut receiver) = oneshot::channel();
+    queries.0.lock().unwrap().insert(key.clone(), vec![sender]);
+
+    let guard = InFlightGuard { queries: &queries, key: Some(key) };
+    guard.complete(&Ok(RestResponse::String("ok".to_string())));
+
+    assert!(queries.0.lock().unwrap().is_empty());
+    assert!(matches!(receiver.try_recv(), Ok(Ok(RestResponse::String(s))) if s == "ok"));
+}
+
+#[test]
+fn test_in_flight_guard_drop_releases_waiters() {
+    let queries = InFlightQueries::default();
+    let key: QueryKey = ("query_gtv".to_string(), "brid".to_string(), Vec::new(), vec![0xa5]);
+    let (sender, mut receiver) = oneshot::channel();
+    queries.0.lock().unwrap().insert(key.clone(), vec![sender]);
+
+    drop(InFlightGuard { queries: &queries, key: Some(key) });
+
+    assert!(queries.0.lock().unwrap().is_empty());
+    assert!(matches!(receiver.try_recv(), Err(oneshot::error::TryRecvError::Closed)));
+}
+
+#[tokio::test]
+async fn test_offline_mode() {
+    let client = RestClient {
+        node_url: vec!["http://unreachable.invalid:7740"],
+        ..Default::default()
+    }.offline(true);
+
+    let error = client.get_blockchain_rid(0).await.unwrap_err();
+    assert!(matches!(error.type_error, TypeError::Offline));
+    assert!(error.status_code.is_none());
+
+    let error = client.query("brid", None, "get_name", None, None::<&mut Vec<(&str, crate::utils::operation::Params)>>).await.unwrap_err();
+    assert!(matches!(error.type_error, TypeError::Offline));
+}
+
+#[test]
+fn test_parse_peer_status() {
+    let status = parse_peer_status(1, &serde_json::json!(
+        "{\"serial\":1042,\"height\":15,\"state\":\"WaitBlock\",\"round\":0,\"revolting\":false,\"blockRid\":null}"));
+
+    assert_eq!(status, PeerStatus {
+        index: 1,
+        state: Some("WaitBlock".to_string()),
+        height: Some(15),
+        serial: Some(1042),
+        round: Some(0),
+        revolting: Some(false),
+        block_rid: None
+    });
+
+    let status = parse_peer_status(0, &serde_json::json!({"state": "HaveBlock", "blockRid": "ab01"}));
+    assert_eq!(status.state.as_deref(), Some("HaveBlock"));
+    assert_eq!(status.block_rid.as_deref(), Some("ab01"));
+    assert_eq!(status.height, None);
+
+    assert_eq!(parse_peer_status(2, &serde_json::json!("not json")), PeerStatus { index: 2, ..Default::default() });
+}
+
+#[test]
+fn test_weighted_order() {
+    use rand::SeedableRng;
+
+    let nodes = vec![
+        NodeEntry::new(["http://10.0.0.1:7740", "http://[fd00::1]:7740"], 9),
+        NodeEntry::new(["https://public.node:7740"], 1),
+        NodeEntry::new(["https://backup.node:7740"], 0),
+    ];
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let mut own_first = 0;
+
+    for _ in 0..1000 {
+        let order = weighted_order(&nodes, &mut rng);
+        assert_eq!(order.len(), 4);
+        assert_eq!(order[3], "https://backup.node:7740");
+
+        if order[0] == "http://10.0.0.1:7740" {
+            assert_eq!(order[1], "http://[fd00::1]:7740");
+            own_first += 1;
+        }
+    }
+
+    // Expected share is 90%
+    assert!((850..950).contains(&own_first), "own node first {} times", own_first);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_unix_socket_connector() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let path = std::env::temp_dir().join(format!("postchain-client-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = stream.read(&mut buf).await.unwrap();
+        stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 4\r\n\r\nABCD").await.unwrap();
+    });
+
+    let client = RestClient {
+        node_url: vec!["http://sidecar"],
+        connector: Connector::UnixSocket(path.clone()),
+        ..Default::default()
+    };
+
+    let resp = client.get_blockchain_rid(0).await;
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(resp.unwrap(), "ABCD");
+}
+
+#[tokio::test]
+async fn test_send_transaction_rejects_oversized() {
+    use crate::utils::operation::{Operation, Params};
+
+    let client = RestClient {
+        node_url: vec!["http://unreachable.invalid:7740"],
+        max_transaction_size: Some(1024),
+        ..Default::default()
+    };
+
+    let tx = Transaction::new(vec![1; 32],
+        Some(vec![Operation::from_list("store", vec![Params::ByteArray(vec![0; 2048])])]), None, None);
+
+    let error = client.send_transaction(&tx).await.unwrap_err();
+    assert!(matches!(error.type_error, TypeError::InvalidTransaction));
+    assert!(error.error_str.unwrap().contains("exceeds the maximum transaction size of 1024 bytes"));
+}
+
+#[tokio::test]
+async fn test_send_transaction_rejects_malformed() {
+    let client = RestClient {
+        node_url: vec!["http://unreachable.invalid:7740"],
+        ..Default::default()
+    };
+
+    let tx = Transaction::new(vec![1; 32], None, None, None);
+
+    let error = client.send_transaction(&tx).await.unwrap_err();
+    assert!(matches!(error.type_error, TypeError::InvalidTransaction));
+    assert_eq!(error.error_str.as_deref(), Some("Transaction has no operations"));
+}
+
+#[tokio::test]
+async fn test_send_transactions() {
+    use crate::utils::operation::Operation;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let node_url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 4096];
+                let len = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..len]);
+                let body = if request.starts_with("POST") { "{}" } else { "{\"status\":\"confirmed\"}" };
+                let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body);
+                stream.write_all(response.as_bytes()).await.unwrap();
+            });
+        }
+    });
+
+    let client = RestClient {
+        node_url: vec![&node_url],
+        ..Default::default()
+    };
+
+    let txs = vec![
+        Transaction::new(vec![1; 32], Some(vec![Operation::nop_unique()]), None, None),
+        Transaction::new(vec![1; 32], None, None, None),
+        Transaction::new(vec![1; 32], Some(vec![Operation::nop_unique()]), None, None),
+    ];
+
+    let results = client.send_transactions(&txs).await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().status, TransactionStatus::CONFIRMED);
+    assert!(matches!(results[1].as_ref().unwrap_err().type_error, TypeError::InvalidTransaction));
+    assert_eq!(results[2].as_ref().unwrap().status, TransactionStatus::CONFIRMED);
+}
+
+#[tokio::test]
+async fn test_send_transaction_rejects_stale_signatures() {
+    use crate::utils::operation::Operation;
+
+    let client = RestClient {
+        node_url: vec!["http://unreachable.invalid:7740"],
+        ..Default::default()
+    };
+
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), None, None);
+    tx.sign(&[1u8; 32]).unwrap();
+    tx.operations.as_mut().unwrap().push(Operation::from_list("mint", vec![]));
+
+    let error = client.send_transaction(&tx).await.unwrap_err();
+    assert!(matches!(error.type_error, TypeError::InvalidTransaction));
+    assert!(error.error_str.unwrap().contains("does not match the current transaction contents"));
+}
+
+/// HTTP/1.1 server on a Unix socket that records the requests it receives.
+#[cfg(all(test, unix))]
+struct UnixTestServer {
+    path: PathBuf,
+    /// Request lines, e.g. `POST /query_gtv/brid HTTP/1.1`, in arrival order
+    requests: Arc<Mutex<Vec<String>>>,
+    /// Largest number of requests the server was handling at the same time
+    max_concurrent: Arc<std::sync::atomic::AtomicUsize>
+}
+
+#[cfg(all(test, unix))]
+impl UnixTestServer {
+    /// Starts serving on a fresh socket.
+    ///
+    /// Each request is answered after `delay` with the raw response returned by `respond`
+    /// for its request line and body; `None` closes the connection instead.
+    fn start<F>(name: &str, delay: Duration, respond: F) -> Self
+    where
+        F: Fn(&str, &[u8]) -> Option<String> + Send + Sync + 'static
+    {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let path = std::env::temp_dir().join(format!("postchain-client-{}-{}.sock", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = UnixTestServer { path, requests: Arc::default(), max_concurrent: Arc::default() };
+
+        let respond = Arc::new(respond);
+        let requests = server.requests.clone();
+        let max_concurrent = server.max_concurrent.clone();
+        let concurrent = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let (respond, requests, max_concurrent, concurrent) = (respond.clone(), requests.clone(), max_concurrent.clone(), concurrent.clone());
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    loop {
+                        let head_len = loop {
+                            if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+                                break end + 4;
+                            }
+                            let mut chunk = [0u8; 4096];
+                            match stream.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(len) => buf.extend_from_slice(&chunk[..len])
+                            }
+                        };
+                        let head = String::from_utf8_lossy(&buf[..head_len]).to_string();
+                        let body_len = head.lines()
+                            .filter_map(|line| line.split_once(':'))
+                            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                            .map_or(0, |(_, value)| value.trim().parse().unwrap());
+                        while buf.len() < head_len + body_len {
+                            let mut chunk = [0u8; 4096];
+                            match stream.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(len) => buf.extend_from_slice(&chunk[..len])
+                            }
+                        }
+                        let body: Vec<u8> = buf.drain(..head_len + body_len).skip(head_len).collect();
+                        let request_line = head.lines().next().unwrap_or_default().to_string();
+                        requests.lock().unwrap().push(request_line.clone());
+
+                        let now = concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_concurrent.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(delay).await;
+                        concurrent.fetch_sub(1, Ordering::SeqCst);
+
+                        match respond(&request_line, &body) {
+                            Some(response) => if stream.write_all(response.as_bytes()).await.is_err() { return },
+                            None => return
+                        }
+                    }
+                });
+            }
+        });
+
+        server
+    }
+
+    /// Creates a client sending its requests to this server.
+    fn client<'a>(&self) -> RestClient<'a> {
+        RestClient {
+            node_url: vec!["http://sidecar"],
+            connector: Connector::UnixSocket(self.path.clone()),
+            ..Default::default()
+        }
+    }
+
+    /// Request lines received so far.
+    fn request_lines(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg(all(test, unix))]
+impl Drop for UnixTestServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Builds a raw HTTP/1.1 response.
+#[cfg(all(test, unix))]
+fn test_response(status: &str, headers: &str, body: &str) -> String {
+    format!("HTTP/1.1 {}\r\n{}Content-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}", status, headers, body.len(), body)
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_http_client_built_once() {
+    let server = UnixTestServer::start("client-cache", Duration::ZERO, |_, _| Some(test_response("200 OK", "", "ABCD")));
+    let mut client = server.client();
+
+    assert_eq!(client.get_blockchain_rid(0).await.unwrap(), "ABCD");
+
+    // The client built by the first request keeps using the socket
+    client.connector = Connector::Tcp;
+    assert_eq!(client.get_blockchain_rid(0).await.unwrap(), "ABCD");
+    assert_eq!(server.request_lines().len(), 2);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_http2_prior_knowledge() {
+    let server = UnixTestServer::start("http2", Duration::ZERO, |_, _| None);
+    let client = RestClient { http2_prior_knowledge: true, ..server.client() };
+
+    // The test server only speaks HTTP/1.1, so the request fails after the preface
+    assert!(client.get_blockchain_rid(0).await.is_err());
+    assert_eq!(server.request_lines()[0], "PRI * HTTP/2.0");
+}
+
+/// Starts a server where `/redirect/{n}` redirects to `/redirect/{n - 1}` and `/redirect/0` answers `done`
+#[cfg(all(test, unix))]
+fn redirecting_server(name: &str) -> UnixTestServer {
+    UnixTestServer::start(name, Duration::ZERO, |request_line, _| {
+        let hops: usize = request_line.split(' ').nth(1)?.strip_prefix("/redirect/")?.parse().ok()?;
+        Some(match hops {
+            0 => test_response("200 OK", "", "done"),
+            hops => test_response("302 Found", &format!("Location: /redirect/{}\r\n", hops - 1), "")
+        })
+    })
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_redirect_policy_none() {
+    let server = redirecting_server("redirect-none");
+    let client = RestClient { redirect_policy: RedirectPolicy::None, ..server.client() };
+
+    let error = client.raw_request(RestRequestMethod::GET, &["redirect", "1"], None, None).await.unwrap_err();
+    assert_eq!(error.status_code.as_deref(), Some("302 Found"));
+    assert_eq!(server.request_lines(), ["GET /redirect/1 HTTP/1.1"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_redirect_policy_limited() {
+    let server = redirecting_server("redirect-limited");
+    let client = RestClient { redirect_policy: RedirectPolicy::Limited(2), ..server.client() };
+
+    let resp = client.raw_request(RestRequestMethod::GET, &["redirect", "2"], None, None).await.unwrap();
+    assert!(matches!(resp, RestResponse::String(body) if body == "done"));
+
+    let error = client.raw_request(RestRequestMethod::GET, &["redirect", "3"], None, None).await.unwrap_err();
+    assert!(matches!(error.type_error, TypeError::FromReqClient));
+    // The third redirect, to /redirect/0, is not followed
+    assert_eq!(server.request_lines().len(), 6);
+    assert_eq!(server.request_lines()[5], "GET /redirect/1 HTTP/1.1");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_redirect_policy_custom() {
+    let server = redirecting_server("redirect-custom");
+    let consulted = Arc::new(Mutex::new(Vec::new()));
+    let follow: RedirectFn = {
+        let consulted = consulted.clone();
+        Arc::new(move |url, previous| {
+            consulted.lock().unwrap().push((url.path().to_string(), previous.len()));
+            url.path() != "/redirect/0"
+        })
+    };
+    let client = RestClient { redirect_policy: RedirectPolicy::Custom(follow), ..server.client() };
+
+    let error = client.raw_request(RestRequestMethod::GET, &["redirect", "2"], None, None).await.unwrap_err();
+    assert_eq!(error.status_code.as_deref(), Some("302 Found"));
+    assert_eq!(*consulted.lock().unwrap(), [("/redirect/1".to_string(), 1), ("/redirect/0".to_string(), 2)]);
+    assert_eq!(server.request_lines(), ["GET /redirect/2 HTTP/1.1", "GET /redirect/1 HTTP/1.1"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_query_coalescing() {
+    use crate::utils::operation::Params;
+    use futures_util::future::join_all;
+
+    let server = UnixTestServer::start("coalesce", Duration::from_millis(200), |_, body| Some(test_response("200 OK", "", &hex::encode(body))));
+    let client = RestClient { coalesce_queries: true, ..server.client() };
+
+    let mut args: Vec<Vec<(&str, Params)>> = (0..5).map(|_| vec![("id", Params::Integer(1))]).collect();
+    let results = join_all(args.iter_mut().map(|args| client.query("brid", None, "get_item", None, Some(args)))).await;
+
+    assert_eq!(server.request_lines(), ["POST /query_gtv/brid HTTP/1.1"]);
+    let bodies: Vec<String> = results.into_iter().map(|result| match result.unwrap() {
+        RestResponse::String(body) => body,
+        other => panic!("Unexpected response: {:?}", other)
+    }).collect();
+    assert!(bodies.iter().all(|body| *body == bodies[0]));
+
+    // Queries with other arguments are sent separately and get their own results
+    let mut args: Vec<Vec<(&str, Params)>> = (0..3).map(|id| vec![("id", Params::Integer(id))]).collect();
+    let results = join_all(args.iter_mut().map(|args| client.query("brid", None, "get_item", None, Some(args)))).await;
+
+    assert_eq!(server.request_lines().len(), 4);
+    let bodies: std::collections::HashSet<String> = results.into_iter().map(|result| match result.unwrap() {
+        RestResponse::String(body) => body,
+        other => panic!("Unexpected response: {:?}", other)
+    }).collect();
+    assert_eq!(bodies.len(), 3);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_query_coalescing_disabled() {
+    use crate::utils::operation::Params;
+    use futures_util::future::join_all;
+
+    let server = UnixTestServer::start("no-coalesce", Duration::from_millis(200), |_, _| Some(test_response("200 OK", "", "ok")));
+    let client = server.client();
+    assert!(!client.coalesce_queries);
+
+    let mut args: Vec<Vec<(&str, Params)>> = (0..3).map(|_| vec![("id", Params::Integer(1))]).collect();
+    let results = join_all(args.iter_mut().map(|args| client.query("brid", None, "get_item", None, Some(args)))).await;
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(server.request_lines().len(), 3);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_max_concurrent_requests() {
+    use std::sync::atomic::Ordering;
+
+    for (max_concurrent_requests, expected) in [(None, 2), (Some(1), 1), (Some(0), 1)] {
+        let server = UnixTestServer::start("limiter", Duration::from_millis(150), |_, _| Some(test_response("200 OK", "", "ABCD")));
+        let client = RestClient { max_concurrent_requests, ..server.client() };
+
+        let (first, second) = tokio::join!(client.get_blockchain_rid(0), client.get_blockchain_rid(1));
+
+        assert_eq!((first.unwrap(), second.unwrap()), ("ABCD".to_string(), "ABCD".to_string()));
+        assert_eq!(server.max_concurrent.load(Ordering::SeqCst), expected, "limit {:?}", max_concurrent_requests);
+    }
+}
@@ -0,0 +1,138 @@
+//! Blockchain RID handling.
+//!
+//! A blockchain RID identifies a chain and is always 32 bytes long. Parsing it into a
+//! `BlockchainRid` catches a mistyped RID locally, instead of at the node with a less
+//! obvious error.
+//!
+//! # Example
+//! ```
+//! use postchain_client::utils::{blockchain_rid::BlockchainRid, operation::Operation, transaction::TransactionBuilder};
+//!
+//! let brid: BlockchainRid = "7E5B6E5A1D3B5E4F8E6C0B9A8F7D6C5B4A39281706F5E4D3C2B1A09F8E7D6C5B".parse()
+//!     .expect("Invalid blockchain RID");
+//!
+//! let tx = TransactionBuilder::new()
+//!     .blockchain_rid(brid)
+//!     .add_operation(Operation::from_list("nop", vec![]))
+//!     .build();
+//!
+//! assert_eq!(tx.blockchain_rid, brid.as_bytes());
+//! ```
+
+use std::str::FromStr;
+
+/// Errors that can occur when parsing a blockchain RID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockchainRidError {
+    /// The string is not valid hex
+    InvalidHex(String),
+    /// The RID is not 32 bytes long; holds the actual length
+    InvalidLength(usize),
+}
+
+impl std::fmt::Display for BlockchainRidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockchainRidError::InvalidHex(error) => write!(f, "Blockchain RID is not valid hex: {}", error),
+            BlockchainRidError::InvalidLength(len) => write!(f, "Blockchain RID must be 32 bytes, got {} bytes", len),
+        }
+    }
+}
+
+impl std::error::Error for BlockchainRidError {}
+
+/// A validated 32-byte blockchain RID.
+///
+/// Displays as lowercase hex, the form used in REST API paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockchainRid([u8; 32]);
+
+impl BlockchainRid {
+    /// Parses a hex-encoded blockchain RID, in upper or lower case.
+    ///
+    /// # Arguments
+    /// * `hex` - Blockchain RID as a hex string
+    ///
+    /// # Returns
+    /// The blockchain RID, or an error if the string is not 32 bytes of hex
+    pub fn from_hex(hex: &str) -> Result<Self, BlockchainRidError> {
+        let bytes = hex::decode(hex)
+            .map_err(|error| BlockchainRidError::InvalidHex(error.to_string()))?;
+        Self::try_from(bytes.as_slice())
+    }
+
+    /// Returns the raw bytes of the RID.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Returns the RID as a lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+impl FromStr for BlockchainRid {
+    type Err = BlockchainRidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+impl std::fmt::Display for BlockchainRid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl From<[u8; 32]> for BlockchainRid {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for BlockchainRid {
+    type Error = BlockchainRidError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; 32]>::try_from(bytes)
+            .map(Self)
+            .map_err(|_| BlockchainRidError::InvalidLength(bytes.len()))
+    }
+}
+
+impl TryFrom<Vec<u8>> for BlockchainRid {
+    type Error = BlockchainRidError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl From<BlockchainRid> for Vec<u8> {
+    fn from(brid: BlockchainRid) -> Self {
+        brid.0.to_vec()
+    }
+}
+
+impl AsRef<[u8]> for BlockchainRid {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[test]
+fn test_blockchain_rid_parse() {
+    let hex = "7E5B6E5A1D3B5E4F8E6C0B9A8F7D6C5B4A39281706F5E4D3C2B1A09F8E7D6C5B";
+    let brid: BlockchainRid = hex.parse().unwrap();
+
+    assert_eq!(brid.to_string(), hex.to_lowercase());
+    assert_eq!(BlockchainRid::from_hex(&brid.to_hex()), Ok(brid));
+    assert_eq!(BlockchainRid::try_from(Vec::from(brid)), Ok(brid));
+    assert_eq!(BlockchainRid::from([7u8; 32]).as_bytes(), &[7u8; 32]);
+
+    assert_eq!("abcd".parse::<BlockchainRid>(), Err(BlockchainRidError::InvalidLength(2)));
+    assert!(matches!(hex.replace('E', "G").parse::<BlockchainRid>(), Err(BlockchainRidError::InvalidHex(_))));
+    assert_eq!(BlockchainRid::try_from(&[0u8; 33][..]), Err(BlockchainRidError::InvalidLength(33)));
+}
@@ -0,0 +1,172 @@
+//! Maps keyed by byte arrays.
+//!
+//! GTV dictionaries only have text keys, so Rell encodes a `map<byte_array, V>` as an array
+//! of `[key, value]` pairs. `ByteKeyMap` reads that form into a map with byte array lookups
+//! and converts back to the same `Params` layout, keeping the order of the entries.
+//!
+//! # Example
+//! ```
+//! use postchain_client::{gtv_array, utils::{byte_key_map::ByteKeyMap, operation::Params}};
+//!
+//! let key = vec![0x03u8, 0x73, 0x59];
+//! // As returned by the `test_map_with_bytearray_key` query
+//! let response = gtv_array![gtv_array![key.clone(), "sample_value"]];
+//!
+//! let map = ByteKeyMap::from_params(&response).unwrap();
+//! assert_eq!(map.get(&key), Some(&Params::Text("sample_value".to_string())));
+//! assert_eq!(map.get_hex("037359"), map.get(&key));
+//! assert_eq!(Params::from(map), response);
+//! ```
+
+use crate::utils::operation::Params;
+
+/// A map from byte arrays to values, in the order the entries were inserted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ByteKeyMap {
+    entries: Vec<(Vec<u8>, Params)>,
+}
+
+impl ByteKeyMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a map from its GTV form, an array of `[byte_array, value]` pairs.
+    ///
+    /// # Arguments
+    /// * `value` - The array to read
+    ///
+    /// # Returns
+    /// The map, or `None` if the value is not an array of pairs with byte array keys or has
+    /// duplicate keys
+    pub fn from_params(value: &Params) -> Option<Self> {
+        let mut map = ByteKeyMap::new();
+        for pair in value.as_array()? {
+            let [key, value] = pair.as_array()? else {
+                return None;
+            };
+            if map.insert(key.as_bytes()?.to_vec(), value.clone()).is_some() {
+                return None;
+            }
+        }
+        Some(map)
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&self, key: &[u8]) -> Option<&Params> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, value)| value)
+    }
+
+    /// Returns the value stored under the hex encoded `key`, in either case.
+    ///
+    /// # Returns
+    /// The value, or `None` if `key` is not valid hex or not in the map
+    pub fn get_hex(&self, key: &str) -> Option<&Params> {
+        self.get(&hex::decode(key).ok()?)
+    }
+
+    /// Inserts a value, keeping the position of an existing entry with the same key.
+    ///
+    /// # Returns
+    /// The previous value stored under `key`, if any
+    pub fn insert(&mut self, key: Vec<u8>, value: Params) -> Option<Params> {
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the entry stored under `key`.
+    ///
+    /// # Returns
+    /// The removed value, if any
+    pub fn remove(&mut self, key: &[u8]) -> Option<Params> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Returns true if the map has an entry for `key`.
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &Params)> {
+        self.entries.iter().map(|(key, value)| (key.as_slice(), value))
+    }
+}
+
+impl From<ByteKeyMap> for Params {
+    fn from(map: ByteKeyMap) -> Self {
+        Params::Array(map.entries.into_iter()
+            .map(|(key, value)| Params::Array(vec![Params::ByteArray(key), value]))
+            .collect())
+    }
+}
+
+impl FromIterator<(Vec<u8>, Params)> for ByteKeyMap {
+    fn from_iter<I: IntoIterator<Item = (Vec<u8>, Params)>>(iter: I) -> Self {
+        let mut map = ByteKeyMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl IntoIterator for ByteKeyMap {
+    type Item = (Vec<u8>, Params);
+    type IntoIter = std::vec::IntoIter<(Vec<u8>, Params)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+#[test]
+fn test_byte_key_map() {
+    use crate::{encoding::gtv, gtv_array};
+
+    // Response of the `test_map_with_bytearray_key` query in the test dapp
+    let encoded = "a53b3039a5373035a12304210373599a61cc6b3bc02a78c34313e1737ae9cfd56b9bb24360b437d469efdf3b15a20e0c0c73616d706c655f76616c7565";
+    let key = hex::decode("0373599a61cc6b3bc02a78c34313e1737ae9cfd56b9bb24360b437d469efdf3b15").unwrap();
+
+    let map = ByteKeyMap::from_params(&gtv::decode(&hex::decode(encoded).unwrap()).unwrap()).unwrap();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&key), Some(&Params::Text("sample_value".to_string())));
+    assert_eq!(map.get_hex("0373599A61CC6B3BC02A78C34313E1737AE9CFD56B9BB24360B437D469EFDF3B15"), map.get(&key));
+    assert_eq!(map.get_hex("zz"), None);
+    assert_eq!(hex::encode(gtv::encode_value(&Params::from(map)).unwrap()), encoded);
+
+    let mut map: ByteKeyMap = [(vec![2u8], Params::Integer(2)), (vec![1u8], Params::Integer(1))].into_iter().collect();
+    assert_eq!(map.insert(vec![2u8], Params::Null), Some(Params::Integer(2)));
+    assert!(map.contains_key(&[1u8]));
+    assert_eq!(map.iter().map(|(key, _)| key).collect::<Vec<_>>(), [&[2u8][..], &[1u8][..]]);
+    assert_eq!(Params::from(map.clone()), gtv_array![gtv_array![vec![2u8], Params::Null], gtv_array![vec![1u8], 1]]);
+    assert_eq!(map.remove(&[2u8]), Some(Params::Null));
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [(vec![1u8], Params::Integer(1))]);
+
+    assert_eq!(ByteKeyMap::from_params(&gtv_array![]), Some(ByteKeyMap::new()));
+    for invalid in [
+        Params::Null,
+        gtv_array![gtv_array!["text", 1]],
+        gtv_array![gtv_array![vec![1u8]]],
+        gtv_array![gtv_array![vec![1u8], 1], gtv_array![vec![1u8], 2]],
+    ] {
+        assert_eq!(ByteKeyMap::from_params(&invalid), None, "{:?}", invalid);
+    }
+}
@@ -0,0 +1,325 @@
+//! Verification of transaction confirmation proofs.
+//!
+//! `GET /tx/{blockchain_rid}/{tx_rid}/confirmationProof` returns `{"proof": "<hex>"}`, a GTV
+//! dictionary with:
+//!
+//! * `hash` - hash of the signed transaction, which the block commits to
+//! * `blockHeader` - the encoded header of the block holding the transaction
+//! * `witness` - the block signatures
+//! * `merkleProofTree` - the path from the transaction hash to the Merkle root of the header
+//! * `txIndex` - position of the transaction in the block
+//!
+//! `ConfirmationProof::verify` checks the proof against a header the caller trusts, for
+//! example one whose witness was checked against the known signers, so confirmation
+//! does not rest on the word of the node that answered.
+//!
+//! The proof tree is serialized as nested arrays tagged with their kind: `[100, hash]` for
+//! a subtree known only by its hash, `[101, value]` for the proven value, `[102, left, right]`
+//! for an inner node, and `[103, size, left, right]` or `[104, size, left, right]` for the
+//! root of an array or dictionary.
+
+use std::fmt;
+
+use crate::encoding::block::{BlockError, BlockHeader, BlockWitness};
+use crate::encoding::gtv;
+use crate::utils::hasher::{self, gtv_hash, MerkleNode};
+use crate::utils::operation::Params;
+use crate::utils::transaction::Transaction;
+
+/// Tag of a subtree given by its hash
+const PROOF_HASH: i64 = 100;
+/// Tag of the proven value
+const PROOF_VALUE: i64 = 101;
+/// Tag of an inner node
+const PROOF_NODE: i64 = 102;
+/// Tag of the root of an array
+const PROOF_ARRAY: i64 = 103;
+/// Tag of the root of a dictionary
+const PROOF_DICT: i64 = 104;
+
+/// Errors that can occur when decoding or verifying a confirmation proof.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// The proof bytes are not valid GTV
+    Decode(Box<asn1::ParseError>),
+    /// The proof or the header does not have the expected shape
+    Malformed(String),
+    /// The proof was made for another block header than the trusted one
+    HeaderMismatch,
+    /// The proof is for another transaction, or the same one with other signatures
+    TransactionMismatch,
+    /// The Merkle tree does not prove the transaction hash
+    LeafMismatch,
+    /// The Merkle tree does not lead to the Merkle root of the header
+    RootMismatch {
+        /// Merkle root stated by the header
+        expected: Vec<u8>,
+        /// Root computed from the proof tree
+        computed: [u8; 32],
+    },
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::Decode(error) => write!(f, "Invalid GTV: {}", error),
+            ProofError::Malformed(message) => write!(f, "Malformed proof: {}", message),
+            ProofError::HeaderMismatch => write!(f, "The proof is for another block header"),
+            ProofError::TransactionMismatch => write!(f, "The proof is for another transaction"),
+            ProofError::LeafMismatch => write!(f, "The Merkle tree does not prove the transaction hash"),
+            ProofError::RootMismatch { expected, computed } => write!(
+                f, "The Merkle tree leads to {} instead of the header root {}", hex::encode(computed), hex::encode(expected)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+impl From<asn1::ParseError> for ProofError {
+    fn from(error: asn1::ParseError) -> Self {
+        ProofError::Decode(Box::new(error))
+    }
+}
+
+impl From<BlockError> for ProofError {
+    fn from(error: BlockError) -> Self {
+        match error {
+            BlockError::Decode(error) => ProofError::Decode(error),
+            error => ProofError::Malformed(error.to_string()),
+        }
+    }
+}
+
+/// A confirmation proof, as returned by the node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmationProof {
+    /// Hash of the signed transaction
+    pub tx_hash: Vec<u8>,
+    /// The encoded header of the block holding the transaction
+    pub block_header: Vec<u8>,
+    /// The encoded block witness
+    pub witness: Vec<u8>,
+    /// The serialized Merkle proof tree
+    pub merkle_proof_tree: Params,
+    /// Position of the transaction in the block, as stated by the node. It is not verified:
+    /// the header commits to the transaction hashes but not to their count, so the path
+    /// through the proof tree does not pin down the position.
+    pub tx_index: u64,
+}
+
+/// A transaction confirmation that passed verification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedConfirmation {
+    /// Hash of the signed transaction
+    pub tx_hash: [u8; 32],
+    /// RID of the block, the hash of its header
+    pub block_rid: [u8; 32],
+    /// The decoded header of the block
+    pub header: BlockHeader,
+    /// The block signatures carried by the proof
+    pub witness: BlockWitness,
+}
+
+impl ConfirmationProof {
+    /// Decodes the GTV bytes of a proof.
+    ///
+    /// # Arguments
+    /// * `data` - The proof, the hex-decoded `proof` field of the endpoint response
+    ///
+    /// # Errors
+    /// `ProofError` if `data` is not valid GTV or a field is missing
+    pub fn decode(data: &[u8]) -> Result<ConfirmationProof, ProofError> {
+        ConfirmationProof::from_params(&gtv::decode(data)?)
+    }
+
+    /// Reads a proof from its already decoded dictionary.
+    ///
+    /// # Errors
+    /// `ProofError::Malformed` if a field is missing or of the wrong kind
+    pub fn from_params(value: &Params) -> Result<ConfirmationProof, ProofError> {
+        let bytes = |key: &str| value.get(key).and_then(Params::as_bytes).map(<[u8]>::to_vec)
+            .ok_or_else(|| ProofError::Malformed(format!("{} must be a byte array", key)));
+        Ok(ConfirmationProof {
+            tx_hash: bytes("hash")?,
+            block_header: bytes("blockHeader")?,
+            witness: bytes("witness")?,
+            merkle_proof_tree: value.get("merkleProofTree").cloned()
+                .ok_or_else(|| ProofError::Malformed("merkleProofTree is missing".to_string()))?,
+            tx_index: value.get("txIndex").and_then(Params::as_i64).and_then(|index| u64::try_from(index).ok())
+                .ok_or_else(|| ProofError::Malformed("txIndex must be a non-negative integer".to_string()))?,
+        })
+    }
+
+    /// Verifies that the transaction is in the block with the trusted header.
+    ///
+    /// Blocks commit to the hash of the signed transaction, so the signatures are needed
+    /// along with the RID to tie the proof to the transaction.
+    ///
+    /// # Arguments
+    /// * `tx_rid` - RID of the transaction
+    /// * `signatures` - Signatures of the transaction, in signer order
+    /// * `block_header` - The encoded header the caller trusts
+    ///
+    /// # Returns
+    /// The verified confirmation, with the decoded header and witness
+    ///
+    /// # Errors
+    /// `ProofError` naming the first check that failed
+    pub fn verify(&self, tx_rid: &[u8], signatures: &[Vec<u8>], block_header: &[u8]) -> Result<VerifiedConfirmation, ProofError> {
+        if self.block_header != block_header {
+            return Err(ProofError::HeaderMismatch);
+        }
+        let tx_rid: [u8; 32] = tx_rid.try_into().map_err(|_| ProofError::Malformed("the tx RID must be 32 bytes".to_string()))?;
+        let tx_hash = hasher::signed_tx_hash(tx_rid, signatures);
+        if self.tx_hash != tx_hash {
+            return Err(ProofError::TransactionMismatch);
+        }
+
+        let mut proven = Vec::new();
+        let computed = proof_root(&self.merkle_proof_tree, &mut proven, gtv::DEFAULT_MAX_DEPTH)?;
+        if proven != [Params::ByteArray(tx_hash.to_vec())] {
+            return Err(ProofError::LeafMismatch);
+        }
+        let header = BlockHeader::decode(block_header)?;
+        if header.merkle_root_hash != computed {
+            return Err(ProofError::RootMismatch { expected: header.merkle_root_hash, computed });
+        }
+
+        let block_rid = gtv_hash(&gtv::decode(block_header)?)
+            .map_err(|error| ProofError::Malformed(format!("the header cannot be hashed: {:?}", error)))?;
+        Ok(VerifiedConfirmation {
+            tx_hash,
+            block_rid,
+            header,
+            witness: BlockWitness::decode(&self.witness)?,
+        })
+    }
+
+    /// Verifies the proof for a signed transaction, like `verify`.
+    ///
+    /// # Errors
+    /// `ProofError` naming the first check that failed, or `ProofError::Malformed` if the
+    /// transaction RID cannot be computed
+    pub fn verify_transaction(&self, tx: &Transaction<'_>, block_header: &[u8]) -> Result<VerifiedConfirmation, ProofError> {
+        let tx_rid = tx.tx_rid().map_err(|error| ProofError::Malformed(format!("the tx RID cannot be computed: {:?}", error)))?;
+        self.verify(&tx_rid, tx.signatures.as_deref().unwrap_or_default(), block_header)
+    }
+}
+
+/// Computes the Merkle root of a serialized proof tree, collecting the proven values
+fn proof_root(node: &Params, proven: &mut Vec<Params>, remaining_depth: usize) -> Result<[u8; 32], ProofError> {
+    let malformed = || ProofError::Malformed(format!("invalid proof tree node {}", gtv::to_tagged_json(node)));
+    if remaining_depth == 0 {
+        return Err(ProofError::Malformed("the proof tree is nested too deep".to_string()));
+    }
+    let items = node.as_array().ok_or_else(malformed)?;
+    let child = |index: usize, proven: &mut Vec<Params>| items.get(index)
+        .ok_or_else(malformed)
+        .and_then(|child| proof_root(child, proven, remaining_depth - 1));
+
+    match (items.first().and_then(Params::as_i64), items.len()) {
+        (Some(PROOF_HASH), 2) => items[1].as_bytes().and_then(|hash| hash.try_into().ok()).ok_or_else(malformed),
+        (Some(PROOF_VALUE), 2) => {
+            proven.push(items[1].clone());
+            gtv_hash(&items[1]).map_err(|error| ProofError::Malformed(format!("the proven value cannot be hashed: {:?}", error)))
+        }
+        (Some(PROOF_NODE), 3) => Ok(hasher::merkle_node_hash(MerkleNode::Inner, child(1, proven)?, child(2, proven)?)),
+        (Some(PROOF_ARRAY), 4) => Ok(hasher::merkle_node_hash(MerkleNode::Array, child(2, proven)?, child(3, proven)?)),
+        (Some(PROOF_DICT), 4) => Ok(hasher::merkle_node_hash(MerkleNode::Dict, child(2, proven)?, child(3, proven)?)),
+        _ => Err(malformed()),
+    }
+}
+
+/// Builds the proof tree of `hashes[index]` in the array of transaction hashes, pairing
+/// nodes layer by layer as the Merkle tree of an array does
+#[cfg(test)]
+fn build_proof_tree(hashes: &[[u8; 32]], index: usize) -> Params {
+    use crate::gtv_array;
+
+    let mut layer: Vec<(Params, [u8; 32])> = hashes.iter().enumerate()
+        .map(|(position, hash)| match position == index {
+            true => (gtv_array![PROOF_VALUE, hash.to_vec()], gtv_hash(&Params::ByteArray(hash.to_vec())).unwrap()),
+            false => {
+                let leaf = gtv_hash(&Params::ByteArray(hash.to_vec())).unwrap();
+                (gtv_array![PROOF_HASH, leaf.to_vec()], leaf)
+            }
+        })
+        .collect();
+    while layer.len() > 2 {
+        layer = layer.chunks(2)
+            .map(|pair| match pair {
+                [(left, left_hash), (right, right_hash)] => (
+                    gtv_array![PROOF_NODE, left.clone(), right.clone()],
+                    hasher::merkle_node_hash(MerkleNode::Inner, *left_hash, *right_hash),
+                ),
+                [single] => single.clone(),
+                _ => unreachable!("chunks have one or two elements"),
+            })
+            .collect();
+    }
+    let mut children = layer.into_iter().map(|(node, _)| node);
+    let left = children.next().unwrap();
+    let right = children.next().unwrap_or_else(|| gtv_array![PROOF_HASH, vec![0u8; 32]]);
+    gtv_array![PROOF_ARRAY, hashes.len() as i64, left, right]
+}
+
+#[cfg(test)]
+fn signed_block(transactions: &[Transaction<'_>], index: usize) -> (ConfirmationProof, Vec<u8>) {
+    use crate::{dict, gtv_array};
+
+    let hashes: Vec<[u8; 32]> = transactions.iter()
+        .map(|tx| hasher::signed_tx_hash(tx.tx_rid().unwrap(), tx.signatures.as_deref().unwrap_or_default()))
+        .collect();
+    let root = gtv_hash(&Params::Array(hashes.iter().map(|hash| Params::ByteArray(hash.to_vec())).collect())).unwrap();
+    let header = gtv::encode_value(&gtv_array![vec![1u8; 32], vec![0u8; 32], root.to_vec(), 1736929874000i64, 7, Params::Null, dict!{}]).unwrap();
+    let proof = dict!{
+        "hash" => hashes[index].to_vec(),
+        "blockHeader" => header.clone(),
+        "witness" => vec![0u8, 0, 0, 1, 0, 0, 0, 1, 0xaa, 0, 0, 0, 1, 0xbb],
+        "merkleProofTree" => build_proof_tree(&hashes, index),
+        "txIndex" => index as i64
+    };
+    (ConfirmationProof::decode(&gtv::encode_value(&proof).unwrap()).unwrap(), header)
+}
+
+#[test]
+fn test_confirmation_proof_verify() {
+    use crate::utils::operation::Operation;
+
+    let transactions: Vec<Transaction> = (0..5)
+        .map(|n| Transaction::new(vec![1; 32], Some(vec![Operation::from_list("op", vec![Params::Integer(n)])]), Some(vec![vec![2; 33]]), Some(vec![vec![n as u8; 64]])))
+        .collect();
+    for count in [1, 2, 5] {
+        for index in 0..count {
+            let (proof, header) = signed_block(&transactions[..count], index);
+            let verified = proof.verify_transaction(&transactions[index], &header).unwrap();
+            assert_eq!(verified.tx_hash, hasher::signed_tx_hash(transactions[index].tx_rid().unwrap(), transactions[index].signatures.as_ref().unwrap()));
+            assert_eq!(verified.header.height, 7);
+            assert_eq!(verified.block_rid, gtv_hash(&gtv::decode(&header).unwrap()).unwrap());
+            assert_eq!(verified.witness.signatures.len(), 1);
+        }
+    }
+
+    let (proof, header) = signed_block(&transactions, 2);
+    let tx = &transactions[2];
+    assert_eq!(proof.verify(&tx.tx_rid().unwrap(), tx.signatures.as_ref().unwrap(), &header).unwrap().tx_hash.to_vec(), proof.tx_hash);
+    assert_eq!(proof.verify_transaction(&transactions[3], &header), Err(ProofError::TransactionMismatch));
+    assert_eq!(proof.verify(&tx.tx_rid().unwrap(), &[], &header), Err(ProofError::TransactionMismatch));
+
+    let (_, other_header) = signed_block(&transactions[..4], 2);
+    assert_eq!(proof.verify_transaction(tx, &other_header), Err(ProofError::HeaderMismatch));
+
+    // A proof tree from a block with other transactions does not lead to the header root
+    let mut forged = proof.clone();
+    forged.merkle_proof_tree = signed_block(&transactions[..4], 2).0.merkle_proof_tree;
+    assert!(matches!(forged.verify_transaction(tx, &header), Err(ProofError::RootMismatch { .. })));
+
+    let mut forged = proof.clone();
+    forged.merkle_proof_tree = build_proof_tree(&[[9; 32]], 0);
+    assert_eq!(forged.verify_transaction(tx, &header), Err(ProofError::LeafMismatch));
+
+    let mut forged = proof;
+    forged.merkle_proof_tree = crate::gtv_array![PROOF_NODE, 1];
+    assert_eq!(forged.verify_transaction(tx, &header).unwrap_err().to_string(), "Malformed proof: invalid proof tree node [102,1]");
+}
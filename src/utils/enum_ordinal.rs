@@ -0,0 +1,240 @@
+//! Rell enum values as ordinals.
+//!
+//! Struct conversions turn a unit enum variant into `Params::Text` with its serde name.
+//! Rell also represents enum values by their position in the declaration, which this
+//! module produces when used as a serde `with` attribute. When deserializing it accepts
+//! either form.
+//!
+//! # Example
+//! ```
+//! use postchain_client::utils::operation::{Params, StructMetadata};
+//!
+//! #[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+//! enum Tier {
+//!     #[default]
+//!     Bronze,
+//!     Silver,
+//! }
+//!
+//! #[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, postchain_client::StructMetadata)]
+//! struct Provider {
+//!     #[serde(with = "postchain_client::utils::enum_ordinal")]
+//!     tier: Tier,
+//! }
+//!
+//! let params = Params::from_struct(&Provider { tier: Tier::Silver });
+//! assert_eq!(params.get("tier"), Some(&Params::Integer(1)));
+//! assert_eq!(params.to_struct::<Provider>(), Ok(Provider { tier: Tier::Silver }));
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, IntoDeserializer};
+use serde::ser::{self, Impossible};
+
+/// Serializes a unit enum variant as its ordinal.
+///
+/// # Errors
+/// Fails for values other than unit enum variants
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: serde::Serializer,
+{
+    let ordinal = value.serialize(OrdinalSerializer).map_err(ser::Error::custom)?;
+    serializer.serialize_u32(ordinal)
+}
+
+/// Deserializes a unit enum variant from its ordinal or its name.
+///
+/// # Errors
+/// Fails if the value is neither a known ordinal nor a known variant name
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    deserializer.deserialize_any(OrdinalVisitor(PhantomData))
+}
+
+struct OrdinalVisitor<T>(PhantomData<T>);
+
+impl<'de, T: serde::Deserialize<'de>> de::Visitor<'de> for OrdinalVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an enum ordinal or variant name")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        let ordinal = u32::try_from(value)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))?;
+        T::deserialize(ordinal.into_deserializer())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        let ordinal = u64::try_from(value)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))?;
+        self.visit_u64(ordinal)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        T::deserialize(value.into_deserializer())
+    }
+}
+
+/// Error of `OrdinalSerializer` for values that are not unit enum variants.
+#[derive(Debug)]
+struct NotUnitVariant(String);
+
+impl fmt::Display for NotUnitVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for NotUnitVariant {}
+
+impl ser::Error for NotUnitVariant {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        NotUnitVariant(msg.to_string())
+    }
+}
+
+/// Serializer that only accepts unit enum variants and returns their index.
+struct OrdinalSerializer;
+
+/// Implements serializer methods that reject their value.
+macro_rules! reject {
+    ($($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $method(self $(, _: $arg)*) -> Result<$ok, NotUnitVariant> {
+                Err(NotUnitVariant("enum_ordinal only supports unit enum variants".to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for OrdinalSerializer {
+    type Ok = u32;
+    type Error = NotUnitVariant;
+    type SerializeSeq = Impossible<u32, NotUnitVariant>;
+    type SerializeTuple = Impossible<u32, NotUnitVariant>;
+    type SerializeTupleStruct = Impossible<u32, NotUnitVariant>;
+    type SerializeTupleVariant = Impossible<u32, NotUnitVariant>;
+    type SerializeMap = Impossible<u32, NotUnitVariant>;
+    type SerializeStruct = Impossible<u32, NotUnitVariant>;
+    type SerializeStructVariant = Impossible<u32, NotUnitVariant>;
+
+    fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, _variant: &'static str) -> Result<u32, NotUnitVariant> {
+        Ok(variant_index)
+    }
+
+    reject! {
+        serialize_bool(bool) -> u32;
+        serialize_i8(i8) -> u32;
+        serialize_i16(i16) -> u32;
+        serialize_i32(i32) -> u32;
+        serialize_i64(i64) -> u32;
+        serialize_u8(u8) -> u32;
+        serialize_u16(u16) -> u32;
+        serialize_u32(u32) -> u32;
+        serialize_u64(u64) -> u32;
+        serialize_f32(f32) -> u32;
+        serialize_f64(f64) -> u32;
+        serialize_char(char) -> u32;
+        serialize_str(&str) -> u32;
+        serialize_bytes(&[u8]) -> u32;
+        serialize_none() -> u32;
+        serialize_unit() -> u32;
+        serialize_unit_struct(&'static str) -> u32;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: ?Sized + serde::Serialize>(self, _value: &T) -> Result<u32, NotUnitVariant> {
+        Err(NotUnitVariant("enum_ordinal only supports unit enum variants".to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(self, _name: &'static str, value: &T) -> Result<u32, NotUnitVariant> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T) -> Result<u32, NotUnitVariant> {
+        Err(NotUnitVariant("enum_ordinal only supports unit enum variants".to_string()))
+    }
+}
+
+#[test]
+fn test_enum_ordinal() {
+    use crate::{dict, utils::operation::{Params, StructMetadata}};
+    use postchain_client_derive::StructMetadata;
+    use crate::encoding::gtv;
+
+    #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Tier {
+        #[default]
+        Bronze,
+        GoldPlus,
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    enum Payment {
+        Card { number: String },
+        Cash(i64),
+        #[default]
+        Free,
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize, StructMetadata)]
+    struct Order {
+        tier: Tier,
+        #[serde(with = "crate::utils::enum_ordinal")]
+        tier_ordinal: Tier,
+        card: Payment,
+        cash: Payment,
+        free: Payment,
+    }
+
+    let order = Order {
+        tier: Tier::GoldPlus,
+        tier_ordinal: Tier::GoldPlus,
+        card: Payment::Card { number: "1234".to_string() },
+        cash: Payment::Cash(5),
+        free: Payment::Free,
+    };
+
+    let params = Params::from_struct(&order);
+    assert_eq!(params, dict!{
+        "tier" => "gold_plus",
+        "tier_ordinal" => 1,
+        "card" => dict!{"Card" => dict!{"number" => "1234"}},
+        "cash" => dict!{"Cash" => 5},
+        "free" => "Free"
+    });
+
+    let decoded = gtv::decode(&gtv::encode_value(&params).unwrap()).unwrap();
+    assert_eq!(decoded.to_struct::<Order>(), Ok(order.clone()));
+
+    let by_name = dict!{"tier" => "bronze", "tier_ordinal" => "gold_plus", "card" => "Free", "cash" => "Free", "free" => "Free"};
+    assert_eq!(by_name.to_struct::<Order>().map(|order| order.tier_ordinal), Ok(Tier::GoldPlus));
+
+    let out_of_range = dict!{"tier" => "bronze", "tier_ordinal" => 2, "card" => "Free", "cash" => "Free", "free" => "Free"};
+    assert!(out_of_range.to_struct::<Order>().unwrap_err().starts_with("Failed to convert Params to struct: tier_ordinal:"));
+
+    #[derive(Debug, serde::Serialize, StructMetadata)]
+    struct Invalid {
+        #[serde(with = "crate::utils::enum_ordinal")]
+        payment: Payment,
+    }
+
+    let result = std::panic::catch_unwind(|| Params::from_struct(&Invalid { payment: Payment::Cash(1) }));
+    assert!(result.is_err());
+}
@@ -3,15 +3,15 @@
 //! Merkle tree-based hashing implementation for Generic Tree Value (GTV) data structures.
 //! 
 //! This module implements a specialized Merkle tree for hashing GTV data structures. It provides:
-//! - Construction of Merkle trees from GTV data (arrays, dictionaries, and primitive values)
+//! - Merkle hashing of GTV data (arrays, dictionaries, and primitive values)
 //! - Cryptographic hashing using SHA-256 with type-specific prefixes
 //! - Deterministic hash computation for complex nested data structures
 //! 
 //! # Architecture
-//! The implementation consists of three main components:
-//! - `BinaryTreeNode`: The basic building block of the Merkle tree
-//! - `BinaryTreeFactory`: Constructs Merkle trees from GTV data
-//! - `MerkleHashCalculator`: Computes cryptographic hashes of the tree
+//! `MerkleHashCalculator` hashes values bottom-up without building the tree: the elements
+//! of an array, or the keys and values of a dictionary, are hashed first and then paired
+//! layer by layer in one buffer. Values are borrowed, and only nesting recurses, bounded
+//! by the maximum depth.
 //! 
 //! # Hash Prefixes
 //! Different node types use distinct prefixes to ensure unique hashes:
@@ -24,65 +24,43 @@
 //! 
 //! Hashing an array:
 //! ```
-//! use crate::utils::operation::Params;
+//! use postchain_client::utils::{hasher::gtv_hash, operation::Params};
 //! 
 //! let array_data = Params::Array(vec![
 //!     Params::Text("foo".to_string()),
 //!     Params::Text("bar".to_string())
 //! ]);
 //! 
-//! let hash = gtv_hash(array_data).unwrap();
+//! let hash = gtv_hash(&array_data).unwrap();
 //! ```
 //! 
 //! Hashing a dictionary:
 //! ```
 //! use std::collections::BTreeMap;
-//! use crate::utils::operation::Params;
+//! use postchain_client::utils::{hasher::gtv_hash, operation::Params};
 //! 
 //! let mut dict = BTreeMap::new();
 //! dict.insert("key".to_string(), Params::Integer(42));
 //! let dict_data = Params::Dict(dict);
 //! 
-//! let hash = gtv_hash(dict_data).unwrap();
+//! let hash = gtv_hash(&dict_data).unwrap();
 //! ```
 //! 
 //! # Error Handling
 //! The module uses `HashError` to handle error cases:
-//! - `EmptyArray`: When processing an empty array structure
-//! - `EmptyDict`: When processing an empty dictionary structure
+//! - `EmptyArray`, `EmptyDict`: Kept for compatibility; empty arrays and dictionaries hash normally
+//! - `Encode`: When a leaf value cannot be GTV-encoded
+//! - `MaxDepthExceeded`: When arrays and dictionaries are nested deeper than the limit
 
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
 use sha2::{Sha256, Digest};
-use crate::utils::operation::Params;
-use crate::encoding::gtv::encode_value as gtv_encode_value;
 
-/// Represents different types of nodes in the Merkle tree structure.
-/// 
-/// Each node type serves a specific purpose in building and hashing the tree:
-/// - `Node`: Regular internal nodes that combine and hash their children
-/// - `Leaf`: Contains actual GTV data to be hashed
-/// - `EmptyLeaf`: Used for padding incomplete trees (returns zero hash)
-/// - `DictNode`: Special node for dictionaries with sorted key-value pairs
-/// - `ArrayNode`: Special node for arrays preserving element order
-/// 
-/// The node type determines:
-/// 1. How the node's hash is computed
-/// 2. What prefix is used in the hash computation
-/// 3. How child nodes are processed
-#[derive(Clone, PartialEq, Debug)]
-enum NodeType {
-    /// Internal node with two children, uses prefix 0
-    Node,
-    /// Leaf node containing actual data, uses prefix 1
-    Leaf,
-    /// Empty leaf node for padding, returns zero hash
-    EmptyLeaf,
-    /// Dictionary node for key-value pairs, uses prefix 8
-    DictNode,
-    /// Array node for ordered elements, uses prefix 7
-    ArrayNode,
-}
+use crate::utils::{operation::Params, shared_params::SharedParams};
+#[cfg(feature = "std")]
+use crate::utils::transaction::Transaction;
+use crate::encoding::gtv::{write_encoded, write_primitive, Element, EncodeError, GtvValue, DEFAULT_MAX_DEPTH};
 
-/// Errors that can occur during Merkle tree construction and hashing.
+/// Errors that can occur during Merkle hashing.
 /// 
 /// These errors help identify issues with input data structures:
 /// - `EmptyArray`: Indicates an attempt to process an invalid or empty array
@@ -90,15 +68,16 @@ enum NodeType {
 /// 
 /// # Example
 /// ```
-/// use crate::utils::operation::Params;
+/// use postchain_client::{encoding::gtv::DEFAULT_MAX_DEPTH, utils::{hasher::{gtv_hash, HashError}, operation::Params}};
 /// 
-/// // Attempting to hash an empty array
-/// let empty_array = Params::Array(vec![]);
-/// match gtv_hash(empty_array) {
+/// // Attempting to hash a value nested deeper than the depth limit
+/// let deep = (0..=DEFAULT_MAX_DEPTH).fold(Params::Null, |inner, _| Params::Array(vec![inner]));
+/// match gtv_hash(&deep) {
 ///     Ok(_) => println!("Hash computed successfully"),
-///     Err(HashError::EmptyArray(msg)) => println!("{}", msg),
-///     _ => println!("Other error occurred"),
+///     Err(HashError::MaxDepthExceeded) => println!("Value is nested too deeply"),
+///     Err(error) => println!("Other error occurred: {:?}", error),
 /// }
+/// assert!(matches!(gtv_hash(&deep), Err(HashError::MaxDepthExceeded)));
 /// ```
 #[derive(Clone, Debug)]
 pub enum HashError {
@@ -106,255 +85,15 @@ pub enum HashError {
     EmptyArray(String),
     /// Error when processing an invalid or empty dictionary
     EmptyDict(String),
+    /// Error when a leaf value cannot be GTV-encoded
+    Encode(EncodeError),
+    /// Error when arrays and dictionaries are nested deeper than the limit
+    MaxDepthExceeded,
 }
 
-/// Represents a node in the binary Merkle tree.
-/// 
-/// A `BinaryTreeNode` is the fundamental building block of the Merkle tree structure.
-/// Each node can be:
-/// - An internal node (Node, ArrayNode, or DictNode) with left and right children
-/// - A leaf node containing a GTV value
-/// - An empty leaf node used for padding incomplete trees
-/// 
-/// The node's behavior during hash computation is determined by its `type_of_node` field,
-/// which specifies what prefix to use and how to process child nodes.
-/// 
-/// # Node Structure
-/// - `left`: Left child node (for internal nodes)
-/// - `right`: Right child node (for internal nodes)
-/// - `value`: Stored GTV value (for leaf nodes)
-/// - `type_of_node`: Determines the node's role and hash computation method
-#[derive(Clone, Debug)]
-struct BinaryTreeNode {
-    /// Left child of the node
-    left: Option<Box<BinaryTreeNode>>,
-    /// Right child of the node
-    right: Option<Box<BinaryTreeNode>>,
-    /// Value stored in the node (for leaf nodes)
-    value: Option<Box<Params>>,
-    /// Type of the node (internal, leaf, empty, etc.)
-    type_of_node: NodeType
-}
-
-impl Default for BinaryTreeNode {
-    fn default() -> Self {
-        BinaryTreeNode {
-            left: None,
-            right: None,
-            value: None,
-            type_of_node: NodeType::EmptyLeaf,
-        }
-    }
-}
-
-impl BinaryTreeNode {
-    /// Creates a new internal node with specified children, value, and type.
-    /// 
-    /// # Arguments
-    /// * `left` - Left child node
-    /// * `right` - Right child node
-    /// * `value` - Optional value stored in the node
-    /// * `type_of_node` - Type of the node
-    fn new_node(left: Option<Box<BinaryTreeNode>>, right: Option<Box<BinaryTreeNode>>, value: Option<Box<Params>>, type_of_node: NodeType) -> Self {
-        BinaryTreeNode {
-            left, right, value, type_of_node
-        }
-    }
-
-    /// Creates a new leaf node with an optional value.
-    /// 
-    /// # Arguments
-    /// * `value` - Optional value to store in the leaf
-    /// * `is_empty_leaf` - If true, creates an empty leaf node
-    fn new_leaf(value: Option<Box<Params>>, is_empty_leaf: bool) -> Box<Self> {
-        let type_of_node = match is_empty_leaf {
-            true => NodeType::EmptyLeaf,
-            false => NodeType::Leaf,
-        };
-
-        Box::new(BinaryTreeNode {
-            value, type_of_node, ..Default::default()
-        })
-    }
-}
-
-/// Factory for creating binary Merkle trees from GTV data structures.
-#[derive(Clone, Debug)]
-struct BinaryTreeFactory;
-
-impl BinaryTreeFactory {
-    /// Processes a layer of nodes in the Merkle tree construction.
-    /// 
-    /// This function implements the core Merkle tree building logic by:
-    /// 1. Taking pairs of nodes and combining them into parent nodes
-    /// 2. Recursively processing layers until a single root node is formed
-    /// 3. Handling odd numbers of nodes by promoting the last unpaired node
-    /// 
-    /// # Arguments
-    /// * `leaves` - Vector of nodes to process into a tree layer
-    /// 
-    /// # Returns
-    /// * `Ok(Box<BinaryTreeNode>)` - The root node of the processed layer
-    /// * `Err(HashError::EmptyArray)` - If the input vector is empty
-    /// 
-    /// # Note
-    /// When processing an odd number of nodes, the last node is promoted to the next layer without a pair
-    fn process_layer(leaves: Vec<Box<BinaryTreeNode>>) -> Result<Box<BinaryTreeNode>, HashError> {
-        if leaves.is_empty() {
-            return Err(HashError::EmptyArray("Cannot process empty layer of nodes".to_string()));
-        }
-
-        if leaves.len() == 1 {
-            return Ok(leaves.into_iter().next().unwrap());
-        }
-
-        let results = leaves.chunks(2)
-            .map(|chunk| {
-                if chunk.len() == 2 {
-                    let left = chunk[0].clone();
-                    let right = chunk[1].clone();
-                    BinaryTreeNode::new_node(Some(left), Some(right), None, NodeType::Node)
-                } else {
-                    *chunk[0].clone()
-                }
-            })
-            .map(Box::new)
-            .collect::<Vec<_>>();
-
-        Self::process_layer(results)
-    }
-
-    /// Processes an array parameter into a Merkle tree node.
-    /// 
-    /// Creates a tree structure from an array of values, with array-specific
-    /// hash prefixes (7) for the nodes. Handles special cases:
-    /// - Empty arrays get two empty leaf children
-    /// - Single-element arrays get an empty right child
-    /// 
-    /// # Arguments
-    /// * `params` - Box containing array parameters to process
-    /// 
-    /// # Returns
-    /// * `Ok(Box<BinaryTreeNode>)` - A tree node representing the array structure
-    /// * `Err(HashError::EmptyArray)` - If the input is not a valid array parameter
-    /// 
-    /// # Note
-    /// The resulting tree preserves the order of array elements in the leaf nodes
-    fn process_array_node(params: Box<Params>) -> Result<Box<BinaryTreeNode>, HashError> {
-        if let Params::Array(array_value) = &*params {
-            if array_value.is_empty() {
-                let left = BinaryTreeNode::new_leaf(None, true);
-                let right = BinaryTreeNode::new_leaf(None, true);
-                let value = Box::new(Params::Array(Vec::new()));
-                return Ok(Box::new(BinaryTreeNode::new_node(Some(left), Some(right), Some(value), NodeType::ArrayNode)));
-            }
-
-            let leaves: Result<Vec<_>, _> = array_value
-                .iter()
-                .map(|value| Box::new(value.clone()))
-                .map(Self::build_tree)
-                .collect();
-
-            let leaves = leaves?;
-
-            let value = Box::new(Params::Array(array_value.clone()));
-
-            let tree_root = if leaves.len() == 1 {
-                let left = leaves.into_iter().next().unwrap();
-                let right = BinaryTreeNode::new_leaf(None, true);
-                BinaryTreeNode::new_node(Some(left), Some(right), None, NodeType::Node)
-            } else {
-                *Self::process_layer(leaves)?
-            };
-
-            let node = BinaryTreeNode::new_node(tree_root.left, tree_root.right, Some(value), NodeType::ArrayNode);
-            Ok(Box::new(node))
-        } else {
-            Err(HashError::EmptyArray("Invalid array parameter provided".to_string()))
-        }
-    }
-
-    /// Processes a dictionary parameter into a Merkle tree node.
-    /// 
-    /// Creates a tree structure from dictionary key-value pairs, with
-    /// dictionary-specific hash prefixes (8) for the nodes. Special handling:
-    /// - Empty dictionaries get two empty leaf children
-    /// - Keys and values are stored as alternating leaf nodes
-    /// - Keys are stored as Text parameters
-    /// 
-    /// # Arguments
-    /// * `params` - Box containing dictionary parameters to process
-    /// 
-    /// # Returns
-    /// * `Ok(Box<BinaryTreeNode>)` - A tree node representing the dictionary structure
-    /// * `Err(HashError::EmptyDict)` - If the input is not a valid dictionary parameter
-    /// 
-    /// # Note
-    /// Dictionary entries are processed in sorted order by key to ensure consistent hashing
-    fn process_dict_node(params: Box<Params>) -> Result<Box<BinaryTreeNode>, HashError> {
-        if let Params::Dict(dict_value) = &*params {
-            if dict_value.is_empty() {
-                let left = BinaryTreeNode::new_leaf(None, true);
-                let right = BinaryTreeNode::new_leaf(None, true);
-                let value = Box::new(Params::Dict(std::collections::BTreeMap::new()));
-                return Ok(Box::new(BinaryTreeNode::new_node(Some(left), Some(right), Some(value), NodeType::DictNode)));
-            }
-
-            let leaves: Result<Vec<_>, _> = dict_value
-                .iter()
-                .flat_map(|(key, value)| {
-                    let key_leaf = BinaryTreeNode::new_leaf(Some(Box::new(Params::Text(key.clone()))), false);
-                    let value_tree = Self::build_tree(Box::new(value.clone()));
-                    match value_tree {
-                        Ok(tree) => vec![Ok(key_leaf), Ok(tree)],
-                        Err(err) => vec![Err(err)],
-                    }
-                })
-                .collect();
-
-            let leaves = leaves?;
-            let value = Box::new(Params::Dict(dict_value.clone()));
-
-            let tree_root = if leaves.len() == 1 {
-                let left = leaves.into_iter().next().unwrap();
-                let right = BinaryTreeNode::new_leaf(None, true);
-                BinaryTreeNode::new_node(Some(left), Some(right), None, NodeType::Node)
-            } else {
-                *Self::process_layer(leaves)?
-            };
-
-            let node = BinaryTreeNode::new_node(tree_root.left, tree_root.right, Some(value), NodeType::DictNode);
-            Ok(Box::new(node))
-        } else {
-            Err(HashError::EmptyDict("Invalid dictionary parameter provided".to_string()))
-        }
-    }
-
-    /// Builds a complete Merkle tree from a parameter value.
-    /// 
-    /// Recursively processes the input parameter based on its type:
-    /// - Arrays are processed into ArrayNode trees
-    /// - Dictionaries are processed into DictNode trees
-    /// - Other values become leaf nodes
-    /// 
-    /// # Arguments
-    /// * `params` - Box containing the parameter to process into a tree
-    /// 
-    /// # Returns
-    /// * `Ok(Box<BinaryTreeNode>)` - The root node of the complete Merkle tree
-    /// * `Err(HashError)` - If processing fails due to invalid input
-    /// 
-    /// # Note
-    /// The resulting tree structure preserves the semantic structure of the input data
-    fn build_tree(params: Box<Params>) -> Result<Box<BinaryTreeNode>, HashError> {
-        match *params {
-            Params::Array(_) =>
-                Self::process_array_node(params),
-            Params::Dict(_) =>
-                Self::process_dict_node(params),
-            _ =>
-                Ok(BinaryTreeNode::new_leaf(Some(params), false))
-        }
+impl From<EncodeError> for HashError {
+    fn from(error: EncodeError) -> Self {
+        HashError::Encode(error)
     }
 }
 
@@ -387,7 +126,7 @@ impl MerkleHashCalculator {
     /// 
     /// Creates a hash for a leaf node by:
     /// 1. Prepending the leaf prefix (1)
-    /// 2. GTV-encoding the parameter value
+    /// 2. GTV-encoding the parameter value straight into the hasher
     /// 3. Computing SHA-256 of the combined bytes
     /// 
     /// # Arguments
@@ -398,12 +137,27 @@ impl MerkleHashCalculator {
     /// 
     /// # Note
     /// The leaf prefix ensures leaf node hashes are distinct from internal node hashes
-    fn calculate_leaf_hash(value: &Params) -> [u8; 32] {
-        let gev = gtv_encode_value(value);
-        let mut buffer = Vec::with_capacity(1 + gev.len());
-        buffer.push(HASH_PREFIX_LEAF);
-        buffer.extend_from_slice(&gev);
-        Self::sha256(&buffer)
+    fn calculate_leaf_hash<V: GtvValue>(value: &V) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([HASH_PREFIX_LEAF]);
+        write_encoded(value, &mut hasher).expect("hashing does not fail");
+        hasher.finalize().into()
+    }
+
+    /// Calculates the hash of a text leaf, such as a dictionary key, without wrapping it in `Params`.
+    fn calculate_text_leaf_hash(text: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([HASH_PREFIX_LEAF]);
+        write_primitive(&mut hasher, 0xa2, 0x0c, text.as_bytes()).expect("hashing does not fail");
+        hasher.finalize().into()
+    }
+
+    /// Calculates the hash of a byte array leaf, such as a signer, without wrapping it in `Params`.
+    fn calculate_bytes_leaf_hash(bytes: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([HASH_PREFIX_LEAF]);
+        write_primitive(&mut hasher, 0xa1, 0x04, bytes).expect("hashing does not fail");
+        hasher.finalize().into()
     }
 
     /// Calculates hash for an internal node.
@@ -434,38 +188,70 @@ impl MerkleHashCalculator {
         Self::sha256(&buffer)
     }
 
-    /// Recursively calculates the Merkle hash of a tree node.
+    /// Calculates the hash of an array or dictionary node from the hashes of its leaves.
     /// 
-    /// Traverses the tree structure and computes hashes according to node types:
-    /// - Empty leaves return a zero hash
-    /// - Regular leaves are hashed with prefix 1
-    /// - Array nodes are hashed with prefix 7
-    /// - Dictionary nodes are hashed with prefix 8
-    /// - Other internal nodes are hashed with prefix 0
+    /// Pairs up the hashes layer by layer, in place, promoting the last hash of a layer
+    /// with an odd length. The two hashes left are the children of the node, with zero
+    /// hashes standing in for missing children of empty and single-element nodes.
     /// 
     /// # Arguments
-    /// * `btn` - Root node of the tree or subtree to hash
+    /// * `has_prefix` - `HASH_PREFIX_NODE_ARRAY` or `HASH_PREFIX_NODE_DICT`
+    /// * `hashes` - Hashes of the elements, or of alternating keys and values
     /// 
     /// # Returns
-    /// A fixed-size array containing the 32-byte Merkle hash of the tree/subtree
-    /// 
-    /// # Note
-    /// The hash computation preserves the structural properties of the tree
-    fn calculate_merkle_hash(btn: &BinaryTreeNode) -> [u8; 32] {
-        match &btn.type_of_node {
-            NodeType::EmptyLeaf => [0; 32],
-            NodeType::Leaf => Self::calculate_leaf_hash(btn.value.as_ref().unwrap()),
-            NodeType::ArrayNode | NodeType::DictNode | NodeType::Node => {
-                let has_prefix = match btn.type_of_node {
-                    NodeType::ArrayNode => HASH_PREFIX_NODE_ARRAY,
-                    NodeType::DictNode => HASH_PREFIX_NODE_DICT,
-                    _ => HASH_PREFIX_NODE,
+    /// A fixed-size array containing the 32-byte hash of the node
+    fn calculate_root_hash(has_prefix: u8, mut hashes: Vec<[u8; 32]>) -> [u8; 32] {
+        while hashes.len() > 2 {
+            let paired = hashes.len().div_ceil(2);
+            for index in 0..paired {
+                let left = hashes[2 * index];
+                hashes[index] = match hashes.get(2 * index + 1) {
+                    Some(right) => Self::calculate_node_hash(HASH_PREFIX_NODE, left, *right),
+                    None => left,
                 };
-                let left_hash = btn.left.as_ref().map(|left| Self::calculate_merkle_hash(left)).unwrap_or([0; 32]);
-                let right_hash = btn.right.as_ref().map(|right| Self::calculate_merkle_hash(right)).unwrap_or([0; 32]);
+            }
+            hashes.truncate(paired);
+        }
+        let left = hashes.first().copied().unwrap_or([0; 32]);
+        let right = hashes.get(1).copied().unwrap_or([0; 32]);
+        Self::calculate_node_hash(has_prefix, left, right)
+    }
 
-                Self::calculate_node_hash(has_prefix, left_hash, right_hash)
+    /// Calculates the Merkle hash of a value.
+    /// 
+    /// Scalars are leaves hashed with prefix 1. Arrays hash their elements, and dictionaries
+    /// their keys and values alternately, then combine them with `calculate_root_hash`
+    /// using prefix 7 or 8.
+    /// 
+    /// # Arguments
+    /// * `value` - The value to hash
+    /// * `remaining_depth` - How many levels of arrays and dictionaries `value` may nest
+    /// 
+    /// # Returns
+    /// A fixed-size array containing the 32-byte Merkle hash of the value
+    /// 
+    /// # Errors
+    /// `HashError::MaxDepthExceeded` if `value` is nested deeper than `remaining_depth`
+    fn calculate_value_hash<V: GtvValue>(value: &V, remaining_depth: usize) -> Result<[u8; 32], HashError> {
+        match value.element() {
+            Element::Array(_) | Element::Dict(_) if remaining_depth == 0 => Err(HashError::MaxDepthExceeded),
+            Element::Array(values) => {
+                let hashes = values.iter()
+                    .map(|value| Self::calculate_value_hash(value, remaining_depth - 1))
+                    .collect::<Result<_, _>>()?;
+                Ok(Self::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, hashes))
+            }
+            Element::Dict(entries) => {
+                let mut hashes = Vec::with_capacity(2 * entries.len());
+                for (key, value) in entries {
+                    hashes.push(Self::calculate_text_leaf_hash(key.as_ref()));
+                    hashes.push(Self::calculate_value_hash(value, remaining_depth - 1)?);
+                }
+                Ok(Self::calculate_root_hash(HASH_PREFIX_NODE_DICT, hashes))
             }
+            Element::Text(text) => Ok(Self::calculate_text_leaf_hash(text)),
+            Element::ByteArray(bytes) => Ok(Self::calculate_bytes_leaf_hash(bytes)),
+            _ => Ok(Self::calculate_leaf_hash(value)),
         }
     }
 }
@@ -473,9 +259,9 @@ impl MerkleHashCalculator {
 /// Computes a cryptographic hash of a GTV (Generic Tree Value) parameter using a Merkle tree.
 /// 
 /// This function:
-/// 1. Constructs a Merkle tree from the input GTV data
-/// 2. Computes SHA-256 hashes for each node with type-specific prefixes
-/// 3. Combines hashes up the tree to produce a final 32-byte hash
+/// 1. Hashes the leaves of the input GTV data
+/// 2. Pairs the hashes bottom-up, with type-specific prefixes for each node
+/// 3. Combines them into a final 32-byte hash, without building the tree in memory
 /// 
 /// The hashing process ensures:
 /// - Unique hashes for different data structures
@@ -494,19 +280,19 @@ impl MerkleHashCalculator {
 /// 
 /// Hashing primitive values:
 /// ```
-/// use crate::utils::operation::Params;
+/// use postchain_client::utils::{hasher::gtv_hash, operation::Params};
 /// 
 /// // Hash an integer
-/// let int_hash = gtv_hash(Params::Integer(42)).unwrap();
+/// let int_hash = gtv_hash(&Params::Integer(42)).unwrap();
 /// 
 /// // Hash a string
-/// let text_hash = gtv_hash(Params::Text("hello".to_string())).unwrap();
+/// let text_hash = gtv_hash(&Params::Text("hello".to_string())).unwrap();
 /// ```
 /// 
 /// Hashing nested structures:
 /// ```
 /// use std::collections::BTreeMap;
-/// use crate::utils::operation::Params;
+/// use postchain_client::utils::{hasher::gtv_hash, operation::Params};
 /// 
 /// // Create a nested structure
 /// let mut dict = BTreeMap::new();
@@ -517,11 +303,131 @@ impl MerkleHashCalculator {
 /// let data = Params::Dict(dict);
 /// 
 /// // Compute hash
-/// let hash = gtv_hash(data).unwrap();
+/// let hash = gtv_hash(&data).unwrap();
 /// ```
-pub fn gtv_hash(value: Params) -> Result<[u8; 32], HashError> {
-    let tree = BinaryTreeFactory::build_tree(Box::new(value))?;
-    Ok(MerkleHashCalculator::calculate_merkle_hash(&tree))
+pub fn gtv_hash(value: &Params) -> Result<[u8; 32], HashError> {
+    gtv_hash_with_max_depth(value, DEFAULT_MAX_DEPTH)
+}
+
+/// Computes the hash of a GTV parameter like `gtv_hash`, with a custom nesting limit.
+/// 
+/// # Arguments
+/// * `value` - GTV parameter to hash
+/// * `max_depth` - Maximum nesting depth of arrays and dictionaries; a scalar has depth 0
+/// 
+/// # Returns
+/// * `Ok([u8; 32])` - The 32-byte hash of the parameter
+/// * `Err(HashError::MaxDepthExceeded)` - If `value` is nested deeper than `max_depth`
+pub fn gtv_hash_with_max_depth(value: &Params, max_depth: usize) -> Result<[u8; 32], HashError> {
+    MerkleHashCalculator::calculate_value_hash(value, max_depth)
+}
+
+/// Computes the hash of a shared value like `gtv_hash`, reading it in place.
+/// 
+/// Gives the same hash as `gtv_hash(&Params::from(value))` without copying the value.
+/// 
+/// # Arguments
+/// * `value` - The value to hash
+/// 
+/// # Returns
+/// * `Ok([u8; 32])` - The 32-byte hash of the value
+/// * `Err(HashError::MaxDepthExceeded)` - If `value` is nested deeper than the default limit
+pub fn gtv_hash_shared(value: &SharedParams) -> Result<[u8; 32], HashError> {
+    MerkleHashCalculator::calculate_value_hash(value, DEFAULT_MAX_DEPTH)
+}
+
+/// Computes the transaction RID, the hash of `gtv::to_draw_gtx(tx)`, without copying the
+/// operations into one `Params`.
+/// 
+/// Hashes every argument on its own and combines the hashes as the Merkle tree of the
+/// whole body would, with the same nesting limit.
+/// 
+/// # Arguments
+/// * `tx` - The transaction; callers check the operation names first
+/// 
+/// # Returns
+/// * `Ok([u8; 32])` - The 32-byte transaction RID
+/// * `Err(HashError::MaxDepthExceeded)` - If an argument is nested too deep
+#[cfg(feature = "std")]
+pub(crate) fn tx_body_hash(tx: &Transaction<'_>) -> Result<[u8; 32], HashError> {
+    // Arguments sit in the argument list of an operation in the operation list of the body
+    let args_depth = DEFAULT_MAX_DEPTH - 4;
+
+    let operations = tx.operations.iter().flatten()
+        .map(|operation| {
+            let args = if let Some(list) = &operation.list {
+                list.iter().map(|arg| gtv_hash_with_max_depth(arg, args_depth)).collect::<Result<Vec<_>, _>>()?
+            } else if let Some(list) = &operation.shared_list {
+                list.iter()
+                    .map(|arg| MerkleHashCalculator::calculate_value_hash(arg, args_depth))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else if let Some(dict) = &operation.dict {
+                // Sorted, and later entries win, as in the dictionary argument `to_draw_gtx` builds
+                let entries: BTreeMap<&str, &Params> = dict.iter().map(|(key, value)| (key.as_ref(), value)).collect();
+                let mut hashes = Vec::with_capacity(2 * entries.len());
+                for (key, value) in entries {
+                    hashes.push(MerkleHashCalculator::calculate_text_leaf_hash(key));
+                    hashes.push(gtv_hash_with_max_depth(value, args_depth - 1)?);
+                }
+                vec![MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_DICT, hashes)]
+            } else {
+                Vec::new()
+            };
+            let name = operation.operation_name.as_deref().unwrap_or_default();
+            Ok(MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, vec![
+                MerkleHashCalculator::calculate_text_leaf_hash(name),
+                MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, args),
+            ]))
+        })
+        .collect::<Result<Vec<_>, HashError>>()?;
+
+    let signers = tx.signers.iter().flatten()
+        .map(|signer| MerkleHashCalculator::calculate_bytes_leaf_hash(signer))
+        .collect();
+
+    Ok(MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, vec![
+        MerkleHashCalculator::calculate_bytes_leaf_hash(&tx.blockchain_rid),
+        MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, operations),
+        MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, signers),
+    ]))
+}
+
+/// Computes the hash of a signed transaction, `[body, signatures]`, from its RID.
+///
+/// Blocks commit to this hash rather than the RID, so it changes with the signatures.
+///
+/// # Arguments
+/// * `tx_rid` - The RID, the hash of the transaction body
+/// * `signatures` - The signatures, in signer order
+pub(crate) fn signed_tx_hash(tx_rid: [u8; 32], signatures: &[Vec<u8>]) -> [u8; 32] {
+    let signatures = signatures.iter()
+        .map(|signature| MerkleHashCalculator::calculate_bytes_leaf_hash(signature))
+        .collect();
+    MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, vec![
+        tx_rid,
+        MerkleHashCalculator::calculate_root_hash(HASH_PREFIX_NODE_ARRAY, signatures),
+    ])
+}
+
+/// Kind of an inner Merkle tree node, which selects the prefix of its hash
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MerkleNode {
+    /// A node pairing two subtrees
+    Inner,
+    /// The root of an array
+    Array,
+    /// The root of a dictionary
+    Dict,
+}
+
+/// Computes the hash of an inner Merkle tree node from the hashes of its children.
+pub(crate) fn merkle_node_hash(kind: MerkleNode, left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    let prefix = match kind {
+        MerkleNode::Inner => HASH_PREFIX_NODE,
+        MerkleNode::Array => HASH_PREFIX_NODE_ARRAY,
+        MerkleNode::Dict => HASH_PREFIX_NODE_DICT,
+    };
+    MerkleHashCalculator::calculate_node_hash(prefix, left, right)
 }
 
 #[test]
@@ -542,11 +448,11 @@ fn test_gtv_hash() {
 
     let data2 = Params::Dict(data2_btree);
     
-    let result1 = gtv_hash(data1).unwrap();
-    let result2 = gtv_hash(data2).unwrap();
+    let result1 = gtv_hash(&data1).unwrap();
+    let result2 = gtv_hash(&data2).unwrap();
 
     assert_eq!("6357d3200e0dfb1bce5f3eb789714842747b39810248f83dba6382c7e7020e20", hex::encode(result1));
-    assert_eq!("9f3d80d08a942b86e20932ad74356703dba7ba78b792f2d6ad93201ab9a71bab", hex::encode(result2));
+    assert_eq!("6981e7efd8ce0634bdadf3d7c76cc69ad5abf9792af951bd0fe5698571589f12", hex::encode(result2));
 }
 
 #[test]
@@ -554,9 +460,50 @@ fn test_gtv_hash_v2() {
     let data1 = Params::Array(vec![Params::Text("a".to_string())]);
     let data2 = Params::Array(vec![Params::Array(vec![Params::Text("a".to_string())])]);
 
-    let result1 = gtv_hash(data1).unwrap();
-    let result2 = gtv_hash(data2).unwrap();
+    let result1 = gtv_hash(&data1).unwrap();
+    let result2 = gtv_hash(&data2).unwrap();
 
     assert_eq!(hex::encode(result1), "5ad2414edcd34b9a8bdc22921b8a1b8cef6cab04115dd0e7eb000b05353b315a");
     assert_eq!(hex::encode(result2), "19605d1044cc20248e315f98f2d4c4aa7adfe6861607a0d000641837c3b962f8");
-}
\ No newline at end of file
+}
+#[test]
+fn test_gtv_hash_odd_layers() {
+    let data = Params::Array(vec![
+        Params::Integer(1), Params::Text("two".to_string()), Params::ByteArray(vec![3; 1024]),
+        Params::Dict(std::collections::BTreeMap::from([("five".to_string(), Params::Null)])), Params::Boolean(true)
+    ]);
+
+    assert_eq!(hex::encode(gtv_hash(&data).unwrap()), "498e3fb08f124f0001cc56da70775b738c0ac173c2b9eebd7a03091be17fcd74");
+}
+
+#[test]
+fn test_gtv_hash_max_depth() {
+    let nested = |depth: usize| (0..depth).fold(Params::Integer(1), |inner, _| Params::Array(vec![inner]));
+
+    assert!(gtv_hash(&nested(DEFAULT_MAX_DEPTH)).is_ok());
+    assert!(matches!(gtv_hash(&nested(DEFAULT_MAX_DEPTH + 1)), Err(HashError::MaxDepthExceeded)));
+
+    let mut dict = std::collections::BTreeMap::new();
+    dict.insert("key".to_string(), nested(1));
+    assert!(gtv_hash_with_max_depth(&Params::Dict(dict.clone()), 2).is_ok());
+    assert!(matches!(gtv_hash_with_max_depth(&Params::Dict(dict), 1), Err(HashError::MaxDepthExceeded)));
+    assert!(gtv_hash_with_max_depth(&Params::Integer(1), 0).is_ok());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_signed_tx_hash() {
+    use crate::utils::operation::Operation;
+
+    let tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), Some(vec![vec![2; 33], vec![3; 33]]), Some(vec![vec![4; 64], vec![5; 64]]));
+    let signed = Params::Array(vec![
+        crate::encoding::gtv::to_draw_gtx(&tx),
+        Params::Array(vec![Params::ByteArray(vec![4; 64]), Params::ByteArray(vec![5; 64])]),
+    ]);
+    assert_eq!(signed_tx_hash(tx.tx_rid().unwrap(), tx.signatures.as_ref().unwrap()), gtv_hash(&signed).unwrap());
+    assert_eq!(
+        signed_tx_hash(tx.tx_rid().unwrap(), &[]),
+        gtv_hash(&Params::Array(vec![crate::encoding::gtv::to_draw_gtx(&tx), Params::Array(vec![])])).unwrap()
+    );
+}
+
@@ -0,0 +1,106 @@
+//! Key pair handling for transaction signing.
+//! 
+//! This module wraps a secp256k1 private key together with its public key, so
+//! transactions can be signed without passing raw `[u8; 32]` arrays around.
+//! 
+//! # Example
+//! ```
+//! use postchain_client::utils::{keypair::KeyPair, operation::Operation, transaction::Transaction};
+//!
+//! let keypair = KeyPair::from_private_key_hex("C70D5A77CC10552019179B7390545C46647C9FCA1B6485850F2B913F87270300")
+//!     .expect("Invalid private key");
+//!
+//! let mut tx = Transaction::new(vec![0; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+//! tx.sign_with_keypair(&keypair).expect("Failed to sign transaction");
+//!
+//! assert_eq!(tx.signers.unwrap()[0], keypair.public_key().to_vec());
+//! ```
+
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use hex::FromHex;
+
+use super::transaction::{TransactionError, TxSigner};
+
+/// A secp256k1 private key and its public key.
+/// 
+/// The `Debug` output never shows the private key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPair {
+    private_key: SecretKey,
+    public_key: PublicKey
+}
+
+impl KeyPair {
+    /// Generates a new random key pair.
+    /// 
+    /// # Returns
+    /// A new KeyPair instance
+    pub fn generate() -> Self {
+        let (private_key, public_key) = Secp256k1::new().generate_keypair(&mut secp256k1::rand::thread_rng());
+        Self { private_key, public_key }
+    }
+
+    /// Creates a key pair from a 32-byte private key.
+    /// 
+    /// # Arguments
+    /// * `private_key` - 32-byte private key
+    /// 
+    /// # Returns
+    /// The key pair, or an error if the private key is invalid
+    pub fn from_private_key(private_key: &[u8; 32]) -> Result<Self, secp256k1::Error> {
+        let private_key = SecretKey::from_byte_array(private_key)?;
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &private_key);
+        Ok(Self { private_key, public_key })
+    }
+
+    /// Creates a key pair from a hex-encoded private key.
+    /// 
+    /// # Arguments
+    /// * `private_key` - Private key as a hex string
+    /// 
+    /// # Returns
+    /// The key pair, or an error if the private key is invalid
+    pub fn from_private_key_hex(private_key: &str) -> Result<Self, secp256k1::Error> {
+        let private_key = <[u8; 32]>::from_hex(private_key).map_err(|_| secp256k1::Error::InvalidSecretKey)?;
+        Self::from_private_key(&private_key)
+    }
+
+    /// Returns the 33-byte compressed public key.
+    pub fn public_key(&self) -> [u8; 33] {
+        self.public_key.serialize()
+    }
+
+    /// Returns the 32-byte private key.
+    pub fn private_key(&self) -> [u8; 32] {
+        self.private_key.secret_bytes()
+    }
+}
+
+impl std::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public_key", &hex::encode(self.public_key()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl TxSigner for KeyPair {
+    async fn pubkey(&self) -> [u8; 33] {
+        self.public_key()
+    }
+
+    async fn sign(&self, digest: &[u8; 32]) -> Result<[u8; 64], TransactionError> {
+        self.private_key.sign(digest).await
+    }
+}
+
+#[test]
+fn test_keypair_from_private_key_hex() {
+    let keypair = KeyPair::from_private_key_hex("C70D5A77CC10552019179B7390545C46647C9FCA1B6485850F2B913F87270300").unwrap();
+    let same = KeyPair::from_private_key(&keypair.private_key()).unwrap();
+
+    assert_eq!(keypair, same);
+    assert!(!format!("{:?}", keypair).contains("c70d5a77"));
+    assert!(KeyPair::from_private_key_hex("zz").is_err());
+    assert_ne!(KeyPair::generate(), KeyPair::generate());
+}
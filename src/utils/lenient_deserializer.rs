@@ -0,0 +1,125 @@
+//! Deserializer behind `Params::to_struct_lenient`.
+//!
+//! Wraps the JSON form of a `Params` value and coerces scalars to the type the target
+//! field asks for, where the GTV encoding differs from the Rust type in a harmless way:
+//! - integers `0` and `1` into `bool`, since the chain encodes booleans as integers
+//! - booleans and numeric texts into integers
+//! - numeric texts, such as decimals, into floats
+//! - numbers into strings, so `BigInt` fields read through a string accept integers
+//!
+//! Values inside enums are not coerced.
+
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde_json::Value;
+
+type Error = serde_json::Error;
+
+/// Deserializer over a JSON value that coerces compatible scalars.
+pub(crate) struct LenientDeserializer(pub(crate) Value);
+
+impl<'de> IntoDeserializer<'de, Error> for LenientDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Implements integer methods that also accept booleans and numeric texts.
+macro_rules! deserialize_integer {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0 {
+                    Value::Bool(b) => visitor.visit_u64(b as u64),
+                    Value::String(ref s) => match (s.parse::<i64>(), s.parse::<u64>()) {
+                        (Ok(i), _) => visitor.visit_i64(i),
+                        (_, Ok(u)) => visitor.visit_u64(u),
+                        _ => self.deserialize_any(visitor),
+                    },
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for LenientDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Array(array) => {
+                let mut seq = SeqDeserializer::new(array.into_iter().map(LenientDeserializer));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Object(object) => {
+                let mut map = MapDeserializer::new(object.into_iter().map(|(key, value)| (key, LenientDeserializer(value))));
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.as_i64() {
+            Some(0) => visitor.visit_bool(false),
+            Some(1) => visitor.visit_bool(true),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    deserialize_integer! {
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::String(ref s) => match s.parse::<f64>() {
+                Ok(f) => visitor.visit_f64(f),
+                Err(_) => self.deserialize_any(visitor),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Number(n) => visitor.visit_string(n.to_string()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        char bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
@@ -1,3 +1,19 @@
-pub(crate) mod hasher;
+#[cfg(feature = "std")]
+pub mod blockchain_rid;
+#[cfg(feature = "std")]
+pub mod byte_key_map;
+#[cfg(feature = "std")]
+pub mod confirmation_proof;
+#[cfg(feature = "std")]
+pub mod enum_ordinal;
+pub mod hasher;
+#[cfg(feature = "std")]
+pub mod keypair;
+#[cfg(feature = "std")]
+pub(crate) mod lenient_deserializer;
 pub mod operation;
+#[cfg(feature = "std")]
+pub mod params_deserializer;
+pub mod shared_params;
+#[cfg(feature = "std")]
 pub mod transaction;
\ No newline at end of file
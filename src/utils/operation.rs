@@ -13,7 +13,7 @@
 //! # Example
 //! ```
 //! use std::collections::BTreeMap;
-//! use crate::utils::operation::{Operation, Params};
+//! use postchain_client::utils::operation::{Operation, Params};
 //! 
 //! // Create operation parameters
 //! let params = vec![
@@ -27,17 +27,30 @@
 
 extern crate num_bigint;
 
-use std::{collections::BTreeMap, fmt::Debug};
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap, format, string::{String, ToString}, vec, vec::Vec};
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
 use num_bigint::BigInt;
 use bigdecimal::BigDecimal;
-use std::str::FromStr;
+use core::str::FromStr;
 use base64::{Engine as _, engine::general_purpose};
+use crate::utils::shared_params::SharedParams;
 
 #[allow(unused_imports)]
 use postchain_client_derive::StructMetadata;
 
 pub trait StructMetadata {
-    fn field_names_and_types() -> std::collections::BTreeMap<String, String>;
+    fn field_names_and_types() -> BTreeMap<String, String>;
+
+    /// Returns the field names in declaration order.
+    /// 
+    /// Rell operations take their arguments in declaration order, while
+    /// `field_names_and_types` is sorted by name. Defaults to the sorted order
+    /// for implementations written before this method existed.
+    fn field_names() -> Vec<String> {
+        Self::field_names_and_types().into_keys().collect()
+    }
 }
 
 /// Represents different types of operation parameters.
@@ -45,21 +58,31 @@ pub trait StructMetadata {
 /// This enum provides a type-safe way to handle various data types
 /// used in blockchain operations, including primitive types, collections,
 /// and special types like BigInteger.
-#[derive(Clone, Debug, PartialEq)]
+/// 
+/// With serde it uses a tagged representation such as `{"type": "Integer", "value": 42}`,
+/// which keeps every variant distinct so decoded values can be stored and reloaded
+/// without loss. Big numbers are written as strings and byte arrays as base64.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum Params {
     /// Represents a null value
     Null,
     /// Represents a boolean value (true/false)
     Boolean(bool),
-    /// Represents a 64-bit signed integer
+    /// Represents a 64-bit signed integer, encoded as a GTV `INTEGER`
     Integer(i64),
-    /// Represents an arbitrary-precision integer using BigInt
+    /// Represents an arbitrary-precision integer using BigInt, encoded as a GTV `BIGINTEGER`
+    /// whatever its magnitude; a GTV `INTEGER` wider than 64 bits also decodes to this variant
+    #[serde(serialize_with = "serialize_bigint", deserialize_with = "deserialize_bigint")]
     BigInteger(BigInt),
-    /// Represents an arbitrary-precision decimal using BigDecimal
+    /// Represents an arbitrary-precision decimal using BigDecimal, encoded as a GTV string in the
+    /// canonical form of `gtv::format_decimal`
+    #[serde(serialize_with = "serialize_bigdecimal", deserialize_with = "deserialize_bigdecimal")]
     Decimal(BigDecimal),
     /// Represents a UTF-8 encoded string
     Text(String),
     /// Represents a raw byte array
+    #[serde(serialize_with = "serialize_byte_array", deserialize_with = "deserialize_byte_array")]
     ByteArray(Vec<u8>),
     /// Represents an ordered collection of Params
     Array(Vec<Params>),
@@ -110,6 +133,22 @@ where
     general_purpose::STANDARD.decode(&base64_str).map_err(serde::de::Error::custom)
 }
 
+/// Serializes a byte array into a base64 string.
+/// 
+/// This is the counterpart of `deserialize_byte_array`.
+/// 
+/// # Arguments
+/// * `bytes` - The byte array to serialize
+/// * `serializer` - The serializer to use
+/// 
+/// # Returns
+/// Result containing either the serialized string or an error
+fn serialize_byte_array<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&general_purpose::STANDARD.encode(bytes))
+}
 
 /// Serializes a BigInt into a string.
 /// 
@@ -145,16 +184,16 @@ where
 /// or an error if serialization fails.
 ///
 /// # Example
+/// `Params::Decimal` is serialized with this function:
 /// ```
-/// #[derive(Debug, serde::Serialize)]
-/// struct MyStruct {
-///     #[serde(serialize_with = "serialize_bigdecimal")]
-///     value: BigDecimal,
-/// }
-///
-/// let my_struct = MyStruct { value: BigDecimal::from_str("3.14").unwrap() };
-/// let json = serde_json::to_string(&my_struct).unwrap();
+/// use std::str::FromStr;
+/// use bigdecimal::BigDecimal;
+/// use postchain_client::utils::operation::Params;
 ///
+/// let value = Params::Decimal(BigDecimal::from_str("3.14").unwrap());
+/// let json = serde_json::to_string(&value).unwrap();
+/// assert_eq!(json, r#"{"type":"Decimal","value":"3.14"}"#);
+/// ```
 #[allow(dead_code)]
 fn serialize_bigdecimal<S>(bigdecimal: &BigDecimal, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -175,15 +214,15 @@ where
 /// Returns the deserialized `BigDecimal` if successful, or an error if deserialization fails.
 ///
 /// # Example
+/// `Params::Decimal` is deserialized with this function:
 /// ```
-/// #[derive(Debug, serde::Deserialize)]
-/// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_bigdecimal")]
-///     value: BigDecimal,
-/// }
+/// use std::str::FromStr;
+/// use bigdecimal::BigDecimal;
+/// use postchain_client::utils::operation::Params;
 ///
-/// let json = r#"{"value": "3.14"}"#;
-/// let my_struct: MyStruct = serde_json::from_str(json).unwrap();
+/// let json = r#"{"type": "Decimal", "value": "3.14"}"#;
+/// let value: Params = serde_json::from_str(json).unwrap();
+/// assert_eq!(value, Params::Decimal(BigDecimal::from_str("3.14").unwrap()));
 /// ```
 #[allow(dead_code)]
 fn deserialize_bigdecimal<'de, D>(deserializer: D) -> Result<BigDecimal, D::Error>
@@ -195,29 +234,33 @@ where
         .map_err(serde::de::Error::custom)
 }
 
+/// Name of the GTX `nop` operation, which does nothing and accepts any arguments.
+pub const NOP_OPERATION: &str = "nop";
+
+/// Name of the GTX `timeb` operation, which bounds the time a transaction is valid.
+pub const TIMEB_OPERATION: &str = "timeb";
+
+/// Prefix of system operations handled by the node rather than by the dapp.
+pub const SYSTEM_OPERATION_PREFIX: &str = "__";
+
 /// Represents a blockchain operation with parameters.
 /// 
 /// An operation can contain either a dictionary of named parameters
 /// or a list of unnamed parameters, along with an operation name.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Operation<'a> {
-    /// Dictionary of named parameters
+    /// Dictionary of named parameters, sent as a single struct argument
+    pub dict: Option<Vec<(Cow<'a, str>, Params)>>,
     /// List of unnamed parameters
-    /// Name of the operation
-    pub dict: Option<Vec<(&'a str, Params)>>,
     pub list: Option<Vec<Params>>,
-    pub operation_name: Option<&'a str>,
+    /// List of unnamed parameters whose content is shared, encoded and hashed in place
+    pub shared_list: Option<Vec<SharedParams>>,
+    /// Name of the operation
+    pub operation_name: Option<Cow<'a, str>>,
 }
 
-impl<'a> Default for Operation<'a> {
-    fn default() -> Self {
-        Self {
-            dict: None,
-            list: None,
-            operation_name: None,
-        }
-    }
-}
+/// An operation that owns its name and argument names, e.g. one built from runtime data.
+pub type OperationOwned = Operation<'static>;
 
 /// Checks if a vector of JSON values represents a byte array.
 /// 
@@ -226,7 +269,7 @@ impl<'a> Default for Operation<'a> {
 /// 
 /// # Returns
 /// true if all values are valid u8 numbers
-fn is_vec_u8(value: &Vec<serde_json::Value>) -> bool {
+fn is_vec_u8(value: &[serde_json::Value]) -> bool {
     value.iter().all(|v| {
             if let serde_json::Value::Number(n) = v {
                 n.is_u64() && n.as_u64().unwrap() <= u8::MAX as u64
@@ -236,19 +279,149 @@ fn is_vec_u8(value: &Vec<serde_json::Value>) -> bool {
         })    
 }
 
+/// Checks whether a stringified field type is a decimal type converted to `Params::Decimal`.
+fn is_decimal_type(field_type: &str) -> bool {
+    type_mentions(field_type, "BigDecimal") || (cfg!(feature = "rust_decimal") && type_mentions(field_type, "Decimal"))
+}
+
+/// Prefix of the field type recorded by `StructMetadata` for fields marked `#[params(bytes)]`.
+const BYTES_FIELD_MARKER: &str = "#[params(bytes)]";
+
+/// Removes whitespace and any `Option<...>` wrappers from a stringified field type.
+fn normalize_type(field_type: &str) -> String {
+    let mut normalized: String = field_type.chars().filter(|c| !c.is_whitespace()).collect();
+    while let Some(inner) = normalized.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        normalized = inner.to_string();
+    }
+    normalized
+}
+
+/// Checks whether a stringified field type holds bytes: `Vec<u8>`, `[u8; N]`
+/// or any field marked `#[params(bytes)]`.
+fn is_bytes_field_type(field_type: &str) -> bool {
+    if field_type.starts_with(BYTES_FIELD_MARKER) {
+        return true;
+    }
+    let normalized = normalize_type(field_type);
+    normalized == "Vec<u8>" || normalized.starts_with("[u8;")
+}
+
+/// Returns the element type of a stringified `Vec<T>` or `[T; N]` field type,
+/// or an empty string if it cannot be determined.
+fn element_type(field_type: &str) -> String {
+    let normalized = normalize_type(field_type);
+    if let Some(inner) = normalized.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+        return inner.to_string();
+    }
+    if let Some((inner, _)) = normalized.strip_prefix('[').and_then(|t| t.rsplit_once(';')) {
+        return inner.to_string();
+    }
+    String::new()
+}
+
+/// Checks whether a stringified field type refers to the type `name`.
+/// 
+/// Only whole identifiers match, so `Option<num_bigint::BigInt>` mentions `BigInt`
+/// but `BigIntLabel` does not.
+/// 
+/// # Arguments
+/// * `field_type` - Field type as produced by the `StructMetadata` derive
+/// * `name` - Type name to look for
+fn type_mentions(field_type: &str, name: &str) -> bool {
+    field_type
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|ident| ident == name)
+}
+
+/// Names the `Params` variant matching what a serde visitor expects, e.g. `a string` or `i64`,
+/// falling back to the serde description.
+#[cfg(feature = "std")]
+fn expected_kind(expected: &str) -> String {
+    let kind = match expected {
+        "a string" | "a borrowed string" | "a character" => "Params::Text",
+        "a boolean" => "Params::Boolean",
+        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128" => "Params::Integer",
+        "f32" | "f64" => "Params::Decimal",
+        "a sequence" => "Params::Array",
+        "a map" => "Params::Dict",
+        _ if expected.starts_with("struct ") => "Params::Dict",
+        _ if expected.starts_with("a tuple") || expected.starts_with("an array") => "Params::Array",
+        _ => return expected.to_string()
+    };
+    kind.to_string()
+}
+
+/// Returns the `Params` variants a stringified field type accepts, or `None` if any value is accepted.
+fn accepted_kinds(field_type: &str) -> Option<&'static [&'static str]> {
+    if is_bytes_field_type(field_type) {
+        return Some(&["ByteArray"]);
+    }
+    let normalized = normalize_type(field_type);
+    let kinds: &'static [&'static str] = match normalized.as_str() {
+        "bool" => &["Boolean"],
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => &["Integer"],
+        "f32" | "f64" => &["Integer", "Decimal"],
+        "String" | "&str" | "char" => &["Text"],
+        ty if is_decimal_type(ty) => &["Integer", "BigInteger", "Decimal", "Text"],
+        ty if type_mentions(ty, "BigInt") => &["Integer", "BigInteger"],
+        ty if ty.starts_with("Vec<") || ty.starts_with('[') => &["Array"],
+        ty if ["BTreeMap<", "HashMap<"].iter().any(|prefix| ty.contains(prefix)) => &["Dict"],
+        _ => return None
+    };
+    Some(kinds)
+}
+
+/// Removes the `#[params(bytes)]` marker and whitespace from a stringified field type.
+fn compact_type(field_type: &str) -> String {
+    field_type.trim_start_matches(BYTES_FIELD_MARKER).chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Collects the violations of `value` against a stringified field type into `violations`.
+fn check_field_type(path: &str, field_type: &str, value: &Params, violations: &mut Vec<String>) {
+    let compact = compact_type(field_type);
+
+    if value.is_null() {
+        if !compact.starts_with("Option<") {
+            violations.push(format!("{}: expected {}, found Params::Null", path, compact));
+        }
+        return;
+    }
+
+    let Some(kinds) = accepted_kinds(field_type) else {
+        return;
+    };
+
+    if !kinds.contains(&value.kind()) {
+        let expected: Vec<String> = kinds.iter().map(|kind| format!("Params::{}", kind)).collect();
+        violations.push(format!("{}: expected {} for {}, found Params::{}", path, expected.join(" or "), compact, value.kind()));
+    } else if let Params::Array(array) = value {
+        let item_type = element_type(field_type);
+        for (i, item) in array.iter().enumerate() {
+            check_field_type(&format!("{}[{}]", path, i), &item_type, item, violations);
+        }
+    }
+}
+
 impl<'a> Operation<'a> {
     /// Creates a new Operation from a dictionary of parameters.
     /// 
+    /// The parameters are the fields of the operation's struct argument, e.g.
+    /// `operation set_book(book: book_dto)` in Rell, and are encoded as one GTV dict.
+    /// 
     /// # Arguments
-    /// * `operation_name` - Name of the operation
+    /// * `operation_name` - Name of the operation, borrowed (`&str`) or owned (`String`)
     /// * `params` - Vector of key-value parameter pairs
     /// 
     /// # Returns
     /// A new Operation instance with dictionary parameters
-    pub fn from_dict(operation_name: &'a str, params: Vec<(&'a str, Params)>) -> Self {
+    pub fn from_dict<N, K>(operation_name: N, params: Vec<(K, Params)>) -> Self
+    where
+        N: Into<Cow<'a, str>>,
+        K: Into<Cow<'a, str>>,
+    {
         Self {
-            dict: Some(params),
-            operation_name: Some(operation_name),
+            dict: Some(params.into_iter().map(|(key, value)| (key.into(), value)).collect()),
+            operation_name: Some(operation_name.into()),
             ..Default::default()
         }
     }
@@ -256,256 +429,1481 @@ impl<'a> Operation<'a> {
     /// Creates a new Operation from a list of parameters.
     /// 
     /// # Arguments
-    /// * `operation_name` - Name of the operation
+    /// * `operation_name` - Name of the operation, borrowed (`&str`) or owned (`String`)
     /// * `params` - Vector of parameters
     /// 
     /// # Returns
     /// A new Operation instance with list parameters
-    pub fn from_list(operation_name: &'a str, params: Vec<Params>) -> Self {
+    pub fn from_list<N: Into<Cow<'a, str>>>(operation_name: N, params: Vec<Params>) -> Self {
         Self {
             list: Some(params),
-            operation_name: Some(operation_name),
+            operation_name: Some(operation_name.into()),
             ..Default::default()
         }
     }
-}
 
-impl Params {
-    /// Converts a boxed f64 value to its string representation.
+    /// Creates a new Operation from a list of shared parameters.
+    /// 
+    /// Encoding and hashing read the arguments in place, so large texts and byte arrays
+    /// held as `SharedParams`, e.g. from a `ParamsInterner`, are never copied. The operation
+    /// encodes and hashes like `from_list` with the same values as `Params`.
     /// 
     /// # Arguments
-    /// * `val` - Boxed f64 value to convert
+    /// * `operation_name` - Name of the operation, borrowed (`&str`) or owned (`String`)
+    /// * `params` - Vector of shared parameters
     /// 
     /// # Returns
-    /// String representation of the decimal value
-    pub fn decimal_to_string(val: Box<f64>) -> String {
-        val.to_string()
+    /// A new Operation instance with shared list parameters
+    pub fn from_shared_list<N: Into<Cow<'a, str>>>(operation_name: N, params: Vec<SharedParams>) -> Self {
+        Self {
+            shared_list: Some(params),
+            operation_name: Some(operation_name.into()),
+            ..Default::default()
+        }
     }
 
-    /// Converts a dictionary parameter to an array of its values.
-    /// 
+    /// Creates an operation that takes no arguments, encoded as `[name, []]`.
+    ///
+    /// An operation without `list` and `dict` encodes the same way, but `from_dict` with no
+    /// entries does not: it passes one empty struct argument, `[name, [{}]]`.
+    ///
     /// # Arguments
-    /// * `self` - Dictionary parameter to convert
-    /// 
+    /// * `operation_name` - Name of the operation, borrowed (`&str`) or owned (`String`)
+    ///
     /// # Returns
-    /// Vector containing the values from the dictionary
-    /// 
-    /// # Panics
-    /// Panics if self is not a Params::Dict
-    pub fn dict_to_array(self) -> Vec<Params> {
-        match self {
-            Params::Dict(dict) => {
-                let values: Vec<Params> = dict.into_iter()
-                    .filter_map(|(_, value)| {
-                        Some(value)
-                    })
-                    .collect();
-                values
-            },
-            _ => panic!("Expected Params::Dict, found {:?}", self),
-        }
+    /// A new Operation instance without arguments
+    pub fn without_args<N: Into<Cow<'a, str>>>(operation_name: N) -> Self {
+        Self::from_list(operation_name, vec![])
     }
 
-    /// Checks if the parameter value is empty.
+    /// Starts building an operation argument by argument.
     /// 
-    /// Works with Array, Dict, ByteArray, and Text parameter types.
+    /// # Arguments
+    /// * `operation_name` - Name of the operation, borrowed (`&str`) or owned (`String`)
     /// 
     /// # Returns
-    /// true if the parameter value is empty
+    /// An `OperationBuilder` for the operation
     /// 
-    /// # Panics
-    /// Panics if called on parameter types that don't support emptiness check
-    pub fn is_empty(self) -> bool {
-        match self {
-            Params::Array(array) => array.is_empty(),
-            Params::Dict(dict) => dict.is_empty(),
-            Params::ByteArray(bytearray) => bytearray.is_empty(),
-            Params::Text(text) => text.is_empty(),
-            _ => panic!("Cannot check empty of this type {:?}", self)
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::operation::{Operation, OperationOwned, Params};
+    /// let name = format!("create_{}", "book");
+    /// let op: OperationOwned = Operation::builder(name)
+    ///     .arg("ISBN1")
+    ///     .arg(42)
+    ///     .build();
+    ///
+    /// assert_eq!(op.list, Some(vec![Params::from("ISBN1"), Params::Integer(42)]));
+    /// ```
+    pub fn builder<N: Into<Cow<'a, str>>>(operation_name: N) -> OperationBuilder<'a> {
+        OperationBuilder {
+            operation_name: operation_name.into(),
+            list: Vec::new(),
+            dict: Vec::new()
         }
     }
 
-    /// Returns the length of the parameter value.
-    /// 
-    /// Works with Array, Dict, ByteArray, and Text parameter types.
+    /// Creates a `nop` operation without arguments.
     /// 
     /// # Returns
-    /// Length of the parameter value
-    /// 
-    /// # Panics
-    /// Panics if called on parameter types that don't support length
-    pub fn len(self) -> usize {
-        match self {
-            Params::Array(array) => array.len(),
-            Params::Dict(dict) => dict.len(),
-            Params::ByteArray(bytearray) => bytearray.len(),
-            Params::Text(text) => text.len(),
-            _ => panic!("Cannot get length of this type {:?}", self)
-        }
+    /// A new `nop` operation
+    pub fn nop() -> Self {
+        Self::without_args(NOP_OPERATION)
     }
 
-    /// Converts a dictionary parameter to a Rust struct.
-    /// 
-    /// # Type Parameters
-    /// * `T` - The target struct type that implements Default + Debug + Deserialize
+    /// Creates a system operation, adding the `__` prefix to the name if it is missing.
     /// 
-    /// # Returns
-    /// Result containing either the converted struct or an error message
+    /// System operations are handled by GTX modules of the node; their arguments
+    /// depend on the module, so they are passed as given.
     /// 
-    /// # Example
-    /// ```
-    /// #[derive(Debug, Default, serde::Deserialize)]
-    /// struct MyStruct {
-    ///     field: String,
-    ///     value: i64,
-    /// }
+    /// # Arguments
+    /// * `name` - Name of the operation, with or without the `__` prefix
+    /// * `params` - Arguments of the operation
     /// 
-    /// let dict = Params::Dict(/* ... */);
-    /// let result: Result<MyStruct, String> = dict.to_struct();
-    /// ```
-    pub fn to_struct<T>(&self) -> Result<T, String>
-    where
-        T: Default + std::fmt::Debug + for<'de> serde::Deserialize<'de>,
-    {
-        match self {
-            Params::Dict(_) => {
-                let json_value = self.to_json_value();
-                
-                serde_json::from_value(json_value)
-                    .map_err(|e| format!("Failed to convert Params to struct: {}", e))
-            },
-            _ => Err(format!("Expected Params::Dict, found {:?}", self)),
-        }
+    /// # Returns
+    /// A new system operation
+    pub fn system(name: &str, params: Vec<Params>) -> OperationOwned {
+        let name = if name.starts_with(SYSTEM_OPERATION_PREFIX) {
+            name.to_string()
+        } else {
+            format!("{}{}", SYSTEM_OPERATION_PREFIX, name)
+        };
+
+        Operation::from_list(name, params)
     }
 
-    /// Converts the parameter to a serde_json::Value.
-    /// 
-    /// This method handles all parameter types, including complex types
-    /// like BigInteger and ByteArray.
-    /// 
-    /// # Returns
-    /// JSON representation of the parameter
-    pub fn to_json_value(&self) -> serde_json::Value {
-        match *self {
-            Params::Null => serde_json::Value::Null,
-            Params::Boolean(b) => serde_json::Value::Bool(b),
-            Params::Integer(i) => serde_json::Value::Number(serde_json::Number::from(i)),
-            Params::BigInteger(ref big_int) => serde_json::Value::String(big_int.to_string()),
-            Params::Decimal(ref big_decimal) => serde_json::Value::String(big_decimal.to_string()),
-            Params::Text(ref text) => serde_json::Value::String(text.to_string()),
-            Params::ByteArray(ref bytearray) => {
-                if bytearray.len() == 33 {
-                    serde_json::Value::String(hex::encode(bytearray))
-                } else {
-                    let base64_encoded = general_purpose::STANDARD.encode(bytearray);
-                    serde_json::Value::String(base64_encoded)
-                }
-            },
-            Params::Array(ref array) => {
-                let json_array: Vec<serde_json::Value> = array.iter().map(|param| param.to_json_value()).collect();
-                serde_json::Value::Array(json_array)
-            },
-            Params::Dict(ref dict) => {
-                let json_object: serde_json::Map<String, serde_json::Value> = dict.iter()
-                    .map(|(key, value)| (key.to_string(), value.to_json_value()))
-                    .collect();
-                serde_json::Value::Object(json_object)
-            },
-        }
+    /// Returns true for operations handled by the node itself: `nop`, `timeb` and `__`-prefixed operations.
+    pub fn is_special(&self) -> bool {
+        self.name().is_some_and(|name| name == NOP_OPERATION
+            || name == TIMEB_OPERATION
+            || name.starts_with(SYSTEM_OPERATION_PREFIX))
     }
 
-    /// Creates a parameter from a Rust struct.
+    /// Creates a `timeb` operation, which makes the transaction valid only in a time window.
     /// 
-    /// # Type Parameters
-    /// * `T` - The source struct type that implements Debug + Serialize
+    /// Nodes reject a transaction containing this operation unless the timestamp of the
+    /// block including it is at or after `from` and before `until`.
     /// 
     /// # Arguments
-    /// * `struct_instance` - Reference to the struct to convert
+    /// * `from` - Start of the window, in milliseconds since the epoch
+    /// * `until` - End of the window (exclusive), in milliseconds since the epoch
     /// 
     /// # Returns
-    /// Dictionary parameter containing the struct's fields
+    /// A new `timeb` operation
+    pub fn timeb(from: i64, until: i64) -> Self {
+        Self::from_list(TIMEB_OPERATION, vec![Params::Integer(from), Params::Integer(until)])
+    }
+
+    /// Creates a `nop` operation that makes the transaction unique.
     /// 
-    /// # Example
-    /// ```
-    /// #[derive(Debug, serde::Serialize)]
-    /// struct MyStruct {
-    ///     field: String,
-    ///     value: i64,
-    /// }
+    /// Nodes reject a transaction whose RID was already seen, so two transactions with
+    /// the same operations and signers need something to tell them apart. The `nop`
+    /// carries the current time in milliseconds and 16 random bytes.
     /// 
-    /// let my_struct = MyStruct { field: "test".into(), value: 42 };
-    /// let params = Params::from_struct(&my_struct);
-    /// ```
-    pub fn from_struct<T>(struct_instance: &T) -> Params
-    where
-        T: std::fmt::Debug + serde::Serialize + StructMetadata,
-    {
-        let json_value = serde_json::to_value(struct_instance)
-            .expect("Failed to convert struct to JSON value");
-
-        let fnat = T::field_names_and_types();
+    /// # Returns
+    /// A new `nop` operation
+    #[cfg(feature = "std")]
+    pub fn nop_unique() -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let nonce: [u8; 16] = rand::random();
+
+        Self::from_list(NOP_OPERATION, vec![
+            Params::Integer(i64::try_from(timestamp).unwrap_or(i64::MAX)),
+            Params::ByteArray(nonce.to_vec())
+        ])
+    }
 
-        Params::Dict(Self::json_value_to_params_dict(json_value, fnat))
+    /// Returns the name of the operation.
+    pub fn name(&self) -> Option<&str> {
+        self.operation_name.as_deref()
     }
 
-    /// Converts a JSON value to a parameter dictionary, utilizing a provided function name to argument type (fnat) mapping.
-    ///
-    /// # Parameters
-    /// * `value`: The JSON value to be converted.
-    /// * `fnat`: A mapping of function names to argument types, used to determine the type of each parameter.
-    ///
+    /// Converts the operation into one that owns its name and argument names.
+    /// 
     /// # Returns
-    /// A `BTreeMap` containing the converted parameters, where each key is a parameter name and each value is a `Params` object.
-    ///
-    /// # Notes
-    /// * This function assumes that the input JSON value is an object, and will only process key-value pairs within that object.
-    /// * The `fnat` mapping is used to determine the type of each parameter, and should contain a mapping of function names to argument types.
-    /// * If a key in the input JSON value is not present in the `fnat` mapping, the function will use a default type for that parameter.
-    fn json_value_to_params_dict(value: serde_json::Value, fnat: BTreeMap<String, String>) -> BTreeMap<String, Params> {
-        let mut dict: BTreeMap<String, Params> = BTreeMap::new();
-
-        if let serde_json::Value::Object(map) = value {
-            for (key, val) in map {
-                let f_type = fnat.get(&key).cloned();
-                dict.insert(key, Self::value_to_params(val, f_type));
-            }
+    /// An `OperationOwned` that no longer borrows from anything
+    pub fn into_owned(self) -> OperationOwned {
+        Operation {
+            dict: self.dict.map(|dict| dict.into_iter()
+                .map(|(key, value)| (Cow::Owned(key.into_owned()), value))
+                .collect()),
+            list: self.list,
+            shared_list: self.shared_list,
+            operation_name: self.operation_name.map(|name| Cow::Owned(name.into_owned())),
         }
-
-        dict
     }
 
-    /// Creates a list of parameters from a Rust struct.
+    /// Serializes the operation to its GTV encoding, as it appears in a transaction.
     /// 
-    /// Similar to from_struct, but returns a vector of values
-    /// instead of a dictionary.
+    /// # Returns
+    /// GTV-encoded operation
     /// 
-    /// # Type Parameters
-    /// * `T` - The source struct type that implements Debug + Serialize
+    /// # Errors
+    /// `EncodeError::MissingOperationName` if the operation has no name
+    pub fn to_bytes(&self) -> Result<Vec<u8>, crate::encoding::gtv::EncodeError> {
+        crate::encoding::gtv::encode_operation(self)
+    }
+
+    /// Deserializes an operation from its GTV encoding.
+    /// 
+    /// The operation name borrows from `data`, and arguments are always decoded as a list.
     /// 
     /// # Arguments
-    /// * `struct_instance` - Reference to the struct to convert
+    /// * `data` - Bytes produced by [`Operation::to_bytes`]
     /// 
     /// # Returns
-    /// Vector of parameters containing the struct's field values
-    pub fn from_struct_to_list<T>(struct_instance: &T) -> Vec<Params>
-    where
-        T: std::fmt::Debug + serde::Serialize + StructMetadata,
-    {
-        let json_value = serde_json::to_value(struct_instance)
-            .expect("Failed to convert struct to JSON value");
+    /// The decoded operation, or a parse error
+    #[allow(clippy::result_large_err)]
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, asn1::ParseError> {
+        crate::encoding::gtv::decode_operation(data)
+    }
+}
 
-        let mut vec = Vec::new();
+/// How `Params::merge` resolves a key present in both dictionaries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The value from the other dictionary replaces the existing one
+    Overwrite,
+    /// The existing value is kept
+    Keep,
+    /// Nested dictionaries are merged recursively and nested arrays concatenated;
+    /// other values are replaced
+    Deep,
+}
 
-        let fnat = T::field_names_and_types();
+/// A difference between two values found by `Params::diff`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffEntry {
+    /// Path of the value, in the syntax of `Params::get_path`
+    pub path: String,
+    /// What differs at that path
+    pub kind: DiffKind,
+}
+
+/// Kind of difference reported in a `DiffEntry`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DiffKind {
+    /// The value exists only in the other value
+    Added(Params),
+    /// The value exists only in this value
+    Removed(Params),
+    /// The value differs between both
+    Changed {
+        /// Value in this value
+        old: Params,
+        /// Value in the other value
+        new: Params
+    },
+}
+
+/// Formats the entry as one line: `+` for added, `-` for removed and `~` for changed values.
+impl core::fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let path = if self.path.is_empty() { "<root>" } else { &self.path };
+        match &self.kind {
+            DiffKind::Added(value) => write!(f, "+ {}: {:?}", path, value),
+            DiffKind::Removed(value) => write!(f, "- {}: {:?}", path, value),
+            DiffKind::Changed { old, new } => write!(f, "~ {}: {:?} -> {:?}", path, old, new),
+        }
+    }
+}
+
+/// Collects the differences between `old` and `new` at `path` into `entries`.
+fn diff_into(old: &Params, new: &Params, path: String, entries: &mut Vec<DiffEntry>) {
+    let child_path = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+    match (old, new) {
+        (Params::Dict(old_dict), Params::Dict(new_dict)) => {
+            for (key, old_value) in old_dict {
+                match new_dict.get(key) {
+                    Some(new_value) => diff_into(old_value, new_value, child_path(key), entries),
+                    None => entries.push(DiffEntry { path: child_path(key), kind: DiffKind::Removed(old_value.clone()) })
+                }
+            }
+            for (key, new_value) in new_dict.iter().filter(|(key, _)| !old_dict.contains_key(*key)) {
+                entries.push(DiffEntry { path: child_path(key), kind: DiffKind::Added(new_value.clone()) });
+            }
+        },
+        (Params::Array(old_array), Params::Array(new_array)) => {
+            for index in 0..old_array.len().max(new_array.len()) {
+                let item_path = format!("{}[{}]", path, index);
+                match (old_array.get(index), new_array.get(index)) {
+                    (Some(old_value), Some(new_value)) => diff_into(old_value, new_value, item_path, entries),
+                    (Some(old_value), None) => entries.push(DiffEntry { path: item_path, kind: DiffKind::Removed(old_value.clone()) }),
+                    (None, Some(new_value)) => entries.push(DiffEntry { path: item_path, kind: DiffKind::Added(new_value.clone()) }),
+                    (None, None) => {}
+                }
+            }
+        },
+        _ if old != new => entries.push(DiffEntry { path, kind: DiffKind::Changed { old: old.clone(), new: new.clone() } }),
+        _ => {}
+    }
+}
+
+/// Iterator over the elements of an array or the values of a dictionary, created by `Params::iter`.
+#[derive(Clone, Debug)]
+pub struct Iter<'a>(IterInner<'a>);
+
+#[derive(Clone, Debug)]
+enum IterInner<'a> {
+    Array(core::slice::Iter<'a, Params>),
+    Dict(alloc::collections::btree_map::Values<'a, String, Params>),
+    Empty
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Params;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterInner::Array(iter) => iter.next(),
+            IterInner::Dict(iter) => iter.next(),
+            IterInner::Empty => None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterInner::Array(iter) => iter.size_hint(),
+            IterInner::Dict(iter) => iter.size_hint(),
+            IterInner::Empty => (0, Some(0))
+        }
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterInner::Array(iter) => iter.next_back(),
+            IterInner::Dict(iter) => iter.next_back(),
+            IterInner::Empty => None
+        }
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// Iterator over the keys of a dictionary, created by `Params::keys`.
+#[derive(Clone, Debug)]
+pub struct Keys<'a>(Option<alloc::collections::btree_map::Keys<'a, String, Params>>);
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.as_mut()?.next().map(String::as_str)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.as_ref().map_or((0, Some(0)), Iterator::size_hint)
+    }
+}
+
+impl DoubleEndedIterator for Keys<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.as_mut()?.next_back().map(String::as_str)
+    }
+}
+
+impl ExactSizeIterator for Keys<'_> {}
+
+/// Iterator over the values of a dictionary, created by `Params::values`.
+#[derive(Clone, Debug)]
+pub struct Values<'a>(Option<alloc::collections::btree_map::Values<'a, String, Params>>);
+
+impl<'a> Iterator for Values<'a> {
+    type Item = &'a Params;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.as_mut()?.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.as_ref().map_or((0, Some(0)), Iterator::size_hint)
+    }
+}
+
+impl DoubleEndedIterator for Values<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.as_mut()?.next_back()
+    }
+}
+
+impl ExactSizeIterator for Values<'_> {}
+
+/// Owning iterator over the elements of an array or the values of a dictionary.
+#[derive(Debug)]
+pub struct IntoIter(IntoIterInner);
+
+#[derive(Debug)]
+enum IntoIterInner {
+    Array(alloc::vec::IntoIter<Params>),
+    Dict(alloc::collections::btree_map::IntoValues<String, Params>),
+    Empty
+}
+
+impl Iterator for IntoIter {
+    type Item = Params;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterInner::Array(iter) => iter.next(),
+            IntoIterInner::Dict(iter) => iter.next(),
+            IntoIterInner::Empty => None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IntoIterInner::Array(iter) => iter.size_hint(),
+            IntoIterInner::Dict(iter) => iter.size_hint(),
+            IntoIterInner::Empty => (0, Some(0))
+        }
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterInner::Array(iter) => iter.next_back(),
+            IntoIterInner::Dict(iter) => iter.next_back(),
+            IntoIterInner::Empty => None
+        }
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+/// Consumes an array or dictionary into its elements or values; any other variant yields nothing.
+impl IntoIterator for Params {
+    type Item = Params;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(match self {
+            Params::Array(array) => IntoIterInner::Array(array.into_iter()),
+            Params::Dict(dict) => IntoIterInner::Dict(dict.into_values()),
+            _ => IntoIterInner::Empty
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a Params {
+    type Item = &'a Params;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Looks up a key of a dictionary, e.g. `result["balance"]`.
+///
+/// # Panics
+/// Panics if the parameter is not a dictionary or has no such key; use [`Params::get`]
+/// to look up keys without panicking
+impl core::ops::Index<&str> for Params {
+    type Output = Params;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            Params::Dict(dict) => dict.get(key).unwrap_or_else(|| panic!("Key {:?} not found in Params::Dict", key)),
+            _ => panic!("Cannot index {:?} by key {:?}", self, key)
+        }
+    }
+}
+
+/// Looks up an element of an array, e.g. `result[0]`.
+///
+/// # Panics
+/// Panics if the parameter is not an array or the index is out of bounds; use
+/// [`Params::get_index`] to look up elements without panicking
+impl core::ops::Index<usize> for Params {
+    type Output = Params;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Params::Array(array) => array.get(index)
+                .unwrap_or_else(|| panic!("Index {} out of bounds for Params::Array of length {}", index, array.len())),
+            _ => panic!("Cannot index {:?} by position {}", self, index)
+        }
+    }
+}
+
+/// Longest text, in characters, that `Display` shows before truncating it.
+const DISPLAY_TEXT_LIMIT: usize = 64;
+
+/// Renders a parameter as indented, human-readable text for logging.
+/// 
+/// Byte arrays are shown as hex literals (`x"0a1b"`) and texts longer than 64 characters
+/// are truncated. Use `Debug` for the exact structure.
+/// 
+/// # Example
+/// ```
+/// use postchain_client::dict;
+/// 
+/// let value = dict!{"name" => "Alice", "key" => vec![0xab_u8, 0xcd]};
+/// assert_eq!(value.to_string(), "{\n  key: x\"abcd\",\n  name: \"Alice\"\n}");
+/// ```
+impl core::fmt::Display for Params {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_indented(f, 0, &|_| false)
+    }
+}
+
+/// Placeholder `Redacted` shows instead of a sensitive value.
+const REDACTED: &str = "<redacted>";
+
+/// `Display` rendering of a parameter that hides the values of sensitive dictionary keys,
+/// created by `Params::redacted` or `Params::redacted_by`.
+pub struct Redacted<'a, F> {
+    params: &'a Params,
+    is_sensitive: F,
+}
+
+impl<F: Fn(&str) -> bool> core::fmt::Display for Redacted<'_, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.params.fmt_indented(f, 0, &self.is_sensitive)
+    }
+}
+
+impl Params {
+    /// Renders the parameter like `Display`, hiding the values of dictionary entries at any
+    /// depth whose key is in `keys`, compared case-insensitively.
+    /// 
+    /// # Arguments
+    /// * `keys` - Keys of sensitive entries, e.g. `&["private_key", "session_token"]`
+    /// 
+    /// # Example
+    /// ```
+    /// use postchain_client::dict;
+    /// 
+    /// let value = dict!{"user" => "alice", "private_key" => vec![1u8, 2]};
+    /// assert_eq!(value.redacted(&["private_key"]).to_string(), "{\n  private_key: <redacted>,\n  user: \"alice\"\n}");
+    /// ```
+    pub fn redacted<'a>(&'a self, keys: &'a [&'a str]) -> Redacted<'a, impl Fn(&str) -> bool + 'a> {
+        self.redacted_by(move |key: &str| keys.iter().any(|sensitive| sensitive.eq_ignore_ascii_case(key)))
+    }
+
+    /// Renders the parameter like `Display`, hiding the values of dictionary entries at any
+    /// depth whose key matches `is_sensitive`.
+    /// 
+    /// # Arguments
+    /// * `is_sensitive` - Returns true for keys whose values must not be shown
+    pub fn redacted_by<F: Fn(&str) -> bool>(&self, is_sensitive: F) -> Redacted<'_, F> {
+        Redacted { params: self, is_sensitive }
+    }
+
+    fn fmt_indented(&self, f: &mut core::fmt::Formatter<'_>, depth: usize, is_sensitive: &dyn Fn(&str) -> bool) -> core::fmt::Result {
+        let pad = "  ".repeat(depth + 1);
+        match self {
+            Params::Null => f.write_str("null"),
+            Params::Boolean(val) => write!(f, "{}", val),
+            Params::Integer(val) => write!(f, "{}", val),
+            Params::BigInteger(val) => write!(f, "{}", val),
+            Params::Decimal(val) => write!(f, "{}", val),
+            Params::Text(val) if val.chars().count() > DISPLAY_TEXT_LIMIT => {
+                let head: String = val.chars().take(DISPLAY_TEXT_LIMIT).collect();
+                write!(f, "{:?}... ({} chars)", head, val.chars().count())
+            }
+            Params::Text(val) => write!(f, "{:?}", val),
+            Params::ByteArray(val) => write!(f, "x\"{}\"", hex::encode(val)),
+            Params::Array(array) if array.is_empty() => f.write_str("[]"),
+            Params::Array(array) => {
+                f.write_str("[\n")?;
+                for (i, item) in array.iter().enumerate() {
+                    f.write_str(&pad)?;
+                    item.fmt_indented(f, depth + 1, is_sensitive)?;
+                    f.write_str(if i + 1 < array.len() { ",\n" } else { "\n" })?;
+                }
+                write!(f, "{}]", "  ".repeat(depth))
+            }
+            Params::Dict(dict) if dict.is_empty() => f.write_str("{}"),
+            Params::Dict(dict) => {
+                f.write_str("{\n")?;
+                for (i, (key, value)) in dict.iter().enumerate() {
+                    write!(f, "{}{}: ", pad, key)?;
+                    if is_sensitive(key) {
+                        f.write_str(REDACTED)?;
+                    } else {
+                        value.fmt_indented(f, depth + 1, is_sensitive)?;
+                    }
+                    f.write_str(if i + 1 < dict.len() { ",\n" } else { "\n" })?;
+                }
+                write!(f, "{}}}", "  ".repeat(depth))
+            }
+        }
+    }
+}
+
+/// Builder for operations whose arguments are only known at runtime.
+/// 
+/// Arguments are either all positional (`arg`) or all named (`named_arg`).
+#[derive(Clone, Debug)]
+pub struct OperationBuilder<'a> {
+    operation_name: Cow<'a, str>,
+    list: Vec<Params>,
+    dict: Vec<(Cow<'a, str>, Params)>
+}
+
+impl<'a> OperationBuilder<'a> {
+    /// Appends a positional argument.
+    /// 
+    /// # Arguments
+    /// * `value` - Argument value
+    pub fn arg(mut self, value: impl Into<Params>) -> Self {
+        self.list.push(value.into());
+        self
+    }
+
+    /// Appends a named argument.
+    /// 
+    /// # Arguments
+    /// * `name` - Argument name, borrowed (`&str`) or owned (`String`)
+    /// * `value` - Argument value
+    pub fn named_arg(mut self, name: impl Into<Cow<'a, str>>, value: impl Into<Params>) -> Self {
+        self.dict.push((name.into(), value.into()));
+        self
+    }
+
+    /// Builds the operation.
+    /// 
+    /// # Returns
+    /// An operation with named arguments if any were added, otherwise with positional arguments
+    /// 
+    /// # Panics
+    /// Panics if both positional and named arguments were added
+    pub fn build(self) -> Operation<'a> {
+        assert!(self.list.is_empty() || self.dict.is_empty(),
+            "Operation {} mixes positional and named arguments", self.operation_name);
+
+        if self.dict.is_empty() {
+            Operation::from_list(self.operation_name, self.list)
+        } else {
+            Operation::from_dict(self.operation_name, self.dict)
+        }
+    }
+}
+
+/// Options for `Params::from_json_value`.
+/// 
+/// Both heuristics are off by default, so JSON strings stay `Params::Text` and
+/// JSON arrays stay `Params::Array`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+    /// Converts strings of decimal digits, with an optional leading `-`, to `Params::BigInteger`
+    pub big_integer_strings: bool,
+    /// Converts arrays whose elements are all numbers from 0 to 255 to `Params::ByteArray`
+    pub byte_arrays: bool,
+}
+
+/// Builder for a `Params::Array`, started with `Params::array()`.
+#[derive(Clone, Debug, Default)]
+pub struct ArrayBuilder {
+    items: Vec<Params>
+}
+
+impl ArrayBuilder {
+    /// Appends an element.
+    /// 
+    /// # Arguments
+    /// * `value` - Element value
+    pub fn push(mut self, value: impl Into<Params>) -> Self {
+        self.items.push(value.into());
+        self
+    }
+
+    /// Builds the array.
+    pub fn build(self) -> Params {
+        Params::Array(self.items)
+    }
+}
+
+/// Builder for a `Params::Dict`, started with `Params::dict()`.
+#[derive(Clone, Debug, Default)]
+pub struct DictBuilder {
+    entries: BTreeMap<String, Params>
+}
+
+impl DictBuilder {
+    /// Adds an entry, replacing any earlier entry with the same key.
+    /// 
+    /// # Arguments
+    /// * `key` - Entry key
+    /// * `value` - Entry value
+    pub fn entry(mut self, key: impl Into<String>, value: impl Into<Params>) -> Self {
+        self.entries.insert(key.into(), value.into());
+        self
+    }
+
+    /// Builds the dictionary.
+    pub fn build(self) -> Params {
+        Params::Dict(self.entries)
+    }
+}
+
+impl Params {
+    /// Starts building an array element by element.
+    /// 
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::operation::Params;
+    /// let array = Params::array().push(1).push("x").build();
+    ///
+    /// assert_eq!(array, Params::Array(vec![Params::Integer(1), Params::from("x")]));
+    /// ```
+    pub fn array() -> ArrayBuilder {
+        ArrayBuilder::default()
+    }
+
+    /// Starts building a dictionary entry by entry.
+    /// 
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::operation::Params;
+    /// let dict = Params::dict().entry("name", "Alice").entry("age", 30).build();
+    ///
+    /// assert_eq!(dict.get("age"), Some(&Params::Integer(30)));
+    /// ```
+    pub fn dict() -> DictBuilder {
+        DictBuilder::default()
+    }
+
+    /// Converts a boxed f64 value to its string representation.
+    /// 
+    /// # Arguments
+    /// * `val` - Boxed f64 value to convert
+    /// 
+    /// # Returns
+    /// String representation of the decimal value
+    #[deprecated(note = "f64 loses precision; use `Params::decimal_from_f64` or `Params::Decimal` instead")]
+    pub fn decimal_to_string(val: Box<f64>) -> String {
+        val.to_string()
+    }
+
+    /// Parses a decimal number into `Params::Decimal` without losing precision.
+    ///
+    /// # Arguments
+    /// * `value` - Decimal number such as `"12.3400"` or `"-5e-3"`
+    ///
+    /// # Returns
+    /// The decimal parameter, or an error if `value` is not a decimal number
+    pub fn parse_decimal(value: &str) -> Result<Params, bigdecimal::ParseBigDecimalError> {
+        BigDecimal::from_str(value).map(Params::Decimal)
+    }
+
+    /// Converts an `f64` into `Params::Decimal` using its shortest exact decimal form,
+    /// so `0.1` becomes `0.1` rather than its binary approximation.
+    ///
+    /// # Returns
+    /// The decimal parameter, or `None` for NaN and infinite values
+    pub fn decimal_from_f64(value: f64) -> Option<Params> {
+        if !value.is_finite() {
+            return None;
+        }
+        BigDecimal::from_str(&value.to_string()).ok().map(Params::Decimal)
+    }
+
+    /// Reads the parameter as a decimal number.
+    ///
+    /// Besides `Params::Decimal`, this accepts integers and `Params::Text` holding a
+    /// decimal number, which is how decimals come back from GTV decoding.
+    ///
+    /// # Returns
+    /// The decimal value, or `None` if the parameter is not a number
+    pub fn to_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Params::Decimal(val) => Some(val.clone()),
+            Params::Integer(val) => Some(BigDecimal::from(*val)),
+            Params::BigInteger(val) => Some(BigDecimal::from(val.clone())),
+            Params::Text(val) => BigDecimal::from_str(val).ok(),
+            _ => None
+        }
+    }
+
+    /// Converts a dictionary parameter to an array of its values.
+    /// 
+    /// # Arguments
+    /// * `self` - Dictionary parameter to convert
+    /// 
+    /// # Returns
+    /// Vector containing the values from the dictionary, sorted by key
+    /// 
+    /// # Panics
+    /// Panics if self is not a Params::Dict
+    pub fn dict_to_array(self) -> Vec<Params> {
+        match self {
+            Params::Dict(dict) => dict.into_values().collect(),
+            _ => panic!("Expected Params::Dict, found {:?}", self),
+        }
+    }
+
+    /// Converts a dictionary parameter to an array of its values in the order of `keys`.
+    /// 
+    /// Use this to turn a dictionary into positional operation arguments, whose order
+    /// must match the parameters of the operation rather than the sorted keys.
+    /// 
+    /// # Arguments
+    /// * `keys` - Keys in the order the values should appear
+    /// 
+    /// # Returns
+    /// Values in the requested order followed by any unlisted values sorted by key,
+    /// or `None` if self is not a dictionary or a listed key is missing
+    pub fn dict_to_array_ordered(self, keys: &[&str]) -> Option<Vec<Params>> {
+        let entries = self.into_entries_ordered(keys)?;
+        if entries.iter().take(keys.len()).map(|(key, _)| key.as_str()).ne(keys.iter().copied()) {
+            return None;
+        }
+        Some(entries.into_iter().map(|(_, value)| value).collect())
+    }
+
+    /// Returns the key-value pairs of a dictionary, sorted by key.
+    /// 
+    /// # Returns
+    /// Borrowed key-value pairs, or an empty vector if self is not a dictionary
+    pub fn entries(&self) -> Vec<(&str, &Params)> {
+        self.as_dict()
+            .map(|dict| dict.iter().map(|(key, value)| (key.as_str(), value)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Converts a dictionary into its key-value pairs, sorted by key.
+    /// 
+    /// # Returns
+    /// Owned key-value pairs, or `None` if self is not a dictionary
+    pub fn into_entries(self) -> Option<Vec<(String, Params)>> {
+        match self {
+            Params::Dict(dict) => Some(dict.into_iter().collect()),
+            _ => None
+        }
+    }
+
+    /// Converts a dictionary into its key-value pairs, listing `keys` first in the given order.
+    /// 
+    /// Keys of `keys` missing from the dictionary are skipped and the remaining entries follow
+    /// sorted by key, so no entry is lost.
+    /// 
+    /// # Arguments
+    /// * `keys` - Keys in the order their entries should appear, e.g. the original insertion order
+    /// 
+    /// # Returns
+    /// Owned key-value pairs, or `None` if self is not a dictionary
+    pub fn into_entries_ordered(self, keys: &[&str]) -> Option<Vec<(String, Params)>> {
+        let Params::Dict(mut dict) = self else {
+            return None;
+        };
+
+        let mut entries: Vec<(String, Params)> = keys.iter()
+            .filter_map(|key| dict.remove_entry(*key))
+            .collect();
+        entries.extend(dict);
+
+        Some(entries)
+    }
+
+    /// Checks if the parameter value is empty.
+    /// 
+    /// Works with Array, Dict, ByteArray, and Text parameter types.
+    /// 
+    /// # Returns
+    /// true if the parameter value is empty
+    /// 
+    /// # Panics
+    /// Panics if called on parameter types that don't support emptiness check
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Params::Array(array) => array.is_empty(),
+            Params::Dict(dict) => dict.is_empty(),
+            Params::ByteArray(bytearray) => bytearray.is_empty(),
+            Params::Text(text) => text.is_empty(),
+            _ => panic!("Cannot check empty of this type {:?}", self)
+        }
+    }
+
+    /// Returns the length of the parameter value.
+    /// 
+    /// Works with Array, Dict, ByteArray, and Text parameter types.
+    /// 
+    /// # Returns
+    /// Length of the parameter value
+    /// 
+    /// # Panics
+    /// Panics if called on parameter types that don't support length
+    pub fn len(&self) -> usize {
+        match self {
+            Params::Array(array) => array.len(),
+            Params::Dict(dict) => dict.len(),
+            Params::ByteArray(bytearray) => bytearray.len(),
+            Params::Text(text) => text.len(),
+            _ => panic!("Cannot get length of this type {:?}", self)
+        }
+    }
+
+    /// Returns the name of the variant, e.g. `"Integer"`, for logging and error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Params::Null => "Null",
+            Params::Boolean(_) => "Boolean",
+            Params::Integer(_) => "Integer",
+            Params::BigInteger(_) => "BigInteger",
+            Params::Decimal(_) => "Decimal",
+            Params::Text(_) => "Text",
+            Params::ByteArray(_) => "ByteArray",
+            Params::Array(_) => "Array",
+            Params::Dict(_) => "Dict",
+        }
+    }
+
+    /// Returns true if the parameter is `Params::Null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Params::Null)
+    }
+
+    /// Maps `Params::Null` to `None` and any other value to `Some`.
+    /// 
+    /// Nullable Rell values decode to `Params::Null`, so this can be chained with the typed
+    /// accessors, e.g. `value.as_option().map(Params::as_str)`.
+    pub fn as_option(&self) -> Option<&Params> {
+        match self {
+            Params::Null => None,
+            value => Some(value)
+        }
+    }
+
+    /// Returns the value of a `Params::Boolean`, or `None` for any other variant.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Params::Boolean(val) => Some(*val),
+            _ => None
+        }
+    }
+
+    /// Returns the value of a `Params::Integer`, or `None` for any other variant.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Params::Integer(val) => Some(*val),
+            _ => None
+        }
+    }
+
+    /// Creates a `Params::Text` holding the hyphenated form of a UUID.
+    #[cfg(feature = "uuid")]
+    pub fn uuid_text(value: uuid::Uuid) -> Params {
+        Params::Text(value.hyphenated().to_string())
+    }
+
+    /// Reads a UUID stored as 16 bytes in a `Params::ByteArray` or as text in a `Params::Text`.
+    /// 
+    /// # Returns
+    /// The UUID, or `None` if the parameter holds no valid UUID
+    #[cfg(feature = "uuid")]
+    pub fn as_uuid(&self) -> Option<uuid::Uuid> {
+        match self {
+            Params::ByteArray(bytes) => uuid::Uuid::from_slice(bytes).ok(),
+            Params::Text(text) => uuid::Uuid::parse_str(text).ok(),
+            _ => None
+        }
+    }
+
+    /// Reads the parameter as a `rust_decimal::Decimal`, accepting the same variants as `to_decimal`.
+    /// 
+    /// # Returns
+    /// The decimal value, or `None` if the parameter is not a number or does not fit into a `Decimal`
+    #[cfg(feature = "rust_decimal")]
+    pub fn to_rust_decimal(&self) -> Option<rust_decimal::Decimal> {
+        let decimal = self.to_decimal()?;
+        rust_decimal::Decimal::from_str_exact(&decimal.to_plain_string()).ok()
+    }
+
+    /// Returns a reference to the value of a `Params::BigInteger`, or `None` for any other variant.
+    pub fn as_bigint(&self) -> Option<&BigInt> {
+        match self {
+            Params::BigInteger(val) => Some(val),
+            _ => None
+        }
+    }
+
+    /// Returns a reference to the value of a `Params::Decimal`, or `None` for any other variant.
+    pub fn as_decimal(&self) -> Option<&BigDecimal> {
+        match self {
+            Params::Decimal(val) => Some(val),
+            _ => None
+        }
+    }
+
+    /// Returns the text of a `Params::Text`, or `None` for any other variant.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Params::Text(val) => Some(val),
+            _ => None
+        }
+    }
+
+    /// Returns the bytes of a `Params::ByteArray`, or `None` for any other variant.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Params::ByteArray(val) => Some(val),
+            _ => None
+        }
+    }
+
+    /// Returns the elements of a `Params::Array`, or `None` for any other variant.
+    pub fn as_array(&self) -> Option<&[Params]> {
+        match self {
+            Params::Array(val) => Some(val),
+            _ => None
+        }
+    }
+
+    /// Returns the entries of a `Params::Dict`, or `None` for any other variant.
+    pub fn as_dict(&self) -> Option<&BTreeMap<String, Params>> {
+        match self {
+            Params::Dict(val) => Some(val),
+            _ => None
+        }
+    }
+
+    /// Returns the value stored under `key` in a `Params::Dict`.
+    ///
+    /// # Returns
+    /// The value, or `None` if the parameter is not a dictionary or has no such key
+    pub fn get(&self, key: &str) -> Option<&Params> {
+        self.as_dict()?.get(key)
+    }
+
+    /// Returns the element at `index` in a `Params::Array`.
+    ///
+    /// # Returns
+    /// The element, or `None` if the parameter is not an array or the index is out of bounds
+    pub fn get_index(&self, index: usize) -> Option<&Params> {
+        self.as_array()?.get(index)
+    }
+
+    /// Navigates into nested dictionaries and arrays with a path such as `accounts[0].balance`.
+    ///
+    /// Dictionary keys are separated by `.` and array indexes are written in brackets.
+    /// Keys containing `.` or `[` cannot be addressed this way; use `get` for those.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the value; an empty path returns `self`
+    ///
+    /// # Returns
+    /// The value, or `None` if the path is malformed or does not exist
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::{dict, gtv_array, utils::operation::Params};
+    /// let result = dict!{"accounts" => gtv_array![dict!{"balance" => 100}]};
+    ///
+    /// assert_eq!(result.get_path("accounts[0].balance"), Some(&Params::Integer(100)));
+    /// assert_eq!(result.get_path("accounts[1].balance"), None);
+    /// ```
+    pub fn get_path(&self, path: &str) -> Option<&Params> {
+        parse_path(path)?.iter().try_fold(self, |current, segment| match segment {
+            PathSegment::Key(key) => current.get(key),
+            PathSegment::Index(index) => current.get_index(*index),
+        })
+    }
+
+    /// Merges `other` into this parameter.
+    ///
+    /// Two dictionaries are merged key by key, where `strategy` decides what happens to keys present
+    /// in both. Two arrays are concatenated. For any other pair of values, `Keep` leaves this value
+    /// unchanged and the other strategies replace it with `other`.
+    ///
+    /// # Arguments
+    /// * `other` - Value to merge into this one
+    /// * `strategy` - How to resolve keys present in both dictionaries
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::{dict, gtv_array, utils::operation::{MergeStrategy, Params}};
+    /// let mut args = dict!{"fee" => 1, "limits" => dict!{"daily" => 10}, "tags" => gtv_array!["a"]};
+    /// args.merge(dict!{"limits" => dict!{"weekly" => 50}, "tags" => gtv_array!["b"]}, MergeStrategy::Deep);
+    ///
+    /// assert_eq!(args, dict!{
+    ///     "fee" => 1,
+    ///     "limits" => dict!{"daily" => 10, "weekly" => 50},
+    ///     "tags" => gtv_array!["a", "b"]
+    /// });
+    /// ```
+    pub fn merge(&mut self, other: Params, strategy: MergeStrategy) {
+        match (self, other) {
+            (Params::Dict(dict), Params::Dict(other)) => {
+                for (key, value) in other {
+                    match dict.entry(key) {
+                        alloc::collections::btree_map::Entry::Vacant(entry) => {
+                            entry.insert(value);
+                        },
+                        alloc::collections::btree_map::Entry::Occupied(mut entry) => match strategy {
+                            MergeStrategy::Overwrite => {
+                                entry.insert(value);
+                            },
+                            MergeStrategy::Keep => {},
+                            MergeStrategy::Deep => entry.get_mut().merge(value, strategy)
+                        }
+                    }
+                }
+            },
+            (Params::Array(array), Params::Array(other)) => array.extend(other),
+            (_, _) if strategy == MergeStrategy::Keep => {},
+            (this, other) => *this = other
+        }
+    }
+
+    /// Compares this value with `other` and reports every path where they differ.
+    ///
+    /// Paths use the syntax of `get_path`, e.g. `accounts[0].balance`; the root is the empty path.
+    /// Dictionaries are compared key by key and arrays index by index; any other pair of values
+    /// that is not equal is reported as changed.
+    ///
+    /// # Arguments
+    /// * `other` - Value to compare with, e.g. a newer query result
+    ///
+    /// # Returns
+    /// The differences in path order; empty if both values are equal
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::{dict, utils::operation::Params};
+    /// let before = dict!{"balance" => 100, "name" => "a"};
+    /// let after = dict!{"balance" => 150, "name" => "a"};
+    ///
+    /// let report: Vec<String> = before.diff(&after).iter().map(ToString::to_string).collect();
+    /// assert_eq!(report, vec!["~ balance: Integer(100) -> Integer(150)"]);
+    /// ```
+    pub fn diff(&self, other: &Params) -> Vec<DiffEntry> {
+        let mut entries = Vec::new();
+        diff_into(self, other, String::new(), &mut entries);
+        entries
+    }
+
+    /// Iterates over the elements of a `Params::Array` or the values of a `Params::Dict`.
+    ///
+    /// Any other variant yields nothing.
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::{gtv_array, utils::operation::Params};
+    /// let total: i64 = gtv_array![1, 2, 3].iter().filter_map(Params::as_i64).sum();
+    /// assert_eq!(total, 6);
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(match self {
+            Params::Array(array) => IterInner::Array(array.iter()),
+            Params::Dict(dict) => IterInner::Dict(dict.values()),
+            _ => IterInner::Empty
+        })
+    }
+
+    /// Iterates over the keys of a `Params::Dict` in order; any other variant yields nothing.
+    pub fn keys(&self) -> Keys<'_> {
+        Keys(self.as_dict().map(BTreeMap::keys))
+    }
+
+    /// Iterates over the values of a `Params::Dict` in key order; any other variant yields nothing.
+    pub fn values(&self) -> Values<'_> {
+        Values(self.as_dict().map(BTreeMap::values))
+    }
+
+    /// Converts a dictionary parameter to a Rust struct.
+    /// 
+    /// `Option` fields become `None` for `Params::Null` values and missing keys.
+    /// 
+    /// # Type Parameters
+    /// * `T` - The target struct type that implements Default + Debug + Deserialize
+    /// 
+    /// # Returns
+    /// Result containing either the converted struct or an error message naming the
+    /// path of the offending field, e.g. `provider.tier: expected Params::Text, found Params::Integer`
+    /// 
+    /// # Example
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # use postchain_client::utils::operation::Params;
+    /// #[derive(Debug, Default, serde::Deserialize)]
+    /// struct MyStruct {
+    ///     field: String,
+    ///     value: i64,
+    /// }
+    /// 
+    /// let dict = Params::Dict(BTreeMap::from([
+    ///     ("field".to_string(), Params::Text("test".to_string())),
+    ///     ("value".to_string(), Params::Integer(42)),
+    /// ]));
+    /// let result: Result<MyStruct, String> = dict.to_struct();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_struct<T>(&self) -> Result<T, String>
+    where
+        T: Default + core::fmt::Debug + for<'de> serde::Deserialize<'de>,
+    {
+        match self {
+            Params::Dict(_) => {
+                let json_value = self.to_json_value();
+                
+                serde_path_to_error::deserialize(json_value)
+                    .map_err(|e| format!("Failed to convert Params to struct: {}", self.describe_struct_error(e)))
+            },
+            _ => Err(format!("Expected Params::Dict, found {:?}", self)),
+        }
+    }
+
+    /// Converts a dictionary parameter to a Rust struct, coercing values whose GTV type
+    /// differs harmlessly from the field type.
+    /// 
+    /// Like `to_struct`, but integers `0` and `1` fill `bool` fields, since the chain encodes
+    /// booleans as integers; booleans and numeric texts fill integer fields; decimals and numeric
+    /// texts fill float fields; and integers fill fields read as strings, such as `BigInt` fields
+    /// with a string deserializer. Values inside enums are not coerced.
+    /// 
+    /// # Type Parameters
+    /// * `T` - The target struct type that implements Default + Debug + Deserialize
+    /// 
+    /// # Returns
+    /// Result containing either the converted struct or an error message
+    /// 
+    /// # Example
+    /// ```
+    /// # use postchain_client::{dict, utils::operation::Params};
+    /// #[derive(Debug, Default, serde::Deserialize)]
+    /// struct Account {
+    ///     active: bool,
+    ///     balance: f64,
+    /// }
+    /// 
+    /// let value = dict!{"active" => 1, "balance" => 10};
+    /// assert!(value.to_struct::<Account>().is_err());
+    /// assert!(value.to_struct_lenient::<Account>().unwrap().active);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_struct_lenient<T>(&self) -> Result<T, String>
+    where
+        T: Default + core::fmt::Debug + for<'de> serde::Deserialize<'de>,
+    {
+        match self {
+            Params::Dict(_) => {
+                let deserializer = crate::utils::lenient_deserializer::LenientDeserializer(self.to_json_value());
+
+                serde_path_to_error::deserialize(deserializer)
+                    .map_err(|e| format!("Failed to convert Params to struct: {}", self.describe_struct_error(e)))
+            },
+            _ => Err(format!("Expected Params::Dict, found {:?}", self)),
+        }
+    }
+
+    /// Describes a `to_struct` failure by the path of the offending field, and for type
+    /// mismatches by the `Params` variant found there, e.g.
+    /// `provider.tier: expected Params::Text, found Params::Integer`.
+    #[cfg(feature = "std")]
+    fn describe_struct_error(&self, error: serde_path_to_error::Error<serde_json::Error>) -> String {
+        let path = error.path().to_string();
+        let message = error.inner().to_string();
+        let path = if path == "." { String::new() } else { path };
+
+        let mismatch = message.strip_prefix("invalid type: ")
+            .and_then(|rest| rest.split_once(", expected "))
+            .zip(self.get_path(&path));
+
+        let description = match mismatch {
+            Some(((_, expected), found)) => format!("expected {}, found Params::{}", expected_kind(expected), found.kind()),
+            None => message,
+        };
+
+        if path.is_empty() {
+            description
+        } else {
+            format!("{}: {}", path, description)
+        }
+    }
+
+    /// Checks a dictionary against the fields of a struct before calling `to_struct`.
+    /// 
+    /// Reports missing and unknown fields, `Params::Null` in non-`Option` fields and values
+    /// whose variant cannot hold the field type, e.g. `Params::Text` for an `i64`. Fields of
+    /// other types, such as nested structs, accept any value.
+    /// 
+    /// # Type Parameters
+    /// * `T` - The target struct type that implements StructMetadata
+    /// 
+    /// # Returns
+    /// `Ok(())`, or every violation found as a human-readable message
+    /// 
+    /// # Example
+    /// ```
+    /// # use postchain_client::{dict, utils::operation::{Params, StructMetadata}};
+    /// #[derive(Debug, Default, serde::Deserialize, postchain_client::StructMetadata)]
+    /// struct Book {
+    ///     isbn: String,
+    ///     rating: i64,
+    /// }
+    /// 
+    /// let value = dict!{"isbn" => 42, "ratings" => 5};
+    /// assert_eq!(value.validate::<Book>(), Err(vec![
+    ///     "isbn: expected Params::Text for String, found Params::Integer".to_string(),
+    ///     "rating: missing field".to_string(),
+    ///     "ratings: unknown field".to_string(),
+    /// ]));
+    /// ```
+    pub fn validate<T: StructMetadata>(&self) -> Result<(), Vec<String>> {
+        let Params::Dict(dict) = self else {
+            return Err(vec![format!("Expected Params::Dict, found Params::{}", self.kind())]);
+        };
+
+        let fields = T::field_names_and_types();
+        let mut violations = Vec::new();
+
+        for name in T::field_names() {
+            let field_type = &fields[&name];
+            match dict.get(&name) {
+                Some(value) => check_field_type(&name, field_type, value, &mut violations),
+                None if compact_type(field_type).starts_with("Option<") => {}
+                None => violations.push(format!("{}: missing field", name)),
+            }
+        }
+
+        for key in dict.keys().filter(|key| !fields.contains_key(*key)) {
+            violations.push(format!("{}: unknown field", key));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Converts the parameter to a serde_json::Value.
+    /// 
+    /// This method handles all parameter types, including complex types
+    /// like BigInteger and ByteArray.
+    /// 
+    /// # Returns
+    /// JSON representation of the parameter
+    pub fn to_json_value(&self) -> serde_json::Value {
+        match *self {
+            Params::Null => serde_json::Value::Null,
+            Params::Boolean(b) => serde_json::Value::Bool(b),
+            Params::Integer(i) => serde_json::Value::Number(serde_json::Number::from(i)),
+            Params::BigInteger(ref big_int) => serde_json::Value::String(big_int.to_string()),
+            Params::Decimal(ref big_decimal) => serde_json::Value::String(big_decimal.to_string()),
+            Params::Text(ref text) => serde_json::Value::String(text.to_string()),
+            Params::ByteArray(ref bytearray) => {
+                if bytearray.len() == 33 {
+                    serde_json::Value::String(hex::encode(bytearray))
+                } else {
+                    let base64_encoded = general_purpose::STANDARD.encode(bytearray);
+                    serde_json::Value::String(base64_encoded)
+                }
+            },
+            Params::Array(ref array) => {
+                let json_array: Vec<serde_json::Value> = array.iter().map(|param| param.to_json_value()).collect();
+                serde_json::Value::Array(json_array)
+            },
+            Params::Dict(ref dict) => {
+                let json_object: serde_json::Map<String, serde_json::Value> = dict.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_json_value()))
+                    .collect();
+                serde_json::Value::Object(json_object)
+            },
+        }
+    }
+
+    /// Creates a parameter from a Rust struct.
+    /// 
+    /// Enum fields follow serde's representation: unit variants become `Params::Text` with the
+    /// variant name, matching Rell enum names, and variants with data become a single-entry
+    /// `Params::Dict` keyed by the variant name. Use `#[serde(with = "postchain_client::utils::enum_ordinal")]`
+    /// to send a unit variant as its `Params::Integer` ordinal instead.
+    /// 
+    /// # Type Parameters
+    /// * `T` - The source struct type that implements Debug + Serialize
+    /// 
+    /// # Arguments
+    /// * `struct_instance` - Reference to the struct to convert
+    /// 
+    /// # Returns
+    /// Dictionary parameter containing the struct's fields
+    /// 
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::operation::{Params, StructMetadata};
+    /// #[derive(Debug, serde::Serialize, postchain_client::StructMetadata)]
+    /// struct MyStruct {
+    ///     field: String,
+    ///     value: i64,
+    /// }
+    /// 
+    /// let my_struct = MyStruct { field: "test".into(), value: 42 };
+    /// let params = Params::from_struct(&my_struct);
+    /// ```
+    pub fn from_struct<T>(struct_instance: &T) -> Params
+    where
+        T: core::fmt::Debug + serde::Serialize + StructMetadata,
+    {
+        let json_value = serde_json::to_value(struct_instance)
+            .expect("Failed to convert struct to JSON value");
 
-        if let serde_json::Value::Object(map) = json_value {
+        let fnat = T::field_names_and_types();
+
+        Params::Dict(Self::json_value_to_params_dict(json_value, fnat))
+    }
+
+    /// Converts a JSON value to a parameter dictionary, utilizing a provided function name to argument type (fnat) mapping.
+    ///
+    /// # Parameters
+    /// * `value`: The JSON value to be converted.
+    /// * `fnat`: A mapping of function names to argument types, used to determine the type of each parameter.
+    ///
+    /// # Returns
+    /// A `BTreeMap` containing the converted parameters, where each key is a parameter name and each value is a `Params` object.
+    ///
+    /// # Notes
+    /// * This function assumes that the input JSON value is an object, and will only process key-value pairs within that object.
+    /// * The `fnat` mapping is used to determine the type of each parameter, and should contain a mapping of function names to argument types.
+    /// * If a key in the input JSON value is not present in the `fnat` mapping, the function will use a default type for that parameter.
+    fn json_value_to_params_dict(value: serde_json::Value, fnat: BTreeMap<String, String>) -> BTreeMap<String, Params> {
+        let mut dict: BTreeMap<String, Params> = BTreeMap::new();
+
+        if let serde_json::Value::Object(map) = value {
             for (key, val) in map {
                 let f_type = fnat.get(&key).cloned();
-                vec.push(Self::value_to_params(val, f_type));
+                dict.insert(key, Self::value_to_params(val, f_type));
             }
         }
 
-        vec
+        dict
+    }
+
+    /// Creates a list of parameters from a Rust struct.
+    /// 
+    /// Similar to from_struct, but returns a vector of values
+    /// instead of a dictionary. The values follow the field declaration
+    /// order, which is the argument order of the matching Rell operation.
+    /// 
+    /// # Type Parameters
+    /// * `T` - The source struct type that implements Debug + Serialize
+    /// 
+    /// # Arguments
+    /// * `struct_instance` - Reference to the struct to convert
+    /// 
+    /// # Returns
+    /// Vector of parameters containing the struct's field values
+    pub fn from_struct_to_list<T>(struct_instance: &T) -> Vec<Params>
+    where
+        T: core::fmt::Debug + serde::Serialize + StructMetadata,
+    {
+        let json_value = serde_json::to_value(struct_instance)
+            .expect("Failed to convert struct to JSON value");
+
+        let fnat = T::field_names_and_types();
+
+        Self::fields_in_declaration_order::<T>(json_value).into_iter().map(|(key, val)| {
+            let f_type = fnat.get(&key).cloned();
+            Self::value_to_params(val, f_type)
+        }).collect()
+    }
+
+    /// Creates named operation arguments from a Rust struct, in field declaration order.
+    /// 
+    /// # Type Parameters
+    /// * `T` - The source struct type that implements Debug + Serialize + StructMetadata
+    /// 
+    /// # Arguments
+    /// * `struct_instance` - Reference to the struct to convert
+    /// 
+    /// # Returns
+    /// Field names and values, ready for `Operation::from_dict`
+    pub fn from_struct_to_ordered_vec<T>(struct_instance: &T) -> Vec<(String, Params)>
+    where
+        T: core::fmt::Debug + serde::Serialize + StructMetadata,
+    {
+        let json_value = serde_json::to_value(struct_instance)
+            .expect("Failed to convert struct to JSON value");
+
+        let fnat = T::field_names_and_types();
+
+        Self::fields_in_declaration_order::<T>(json_value).into_iter().map(|(key, val)| {
+            let f_type = fnat.get(&key).cloned();
+            let value = Self::value_to_params(val, f_type);
+            (key, value)
+        }).collect()
+    }
+
+    /// Takes the fields of a serialized struct in the order reported by `StructMetadata::field_names`.
+    /// 
+    /// `serde_json` objects are sorted by key; fields that are not in `field_names`, e.g.
+    /// renamed by serde, follow in that sorted order.
+    fn fields_in_declaration_order<T: StructMetadata>(value: serde_json::Value) -> Vec<(String, serde_json::Value)> {
+        let serde_json::Value::Object(mut map) = value else {
+            return Vec::new();
+        };
+
+        let mut fields: Vec<(String, serde_json::Value)> = T::field_names().into_iter()
+            .filter_map(|name| map.remove(&name).map(|value| (name, value)))
+            .collect();
+        fields.extend(map);
+        fields
     }
 
     /// Converts a struct into a Vec<(String, Params)>.
@@ -520,7 +1918,7 @@ impl Params {
     /// Vector of tuples containing string keys and Params values
     pub fn from_struct_to_vec<T>(struct_instance: &T) -> Vec<(String, Params)>
     where
-        T: std::fmt::Debug + serde::Serialize,
+        T: core::fmt::Debug + serde::Serialize,
     {
         let json_value = serde_json::to_value(struct_instance)
             .expect("Failed to convert struct to JSON value");
@@ -536,6 +1934,61 @@ impl Params {
         vec
     }
 
+    /// Converts a JSON value to a parameter without a struct definition.
+    /// 
+    /// Integers beyond `i64` become `Params::BigInteger` and fractional numbers
+    /// `Params::Decimal`; `options` controls the other heuristics.
+    /// 
+    /// # Arguments
+    /// * `value` - The JSON value to convert
+    /// * `options` - Heuristics to apply
+    /// 
+    /// # Returns
+    /// The converted parameter
+    /// 
+    /// # Example
+    /// ```
+    /// # use postchain_client::{dict, gtv_array, utils::operation::{JsonOptions, Params}};
+    /// let json = serde_json::json!({"amount": "123456789012345678901234567890", "key": [1, 2]});
+    /// let options = JsonOptions { big_integer_strings: true, byte_arrays: true };
+    /// 
+    /// let params = Params::from_json_value(json, options);
+    /// assert_eq!(params.get("key"), Some(&Params::ByteArray(vec![1, 2])));
+    /// assert!(matches!(params.get("amount"), Some(Params::BigInteger(_))));
+    /// ```
+    pub fn from_json_value(value: serde_json::Value, options: JsonOptions) -> Params {
+        match value {
+            serde_json::Value::Null => Params::Null,
+            serde_json::Value::Bool(b) => Params::Boolean(b),
+            serde_json::Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    Params::Integer(i)
+                } else if let Some(u) = n.as_u64() {
+                    Params::BigInteger(BigInt::from(u))
+                } else {
+                    BigDecimal::from_str(&n.to_string()).map(Params::Decimal).unwrap_or(Params::Null)
+                }
+            },
+            serde_json::Value::String(s) => {
+                let digits = s.strip_prefix('-').unwrap_or(&s);
+                let is_integer = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+                match BigInt::parse_bytes(s.as_bytes(), 10) {
+                    Some(big_int) if options.big_integer_strings && is_integer => Params::BigInteger(big_int),
+                    _ => Params::Text(s)
+                }
+            },
+            serde_json::Value::Array(arr) if options.byte_arrays && is_vec_u8(&arr) => {
+                Params::ByteArray(arr.iter().filter_map(|v| v.as_u64()).map(|v| v as u8).collect())
+            },
+            serde_json::Value::Array(arr) => {
+                Params::Array(arr.into_iter().map(|x| Self::from_json_value(x, options)).collect())
+            },
+            serde_json::Value::Object(dict) => {
+                Params::Dict(dict.into_iter().map(|(k, v)| (k, Self::from_json_value(v, options))).collect())
+            }
+        }
+    }
+
     /// Converts a JSON value to a parameter.
     ///
     /// This function handles the conversion of various JSON types to their corresponding parameter types.
@@ -551,9 +2004,17 @@ impl Params {
     ///
     /// ### Notes
     ///
-    /// * If the `field_type` is `Some` and contains "BigInt", the function will attempt to parse the JSON string value as a BigInteger.
-    /// * If the `field_type` is `Some` and contains "BigDecimal", the function will attempt to parse the JSON string value as a BigDecimal.
-    /// * If the JSON value is an array and all elements are numbers, the function will attempt to convert it to a byte array.
+    /// * If the `field_type` is `Some` and names the `BigInt` type, e.g. `BigInt` or `Option<num_bigint::BigInt>`,
+    ///   the function will attempt to parse the JSON string value as a BigInteger. Other strings, including
+    ///   numeric ones such as ISBNs or phone numbers, stay `Params::Text`.
+    /// * If the `field_type` is `Some` and names the `BigDecimal` type, or `Decimal` with the `rust_decimal` feature,
+    ///   the function will attempt to parse the JSON string value as a BigDecimal.
+    /// * If the `field_type` is a byte array (`Vec<u8>`, `[u8; N]` or a field marked `#[params(bytes)]`), an array
+    ///   of numbers becomes a byte array; a `#[params(bytes)]` string is decoded from hex, so a `#[params(bytes)]`
+    ///   `Uuid` field becomes its 16 bytes while an unmarked one stays text. Arrays in fields of other
+    ///   types, such as `Vec<i64>`, stay arrays.
+    /// * If the `field_type` is `None` and the JSON value is an array whose elements are all numbers up to 255,
+    ///   the function will convert it to a byte array.
     fn value_to_params(value: serde_json::Value, field_type: Option<String>) -> Params {
         match value {
             serde_json::Value::Null => Params::Null,
@@ -567,13 +2028,21 @@ impl Params {
             },
             serde_json::Value::String(s) => {
                 match field_type {
-                    Some(val) if val.contains("BigInt") => {
+                    Some(val) if val.starts_with(BYTES_FIELD_MARKER) => {
+                        // UUIDs serialize as hyphenated hex
+                        let hex_str = if type_mentions(&val, "Uuid") { s.replace('-', "") } else { s };
+                        match hex::decode(hex_str.trim_start_matches("0x")) {
+                            Ok(bytes) => Params::ByteArray(bytes),
+                            Err(_) => panic!("Required field is not a valid hex byte array"),
+                        }
+                    },
+                    Some(val) if type_mentions(&val, "BigInt") => {
                         match BigInt::parse_bytes(s.as_bytes(), 10) {
                             Some(big_int) => Params::BigInteger(big_int),
                             None => panic!("Required field is not a valid BigInteger"),
                         }
                     },
-                    Some(val) if val.contains("BigDecimal") => {
+                    Some(val) if is_decimal_type(&val) => {
                         match BigDecimal::parse_bytes(s.as_bytes(), 10) {
                             Some(big_decimal) => Params::Decimal(big_decimal),
                             None => panic!("Required field is not a valid BigDecimal"),
@@ -583,13 +2052,17 @@ impl Params {
                 }
             },
             serde_json::Value::Array(arr) => {
-                let is_vec_u8 = is_vec_u8(&arr);
-                if is_vec_u8 {
-                    let barr: Vec<u8> = arr.iter().map(|v|{v.as_u64().unwrap() as u8}).collect();
+                let is_bytes = match &field_type {
+                    Some(val) => is_bytes_field_type(val) && is_vec_u8(&arr),
+                    None => is_vec_u8(&arr)
+                };
+                if is_bytes {
+                    let barr: Vec<u8> = arr.iter().filter_map(|v| v.as_u64()).map(|v| v as u8).collect();
                     return Params::ByteArray(barr)
                 }
+                let item_type = field_type.as_deref().map(element_type);
                 let params_array: Vec<Params> = arr.into_iter().map(|x|{
-                    Self::value_to_params(x, None)
+                    Self::value_to_params(x, item_type.clone())
                 }).collect();
                 Params::Array(params_array)
             },
@@ -607,7 +2080,7 @@ impl Params {
     /// 
     /// # Arguments
     /// * `self` - The parameter to debug print
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "std"))]
     pub fn debug_print(&self) {
         match self {
             Params::Array(array) => {
@@ -629,6 +2102,17 @@ impl Params {
                 eprintln!("{:?}", self)
         }
     }
+
+    /// Prints the parameter to stderr with the values of sensitive dictionary keys hidden.
+    /// 
+    /// This method is only available in debug builds.
+    /// 
+    /// # Arguments
+    /// * `keys` - Keys of sensitive entries, compared case-insensitively
+    #[cfg(all(debug_assertions, feature = "std"))]
+    pub fn debug_print_redacted(&self, keys: &[&str]) {
+        eprintln!("{}", self.redacted(keys));
+    }
 }
 
 /// Converts `Params` to `bool`.
@@ -670,77 +2154,361 @@ impl From<Params> for BigInt {
     }
 }
 
-/// Converts `Params` to `BigDecimal`.
+/// Converts `Params` to `BigDecimal`.
+///
+/// # Panics
+/// Panics if the `Params` variant is not `Params::Decimal`.
+impl From<Params> for BigDecimal {
+    fn from(value: Params) -> Self {
+        match value {
+            Params::Decimal(val) => val,
+            _ => panic!("Cannot convert {:?} to BigDecimal", value)
+        }
+    }
+}
+
+/// Converts `Params` to `String`.
+///
+/// # Panics
+/// Panics if the `Params` variant is not `Params::Text`.
+impl From<Params> for String {
+    fn from(value: Params) -> Self {
+        match value {
+            Params::Text(val) => val,
+            _ => panic!("Cannot convert {:?} to String", value)
+        }
+    }
+}
+
+/// Converts `Params` to `Vec<u8>`.
+///
+/// # Panics
+/// Panics if the `Params` variant is not `Params::ByteArray`.
+impl From<Params> for Vec<u8> {
+    fn from(value: Params) -> Self {
+        match value {
+            Params::ByteArray(val) => val,
+            _ => panic!("Cannot convert {:?} to Vec<u8>", value)
+        }
+    }
+}
+
+/// Error returned when a `Params` value is converted into a type of another variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    /// Variant the conversion requires
+    pub expected: &'static str,
+    /// Variant that was found
+    pub found: &'static str,
+}
+
+impl core::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Expected Params::{}, found Params::{}", self.expected, self.found)
+    }
+}
+
+impl core::error::Error for ConversionError {}
+
+/// Implements conversion from Params to `Vec<Params>`.
+/// 
+/// This implementation allows converting an Array parameter
+/// into a vector of parameters.
+/// 
+/// # Errors
+/// Returns a `ConversionError` if the parameter is not an Array type
+impl TryFrom<Params> for Vec<Params> {
+    type Error = ConversionError;
+
+    fn try_from(value: Params) -> Result<Self, Self::Error> {
+        match value {
+            Params::Array(array) => Ok(array),
+            _ => Err(ConversionError { expected: "Array", found: value.kind() }),
+        }
+    }
+}
+
+/// Implements conversion from Params to BTreeMap<String, Params>.
+/// 
+/// This implementation allows converting a Dict parameter
+/// into a BTreeMap of string keys and parameter values.
+/// 
+/// # Errors
+/// Returns a `ConversionError` if the parameter is not a Dict type
+impl TryFrom<Params> for BTreeMap<String, Params> {
+    type Error = ConversionError;
+
+    fn try_from(value: Params) -> Result<Self, Self::Error> {
+        match value {
+            Params::Dict(dict) => Ok(dict),
+            _ => Err(ConversionError { expected: "Dict", found: value.kind() }),
+        }
+    }
+}
+
+/// Converts a `bool` into `Params::Boolean`.
+impl From<bool> for Params {
+    fn from(value: bool) -> Self {
+        Params::Boolean(value)
+    }
+}
+
+/// Converts an `i64` into `Params::Integer`.
+impl From<i64> for Params {
+    fn from(value: i64) -> Self {
+        Params::Integer(value)
+    }
+}
+
+/// Converts a string slice into `Params::Text`.
+impl From<&str> for Params {
+    fn from(value: &str) -> Self {
+        Params::Text(value.to_string())
+    }
+}
+
+/// Converts a `String` into `Params::Text`.
+impl From<String> for Params {
+    fn from(value: String) -> Self {
+        Params::Text(value)
+    }
+}
+
+/// Converts a `Vec<u8>` into `Params::ByteArray`.
+impl From<Vec<u8>> for Params {
+    fn from(value: Vec<u8>) -> Self {
+        Params::ByteArray(value)
+    }
+}
+
+/// Converts a `BigInt` into `Params::BigInteger`.
+impl From<BigInt> for Params {
+    fn from(value: BigInt) -> Self {
+        Params::BigInteger(value)
+    }
+}
+
+/// Converts a `BigDecimal` into `Params::Decimal`.
+impl From<BigDecimal> for Params {
+    fn from(value: BigDecimal) -> Self {
+        Params::Decimal(value)
+    }
+}
+
+/// Converts a `Uuid` into its 16 bytes as `Params::ByteArray`.
+/// 
+/// Use [`Params::uuid_text`] for the hyphenated text form instead.
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Params {
+    fn from(value: uuid::Uuid) -> Self {
+        Params::ByteArray(value.as_bytes().to_vec())
+    }
+}
+
+/// Converts a `rust_decimal::Decimal` into `Params::Decimal`, keeping its scale.
+#[cfg(feature = "rust_decimal")]
+impl From<rust_decimal::Decimal> for Params {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Params::Decimal(BigDecimal::new(BigInt::from(value.mantissa()), i64::from(value.scale())))
+    }
+}
+
+/// Converts a `Vec<Params>` into `Params::Array`.
+impl From<Vec<Params>> for Params {
+    fn from(value: Vec<Params>) -> Self {
+        Params::Array(value)
+    }
+}
+
+/// Converts a `BTreeMap<String, Params>` into `Params::Dict`.
+impl From<BTreeMap<String, Params>> for Params {
+    fn from(value: BTreeMap<String, Params>) -> Self {
+        Params::Dict(value)
+    }
+}
+
+/// Converts an `i32` into `Params::Integer`.
+impl From<i32> for Params {
+    fn from(value: i32) -> Self {
+        Params::Integer(value.into())
+    }
+}
+
+/// Converts a `u32` into `Params::Integer`.
+impl From<u32> for Params {
+    fn from(value: u32) -> Self {
+        Params::Integer(value.into())
+    }
+}
+
+/// Converts a `u64` into `Params::Integer`, or into `Params::BigInteger` when it exceeds `i64::MAX`.
+impl From<u64> for Params {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Params::Integer(value),
+            Err(_) => Params::BigInteger(BigInt::from(value))
+        }
+    }
+}
+
+/// Converts an `Option` into its value, or `Params::Null` for `None`.
+impl<T: Into<Params>> From<Option<T>> for Params {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Params::Null, Into::into)
+    }
+}
+
+/// Converts a fixed-size array into `Params::Array`.
+impl<T: Into<Params>, const N: usize> From<[T; N]> for Params {
+    fn from(value: [T; N]) -> Self {
+        Params::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Converts a `HashMap` with string keys into `Params::Dict`.
+#[cfg(feature = "std")]
+impl<T: Into<Params>, S: std::hash::BuildHasher> From<std::collections::HashMap<String, T, S>> for Params {
+    fn from(value: std::collections::HashMap<String, T, S>) -> Self {
+        Params::Dict(value.into_iter().map(|(key, value)| (key, value.into())).collect())
+    }
+}
+
+/// Implements conversion of tuples into `Params::Array`, one element per tuple field.
+macro_rules! impl_from_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Into<Params>),+> From<($($name,)+)> for Params {
+            #[allow(non_snake_case)]
+            fn from(($($name,)+): ($($name,)+)) -> Self {
+                Params::Array(vec![$($name.into()),+])
+            }
+        }
+    };
+}
+
+impl_from_tuple!(A, B);
+impl_from_tuple!(A, B, C);
+impl_from_tuple!(A, B, C, D);
+impl_from_tuple!(A, B, C, D, E);
+impl_from_tuple!(A, B, C, D, E, F);
+
+/// Builds operation or query arguments.
+///
+/// With `name => value` pairs it creates a `Vec<(&str, Params)>` for `Operation::from_dict`
+/// and queries; with plain values it creates a `Vec<Params>` for `Operation::from_list`.
+/// Every value is converted with `Params::from`, so other macros of this module can be nested.
+///
+/// # Example
+/// ```
+/// use postchain_client::{params, utils::operation::{Operation, Params}};
+///
+/// let op = Operation::from_dict("create_book_review", params!{"isbn" => "X", "rating" => 5});
+/// assert_eq!(op.dict.unwrap()[1].1, Params::Integer(5));
+///
+/// let op = Operation::from_list("create_book", params!["ISBN1", "Book1", true]);
+/// assert_eq!(op.list.unwrap()[2], Params::Boolean(true));
+/// ```
+#[macro_export]
+macro_rules! params {
+    () => {
+        $crate::__private::Vec::new()
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {
+        $crate::__private::vec![$(($key, $crate::utils::operation::Params::from($value))),+]
+    };
+    ($($value:expr),+ $(,)?) => {
+        $crate::__private::vec![$($crate::utils::operation::Params::from($value)),+]
+    };
+}
+
+/// Builds a `Params::Dict` from `key => value` pairs.
 ///
-/// # Panics
-/// Panics if the `Params` variant is not `Params::Decimal`.
-impl From<Params> for BigDecimal {
-    fn from(value: Params) -> Self {
-        match value {
-            Params::Decimal(val) => val,
-            _ => panic!("Cannot convert {:?} to BigDecimal", value)
-        }
-    }
+/// # Example
+/// ```
+/// use postchain_client::{dict, utils::operation::Params};
+///
+/// let author = dict!{"name" => "Jane", "age" => 42};
+/// let Params::Dict(fields) = author else { unreachable!() };
+/// assert_eq!(fields["age"], Params::Integer(42));
+/// ```
+#[macro_export]
+macro_rules! dict {
+    ($($key:expr => $value:expr),* $(,)?) => {
+        $crate::utils::operation::Params::Dict($crate::__private::BTreeMap::from([
+            $(($crate::__private::ToString::to_string(&$key), $crate::utils::operation::Params::from($value))),*
+        ]))
+    };
 }
 
-/// Converts `Params` to `String`.
+/// Builds a `Params::Array` from values of mixed types.
 ///
-/// # Panics
-/// Panics if the `Params` variant is not `Params::Text`.
-impl From<Params> for String {
-    fn from(value: Params) -> Self {
-        match value {
-            Params::Text(val) => val,
-            _ => panic!("Cannot convert {:?} to String", value)
-        }
-    }
+/// # Example
+/// ```
+/// use postchain_client::{bytes, gtv_array, utils::operation::Params};
+///
+/// let array = gtv_array![1, "foo", bytes!(b"\x01\x02")];
+/// assert_eq!(array, Params::Array(vec![
+///     Params::Integer(1),
+///     Params::Text("foo".to_string()),
+///     Params::ByteArray(vec![1, 2])
+/// ]));
+/// ```
+#[macro_export]
+macro_rules! gtv_array {
+    ($($value:expr),* $(,)?) => {
+        $crate::utils::operation::Params::Array($crate::__private::vec![$($crate::utils::operation::Params::from($value)),*])
+    };
 }
 
-/// Converts `Params` to `Vec<u8>`.
-///
-/// # Panics
-/// Panics if the `Params` variant is not `Params::ByteArray`.
-impl From<Params> for Vec<u8> {
-    fn from(value: Params) -> Self {
-        match value {
-            Params::ByteArray(val) => val,
-            _ => panic!("Cannot convert {:?} to Vec<u8>", value)
-        }
-    }
+/// Builds a `Params::ByteArray` from anything that can be viewed as bytes,
+/// such as byte string literals, arrays, slices and vectors.
+#[macro_export]
+macro_rules! bytes {
+    ($value:expr) => {
+        $crate::utils::operation::Params::ByteArray(::core::convert::AsRef::<[u8]>::as_ref(&$value).to_vec())
+    };
 }
 
-/// Implements conversion from Params to `Vec<Params>`.
-/// 
-/// This implementation allows converting an Array parameter
-/// into a vector of parameters.
-/// 
-/// # Panics
-/// Panics if the parameter is not an Array type
-impl Into<Vec<Params>> for Params {
-    fn into(self) -> Vec<Params> {
-        match self {
-            Params::Array(array) => array,
-            _ => panic!("Cannot convert {:?} into Vec<Params>", self),
-        }
-    }
+/// One step of a path in the syntax of `Params::get_path`
+#[derive(Debug, PartialEq)]
+pub(crate) enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
 }
 
-/// Implements conversion from Params to BTreeMap<String, Params>.
-/// 
-/// This implementation allows converting a Dict parameter
-/// into a BTreeMap of string keys and parameter values.
-/// 
-/// # Panics
-/// Panics if the parameter is not a Dict type
-impl Into<BTreeMap<String, Params>> for Params {
-    fn into(self) -> BTreeMap<String, Params> {
-        match self {
-            Params::Dict(dict) => dict,
-            _ => panic!("Cannot convert {:?} into BTreeMap", self),
+/// Splits a path such as `accounts[0].balance` into its segments
+///
+/// # Returns
+/// The segments, or `None` if the path is malformed
+pub(crate) fn parse_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+
+    while !rest.is_empty() {
+        if let Some(after_bracket) = rest.strip_prefix('[') {
+            let (index, after_index) = after_bracket.split_once(']')?;
+            segments.push(PathSegment::Index(index.parse().ok()?));
+            rest = after_index;
+        } else {
+            let key_end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if key_end == 0 {
+                return None;
+            }
+            segments.push(PathSegment::Key(&rest[..key_end]));
+            rest = &rest[key_end..];
+        }
+
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            if after_dot.is_empty() || after_dot.starts_with(['.', '[']) {
+                return None;
+            }
+            rest = after_dot;
         }
     }
+
+    Some(segments)
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_serialize_struct_to_param_dict() {
     #[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq)]
@@ -762,7 +2530,7 @@ fn test_serialize_struct_to_param_dict() {
 
     let ts1 = TestStruct1 {
         foo: "foo".to_string(), bar: 1, ok: true,
-        bigint: num_bigint::BigInt::from(170141183460469231731687303715884105727 as i128),
+        bigint: num_bigint::BigInt::from(170141183460469231731687303715884105727_i128),
         nested_struct: TestStruct2{foo: "bar".to_string()}, bytearray: vec![1, 2, 3, 4, 5]
     };
 
@@ -774,6 +2542,7 @@ fn test_serialize_struct_to_param_dict() {
     
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_deserialize_param_dict_to_struct() {
     use std::str::FromStr;
@@ -781,7 +2550,7 @@ fn test_deserialize_param_dict_to_struct() {
     /// We have two options here for deserialization big integer:
     /// 1. Use `String` struct
     /// 2. Use `num_bigint::BigInt` struct with serder custom function
-    /// name `deserialize_bigint`
+    ///    name `deserialize_bigint`
     #[derive(Debug, Default, serde::Deserialize, PartialEq)]
     struct TestNestedStruct {
         bigint_as_string: String,
@@ -802,7 +2571,7 @@ fn test_deserialize_param_dict_to_struct() {
         t: Option<bool>
     }
 
-    let bigint = num_bigint::BigInt::from(100000000000000000000000 as i128);
+    let bigint = num_bigint::BigInt::from(100000000000000000000000_i128);
     let bytearray_value = b"1234";
     let bytearray_base64_encoded = general_purpose::STANDARD.encode(bytearray_value);
 
@@ -810,9 +2579,9 @@ fn test_deserialize_param_dict_to_struct() {
         t: None,
         x: 1, y: 2, z: "foo".to_string(), dict: TestNestedStruct {
             bigint_as_string: bigint.to_string(),
-            bigint_as_num_bigint: (100000000000000000000000 as i128).into()
+            bigint_as_num_bigint: 100000000000000000000000_i128.into()
         }, l: true, n: BigDecimal::from_str("3.14").unwrap(), m: bytearray_base64_encoded, array: vec![
-            serde_json::Value::Number(serde_json::Number::from(1 as i64)),
+            serde_json::Value::Number(serde_json::Number::from(1_i64)),
             serde_json::Value::String("foo".to_string()),
             ]
     };
@@ -842,6 +2611,7 @@ fn test_deserialize_param_dict_to_struct() {
     }
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_serialize_deserialize_bigint() {
     let large_int_str = "100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
@@ -869,6 +2639,7 @@ fn test_serialize_deserialize_bigint() {
     }
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_serialize_deserialize_bigdecimal() {
     use std::str::FromStr;
@@ -898,6 +2669,7 @@ fn test_serialize_deserialize_bigdecimal() {
     assert_eq!(ts, m.unwrap());
 }
 
+#[cfg(feature = "std")]
 #[test]
 fn test_struct_metadata_derive() {
     #[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, StructMetadata)]
@@ -920,4 +2692,745 @@ fn test_struct_metadata_derive() {
     
     assert_eq!(m.bigdecimal, BigDecimal::parse_bytes("55.77e-5".as_bytes(), 10).unwrap());
     assert_eq!(m.bigint, BigInt::parse_bytes("123".as_bytes(), 10).unwrap());
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_nop_unique() {
+    let nop1 = Operation::nop_unique();
+    let nop2 = Operation::nop_unique();
+
+    assert_eq!(nop1.name(), Some("nop"));
+    assert_eq!(nop1.list.as_ref().unwrap().len(), 2);
+    assert_ne!(nop1, nop2);
+}
+
+#[test]
+fn test_special_operations() {
+    assert_eq!(Operation::nop(), Operation::from_list("nop", vec![]));
+    assert_eq!(Operation::timeb(1, 2).list, Some(vec![Params::Integer(1), Params::Integer(2)]));
+    assert_eq!(Operation::system("nop", vec![]).name(), Some("__nop"));
+    assert_eq!(Operation::system("__nop", vec![]).name(), Some("__nop"));
+
+    assert!(Operation::nop().is_special());
+    assert!(Operation::timeb(1, 2).is_special());
+    assert!(Operation::system("anything", vec![]).is_special());
+    assert!(!Operation::from_list("set_name", vec![]).is_special());
+}
+
+#[test]
+fn test_construction_macros() {
+    let args: Vec<(&str, Params)> = params!{
+        "isbn" => "X",
+        "rating" => 5,
+        "tags" => gtv_array!["fiction", bytes!([0xAB])],
+        "author" => dict!{"name" => String::from("Jane")},
+    };
+
+    assert_eq!(args, vec![
+        ("isbn", Params::Text("X".to_string())),
+        ("rating", Params::Integer(5)),
+        ("tags", Params::Array(vec![Params::Text("fiction".to_string()), Params::ByteArray(vec![0xAB])])),
+        ("author", Params::Dict(BTreeMap::from([("name".to_string(), Params::Text("Jane".to_string()))]))),
+    ]);
+
+    assert_eq!(params![false, -1], vec![Params::Boolean(false), Params::Integer(-1)]);
+    assert_eq!(Operation::from_list("nop", params![]), Operation::from_list("nop", vec![]));
+    assert_eq!(dict!{}, Params::Dict(BTreeMap::new()));
+    assert_eq!(bytes!(vec![1u8, 2]), Params::ByteArray(vec![1, 2]));
+}
+
+#[test]
+fn test_params_from_values() {
+    assert_eq!(Params::from(7), Params::Integer(7));
+    assert_eq!(Params::from(true), Params::Boolean(true));
+    assert_eq!(Params::from("foo"), Params::Text("foo".to_string()));
+    assert_eq!(Params::from("foo".to_string()), Params::Text("foo".to_string()));
+    assert_eq!(Params::from(vec![1u8, 2]), Params::ByteArray(vec![1, 2]));
+    assert_eq!(Params::from(BigInt::from(1) << 70), Params::BigInteger(BigInt::from(1) << 70));
+    assert_eq!(Params::from(vec![Params::Null]), Params::Array(vec![Params::Null]));
+
+    let dict = BTreeMap::from([("a".to_string(), Params::Integer(1))]);
+    assert_eq!(Params::from(dict.clone()), Params::Dict(dict));
+
+    let args: Vec<Params> = vec![42.into(), "text".into(), vec![0u8; 4].into()];
+    assert_eq!(Operation::from_list("op", args.clone()).list, Some(args));
+}
+
+#[test]
+fn test_typed_accessors() {
+    let dict = dict!{"name" => "Jane", "age" => 42, "tags" => gtv_array!["a"], "key" => bytes!([1, 2])};
+
+    let fields = dict.as_dict().unwrap();
+    assert_eq!(fields["name"].as_str(), Some("Jane"));
+    assert_eq!(fields["age"].as_i64(), Some(42));
+    assert_eq!(fields["tags"].as_array().map(<[Params]>::len), Some(1));
+    assert_eq!(fields["key"].as_bytes(), Some(&[1u8, 2][..]));
+
+    assert_eq!(fields["name"].as_i64(), None);
+    assert_eq!(fields["age"].as_str(), None);
+    assert_eq!(dict.as_array(), None);
+    assert_eq!(Params::Null.as_dict(), None);
+    assert!(Params::Null.is_null());
+
+    assert_eq!(Params::Boolean(true).as_bool(), Some(true));
+    assert_eq!(Params::BigInteger(BigInt::from(5)).as_bigint(), Some(&BigInt::from(5)));
+    assert_eq!(Params::Decimal(BigDecimal::from(5)).as_decimal(), Some(&BigDecimal::from(5)));
+    assert_eq!(Params::Integer(5).as_bigint(), None);
+}
+
+#[test]
+fn test_get_path() {
+    let result = dict!{
+        "accounts" => gtv_array![
+            dict!{"id" => "a", "balance" => 100},
+            dict!{"id" => "b", "balance" => 250, "history" => gtv_array![gtv_array![1, 2]]}
+        ],
+        "total" => 350
+    };
+
+    assert_eq!(result.get_path("total"), Some(&Params::Integer(350)));
+    assert_eq!(result.get_path("accounts[1].balance"), Some(&Params::Integer(250)));
+    assert_eq!(result.get_path("accounts[1].history[0][1]"), Some(&Params::Integer(2)));
+    assert_eq!(result.get_path(""), Some(&result));
+    assert_eq!(result.get("accounts").and_then(|a| a.get_index(0)).and_then(|a| a.get("id")), Some(&Params::from("a")));
+    assert_eq!(result.get_path("accounts").unwrap().get_path("[0].id"), Some(&Params::from("a")));
+
+    for path in ["missing", "accounts[2]", "accounts[x]", "accounts[0", "accounts.0", "total.x", "accounts[0].", ".total", "accounts..x"] {
+        assert_eq!(result.get_path(path), None, "path {}", path);
+    }
+}
+
+#[test]
+fn test_decimal_helpers() {
+    let precise = "12345678901234567890.123456789012345678901234567890";
+    let param = Params::parse_decimal(precise).unwrap();
+    assert_eq!(param, Params::Decimal(BigDecimal::from_str(precise).unwrap()));
+    assert!(Params::parse_decimal("12.x").is_err());
+
+    let decoded = crate::encoding::gtv::decode(&crate::encoding::gtv::encode_value(&param).unwrap()).unwrap();
+    assert_eq!(decoded.to_decimal(), param.to_decimal());
+
+    assert_eq!(Params::decimal_from_f64(0.1), Some(Params::Decimal(BigDecimal::from_str("0.1").unwrap())));
+    assert_eq!(Params::decimal_from_f64(f64::NAN), None);
+    assert_eq!(Params::decimal_from_f64(f64::INFINITY), None);
+
+    assert_eq!(Params::Integer(-3).to_decimal(), Some(BigDecimal::from(-3)));
+    assert_eq!(Params::BigInteger(BigInt::from(7)).to_decimal(), Some(BigDecimal::from(7)));
+    assert_eq!(Params::from("abc").to_decimal(), None);
+    assert_eq!(Params::Null.to_decimal(), None);
+    assert_eq!(Params::from(BigDecimal::from(2)), Params::Decimal(BigDecimal::from(2)));
+}
+
+#[test]
+fn test_params_serde_roundtrip() {
+    let value = dict!{
+        "null" => Params::Null,
+        "flag" => true,
+        "count" => 42,
+        "big" => BigInt::from(1) << 80,
+        "price" => BigDecimal::from_str("0.000000012340").unwrap(),
+        "name" => "Jane",
+        "raw" => bytes!([0xDE, 0xAD]),
+        "list" => gtv_array![1, "1"]
+    };
+
+    let json = serde_json::to_value(&value).unwrap();
+    assert_eq!(json["type"], "Dict");
+    assert_eq!(json["value"]["count"], serde_json::json!({"type": "Integer", "value": 42}));
+    assert_eq!(json["value"]["big"], serde_json::json!({"type": "BigInteger", "value": "1208925819614629174706176"}));
+    assert_eq!(json["value"]["raw"], serde_json::json!({"type": "ByteArray", "value": "3q0="}));
+    assert_eq!(json["value"]["null"], serde_json::json!({"type": "Null"}));
+
+    let reloaded: Params = serde_json::from_str(&serde_json::to_string(&value).unwrap()).unwrap();
+    assert_eq!(reloaded, value);
+    assert_eq!(reloaded.get("price").and_then(Params::as_decimal).map(BigDecimal::to_string), Some("1.2340E-8".to_string()));
+    assert!(serde_json::from_value::<Params>(serde_json::json!({"type": "Integer", "value": "x"})).is_err());
+}
+
+#[test]
+fn test_from_struct_keeps_numeric_strings_as_text() {
+    #[derive(Debug, serde::Serialize)]
+    struct BigIntLabel(String);
+
+    #[derive(Debug, serde::Serialize, StructMetadata)]
+    struct Book {
+        isbn: String,
+        phone: Option<String>,
+        label: BigIntLabel,
+        #[serde(serialize_with = "serialize_bigint")]
+        copies: num_bigint::BigInt,
+    }
+
+    let book = Book {
+        isbn: "12345".to_string(),
+        phone: Some("0123456789".to_string()),
+        label: BigIntLabel("42".to_string()),
+        copies: BigInt::from(7),
+    };
+
+    let params = Params::from_struct(&book);
+    assert_eq!(params.get("isbn"), Some(&Params::from("12345")));
+    assert_eq!(params.get("phone"), Some(&Params::from("0123456789")));
+    assert_eq!(params.get("label"), Some(&Params::from("42")));
+    assert_eq!(params.get("copies"), Some(&Params::BigInteger(BigInt::from(7))));
+
+    assert!(type_mentions("Option < num_bigint :: BigInt >", "BigInt"));
+    assert!(!type_mentions("BigIntLabel", "BigInt"));
+}
+
+#[test]
+fn test_from_struct_byte_array_fields() {
+    #[derive(Debug, serde::Serialize, StructMetadata)]
+    struct Record {
+        scores: Vec<i64>,
+        matrix: Vec<Vec<i64>>,
+        data: Vec<u8>,
+        hash: [u8; 4],
+        chunks: Vec<Vec<u8>>,
+        #[params(bytes)]
+        pubkey: String,
+        #[params(bytes)]
+        signature: Option<String>,
+    }
+
+    let record = Record {
+        scores: vec![1, 2, 3],
+        matrix: vec![vec![4, 5]],
+        data: vec![1, 2, 3],
+        hash: [0xDE, 0xAD, 0xBE, 0xEF],
+        chunks: vec![vec![9]],
+        pubkey: "0x02ab".to_string(),
+        signature: None,
+    };
+
+    let params = Params::from_struct(&record);
+    assert_eq!(params.get("scores"), Some(&gtv_array![1, 2, 3]));
+    assert_eq!(params.get("matrix"), Some(&gtv_array![gtv_array![4, 5]]));
+    assert_eq!(params.get("data"), Some(&Params::ByteArray(vec![1, 2, 3])));
+    assert_eq!(params.get("hash"), Some(&Params::ByteArray(vec![0xDE, 0xAD, 0xBE, 0xEF])));
+    assert_eq!(params.get("chunks"), Some(&gtv_array![bytes!([9])]));
+    assert_eq!(params.get("pubkey"), Some(&Params::ByteArray(vec![0x02, 0xAB])));
+    assert_eq!(params.get("signature"), Some(&Params::Null));
+
+    assert_eq!(element_type("Option < [Vec < u8 > ; 2] >"), "Vec<u8>");
+    assert!(!is_bytes_field_type("Vec < u16 >"));
+}
+
+#[test]
+fn test_operation_builder() {
+    struct Store {
+        operations: Vec<OperationOwned>
+    }
+
+    let names = ["isbn".to_string(), "rating".to_string()];
+    let mut store = Store { operations: Vec::new() };
+
+    store.operations.push(Operation::builder(String::from("create_book_review"))
+        .named_arg(names[0].clone(), "X")
+        .named_arg(names[1].clone(), 5)
+        .build());
+    store.operations.push(Operation::builder("nop").build());
+
+    assert_eq!(store.operations[0], Operation::from_dict("create_book_review", params!{"isbn" => "X", "rating" => 5}));
+    assert_eq!(store.operations[1], Operation::from_list("nop", vec![]));
+    assert_eq!(Operation::builder("op").arg(true).arg(vec![1u8]).build().list,
+        Some(vec![Params::Boolean(true), Params::ByteArray(vec![1])]));
+}
+
+#[test]
+#[should_panic(expected = "mixes positional and named arguments")]
+fn test_operation_builder_rejects_mixed_arguments() {
+    Operation::builder("op").arg(1).named_arg("a", 2).build();
+}
+
+#[test]
+fn test_params_from_composite_values() {
+    assert_eq!(Params::from(Some("a")), Params::from("a"));
+    assert_eq!(Params::from(None::<i64>), Params::Null);
+    assert_eq!(Params::from([1, 2]), gtv_array![1, 2]);
+    assert_eq!(Params::from(("a", 1, true)), gtv_array!["a", 1, true]);
+    assert_eq!(Params::from((Some(1u32), None::<String>)), gtv_array![1, Params::Null]);
+
+    #[cfg(feature = "std")]
+    {
+        let map = std::collections::HashMap::from([("x".to_string(), 1), ("y".to_string(), 2)]);
+        assert_eq!(Params::from(map), dict!{"x" => 1, "y" => 2});
+    }
+
+    assert_eq!(Params::from(u32::MAX), Params::Integer(u32::MAX.into()));
+    assert_eq!(Params::from(i64::MAX as u64), Params::Integer(i64::MAX));
+    assert_eq!(Params::from(u64::MAX), Params::BigInteger(BigInt::from(u64::MAX)));
+    assert_eq!(Params::from(BigDecimal::from(3)), Params::Decimal(BigDecimal::from(3)));
+    assert_eq!(params!{"rating" => 5}, vec![("rating", Params::Integer(5))]);
+}
+
+#[test]
+fn test_params_merge() {
+    let base = dict!{"fee" => 1, "limits" => dict!{"daily" => 10}, "tags" => gtv_array!["a"]};
+    let layer = dict!{"fee" => 2, "limits" => dict!{"weekly" => 50}, "tags" => gtv_array!["b"], "memo" => "x"};
+
+    let mut overwritten = base.clone();
+    overwritten.merge(layer.clone(), MergeStrategy::Overwrite);
+    assert_eq!(overwritten, dict!{"fee" => 2, "limits" => dict!{"weekly" => 50}, "tags" => gtv_array!["b"], "memo" => "x"});
+
+    let mut kept = base.clone();
+    kept.merge(layer.clone(), MergeStrategy::Keep);
+    assert_eq!(kept, dict!{"fee" => 1, "limits" => dict!{"daily" => 10}, "tags" => gtv_array!["a"], "memo" => "x"});
+
+    let mut deep = base.clone();
+    deep.merge(layer, MergeStrategy::Deep);
+    assert_eq!(deep, dict!{"fee" => 2, "limits" => dict!{"daily" => 10, "weekly" => 50}, "tags" => gtv_array!["a", "b"], "memo" => "x"});
+
+    let mut array = gtv_array![1];
+    array.merge(gtv_array![2, 3], MergeStrategy::Keep);
+    assert_eq!(array, gtv_array![1, 2, 3]);
+
+    let mut scalar = Params::Integer(1);
+    scalar.merge(Params::from("x"), MergeStrategy::Keep);
+    assert_eq!(scalar, Params::Integer(1));
+    scalar.merge(Params::from("x"), MergeStrategy::Overwrite);
+    assert_eq!(scalar, Params::from("x"));
+}
+
+#[test]
+fn test_params_diff() {
+    let before = dict!{
+        "accounts" => gtv_array![dict!{"balance" => 100}, dict!{"balance" => 5}],
+        "owner" => "a",
+        "closed" => false
+    };
+    let after = dict!{
+        "accounts" => gtv_array![dict!{"balance" => 150}],
+        "owner" => "a",
+        "memo" => "hi"
+    };
+
+    let diff = before.diff(&after);
+    assert_eq!(diff, vec![
+        DiffEntry { path: "accounts[0].balance".to_string(), kind: DiffKind::Changed { old: Params::Integer(100), new: Params::Integer(150) } },
+        DiffEntry { path: "accounts[1]".to_string(), kind: DiffKind::Removed(dict!{"balance" => 5}) },
+        DiffEntry { path: "closed".to_string(), kind: DiffKind::Removed(Params::Boolean(false)) },
+        DiffEntry { path: "memo".to_string(), kind: DiffKind::Added(Params::from("hi")) },
+    ]);
+    for entry in &diff {
+        if let DiffKind::Changed { new, .. } | DiffKind::Added(new) = &entry.kind {
+            assert_eq!(after.get_path(&entry.path), Some(new));
+        }
+    }
+
+    assert_eq!(diff[3].to_string(), "+ memo: Text(\"hi\")");
+    assert_eq!(Params::Integer(1).diff(&Params::from("1"))[0].to_string(), "~ <root>: Integer(1) -> Text(\"1\")");
+    assert!(before.diff(&before).is_empty());
+}
+
+#[test]
+fn test_params_iterators() {
+    let array = gtv_array![1, 2, 3];
+    let dict = dict!{"b" => 2, "a" => 1};
+
+    assert_eq!(array.iter().len(), 3);
+    assert_eq!(array.iter().rev().filter_map(Params::as_i64).collect::<Vec<_>>(), vec![3, 2, 1]);
+    assert_eq!(dict.iter().cloned().collect::<Vec<_>>(), vec![Params::Integer(1), Params::Integer(2)]);
+    assert_eq!(dict.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(dict.values().len(), 2);
+    assert_eq!(array.keys().count(), 0);
+    assert_eq!(array.values().count(), 0);
+    assert_eq!(Params::Integer(1).iter().count(), 0);
+
+    let mut sum = 0;
+    for item in &array {
+        sum += item.as_i64().unwrap();
+    }
+    assert_eq!(sum, 6);
+
+    assert_eq!(array.into_iter().collect::<Vec<_>>(), vec![Params::Integer(1), Params::Integer(2), Params::Integer(3)]);
+    assert_eq!(dict.into_iter().next_back(), Some(Params::Integer(2)));
+    assert_eq!(Params::Null.into_iter().len(), 0);
+}
+
+#[test]
+fn test_params_index() {
+    let result = dict!{"accounts" => gtv_array![dict!{"balance" => 100}]};
+
+    assert_eq!(result["accounts"][0]["balance"], Params::Integer(100));
+    assert_eq!(result.get("accounts").and_then(|a| a.get_index(1)), None);
+}
+
+#[test]
+#[should_panic(expected = "Key \"missing\" not found")]
+fn test_params_index_missing_key() {
+    let _ = &dict!{"a" => 1}["missing"];
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_params_index_out_of_bounds() {
+    let _ = &gtv_array![1][1];
+}
+
+#[test]
+fn test_try_from_params_collections() {
+    assert_eq!(Vec::<Params>::try_from(gtv_array![1]), Ok(vec![Params::Integer(1)]));
+    assert_eq!(BTreeMap::<String, Params>::try_from(dict!{"a" => 1}), Ok(BTreeMap::from([("a".to_string(), Params::Integer(1))])));
+
+    let error = Vec::<Params>::try_from(Params::from("x")).unwrap_err();
+    assert_eq!(error, ConversionError { expected: "Array", found: "Text" });
+    assert_eq!(error.to_string(), "Expected Params::Array, found Params::Text");
+
+    let result: Result<BTreeMap<String, Params>, _> = gtv_array![].try_into();
+    assert_eq!(result, Err(ConversionError { expected: "Dict", found: "Array" }));
+}
+
+#[test]
+fn test_borrowing_inspectors() {
+    let dict = dict!{"a" => gtv_array![1, 2], "b" => "xyz"};
+
+    assert_eq!(dict.len(), 2);
+    assert!(!dict.is_empty());
+    assert_eq!(dict["a"].len(), 2);
+    assert_eq!(dict["b"].len(), 3);
+    assert!(gtv_array![].is_empty());
+    assert_eq!(dict.len(), 2);
+
+    assert_eq!(dict.kind(), "Dict");
+    assert_eq!(dict["b"].kind(), "Text");
+    assert_eq!(Params::Null.kind(), "Null");
+    assert_eq!(Params::BigInteger(BigInt::from(1)).kind(), "BigInteger");
+}
+
+#[test]
+fn test_dict_entries() {
+    let dict = dict!{"title" => "Book", "isbn" => "X", "author" => "A"};
+
+    assert_eq!(dict.entries(), vec![("author", &Params::from("A")), ("isbn", &Params::from("X")), ("title", &Params::from("Book"))]);
+    assert!(Params::Null.entries().is_empty());
+    assert_eq!(dict.clone().into_entries().unwrap()[0], ("author".to_string(), Params::from("A")));
+    assert_eq!(gtv_array![].into_entries(), None);
+
+    let ordered = dict.clone().into_entries_ordered(&["isbn", "missing", "title"]).unwrap();
+    assert_eq!(ordered.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), vec!["isbn", "title", "author"]);
+
+    assert_eq!(dict.clone().dict_to_array_ordered(&["isbn", "title", "author"]),
+        Some(vec![Params::from("X"), Params::from("Book"), Params::from("A")]));
+    assert_eq!(dict.clone().dict_to_array_ordered(&["isbn", "missing"]), None);
+    assert_eq!(dict.dict_to_array_ordered(&[]).map(|values| values.len()), Some(3));
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_uuid_conversions() {
+    let id = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+
+    assert_eq!(Params::from(id), Params::ByteArray(id.as_bytes().to_vec()));
+    assert_eq!(Params::uuid_text(id), Params::from("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+    assert_eq!(Params::from(id).as_uuid(), Some(id));
+    assert_eq!(Params::uuid_text(id).as_uuid(), Some(id));
+    assert_eq!(Params::ByteArray(vec![1; 15]).as_uuid(), None);
+    assert_eq!(Params::Integer(1).as_uuid(), None);
+
+    #[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, StructMetadata)]
+    struct Entity {
+        id: uuid::Uuid,
+        #[params(bytes)]
+        owner: uuid::Uuid,
+    }
+
+    let entity = Entity { id, owner: id };
+    let params = Params::from_struct(&entity);
+    assert_eq!(params["id"], Params::uuid_text(id));
+    assert_eq!(params["owner"], Params::from(id));
+    assert_eq!(params["owner"].as_uuid(), Some(id));
+
+    let text_only = dict!{"id" => Params::uuid_text(id), "owner" => Params::uuid_text(id)};
+    assert_eq!(text_only.to_struct::<Entity>(), Ok(entity));
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn test_rust_decimal_conversions() {
+    use rust_decimal::Decimal;
+
+    let price = Decimal::from_str("-1234.5600").unwrap();
+    let param = Params::from(price);
+
+    assert_eq!(param, Params::Decimal(BigDecimal::from_str("-1234.5600").unwrap()));
+    assert_eq!(crate::encoding::gtv::encode_value(&param), crate::encoding::gtv::encode_value(&Params::from("-1234.56")));
+    assert_eq!(param.to_rust_decimal(), Some(price));
+    assert_eq!(Params::from("0.1").to_rust_decimal(), Decimal::from_str("0.1").ok());
+    assert_eq!(Params::from(BigInt::from(1) << 100u32).to_rust_decimal(), None);
+
+    #[derive(Debug, Default, serde::Serialize, serde::Deserialize, PartialEq, StructMetadata)]
+    struct Order {
+        price: Decimal,
+        fee: Option<Decimal>,
+    }
+
+    let order = Order { price, fee: Some(Decimal::new(5, 2)) };
+    let params = Params::from_struct(&order);
+    assert_eq!(params["price"], param);
+    assert_eq!(params["fee"], Params::Decimal(BigDecimal::from_str("0.05").unwrap()));
+    assert_eq!(params.to_struct::<Order>(), Ok(order));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_option_handling() {
+    assert_eq!(Params::Null.as_option(), None);
+    assert_eq!(Params::Integer(0).as_option(), Some(&Params::Integer(0)));
+    assert_eq!(dict!{"name" => Params::Null}["name"].as_option().and_then(Params::as_str), None);
+
+    #[derive(Debug, Default, serde::Deserialize, PartialEq)]
+    struct Tier {
+        level: i64
+    }
+
+    #[derive(Debug, Default, serde::Deserialize, PartialEq)]
+    struct Provider {
+        name: Option<String>,
+        tier: Option<Tier>,
+        scores: Option<Vec<i64>>,
+        nickname: Option<String>,
+    }
+
+    let nulls = dict!{"name" => Params::Null, "tier" => Params::Null, "scores" => Params::Null};
+    assert_eq!(nulls.to_struct::<Provider>(), Ok(Provider::default()));
+
+    let values = dict!{"name" => "a", "tier" => dict!{"level" => 2}, "scores" => gtv_array![1], "nickname" => Params::Null};
+    assert_eq!(values.to_struct::<Provider>(), Ok(Provider {
+        name: Some("a".to_string()),
+        tier: Some(Tier { level: 2 }),
+        scores: Some(vec![1]),
+        nickname: None,
+    }));
+}
+
+#[test]
+fn test_params_display() {
+    assert_eq!(Params::Null.to_string(), "null");
+    assert_eq!(Params::from(-5).to_string(), "-5");
+    assert_eq!(Params::from("a\"b").to_string(), "\"a\\\"b\"");
+    assert_eq!(gtv_array![].to_string(), "[]");
+    assert_eq!(dict!{}.to_string(), "{}");
+
+    let long = "x".repeat(100);
+    assert_eq!(Params::from(long).to_string(), format!("{:?}... (100 chars)", "x".repeat(64)));
+
+    let value = dict!{
+        "books" => gtv_array![dict!{"isbn" => "ISBN1", "rating" => 5}, Params::Null],
+        "owner" => vec![0x01_u8, 0xff]
+    };
+    let expected = "{\n  books: [\n    {\n      isbn: \"ISBN1\",\n      rating: 5\n    },\n    null\n  ],\n  owner: x\"01ff\"\n}";
+    assert_eq!(value.to_string(), expected);
+}
+
+#[test]
+fn test_from_struct_keeps_field_order() {
+    #[derive(Debug, Default, serde::Serialize, StructMetadata)]
+    struct Book {
+        isbn: String,
+        title: String,
+        author: String,
+    }
+
+    let book = Book { isbn: "ISBN1".to_string(), title: "Title".to_string(), author: "Author".to_string() };
+
+    assert_eq!(Params::from_struct_to_list(&book), vec![
+        Params::from("ISBN1"), Params::from("Title"), Params::from("Author")
+    ]);
+
+    let entries = Params::from_struct_to_ordered_vec(&book);
+    let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["isbn", "title", "author"]);
+
+    let operation = Operation::from_dict("create_book", entries.iter().map(|(key, value)| (key.as_str(), value.clone())).collect());
+    let operation_params: Vec<&str> = operation.dict.as_ref().unwrap().iter().map(|(key, _)| key.as_ref()).collect();
+    assert_eq!(operation_params, ["isbn", "title", "author"]);
+}
+
+#[test]
+fn test_validate_against_struct_metadata() {
+    #[allow(dead_code)]
+    #[derive(StructMetadata)]
+    struct Provider {
+        name: String,
+        #[params(bytes)]
+        pubkey: String,
+        scores: Vec<i64>,
+        stake: BigInt,
+        active: bool,
+        tier: Option<i64>,
+    }
+
+    let valid = dict!{
+        "name" => "a", "pubkey" => vec![1u8, 2], "scores" => gtv_array![1, 2],
+        "stake" => BigInt::from(10).pow(30), "active" => true, "tier" => Params::Null
+    };
+    assert_eq!(valid.validate::<Provider>(), Ok(()));
+    assert_eq!(dict!{"name" => "a", "pubkey" => vec![1u8], "scores" => gtv_array![], "stake" => 1, "active" => false}
+        .validate::<Provider>(), Ok(()));
+
+    let invalid = dict!{
+        "name" => Params::Null, "pubkey" => "0102", "scores" => gtv_array![1, "two"],
+        "stake" => 1, "tier" => true
+    };
+    assert_eq!(invalid.validate::<Provider>(), Err(vec![
+        "name: expected String, found Params::Null".to_string(),
+        "pubkey: expected Params::ByteArray for String, found Params::Text".to_string(),
+        "scores[1]: expected Params::Integer for i64, found Params::Text".to_string(),
+        "active: missing field".to_string(),
+        "tier: expected Params::Integer for Option<i64>, found Params::Boolean".to_string(),
+    ]));
+
+    assert_eq!(gtv_array![].validate::<Provider>(), Err(vec!["Expected Params::Dict, found Params::Array".to_string()]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_struct_error_path() {
+    #[allow(dead_code)]
+    #[derive(Debug, Default, serde::Deserialize)]
+    struct Tier {
+        name: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Default, serde::Deserialize)]
+    struct Provider {
+        tier: Tier,
+        scores: Vec<i64>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Default, serde::Deserialize)]
+    struct Registry {
+        provider: Option<Provider>,
+    }
+
+    let value = dict!{"provider" => dict!{"tier" => dict!{"name" => 1}, "scores" => gtv_array![]}};
+    assert_eq!(value.to_struct::<Registry>().unwrap_err(),
+        "Failed to convert Params to struct: provider.tier.name: expected Params::Text, found Params::Integer");
+
+    let value = dict!{"provider" => dict!{"tier" => dict!{"name" => "a"}, "scores" => gtv_array![1, "two"]}};
+    assert_eq!(value.to_struct::<Registry>().unwrap_err(),
+        "Failed to convert Params to struct: provider.scores[1]: expected Params::Integer, found Params::Text");
+
+    let value = dict!{"provider" => dict!{"tier" => "gold", "scores" => gtv_array![]}};
+    assert_eq!(value.to_struct::<Registry>().unwrap_err(),
+        "Failed to convert Params to struct: provider.tier: expected Params::Dict, found Params::Text");
+
+    let value = dict!{"provider" => dict!{"scores" => gtv_array![]}};
+    assert_eq!(value.to_struct::<Registry>().unwrap_err(),
+        "Failed to convert Params to struct: provider: missing field `tier`");
+}
+
+#[test]
+fn test_array_and_dict_builders() {
+    assert_eq!(Params::array().build(), gtv_array![]);
+    assert_eq!(Params::dict().build(), dict!{});
+
+    let nested = Params::dict()
+        .entry("name", "Alice")
+        .entry(String::from("tags"), Params::array().push("a").push(bytes!(b"\x01")).build())
+        .entry("name", "Bob")
+        .build();
+
+    assert_eq!(nested, dict!{"name" => "Bob", "tags" => gtv_array!["a", bytes!(b"\x01")]});
+}
+
+#[test]
+fn test_from_json_value() {
+    let json = serde_json::json!({
+        "isbn": "9780000000001",
+        "bytes": [1, 255],
+        "empty": [],
+        "big": 18446744073709551615u64,
+        "ratio": -0.25,
+        "nested": [{"flag": true, "none": null}]
+    });
+
+    assert_eq!(Params::from_json_value(json.clone(), JsonOptions::default()), dict!{
+        "isbn" => "9780000000001",
+        "bytes" => gtv_array![1, 255],
+        "empty" => gtv_array![],
+        "big" => u64::MAX,
+        "ratio" => BigDecimal::from_str("-0.25").unwrap(),
+        "nested" => gtv_array![dict!{"flag" => true, "none" => Params::Null}]
+    });
+
+    let options = JsonOptions { big_integer_strings: true, byte_arrays: true };
+    let params = Params::from_json_value(json, options);
+    assert_eq!(params["isbn"], Params::BigInteger(BigInt::from(9780000000001i64)));
+    assert_eq!(params["bytes"], Params::ByteArray(vec![1, 255]));
+    assert_eq!(params["empty"], Params::ByteArray(vec![]));
+
+    let text = Params::from_json_value(serde_json::json!(["-", "12a", "+5"]), options);
+    assert_eq!(text, gtv_array!["-", "12a", "+5"]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_struct_lenient() {
+    #[derive(Debug, Default, PartialEq, serde::Deserialize)]
+    struct Limits {
+        enabled: bool,
+        max: u32,
+    }
+
+    #[derive(Debug, Default, PartialEq, serde::Deserialize)]
+    struct Account {
+        active: bool,
+        balance: f64,
+        ratio: f32,
+        count: i64,
+        #[serde(deserialize_with = "deserialize_bigint")]
+        stake: BigInt,
+        label: Option<String>,
+        flags: Vec<bool>,
+        limits: Option<Limits>,
+    }
+
+    let value = dict!{
+        "active" => 1,
+        "balance" => 10,
+        "ratio" => BigDecimal::from_str("0.5").unwrap(),
+        "count" => "42",
+        "stake" => 7,
+        "label" => Params::Null,
+        "flags" => gtv_array![0, true, 1],
+        "limits" => dict!{"enabled" => 0, "max" => true}
+    };
+
+    assert!(value.to_struct::<Account>().is_err());
+    assert_eq!(value.to_struct_lenient::<Account>(), Ok(Account {
+        active: true,
+        balance: 10.0,
+        ratio: 0.5,
+        count: 42,
+        stake: BigInt::from(7),
+        label: None,
+        flags: vec![false, true, true],
+        limits: Some(Limits { enabled: false, max: 1 }),
+    }));
+
+    let invalid = dict!{"active" => 2, "balance" => 0, "ratio" => 0, "count" => 0, "stake" => 0, "label" => 0, "flags" => gtv_array![], "limits" => Params::Null};
+    assert_eq!(invalid.to_struct_lenient::<Account>().unwrap_err(),
+        "Failed to convert Params to struct: active: expected Params::Boolean, found Params::Integer");
+}
+
+#[test]
+fn test_redacted_display() {
+    let value = dict!{
+        "user" => "alice",
+        "Session_Token" => "secret",
+        "accounts" => gtv_array![dict!{"private_key" => vec![1u8; 32], "balance" => 5}]
+    };
+
+    let rendered = value.redacted(&["private_key", "session_token"]).to_string();
+    assert!(!rendered.contains("secret") && !rendered.contains("0101"));
+    assert_eq!(rendered, "{\n  Session_Token: <redacted>,\n  accounts: [\n    {\n      balance: 5,\n      private_key: <redacted>\n    }\n  ],\n  user: \"alice\"\n}");
+
+    let by_predicate = value.redacted_by(|key| key.ends_with("key")).to_string();
+    assert!(by_predicate.contains("secret") && by_predicate.contains("private_key: <redacted>"));
+    assert_eq!(value.redacted(&[]).to_string(), value.to_string());
+}
@@ -0,0 +1,363 @@
+//! Serde deserializer reading Rust types directly from `Params`.
+//!
+//! Unlike `Params::to_struct`, which goes through JSON, this reads the value as GTV encodes
+//! it and borrows texts and byte arrays where the target type allows:
+//! - integers `0` and `1` fill `bool` fields, since the chain encodes booleans as integers
+//! - big integers fill any integer type they fit in, and strings
+//! - texts holding numbers, which is how Rell encodes decimals, fill float fields
+//! - byte arrays fill `Vec<u8>` and byte fields, and strings as lowercase hex
+//! - enums are read from the variant name, the variant ordinal, or a single-entry
+//!   dictionary for variants with data
+//! - structs are read from dictionaries or, in field order, from arrays
+//!
+//! # Example
+//! ```
+//! use postchain_client::{dict, utils::params_deserializer::from_params};
+//!
+//! #[derive(Debug, PartialEq, serde::Deserialize)]
+//! struct Account<'a> {
+//!     name: &'a str,
+//!     active: bool,
+//!     pubkey: String,
+//! }
+//!
+//! let value = dict!{"name" => "alice", "active" => 1, "pubkey" => vec![0xabu8, 0xcd]};
+//! let account: Account = from_params(&value).unwrap();
+//! assert_eq!(account, Account { name: "alice", active: true, pubkey: "abcd".to_string() });
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::de::value::{BorrowedStrDeserializer, MapDeserializer, SeqDeserializer};
+
+use crate::utils::operation::Params;
+
+/// Error returned when a value does not match the target type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeserializeError {
+    path: String,
+    message: String,
+}
+
+impl DeserializeError {
+    /// Returns the path of the mismatching value, such as `accounts[0].balance`, or an
+    /// empty string for the top-level value.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the description of the mismatch, without the path.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeserializeError { path: String::new(), message: msg.to_string() }
+    }
+}
+
+/// Deserializes a Rust value from `Params`.
+///
+/// # Arguments
+/// * `value` - The value to read
+///
+/// # Returns
+/// The deserialized value, which may borrow texts and byte arrays from `value`
+///
+/// # Errors
+/// Fails if the value does not match the target type, with the path of the mismatch
+pub fn from_params<'a, T: serde::Deserialize<'a>>(value: &'a Params) -> Result<T, DeserializeError> {
+    serde_path_to_error::deserialize(ParamsDeserializer(value)).map_err(|error| {
+        let path = error.path().to_string();
+        let mut error = error.into_inner();
+        if path != "." {
+            error.path = path;
+        }
+        error
+    })
+}
+
+/// Deserializer over a borrowed `Params` value.
+#[derive(Clone, Copy, Debug)]
+pub struct ParamsDeserializer<'a>(pub &'a Params);
+
+impl<'de> IntoDeserializer<'de, DeserializeError> for ParamsDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ParamsDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::Null => visitor.visit_unit(),
+            Params::Boolean(val) => visitor.visit_bool(*val),
+            Params::Integer(val) => visitor.visit_i64(*val),
+            Params::BigInteger(val) => {
+                if let Ok(val) = i64::try_from(val) {
+                    visitor.visit_i64(val)
+                } else if let Ok(val) = u64::try_from(val) {
+                    visitor.visit_u64(val)
+                } else if let Ok(val) = i128::try_from(val) {
+                    visitor.visit_i128(val)
+                } else if let Ok(val) = u128::try_from(val) {
+                    visitor.visit_u128(val)
+                } else {
+                    visitor.visit_string(val.to_string())
+                }
+            }
+            Params::Decimal(val) => visitor.visit_string(val.to_string()),
+            Params::Text(val) => visitor.visit_borrowed_str(val),
+            Params::ByteArray(val) => visitor.visit_borrowed_bytes(val),
+            Params::Array(val) => {
+                let mut seq = SeqDeserializer::new(val.iter().map(ParamsDeserializer));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Params::Dict(val) => {
+                let mut map = MapDeserializer::new(val.iter()
+                    .map(|(key, value)| (BorrowedStrDeserializer::new(key), ParamsDeserializer(value))));
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::Integer(0) => visitor.visit_bool(false),
+            Params::Integer(1) => visitor.visit_bool(true),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        let text = match self.0 {
+            Params::Text(val) => val.clone(),
+            Params::Decimal(val) => val.to_string(),
+            _ => return self.deserialize_any(visitor),
+        };
+        match text.parse::<f64>() {
+            Ok(val) => visitor.visit_f64(val),
+            Err(_) => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::Integer(val) => visitor.visit_string(val.to_string()),
+            Params::BigInteger(val) => visitor.visit_string(val.to_string()),
+            Params::ByteArray(val) => visitor.visit_string(hex::encode(val)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::ByteArray(val) => {
+                let mut seq = SeqDeserializer::new(val.iter().copied());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, DeserializeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, DeserializeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::Text(val) => visitor.visit_enum(BorrowedStrDeserializer::new(val)),
+            Params::Integer(val) => match u32::try_from(*val) {
+                Ok(ordinal) => visitor.visit_enum(ordinal.into_deserializer()),
+                Err(_) => Err(de::Error::invalid_value(de::Unexpected::Signed(*val), &"an enum ordinal")),
+            },
+            Params::Dict(val) if val.len() == 1 => {
+                let (variant, value) = val.iter().next().expect("dictionary has one entry");
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            _ => Err(de::Error::invalid_type(unexpected(self.0), &"an enum variant")),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self.0 {
+            Params::Integer(val) if *val >= 0 => visitor.visit_u64(*val as u64),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeserializeError> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char bytes byte_buf unit unit_struct
+        tuple_struct map struct
+    }
+}
+
+fn unexpected(value: &Params) -> de::Unexpected<'_> {
+    match value {
+        Params::Null => de::Unexpected::Unit,
+        Params::Boolean(val) => de::Unexpected::Bool(*val),
+        Params::Integer(val) => de::Unexpected::Signed(*val),
+        Params::BigInteger(_) => de::Unexpected::Other("big integer"),
+        Params::Decimal(_) => de::Unexpected::Other("decimal"),
+        Params::Text(val) => de::Unexpected::Str(val),
+        Params::ByteArray(val) => de::Unexpected::Bytes(val),
+        Params::Array(_) => de::Unexpected::Seq,
+        Params::Dict(_) => de::Unexpected::Map,
+    }
+}
+
+/// Reads a variant with data from a single-entry dictionary.
+struct EnumDeserializer<'a> {
+    variant: &'a str,
+    value: &'a Params,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = DeserializeError;
+    type Variant = ParamsDeserializer<'de>;
+
+    fn variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self::Variant), DeserializeError> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<DeserializeError>::new(self.variant))?;
+        Ok((variant, ParamsDeserializer(self.value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for ParamsDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<(), DeserializeError> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, DeserializeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, DeserializeError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, DeserializeError> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+#[test]
+fn test_from_params() {
+    use std::collections::BTreeMap;
+    use num_bigint::BigInt;
+    use crate::{dict, gtv_array};
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    enum Tier {
+        Bronze,
+        Silver,
+        Custom { level: u8 },
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Account<'a> {
+        name: &'a str,
+        active: bool,
+        balance: f64,
+        supply: u128,
+        owner: &'a [u8],
+        key: Vec<u8>,
+        note: Option<String>,
+        tiers: Vec<Tier>,
+        point: (i32, i32),
+        extra: BTreeMap<String, i64>,
+    }
+
+    let value = dict!{
+        "name" => "alice",
+        "active" => 1,
+        "balance" => "12.5",
+        "supply" => BigInt::from(u64::MAX) * 4,
+        "owner" => vec![1u8, 2],
+        "key" => vec![3u8],
+        "note" => Params::Null,
+        "tiers" => gtv_array!["Silver", 0, dict!{"Custom" => dict!{"level" => 3}}],
+        "point" => gtv_array![1, -2],
+        "extra" => dict!{"a" => 1},
+        "ignored" => "x"
+    };
+    let account: Account = from_params(&value).unwrap();
+    assert_eq!(account, Account {
+        name: "alice",
+        active: true,
+        balance: 12.5,
+        supply: u64::MAX as u128 * 4,
+        owner: &[1, 2],
+        key: vec![3],
+        note: None,
+        tiers: vec![Tier::Silver, Tier::Bronze, Tier::Custom { level: 3 }],
+        point: (1, -2),
+        extra: BTreeMap::from([("a".to_string(), 1)]),
+    });
+
+    // Rell structs passed as operation arguments are arrays in field order
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Pair {
+        id: String,
+        count: i64,
+    }
+    assert_eq!(from_params::<Pair>(&gtv_array![vec![0xffu8], 2]), Ok(Pair { id: "ff".to_string(), count: 2 }));
+    assert_eq!(from_params::<String>(&Params::BigInteger(BigInt::from(10).pow(30))), Ok(format!("1{}", "0".repeat(30))));
+
+    let error = from_params::<Vec<Pair>>(&gtv_array![dict!{"id" => "a", "count" => "many"}]).unwrap_err();
+    assert_eq!(error.path(), "[0].count");
+    assert_eq!(error.to_string(), "[0].count: invalid type: string \"many\", expected i64");
+    assert_eq!(from_params::<bool>(&Params::Integer(2)).unwrap_err().to_string(), "invalid type: integer `2`, expected a boolean");
+    assert!(from_params::<u8>(&Params::Integer(256)).is_err());
+    assert!(from_params::<Tier>(&Params::Integer(5)).is_err());
+    assert!(from_params::<Tier>(&Params::Null).is_err());
+}
@@ -0,0 +1,319 @@
+//! Shared storage for large numbers of decoded values.
+//!
+//! `SharedParams` mirrors `Params` with reference-counted texts, byte arrays and
+//! collections, so cloning a value never copies its content. A `ParamsInterner` converts
+//! `Params` into `SharedParams` and stores identical strings, byte arrays and subtrees
+//! only once, which keeps memory flat for indexers holding many similar query results.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use postchain_client::{dict, utils::{operation::Params, shared_params::{ParamsInterner, SharedParams}}};
+//!
+//! let mut interner = ParamsInterner::default();
+//! let first = interner.intern(&dict!{"status" => "active", "owner" => vec![1u8; 33]});
+//! let second = interner.intern(&dict!{"status" => "active", "owner" => vec![1u8; 33]});
+//!
+//! // Both values point at the same storage
+//! assert!(first.ptr_eq(&second));
+//! assert_eq!(Params::from(&first), dict!{"status" => "active", "owner" => vec![1u8; 33]});
+//! # }
+//! ```
+
+use alloc::{collections::BTreeMap, string::ToString, sync::Arc};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+
+use crate::utils::operation::Params;
+
+/// A `Params` value whose content is shared through `Arc`s.
+///
+/// Cloning is cheap at every level. Convert with `SharedParams::from(params)` and
+/// `Params::from(&shared)`, or through a `ParamsInterner` to deduplicate content.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SharedParams {
+    /// Represents a null value
+    Null,
+    /// Represents a boolean value (true/false)
+    Boolean(bool),
+    /// Represents a 64-bit signed integer
+    Integer(i64),
+    /// Represents an arbitrary-precision integer
+    BigInteger(Arc<BigInt>),
+    /// Represents an arbitrary-precision decimal
+    Decimal(Arc<BigDecimal>),
+    /// Represents a UTF-8 encoded string
+    Text(Arc<str>),
+    /// Represents a raw byte array
+    ByteArray(Arc<[u8]>),
+    /// Represents an ordered collection of values
+    Array(Arc<[SharedParams]>),
+    /// Represents a key-value mapping where keys are strings
+    Dict(Arc<BTreeMap<Arc<str>, SharedParams>>),
+}
+
+impl SharedParams {
+    /// Returns true if both values are the same shared allocation, or equal values
+    /// that have no allocation such as integers.
+    pub fn ptr_eq(&self, other: &SharedParams) -> bool {
+        match (self, other) {
+            (SharedParams::BigInteger(a), SharedParams::BigInteger(b)) => Arc::ptr_eq(a, b),
+            (SharedParams::Decimal(a), SharedParams::Decimal(b)) => Arc::ptr_eq(a, b),
+            (SharedParams::Text(a), SharedParams::Text(b)) => Arc::ptr_eq(a, b),
+            (SharedParams::ByteArray(a), SharedParams::ByteArray(b)) => Arc::ptr_eq(a, b),
+            (SharedParams::Array(a), SharedParams::Array(b)) => Arc::ptr_eq(a, b),
+            (SharedParams::Dict(a), SharedParams::Dict(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl From<Params> for SharedParams {
+    fn from(value: Params) -> Self {
+        match value {
+            Params::Null => SharedParams::Null,
+            Params::Boolean(val) => SharedParams::Boolean(val),
+            Params::Integer(val) => SharedParams::Integer(val),
+            Params::BigInteger(val) => SharedParams::BigInteger(Arc::new(val)),
+            Params::Decimal(val) => SharedParams::Decimal(Arc::new(val)),
+            Params::Text(val) => SharedParams::Text(val.into()),
+            Params::ByteArray(val) => SharedParams::ByteArray(val.into()),
+            Params::Array(val) => SharedParams::Array(val.into_iter().map(SharedParams::from).collect()),
+            Params::Dict(val) => SharedParams::Dict(Arc::new(val.into_iter()
+                .map(|(key, value)| (key.into(), SharedParams::from(value)))
+                .collect())),
+        }
+    }
+}
+
+impl From<&SharedParams> for Params {
+    fn from(value: &SharedParams) -> Self {
+        match value {
+            SharedParams::Null => Params::Null,
+            SharedParams::Boolean(val) => Params::Boolean(*val),
+            SharedParams::Integer(val) => Params::Integer(*val),
+            SharedParams::BigInteger(val) => Params::BigInteger(BigInt::clone(val)),
+            SharedParams::Decimal(val) => Params::Decimal(BigDecimal::clone(val)),
+            SharedParams::Text(val) => Params::Text(val.to_string()),
+            SharedParams::ByteArray(val) => Params::ByteArray(val.to_vec()),
+            SharedParams::Array(val) => Params::Array(val.iter().map(Params::from).collect()),
+            SharedParams::Dict(val) => Params::Dict(val.iter()
+                .map(|(key, value)| (key.to_string(), Params::from(value)))
+                .collect()),
+        }
+    }
+}
+
+impl From<SharedParams> for Params {
+    fn from(value: SharedParams) -> Self {
+        Params::from(&value)
+    }
+}
+
+/// Pool that stores identical texts, byte arrays and subtrees once.
+///
+/// Values interned by the same pool share storage for everything they have in common.
+/// The pool keeps its entries alive until it is dropped.
+///
+/// Decimals, and the arrays and dictionaries holding them, are never shared: `BigDecimal`
+/// equality ignores the scale, so `1.0` and `1.00` would otherwise come back as one value.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct ParamsInterner {
+    texts: HashSet<Arc<str>>,
+    bytes: HashSet<Arc<[u8]>>,
+    subtrees: HashSet<SharedParams>,
+}
+
+#[cfg(feature = "std")]
+impl ParamsInterner {
+    /// Converts a value into `SharedParams`, reusing storage already in the pool.
+    ///
+    /// # Arguments
+    /// * `value` - The value to intern
+    ///
+    /// # Returns
+    /// The shared value
+    pub fn intern(&mut self, value: &Params) -> SharedParams {
+        self.intern_value(value).0
+    }
+
+    /// Returns the number of distinct texts, byte arrays and subtrees in the pool.
+    pub fn len(&self) -> usize {
+        self.texts.len() + self.bytes.len() + self.subtrees.len()
+    }
+
+    /// Returns true if nothing has been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Interns a value, returning it with whether it holds a decimal and so was not shared.
+    fn intern_value(&mut self, value: &Params) -> (SharedParams, bool) {
+        match value {
+            Params::Null => (SharedParams::Null, false),
+            Params::Boolean(val) => (SharedParams::Boolean(*val), false),
+            Params::Integer(val) => (SharedParams::Integer(*val), false),
+            Params::BigInteger(val) => self.intern_subtree(SharedParams::BigInteger(Arc::new(val.clone())), false),
+            Params::Decimal(val) => (SharedParams::Decimal(Arc::new(val.clone())), true),
+            Params::Text(val) => (SharedParams::Text(self.intern_text(val)), false),
+            Params::ByteArray(val) => {
+                let bytes = match self.bytes.get(val.as_slice()) {
+                    Some(bytes) => bytes.clone(),
+                    None => {
+                        let bytes: Arc<[u8]> = val.as_slice().into();
+                        self.bytes.insert(bytes.clone());
+                        bytes
+                    }
+                };
+                (SharedParams::ByteArray(bytes), false)
+            }
+            Params::Array(val) => {
+                let mut has_decimal = false;
+                let items = val.iter()
+                    .map(|item| {
+                        let (item, item_has_decimal) = self.intern_value(item);
+                        has_decimal |= item_has_decimal;
+                        item
+                    })
+                    .collect();
+                self.intern_subtree(SharedParams::Array(items), has_decimal)
+            }
+            Params::Dict(val) => {
+                let mut has_decimal = false;
+                let entries = val.iter()
+                    .map(|(key, value)| {
+                        let (value, value_has_decimal) = self.intern_value(value);
+                        has_decimal |= value_has_decimal;
+                        (self.intern_text(key), value)
+                    })
+                    .collect();
+                self.intern_subtree(SharedParams::Dict(Arc::new(entries)), has_decimal)
+            }
+        }
+    }
+
+    fn intern_text(&mut self, text: &str) -> Arc<str> {
+        if let Some(text) = self.texts.get(text) {
+            return text.clone();
+        }
+        let text: Arc<str> = text.into();
+        self.texts.insert(text.clone());
+        text
+    }
+
+    /// Shares a subtree, unless it holds a decimal; passes `has_decimal` through.
+    fn intern_subtree(&mut self, subtree: SharedParams, has_decimal: bool) -> (SharedParams, bool) {
+        if has_decimal {
+            return (subtree, true);
+        }
+        if let Some(existing) = self.subtrees.get(&subtree) {
+            return (existing.clone(), false);
+        }
+        self.subtrees.insert(subtree.clone());
+        (subtree, false)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_params_interner() {
+    use crate::{dict, gtv_array};
+
+    let record = |id: i64| dict!{
+        "id" => id,
+        "status" => "active",
+        "owner" => vec![7u8; 33],
+        "tags" => gtv_array!["a", "b"]
+    };
+
+    let mut interner = ParamsInterner::default();
+    let first = interner.intern(&record(1));
+    let second = interner.intern(&record(2));
+
+    let (SharedParams::Dict(first_dict), SharedParams::Dict(second_dict)) = (&first, &second) else {
+        panic!("Expected dicts");
+    };
+    assert!(!first.ptr_eq(&second));
+    for key in ["status", "owner", "tags"] {
+        assert!(first_dict[key].ptr_eq(&second_dict[key]), "{} is not shared", key);
+    }
+    let first_key = first_dict.keys().find(|key| &***key == "status").unwrap();
+    let second_key = second_dict.keys().find(|key| &***key == "status").unwrap();
+    assert!(Arc::ptr_eq(first_key, second_key));
+
+    assert!(interner.intern(&record(1)).ptr_eq(&first));
+    assert_eq!(Params::from(&first), record(1));
+    assert_eq!(Params::from(SharedParams::from(record(2))), record(2));
+
+    let values = gtv_array![Params::Null, true, BigInt::from(10).pow(20), BigDecimal::from(5), dict!{}];
+    assert_eq!(Params::from(interner.intern(&values)), values);
+    assert!(!interner.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_params_interner_keeps_decimal_scale() {
+    use core::str::FromStr;
+    use crate::{dict, gtv_array};
+
+    let decimal = |text: &str| Params::Decimal(BigDecimal::from_str(text).unwrap());
+    let decimal_text = |value: &SharedParams| match value {
+        SharedParams::Decimal(val) => val.to_string(),
+        other => panic!("Expected a decimal, got {:?}", other)
+    };
+
+    let mut interner = ParamsInterner::default();
+    assert_eq!(decimal_text(&interner.intern(&decimal("1.0"))), "1.0");
+    assert_eq!(decimal_text(&interner.intern(&decimal("1.00"))), "1.00");
+
+    let array = |text: &str| gtv_array![decimal(text), "x"];
+    let first = interner.intern(&array("1.0"));
+    let second = interner.intern(&array("1.00"));
+    let (SharedParams::Array(first_items), SharedParams::Array(second_items)) = (&first, &second) else {
+        panic!("Expected arrays");
+    };
+    assert_eq!(decimal_text(&first_items[0]), "1.0");
+    assert_eq!(decimal_text(&second_items[0]), "1.00");
+    // Texts next to the decimals are still shared
+    assert!(first_items[1].ptr_eq(&second_items[1]));
+
+    let record = |text: &str| dict!{"price" => decimal(text), "tags" => gtv_array!["a"]};
+    let second = interner.intern(&record("1.00"));
+    let first = interner.intern(&record("1.0"));
+    let (SharedParams::Dict(first_dict), SharedParams::Dict(second_dict)) = (&first, &second) else {
+        panic!("Expected dicts");
+    };
+    assert_eq!(decimal_text(&first_dict["price"]), "1.0");
+    assert_eq!(decimal_text(&second_dict["price"]), "1.00");
+    assert!(first_dict["tags"].ptr_eq(&second_dict["tags"]));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_shared_list_operation() {
+    use crate::{dict, encoding::gtv, utils::{hasher, operation::Operation, transaction::Transaction}};
+
+    let args = vec![dict!{"payload" => vec![9u8; 4096], "name" => "large"}, Params::Boolean(true)];
+    let shared: Vec<SharedParams> = args.iter().cloned().map(SharedParams::from).collect();
+
+    assert_eq!(gtv::encode_shared_value(&shared[0]).unwrap(), gtv::encode_value(&args[0]).unwrap());
+    assert_eq!(hasher::gtv_hash_shared(&shared[0]).unwrap(), hasher::gtv_hash(&args[0]).unwrap());
+
+    let owned = Operation::from_list("store", args);
+    let borrowed = Operation::from_shared_list("store", shared.clone());
+    assert_eq!(borrowed.to_bytes().unwrap(), owned.to_bytes().unwrap());
+
+    let tx = |operation| Transaction::new(vec![1; 32], Some(vec![operation]), Some(vec![vec![2; 33]]), None);
+    let (owned_tx, shared_tx) = (tx(owned), tx(borrowed));
+    assert_eq!(shared_tx.tx_rid().unwrap(), owned_tx.tx_rid().unwrap());
+    assert_eq!(shared_tx.to_bytes().unwrap(), owned_tx.to_bytes().unwrap());
+    assert_eq!(gtv::to_draw_gtx(&shared_tx), gtv::to_draw_gtx(&owned_tx));
+
+    // The operation holds the same allocation as the caller
+    let stored = &shared_tx.operations.as_ref().unwrap()[0].shared_list.as_ref().unwrap()[0];
+    assert!(stored.ptr_eq(&shared[0]));
+}
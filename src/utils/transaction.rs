@@ -12,48 +12,61 @@
 //! 
 //! # Example
 //! ```
-//! use crate::utils::transaction::{Transaction, TransactionStatus};
+//! use postchain_client::utils::{operation::Operation, transaction::Transaction};
 //!
 //! let brid = "FA189BEBA886669CF7DF7DB3D8CFD878D1F80ED360BDCF26B43ABE3D9B3D53CC"; // Replace with actual blockchain RID
 //!
-//! let brid_to_vec = hex::decode(brid).unwrap();
-//! 
 //! // Create a new transaction
-//! let mut tx = Transaction::new(
-//!     brid_to_vec,    // blockchain RID
-//!     Some(vec![]),   // operations
-//!     None,           // signers
-//!     None            // signatures
-//! );
+//! let mut tx = Transaction::for_chain(brid, vec![Operation::from_list("nop", vec![])])
+//!     .expect("Invalid blockchain RID");
 //!
 //! // Sign the transaction
 //! let private_key1 = "C70D5A77CC10552019179B7390545C46647C9FCA1B6485850F2B913F87270300";  // Replace with actual private key
 //! tx.sign(&hex::decode(private_key1).unwrap().try_into().expect("Invalid private key 1")).expect("Failed to sign transaction");
 //!
-//! // Multi sign the transaction
+//! // Sign the transaction from raw private key
+//! tx.sign_from_raw_priv_key(private_key1).expect("Failed to sign transaction");
+//!
+//! // Multi sign a transaction
 //! let private_key2 = "17106092B72489B785615BD2ACB2DDE8D0EA05A2029DCA4054987494781F988C";  // Replace with actual private key
-//! tx.sign(&[
+//! let mut tx = Transaction::for_chain(brid, vec![Operation::from_list("nop", vec![])]).unwrap();
+//! tx.multi_sign(&[
 //! &hex::decode(private_key1).unwrap().try_into().expect("Invalid private key 1"),
 //! &hex::decode(private_key2).unwrap().try_into().expect("Invalid private key 2")
 //! ]).expect("Failed to multi sign transaction");
 //!
-//! // Sign the transaction from raw private key
-//! tx.sign_from_raw_priv_key(private_key1);
-//!
 //! // Multi sign the transaction from raw private keys
-//! tx.multi_sign_from_raw_priv_keys(&[private_key1, private_key2]);
+//! tx.multi_sign_from_raw_priv_keys(&[private_key1, private_key2]).expect("Failed to multi sign transaction");
+//!
+//! // Declare all signers first, then collect the signatures one by one
+//! let mut tx = Transaction::for_chain(brid, vec![Operation::from_list("nop", vec![])]).unwrap();
+//! let public_key = |private_key: &str| {
+//!     let secret_key: secp256k1::SecretKey = private_key.parse().unwrap();
+//!     secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), &secret_key).serialize().to_vec()
+//! };
+//! tx.set_signers(vec![public_key(private_key1), public_key(private_key2)]);
+//! tx.sign_from_raw_priv_key(private_key2).expect("Failed to sign transaction");
+//! tx.sign_from_raw_priv_key(private_key1).expect("Failed to sign transaction");
 //!
 //! ```
 
 
 use crate::encoding::gtv;
+use base64::{Engine as _, engine::general_purpose};
+#[cfg(test)]
 use crate::utils::hasher::gtv_hash;
-use super::{hasher, operation::Operation};
+use super::{blockchain_rid::{BlockchainRid, BlockchainRidError}, hasher, keypair::KeyPair, operation::{Operation, NOP_OPERATION}};
+#[cfg(test)]
+use super::operation::Params;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
 use secp256k1::{PublicKey, Secp256k1, SecretKey, Message, ecdsa::Signature};
 use hex::FromHex;
 
 /// Represents the current status of a transaction in the blockchain.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TransactionStatus {
     /// Transaction was rejected by the blockchain
     REJECTED,
@@ -65,12 +78,287 @@ pub enum TransactionStatus {
     UNKNOWN
 }
 
+/// Detailed status of a transaction, including the block that confirmed it.
+///
+/// Block fields are only filled in for confirmed transactions, and only when
+/// the node exposes them through its transaction info endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxStatusDetail {
+    /// Current status of the transaction
+    pub status: TransactionStatus,
+    /// Reason given by the node for a rejected transaction
+    pub reject_reason: Option<String>,
+    /// Height of the block that includes the transaction
+    pub block_height: Option<u64>,
+    /// Hex-encoded RID of the block that includes the transaction
+    pub block_rid: Option<String>,
+    /// Timestamp of the block that includes the transaction, in milliseconds since the epoch
+    pub timestamp: Option<u64>
+}
+
+impl TxStatusDetail {
+    /// Creates a status detail with the given status and no block info.
+    ///
+    /// # Arguments
+    /// * `status` - Current status of the transaction
+    pub fn new(status: TransactionStatus) -> Self {
+        Self {
+            status,
+            reject_reason: None,
+            block_height: None,
+            block_rid: None,
+            timestamp: None
+        }
+    }
+}
+
+/// Errors that can occur while building or signing a transaction.
+#[derive(Debug)]
+pub enum TransactionError {
+    /// Invalid key, signature or message
+    Secp256k1(secp256k1::Error),
+    /// The transaction RID could not be computed
+    Hash(hasher::HashError),
+    /// An external signer failed
+    Signer(String),
+    /// The public key is not in the declared signer list
+    UndeclaredSigner(Vec<u8>),
+    /// The signature does not verify against the transaction RID and signer
+    InvalidSignature(Vec<u8>),
+    /// The signing payload was made for a different transaction
+    PayloadMismatch,
+    /// The bytes are not a valid encoded transaction
+    Decode(String),
+    /// The transaction already has signatures, which a change would invalidate
+    AlreadySigned,
+    /// The blockchain RID is not 32 bytes long; holds the actual length
+    InvalidBlockchainRid(usize),
+    /// The transaction has no operations
+    NoOperations,
+    /// The operation at this index has no name
+    MissingOperationName(usize),
+    /// An operation cannot be inserted at `index` in a transaction with `len` operations
+    OperationIndexOutOfRange {
+        /// Requested position
+        index: usize,
+        /// Number of operations in the transaction
+        len: usize
+    },
+    /// The transaction changed after it was signed; holds the public key of the first stale signature
+    StaleSignature(Vec<u8>),
+    /// The transaction could not be GTV-encoded
+    Encode(gtv::EncodeError),
+}
+
+impl std::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionError::Secp256k1(error) => write!(f, "Signature error: {}", error),
+            TransactionError::Hash(error) => write!(f, "Transaction hash error: {:?}", error),
+            TransactionError::Signer(error) => write!(f, "Signer error: {}", error),
+            TransactionError::UndeclaredSigner(public_key) => write!(f, "Signer {} is not declared", hex::encode(public_key)),
+            TransactionError::InvalidSignature(public_key) => write!(f, "Invalid signature from signer {}", hex::encode(public_key)),
+            TransactionError::PayloadMismatch => write!(f, "Signing payload does not match the transaction"),
+            TransactionError::Decode(error) => write!(f, "Invalid encoded transaction: {}", error),
+            TransactionError::AlreadySigned => write!(f, "Transaction is already signed"),
+            TransactionError::InvalidBlockchainRid(len) => write!(f, "Blockchain RID must be 32 bytes, got {} bytes", len),
+            TransactionError::NoOperations => write!(f, "Transaction has no operations"),
+            TransactionError::MissingOperationName(index) => write!(f, "Operation at index {} has no name", index),
+            TransactionError::OperationIndexOutOfRange { index, len } => write!(f, "Operation index {} is out of range for {} operations", index, len),
+            TransactionError::StaleSignature(public_key) => write!(f, "Signature from signer {} does not match the current transaction contents", hex::encode(public_key)),
+            TransactionError::Encode(error) => write!(f, "Transaction encoding error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+impl From<secp256k1::Error> for TransactionError {
+    fn from(error: secp256k1::Error) -> Self {
+        TransactionError::Secp256k1(error)
+    }
+}
+
+impl From<gtv::EncodeError> for TransactionError {
+    fn from(error: gtv::EncodeError) -> Self {
+        TransactionError::Encode(error)
+    }
+}
+
+impl From<hasher::HashError> for TransactionError {
+    fn from(error: hasher::HashError) -> Self {
+        TransactionError::Hash(error)
+    }
+}
+
+/// A signer whose private key is not held by this process, e.g. an HSM or a hardware wallet.
+///
+/// # Example
+/// ```
+/// use postchain_client::utils::transaction::{TransactionError, TxSigner};
+///
+/// struct Hsm;
+///
+/// impl TxSigner for Hsm {
+///     async fn pubkey(&self) -> [u8; 33] {
+///         [2; 33] // fetch from the device
+///     }
+///
+///     async fn sign(&self, digest: &[u8; 32]) -> Result<[u8; 64], TransactionError> {
+///         Err(TransactionError::Signer("device not connected".to_string()))
+///     }
+/// }
+/// ```
+pub trait TxSigner {
+    /// Returns the 33-byte compressed public key of the signer.
+    fn pubkey(&self) -> impl Future<Output = [u8; 33]> + Send;
+
+    /// Signs a 32-byte transaction digest.
+    ///
+    /// # Arguments
+    /// * `digest` - Transaction RID to sign
+    ///
+    /// # Returns
+    /// The 64-byte compact ECDSA signature, or an error
+    fn sign(&self, digest: &[u8; 32]) -> impl Future<Output = Result<[u8; 64], TransactionError>> + Send;
+}
+
+/// Object-safe form of [`TxSigner`], so signers of different types can be mixed in one list.
+///
+/// Implemented for every `TxSigner` that is `Sync`.
+pub trait DynTxSigner: Send + Sync {
+    /// Returns the 33-byte compressed public key of the signer.
+    fn dyn_pubkey(&self) -> Pin<Box<dyn Future<Output = [u8; 33]> + Send + '_>>;
+
+    /// Signs a 32-byte transaction digest.
+    fn dyn_sign<'s>(&'s self, digest: &'s [u8; 32]) -> Pin<Box<dyn Future<Output = Result<[u8; 64], TransactionError>> + Send + 's>>;
+}
+
+impl<T: TxSigner + Send + Sync> DynTxSigner for T {
+    fn dyn_pubkey(&self) -> Pin<Box<dyn Future<Output = [u8; 33]> + Send + '_>> {
+        Box::pin(self.pubkey())
+    }
+
+    fn dyn_sign<'s>(&'s self, digest: &'s [u8; 32]) -> Pin<Box<dyn Future<Output = Result<[u8; 64], TransactionError>> + Send + 's>> {
+        Box::pin(self.sign(digest))
+    }
+}
+
+/// One signer of [`Transaction::multi_sign_mixed`]: a local private key or an external signer.
+pub enum AnySigner<'s> {
+    /// 32-byte private key held by this process
+    Local(&'s [u8; 32]),
+    /// Signer holding its key elsewhere, e.g. an HSM or a remote signing service
+    External(&'s dyn DynTxSigner),
+}
+
+/// A local secp256k1 private key is itself a signer.
+impl TxSigner for SecretKey {
+    async fn pubkey(&self) -> [u8; 33] {
+        PublicKey::from_secret_key(&Secp256k1::new(), self).serialize()
+    }
+
+    async fn sign(&self, digest: &[u8; 32]) -> Result<[u8; 64], TransactionError> {
+        Ok(sign(digest, &self.secret_bytes())?)
+    }
+}
+
+/// Everything a co-signer needs to sign a transaction on another machine.
+///
+/// It holds the digest to sign (the transaction RID) together with the declared
+/// signers and the signatures collected so far, so it also serves as the exported
+/// partially-signed state. It serializes with serde, e.g. to JSON.
+///
+/// # Example
+/// ```
+/// use postchain_client::utils::{operation::Operation, transaction::{SigningPayload, Transaction}};
+///
+/// let private_key = [1u8; 32];
+/// let public_key = secp256k1::PublicKey::from_secret_key(
+///     &secp256k1::Secp256k1::new(), &secp256k1::SecretKey::from_byte_array(&private_key).unwrap()).serialize();
+///
+/// // Coordinator
+/// let mut tx = Transaction::new(vec![0; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+/// tx.set_signers(vec![public_key.to_vec()]);
+/// let json = serde_json::to_string(&tx.signing_payload().unwrap()).unwrap();
+///
+/// // Co-signer
+/// let mut payload: SigningPayload = serde_json::from_str(&json).unwrap();
+/// payload.sign(&private_key).unwrap();
+/// let json = serde_json::to_string(&payload).unwrap();
+///
+/// // Coordinator
+/// tx.add_signatures_from(&serde_json::from_str(&json).unwrap()).unwrap();
+/// assert!(tx.signatures.unwrap()[0].len() == 64);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SigningPayload {
+    /// Hex-encoded blockchain RID
+    pub blockchain_rid: String,
+    /// Hex-encoded transaction RID, the digest to sign
+    pub tx_rid: String,
+    /// Hex-encoded public keys of the declared signers
+    pub signers: Vec<String>,
+    /// Hex-encoded signature of each signer, `None` while missing
+    pub signatures: Vec<Option<String>>
+}
+
+impl SigningPayload {
+    /// Returns the digest to sign.
+    ///
+    /// # Returns
+    /// The 32-byte transaction RID, or an error if it is not valid hex
+    pub fn digest(&self) -> Result<[u8; 32], TransactionError> {
+        <[u8; 32]>::from_hex(&self.tx_rid).map_err(|_| TransactionError::PayloadMismatch)
+    }
+
+    /// Returns the hex-encoded public keys of the signers that have not signed yet.
+    pub fn missing_signers(&self) -> Vec<&str> {
+        self.signers.iter()
+            .zip(self.signatures.iter().chain(std::iter::repeat(&None)))
+            .filter(|(_, signature)| signature.is_none())
+            .map(|(signer, _)| signer.as_str())
+            .collect()
+    }
+
+    /// Signs the digest and stores the signature at the position of the signer.
+    ///
+    /// # Arguments
+    /// * `private_key` - 32-byte private key of a declared signer
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the private key is invalid or its signer is not declared
+    pub fn sign(&mut self, private_key: &[u8; 32]) -> Result<(), TransactionError> {
+        let public_key = get_public_key(private_key)?;
+        let public_key_hex = hex::encode(public_key);
+
+        let index = self.signers.iter()
+            .position(|signer| signer.eq_ignore_ascii_case(&public_key_hex))
+            .ok_or_else(|| TransactionError::UndeclaredSigner(public_key.to_vec()))?;
+
+        let signature = sign(&self.digest()?, private_key)?;
+
+        if self.signatures.len() < self.signers.len() {
+            self.signatures.resize(self.signers.len(), None);
+        }
+
+        self.signatures[index] = Some(hex::encode(signature));
+
+        Ok(())
+    }
+}
+
 /// Represents a blockchain transaction with operations and signatures.
 /// 
 /// A transaction contains a list of operations to be executed, along with
 /// the necessary signatures to authorize these operations. It supports
 /// both single and multi-signature scenarios.
-#[derive(Debug)]
+///
+/// Use [`Transaction::redacted`] to log a transaction without its signatures.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Transaction<'a> {
     /// Unique identifier of the blockchain this transaction belongs to
     pub blockchain_rid: Vec<u8>,
@@ -79,17 +367,22 @@ pub struct Transaction<'a> {
     /// List of public keys of the signers
     pub signers: Option<Vec<Vec<u8>>>,
     /// List of signatures corresponding to the signers
-    pub signatures: Option<Vec<Vec<u8>>>
+    pub signatures: Option<Vec<Vec<u8>>>,
+    /// Transaction RID signed by the previous signing call
+    signed_rid: SignedRid
 }
 
-impl<'a> Default for Transaction<'a> {
-    fn default() -> Self {
-        Self {
-            blockchain_rid: vec![],
-            operations: None,   
-            signers: None,      
-            signatures: None    
-        }
+/// Transaction RID kept between signing calls, so collecting signatures one by one
+/// hashes the transaction body only once.
+///
+/// It is only used while the transaction holds a signature, and it is dropped by every
+/// method that changes the operations or signers. It takes no part in comparisons.
+#[derive(Clone, Debug, Default)]
+struct SignedRid(Option<[u8; 32]>);
+
+impl PartialEq for SignedRid {
+    fn eq(&self, _other: &Self) -> bool {
+        true
     }
 }
 
@@ -112,148 +405,941 @@ impl<'a> Transaction<'a> {
             blockchain_rid,
             operations,
             signers,
-            signatures
+            signatures,
+            signed_rid: SignedRid::default()
         }
     }
 
-    /// Returns the hex-encoded GTV (Generic Tree Value) representation of the transaction.
-    /// 
-    /// This method encodes the transaction into GTV format and returns it as a
-    /// hexadecimal string.
-    /// 
+    /// Creates an unsigned transaction for the chain with the given hex-encoded blockchain RID.
+    ///
+    /// # Arguments
+    /// * `brid_hex` - Blockchain RID as a hex string
+    /// * `operations` - Operations of the transaction
+    ///
     /// # Returns
-    /// Hex-encoded string of the GTV-encoded transaction
-    pub fn gvt_hex_encoded(&self) -> String {
-        let gtv_e = gtv::encode_tx(self);
-        let hex_encode = hex::encode(gtv_e);
-        hex_encode
+    /// The transaction, or an error if `brid_hex` is not a valid blockchain RID
+    pub fn for_chain(brid_hex: &str, operations: Vec<Operation<'a>>) -> Result<Self, BlockchainRidError> {
+        let blockchain_rid = BlockchainRid::from_hex(brid_hex)?;
+        Ok(Self::new(blockchain_rid.into(), Some(operations), None, None))
     }
 
-    /// Computes the unique identifier (RID) of this transaction.
-    /// 
-    /// The transaction RID is computed by hashing the GTV representation
-    /// of the transaction using the GTX hash function.
-    /// 
+    /// Converts the transaction into one that owns all its operation names.
+    ///
+    /// The result has no borrowed data, so it can be stored in structs or moved across tasks.
+    ///
     /// # Returns
-    /// A fixed-size 32 bytes containing the transaction RID
-    pub fn tx_rid(&self) -> Result<[u8; 32], hasher::HashError> {
-        let to_draw_gtx = gtv::to_draw_gtx(self);
-        gtv_hash(to_draw_gtx)
+    /// A `Transaction<'static>`
+    pub fn into_owned(self) -> Transaction<'static> {
+        Transaction {
+            blockchain_rid: self.blockchain_rid,
+            operations: self.operations.map(|operations| operations.into_iter().map(Operation::into_owned).collect()),
+            signers: self.signers,
+            signatures: self.signatures,
+            signed_rid: self.signed_rid
+        }
     }
 
-    /// Returns the hex-encoded transaction RID.
-    /// 
-    /// This is a convenience method that returns the transaction RID
-    /// as a hexadecimal string.
-    /// 
+    /// Appends an operation.
+    ///
+    /// # Arguments
+    /// * `operation` - Operation to append
+    ///
     /// # Returns
-    /// Hex-encoded string of the transaction RID
-    pub fn tx_rid_hex(&self) -> Result<String, hasher::HashError> {
-        Ok(hex::encode(self.tx_rid()?))
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the transaction is already signed; call `clear_signatures` first
+    pub fn push_operation(&mut self, operation: Operation<'a>) -> Result<(), TransactionError> {
+        self.ensure_unsigned()?;
+        self.signed_rid = SignedRid::default();
+        self.operations.get_or_insert_with(Vec::new).push(operation);
+        Ok(())
     }
 
-    /// Signs the transaction using a raw private key string.
-    /// 
+    /// Inserts an operation at position `index`, shifting the following operations.
+    ///
     /// # Arguments
-    /// * `private_key` - Private key as a string
-    /// 
+    /// * `index` - Position of the new operation
+    /// * `operation` - Operation to insert
+    ///
     /// # Returns
-    /// Result indicating success or a secp256k1 error
-    /// 
+    /// Result indicating success or a transaction error
+    ///
     /// # Errors
-    /// Returns an error if the private key is invalid or signing fails
-    pub fn sign_from_raw_priv_key(&mut self, private_key: &str) -> Result<(), secp256k1::Error> {
-        let private_key_bytes = Vec::from_hex(private_key).map_err(|_| secp256k1::Error::InvalidSecretKey)?;
-        let private_key = private_key_bytes.try_into().map_err(|_| secp256k1::Error::InvalidSecretKey)?;
-        self.sign(&private_key)
+    /// Returns an error if the transaction is already signed; call `clear_signatures` first.
+    /// Returns `TransactionError::OperationIndexOutOfRange` if `index` is greater than the number of operations
+    pub fn insert_operation(&mut self, index: usize, operation: Operation<'a>) -> Result<(), TransactionError> {
+        self.ensure_unsigned()?;
+
+        let operations = self.operations.get_or_insert_with(Vec::new);
+        if index > operations.len() {
+            return Err(TransactionError::OperationIndexOutOfRange { index, len: operations.len() });
+        }
+
+        self.signed_rid = SignedRid::default();
+        operations.insert(index, operation);
+        Ok(())
     }
 
-    /// Signs the transaction with multiple raw private key strings.
+    /// Removes all signatures, keeping the declared signers.
     ///
-    /// This method iteratively signs the transaction with each provided
-    /// private key string, enabling multi-signature transactions.
+    /// Use this before changing a signed transaction, including through its public fields;
+    /// the transaction must then be signed again.
+    pub fn clear_signatures(&mut self) {
+        self.signatures = None;
+        self.signed_rid = SignedRid::default();
+    }
+
+    /// Returns true if the transaction holds at least one signature.
+    pub fn is_signed(&self) -> bool {
+        self.signatures.as_ref().is_some_and(|signatures| signatures.iter().any(|sig| !sig.is_empty()))
+    }
+
+    /// Returns an unsigned copy of the transaction with its `nop` operations removed.
+    ///
+    /// Keep the template around and call `instantiate` on it for every transaction to submit,
+    /// e.g. when sending many near-identical transactions in an airdrop or a batch mint.
+    pub fn template(&self) -> Self {
+        let mut template = self.clone();
+        template.clear_signatures();
+        if let Some(operations) = template.operations.as_mut() {
+            operations.retain(|op| op.name() != Some(NOP_OPERATION));
+        }
+        template
+    }
+
+    /// Returns an unsigned copy of the transaction with a fresh unique `nop` operation.
+    ///
+    /// Any existing `nop` operation is replaced, so every instance gets its own transaction RID.
+    /// Declared signers are kept; the instance must be signed before it is sent.
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::{operation::Operation, transaction::Transaction};
+    /// let template = Transaction::new(vec![0; 32], Some(vec![Operation::from_list("mint", vec![])]), None, None);
+    /// let first = template.instantiate();
+    /// let second = template.instantiate();
+    /// assert_ne!(first.tx_rid().unwrap(), second.tx_rid().unwrap());
+    /// ```
+    pub fn instantiate(&self) -> Self {
+        let mut instance = self.template();
+        instance.operations.get_or_insert_with(Vec::new).push(Operation::nop_unique());
+        instance
+    }
+
+    /// Fails with `TransactionError::AlreadySigned` if the transaction holds a signature.
+    fn ensure_unsigned(&self) -> Result<(), TransactionError> {
+        if self.is_signed() {
+            return Err(TransactionError::AlreadySigned);
+        }
+        Ok(())
+    }
+
+    /// Makes the transaction invalid once the deadline has passed.
+    ///
+    /// Appends a `timeb` operation whose window ends at `until`, so a node will not
+    /// include the transaction in a block produced after the deadline.
     ///
     /// # Arguments
-    /// * `private_keys` - Slice of raw private key strings
+    /// * `until` - Deadline, in milliseconds since the epoch
     ///
     /// # Returns
-    /// Result indicating success or a secp256k1 error
+    /// Result indicating success or a transaction error
     ///
     /// # Errors
-    /// Returns an error if any private key is invalid or signing fails
-    pub fn multi_sign_from_raw_priv_keys(&mut self, private_keys: &[&str]) -> Result<(), secp256k1::Error> {
-        let private_keys_bytes: Vec<[u8; 32]> = private_keys
-            .iter()
-            .map(|private_key_hex| {
-                let private_key_bytes = Vec::from_hex(private_key_hex).map_err(|_| secp256k1::Error::InvalidSecretKey)?;
-                private_key_bytes.try_into().map_err(|_| secp256k1::Error::InvalidSecretKey)
-            })
-            .collect::<Result<Vec<[u8; 32]>, secp256k1::Error>>()?;
+    /// Returns an error if the transaction is already signed
+    pub fn set_valid_until(&mut self, until: i64) -> Result<(), TransactionError> {
+        self.push_operation(Operation::timeb(0, until))
+    }
 
-        let private_keys_refs: Vec<&[u8; 32]> = private_keys_bytes.iter().collect();
+    /// Makes the transaction invalid after `ttl` from now.
+    ///
+    /// # Arguments
+    /// * `ttl` - How long the transaction stays valid
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the transaction is already signed
+    pub fn set_ttl(&mut self, ttl: Duration) -> Result<(), TransactionError> {
+        self.set_valid_until(deadline_after(ttl))
+    }
 
-        self.multi_sign(private_keys_refs.as_slice())
+    /// Returns a view of the transaction whose `Debug` output hides the signatures.
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::{operation::Operation, transaction::Transaction};
+    /// let mut tx = Transaction::new(vec![0; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+    /// tx.sign(&[1; 32]).unwrap();
+    /// assert!(format!("{:?}", tx.redacted()).contains("<1 redacted>"));
+    /// ```
+    pub fn redacted(&self) -> RedactedTransaction<'_, 'a> {
+        RedactedTransaction(self)
     }
 
-    /// Signs the transaction using a private key.
-    /// 
-    /// This method:
-    /// 1. Derives the public key from the private key
-    /// 2. Adds the public key to the signers list
-    /// 3. Signs the transaction RID
-    /// 4. Adds the signature to the signatures list
+    /// Returns the hex-encoded GTV (Generic Tree Value) representation of the transaction.
     /// 
-    /// # Arguments
-    /// * `private_key` - 32-byte private key
+    /// This method encodes the transaction into GTV format and returns it as a
+    /// hexadecimal string.
     /// 
     /// # Returns
-    /// Result indicating success or a secp256k1 error
+    /// Hex-encoded string of the GTV-encoded transaction
     /// 
     /// # Errors
-    /// Returns an error if the private key is invalid or signing fails
-    pub fn sign(&mut self, private_key: &[u8; 32]) -> Result<(), secp256k1::Error> {
-        let public_key = get_public_key(private_key)?;
-
-        self.signers.get_or_insert_with(Vec::new).push(public_key.to_vec());
+    /// `TransactionError::Encode` if an operation has no name
+    pub fn gvt_hex_encoded(&self) -> Result<String, TransactionError> {
+        let gtv_e = gtv::encode_tx(self)?;
+        Ok(hex::encode(gtv_e))
+    }
 
-        let digest = self.tx_rid().map_err(|_| secp256k1::Error::InvalidMessage)?;
-        let signature = sign(&digest, private_key)?;
+    /// Serializes the transaction, including any signers and signatures, to its GTV encoding.
+    ///
+    /// This is the format nodes accept, so the bytes can be persisted or handed to
+    /// another service for signing and reloaded with [`Transaction::from_bytes`].
+    ///
+    /// # Returns
+    /// GTV-encoded transaction
+    ///
+    /// # Errors
+    /// `TransactionError::Encode` if an operation has no name
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        Ok(gtv::encode_tx(self)?)
+    }
 
-        self.signatures.get_or_insert_with(Vec::new).push(signature.to_vec());
+    /// Deserializes a transaction from its GTV encoding.
+    ///
+    /// Operation names borrow from `data`. Argument names of dictionary operations
+    /// are not part of the encoding, so all operations come back with list arguments.
+    ///
+    /// # Arguments
+    /// * `data` - Bytes produced by [`Transaction::to_bytes`]
+    ///
+    /// # Returns
+    /// The decoded transaction, or an error if the bytes are not a valid transaction
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, TransactionError> {
+        gtv::decode_transaction(data).map_err(|error| TransactionError::Decode(error.to_string()))
+    }
 
-        Ok(())
+    /// Serializes the transaction to URL-safe base64 without padding.
+    ///
+    /// The payload is the GTV encoding from [`Transaction::to_bytes`], compact enough for
+    /// QR codes and deep links to hand unsigned transactions to a wallet.
+    ///
+    /// # Returns
+    /// Base64-encoded transaction
+    ///
+    /// # Errors
+    /// `TransactionError::Encode` if an operation has no name
+    pub fn to_base64(&self) -> Result<String, TransactionError> {
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(self.to_bytes()?))
     }
 
-    /// Signs the transaction with multiple private keys.
-    /// 
-    /// This method iteratively signs the transaction with each provided
-    /// private key, enabling multi-signature transactions.
-    /// 
+    /// Deserializes a transaction produced by [`Transaction::to_base64`].
+    ///
+    /// Padded input is accepted as well.
+    ///
     /// # Arguments
-    /// * `private_keys` - Slice of 32-byte private keys
-    /// 
+    /// * `data` - URL-safe base64 string
+    ///
     /// # Returns
-    /// Result indicating success or a secp256k1 error
-    /// 
-    /// # Errors
-    /// Returns an error if any private key is invalid or signing fails
-    pub fn multi_sign(&mut self, private_keys: &[&[u8; 32]]) -> Result<(), secp256k1::Error> {
-        let public_keys = get_public_keys(private_keys)?;
+    /// The decoded transaction, or an error if the string is not a valid encoded transaction
+    pub fn from_base64(data: &str) -> Result<Transaction<'static>, TransactionError> {
+        let bytes = general_purpose::URL_SAFE_NO_PAD.decode(data.trim_end_matches('='))
+            .map_err(|error| TransactionError::Decode(error.to_string()))?;
+        Transaction::from_bytes(&bytes).map(Transaction::into_owned)
+    }
 
-        self.signers.get_or_insert_with(Vec::new).extend(public_keys.iter().map(|pk| pk.to_vec()));
+    /// Returns the size in bytes of the GTV-encoded transaction, as sent to the node.
+    ///
+    /// # Returns
+    /// Encoded size in bytes
+    ///
+    /// # Errors
+    /// `TransactionError::Encode` if an operation has no name
+    pub fn encoded_size(&self) -> Result<usize, TransactionError> {
+        Ok(gtv::encode_tx(self)?.len())
+    }
 
-        let digest = self.tx_rid().map_err(|_| secp256k1::Error::InvalidMessage)?;
+    /// Summarizes the encoded size of each operation and of the whole transaction,
+    /// the number of signers and signatures, and the transaction RID.
+    ///
+    /// Front-ends can compare `total_size` with the node limit before submitting.
+    ///
+    /// # Returns
+    /// A TransactionReport instance
+    ///
+    /// # Errors
+    /// `TransactionError::Encode` if an operation has no name
+    pub fn report(&self) -> Result<TransactionReport, TransactionError> {
+        let operations = self.operations.iter().flatten()
+            .map(|op| Ok(OperationReport {
+                name: op.name().unwrap_or_default().to_string(),
+                encoded_size: op.to_bytes()?.len()
+            }))
+            .collect::<Result<_, TransactionError>>()?;
 
-        for private_key in private_keys {
-             let signature = sign(&digest, private_key)?;
-             self.signatures.get_or_insert_with(Vec::new).push(signature.to_vec());
-        }
+        Ok(TransactionReport {
+            operations,
+            total_size: self.encoded_size()?,
+            signer_count: self.signers.as_ref().map_or(0, Vec::len),
+            signature_count: self.signatures.iter().flatten().filter(|sig| !sig.is_empty()).count(),
+            tx_rid: self.tx_rid_hex().ok()
+        })
+    }
 
-        Ok(())
+    /// Computes the unique identifier (RID) of this transaction.
+    /// 
+    /// The transaction RID is computed by hashing the GTV representation
+    /// of the transaction using the GTX hash function.
+    /// 
+    /// # Returns
+    /// A fixed-size 32 bytes containing the transaction RID
+    pub fn tx_rid(&self) -> Result<[u8; 32], hasher::HashError> {
+        gtv::check_operation_names(self.operations.iter().flatten())?;
+        hasher::tx_body_hash(self)
     }
-}
 
-/// Signs a message digest using ECDSA with secp256k1.
+    /// Returns the hex-encoded transaction RID.
+    /// 
+    /// This is a convenience method that returns the transaction RID
+    /// as a hexadecimal string.
+    /// 
+    /// # Returns
+    /// Hex-encoded string of the transaction RID
+    pub fn tx_rid_hex(&self) -> Result<String, hasher::HashError> {
+        Ok(hex::encode(self.tx_rid()?))
+    }
+
+    /// Returns the transaction RID as hex in the given letter case.
+    ///
+    /// # Arguments
+    /// * `case` - Letter case of the digits
+    pub fn tx_rid_hex_case(&self, case: gtv::HexCase) -> Result<String, hasher::HashError> {
+        let mut out = String::with_capacity(64);
+        self.tx_rid_hex_into(case, &mut out)?;
+        Ok(out)
+    }
+
+    /// Appends the hex-encoded transaction RID to `out`, so logging can reuse one buffer.
+    ///
+    /// # Arguments
+    /// * `case` - Letter case of the digits
+    /// * `out` - The string to append to
+    pub fn tx_rid_hex_into(&self, case: gtv::HexCase, out: &mut String) -> Result<(), hasher::HashError> {
+        gtv::encode_hex_into(&self.tx_rid()?, case, out);
+        Ok(())
+    }
+
+    /// Declares the public keys of all signers up front.
+    ///
+    /// The transaction RID covers the signer list, so declaring every signer before the
+    /// first signature keeps the RID stable while signatures are collected. Afterwards
+    /// `sign`, `multi_sign` and `sign_with` only fill in signatures, at the position of
+    /// the matching signer, and reject keys that were not declared.
+    ///
+    /// Any signature already present is dropped, since it no longer matches the RID.
+    ///
+    /// # Arguments
+    /// * `signers` - 33-byte compressed public keys, in signing order
+    pub fn set_signers(&mut self, signers: Vec<Vec<u8>>) {
+        self.signatures = Some(vec![Vec::new(); signers.len()]);
+        self.signers = Some(signers);
+        self.signed_rid = SignedRid::default();
+    }
+
+    /// Exports the digest to sign along with the signers and the signatures collected so far.
+    ///
+    /// Declare all signers with `set_signers` first, so the digest does not change
+    /// while the co-signers sign.
+    ///
+    /// # Returns
+    /// The signing payload, or an error if the transaction RID cannot be computed
+    pub fn signing_payload(&self) -> Result<SigningPayload, TransactionError> {
+        let signers = self.signers.clone().unwrap_or_default();
+        let signatures = self.signatures.clone().unwrap_or_default();
+
+        Ok(SigningPayload {
+            blockchain_rid: hex::encode(&self.blockchain_rid),
+            tx_rid: self.tx_rid_hex()?,
+            signatures: (0..signers.len())
+                .map(|index| signatures.get(index)
+                    .filter(|signature| !signature.is_empty())
+                    .map(hex::encode))
+                .collect(),
+            signers: signers.iter().map(hex::encode).collect()
+        })
+    }
+
+    /// Adds a signature produced elsewhere, after checking it.
+    ///
+    /// # Arguments
+    /// * `public_key` - 33-byte compressed public key of a declared signer
+    /// * `signature` - 64-byte compact signature of the transaction RID
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the signer is not declared or the signature does not verify
+    pub fn add_signature(&mut self, public_key: &[u8], signature: &[u8]) -> Result<(), TransactionError> {
+        let index = self.signers.as_ref()
+            .and_then(|signers| signers.iter().position(|signer| signer == public_key))
+            .ok_or_else(|| TransactionError::UndeclaredSigner(public_key.to_vec()))?;
+
+        let digest = self.tx_rid()?;
+
+        if !verify(&digest, public_key, signature) {
+            return Err(TransactionError::InvalidSignature(public_key.to_vec()));
+        }
+
+        self.put_signature(index, signature.to_vec());
+
+        Ok(())
+    }
+
+    /// Adds every signature present in a signing payload returned by a co-signer.
+    ///
+    /// # Arguments
+    /// * `payload` - Signing payload made from this transaction
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the payload belongs to another transaction or a signature is invalid
+    pub fn add_signatures_from(&mut self, payload: &SigningPayload) -> Result<(), TransactionError> {
+        if payload.digest()? != self.tx_rid()? {
+            return Err(TransactionError::PayloadMismatch);
+        }
+
+        for (signer, signature) in payload.signers.iter().zip(&payload.signatures) {
+            if let Some(signature) = signature {
+                let public_key = Vec::from_hex(signer).map_err(|_| TransactionError::PayloadMismatch)?;
+                let signature = Vec::from_hex(signature).map_err(|_| TransactionError::InvalidSignature(public_key.clone()))?;
+                self.add_signature(&public_key, &signature)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the transaction is well formed before it is signed or sent.
+    ///
+    /// A transaction needs a 32-byte blockchain RID and at least one operation, and
+    /// every operation needs a non-empty name.
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error describing the problem
+    pub fn validate(&self) -> Result<(), TransactionError> {
+        if self.blockchain_rid.len() != 32 {
+            return Err(TransactionError::InvalidBlockchainRid(self.blockchain_rid.len()));
+        }
+
+        let operations = match &self.operations {
+            Some(operations) if !operations.is_empty() => operations,
+            _ => return Err(TransactionError::NoOperations)
+        };
+
+        if let Some(index) = operations.iter().position(|op| op.name().is_none_or(str::is_empty)) {
+            return Err(TransactionError::MissingOperationName(index));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every signature still matches the current contents of the transaction.
+    ///
+    /// Operations, signers and the blockchain RID are public fields, so they can change after
+    /// signing. This recomputes the transaction RID and verifies each signature against it
+    /// and the signer at the same position.
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns `TransactionError::StaleSignature` if a signature does not match its signer and the
+    /// current transaction RID
+    pub fn verify_signatures(&self) -> Result<(), TransactionError> {
+        let Some(signatures) = &self.signatures else {
+            return Ok(());
+        };
+
+        let digest = self.tx_rid()?;
+        let signers = self.signers.as_deref().unwrap_or_default();
+
+        for (index, signature) in signatures.iter().enumerate().filter(|(_, signature)| !signature.is_empty()) {
+            match signers.get(index) {
+                Some(public_key) if verify(&digest, public_key, signature) => {},
+                public_key => return Err(TransactionError::StaleSignature(public_key.cloned().unwrap_or_default()))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Signs the transaction using a raw private key string.
+    /// 
+    /// # Arguments
+    /// * `private_key` - Private key as a string
+    /// 
+    /// # Returns
+    /// Result indicating success or a transaction error
+    /// 
+    /// # Errors
+    /// Returns an error if the transaction is malformed, the private key is invalid, its signer is not declared or signing fails
+    pub fn sign_from_raw_priv_key(&mut self, private_key: &str) -> Result<(), TransactionError> {
+        let private_key_bytes = Vec::from_hex(private_key).map_err(|_| secp256k1::Error::InvalidSecretKey)?;
+        let private_key = private_key_bytes.try_into().map_err(|_| secp256k1::Error::InvalidSecretKey)?;
+        self.sign(&private_key)
+    }
+
+    /// Signs the transaction with multiple raw private key strings.
+    ///
+    /// This method iteratively signs the transaction with each provided
+    /// private key string, enabling multi-signature transactions.
+    ///
+    /// # Arguments
+    /// * `private_keys` - Slice of raw private key strings
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the transaction is malformed, any private key is invalid, its signer is not declared or signing fails
+    pub fn multi_sign_from_raw_priv_keys(&mut self, private_keys: &[&str]) -> Result<(), TransactionError> {
+        let private_keys_bytes: Vec<[u8; 32]> = private_keys
+            .iter()
+            .map(|private_key_hex| {
+                let private_key_bytes = Vec::from_hex(private_key_hex).map_err(|_| secp256k1::Error::InvalidSecretKey)?;
+                private_key_bytes.try_into().map_err(|_| secp256k1::Error::InvalidSecretKey)
+            })
+            .collect::<Result<Vec<[u8; 32]>, secp256k1::Error>>()?;
+
+        let private_keys_refs: Vec<&[u8; 32]> = private_keys_bytes.iter().collect();
+
+        self.multi_sign(private_keys_refs.as_slice())
+    }
+
+    /// Signs the transaction using a private key.
+    /// 
+    /// This method:
+    /// 1. Derives the public key from the private key
+    /// 2. Declares it as the only signer if no signers are declared yet
+    /// 3. Signs the transaction RID
+    /// 4. Stores the signature at the position of the signer
+    /// 
+    /// The transaction RID is computed by the first signing call and reused by the following
+    /// ones while the transaction stays signed, so signing with several keys one by one hashes
+    /// the transaction only once.
+    /// 
+    /// # Arguments
+    /// * `private_key` - 32-byte private key
+    /// 
+    /// # Returns
+    /// Result indicating success or a transaction error
+    /// 
+    /// # Errors
+    /// Returns an error if the transaction is malformed, the private key is invalid, its signer is not declared or signing fails
+    pub fn sign(&mut self, private_key: &[u8; 32]) -> Result<(), TransactionError> {
+        self.validate()?;
+
+        let public_key = get_public_key(private_key)?;
+
+        let index = self.signer_indexes(&[public_key])?[0];
+
+        let digest = self.signing_rid()?;
+        let signature = sign(&digest, private_key)?;
+
+        self.put_signature(index, signature.to_vec());
+
+        Ok(())
+    }
+
+    /// Signs the transaction using a key pair.
+    ///
+    /// # Arguments
+    /// * `keypair` - Key pair of the signer
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the signer is not declared or signing fails
+    pub fn sign_with_keypair(&mut self, keypair: &KeyPair) -> Result<(), TransactionError> {
+        self.sign(&keypair.private_key())
+    }
+
+    /// Signs the transaction with multiple key pairs.
+    ///
+    /// # Arguments
+    /// * `keypairs` - Key pairs of the signers
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if any signer is not declared or signing fails
+    pub fn multi_sign_with_keypairs(&mut self, keypairs: &[&KeyPair]) -> Result<(), TransactionError> {
+        let private_keys: Vec<[u8; 32]> = keypairs.iter().map(|keypair| keypair.private_key()).collect();
+        let private_keys_refs: Vec<&[u8; 32]> = private_keys.iter().collect();
+
+        self.multi_sign(private_keys_refs.as_slice())
+    }
+
+    /// Signs the transaction using an external signer.
+    ///
+    /// Works like [`Transaction::sign`], but the signature is produced by the signer
+    /// so the private key never has to be handed to this crate.
+    ///
+    /// # Arguments
+    /// * `signer` - External signer, e.g. an HSM client
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the transaction is malformed, the signer is not declared, the transaction RID cannot be computed or the signer fails
+    pub async fn sign_with<S: TxSigner>(&mut self, signer: &S) -> Result<(), TransactionError> {
+        self.validate()?;
+
+        let public_key = signer.pubkey().await;
+
+        let index = self.signer_indexes(&[public_key])?[0];
+
+        let digest = self.signing_rid()?;
+        let signature = signer.sign(&digest).await?;
+
+        self.put_signature(index, signature.to_vec());
+
+        Ok(())
+    }
+
+    /// Signs the transaction with a mix of local keys and external signers.
+    ///
+    /// Signers are processed one after the other in the order given. If no signers are
+    /// declared yet, they are declared in that order. Signatures are only stored once
+    /// every signer has succeeded, so a failing signer leaves the transaction unchanged.
+    ///
+    /// # Arguments
+    /// * `signers` - Local private keys and external signers
+    ///
+    /// # Returns
+    /// Result indicating success or a transaction error
+    ///
+    /// # Errors
+    /// Returns an error if the transaction is malformed, a signer is not declared or any signer fails
+    ///
+    /// # Example
+    /// ```
+    /// # use postchain_client::utils::{keypair::KeyPair, operation::Operation, transaction::{AnySigner, Transaction}};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let local_key = [1u8; 32];
+    /// let hsm = KeyPair::generate(); // stands in for any `TxSigner`
+    ///
+    /// let mut tx = Transaction::new(vec![0; 32], Some(vec![Operation::nop()]), None, None);
+    /// tx.multi_sign_mixed(&[AnySigner::Local(&local_key), AnySigner::External(&hsm)]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn multi_sign_mixed(&mut self, signers: &[AnySigner<'_>]) -> Result<(), TransactionError> {
+        self.validate()?;
+
+        let mut public_keys = Vec::with_capacity(signers.len());
+        for signer in signers {
+            public_keys.push(match signer {
+                AnySigner::Local(private_key) => get_public_key(private_key)?,
+                AnySigner::External(signer) => signer.dyn_pubkey().await
+            });
+        }
+
+        let indexes = self.signer_indexes(&public_keys)?;
+
+        let digest = self.signing_rid()?;
+
+        let mut signatures = Vec::with_capacity(signers.len());
+        for signer in signers {
+            signatures.push(match signer {
+                AnySigner::Local(private_key) => sign(&digest, private_key)?,
+                AnySigner::External(signer) => signer.dyn_sign(&digest).await?
+            });
+        }
+
+        for (index, signature) in indexes.into_iter().zip(signatures) {
+            self.put_signature(index, signature.to_vec());
+        }
+
+        Ok(())
+    }
+
+    /// Signs the transaction with multiple private keys.
+    /// 
+    /// If no signers are declared yet, the keys are declared as the signers, in order,
+    /// before the transaction RID is computed.
+    /// 
+    /// # Arguments
+    /// * `private_keys` - Slice of 32-byte private keys
+    /// 
+    /// # Returns
+    /// Result indicating success or a transaction error
+    /// 
+    /// # Errors
+    /// Returns an error if the transaction is malformed, any private key is invalid, its signer is not declared or signing fails
+    pub fn multi_sign(&mut self, private_keys: &[&[u8; 32]]) -> Result<(), TransactionError> {
+        self.validate()?;
+
+        let public_keys = get_public_keys(private_keys)?;
+
+        let indexes = self.signer_indexes(&public_keys)?;
+
+        let digest = self.signing_rid()?;
+
+        for (private_key, index) in private_keys.iter().zip(indexes) {
+             let signature = sign(&digest, private_key)?;
+             self.put_signature(index, signature.to_vec());
+        }
+
+        Ok(())
+    }
+
+    /// Finds the position of each public key in the signer list.
+    ///
+    /// When no signers are declared yet, the keys become the signer list.
+    ///
+    /// # Arguments
+    /// * `public_keys` - 33-byte compressed public keys
+    ///
+    /// # Returns
+    /// The index of each key in the signer list, or an error for an undeclared key
+    fn signer_indexes(&mut self, public_keys: &[[u8; 33]]) -> Result<Vec<usize>, TransactionError> {
+        let signers = self.signers.get_or_insert_with(Vec::new);
+
+        if signers.is_empty() {
+            self.signed_rid = SignedRid::default();
+
+            for public_key in public_keys {
+                if !signers.iter().any(|signer| signer == public_key) {
+                    signers.push(public_key.to_vec());
+                }
+            }
+        }
+
+        public_keys.iter()
+            .map(|public_key| signers.iter()
+                .position(|signer| signer == public_key)
+                .ok_or_else(|| TransactionError::UndeclaredSigner(public_key.to_vec())))
+            .collect()
+    }
+
+    /// Returns the transaction RID to sign, reusing the one of the previous signing call
+    /// while the transaction is still signed.
+    ///
+    /// # Returns
+    /// The transaction RID, or an error if it cannot be computed
+    fn signing_rid(&mut self) -> Result<[u8; 32], TransactionError> {
+        if let Some(rid) = self.signed_rid.0.filter(|_| self.is_signed()) {
+            return Ok(rid);
+        }
+
+        let rid = self.tx_rid()?;
+        self.signed_rid = SignedRid(Some(rid));
+        Ok(rid)
+    }
+
+    /// Stores a signature at the position of its signer.
+    ///
+    /// # Arguments
+    /// * `index` - Position of the signer
+    /// * `signature` - 64-byte signature
+    fn put_signature(&mut self, index: usize, signature: Vec<u8>) {
+        let signatures = self.signatures.get_or_insert_with(Vec::new);
+
+        if signatures.len() <= index {
+            signatures.resize(index + 1, Vec::new());
+        }
+
+        signatures[index] = signature;
+    }
+}
+
+/// Size and signing summary of a transaction, see [`Transaction::report`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TransactionReport {
+    /// Name and encoded size of each operation, in order
+    pub operations: Vec<OperationReport>,
+    /// Size in bytes of the whole encoded transaction, as sent to the node
+    pub total_size: usize,
+    /// Number of declared signers
+    pub signer_count: usize,
+    /// Number of signatures present
+    pub signature_count: usize,
+    /// Hex-encoded transaction RID (the digest signers sign), if it can be computed
+    pub tx_rid: Option<String>
+}
+
+/// Encoded size of one operation, see [`TransactionReport`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OperationReport {
+    /// Name of the operation
+    pub name: String,
+    /// Size in bytes of the encoded operation
+    pub encoded_size: usize
+}
+
+/// `Debug` view of a [`Transaction`] with its signatures redacted, see [`Transaction::redacted`].
+pub struct RedactedTransaction<'t, 'a>(&'t Transaction<'a>);
+
+impl std::fmt::Debug for RedactedTransaction<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tx = self.0;
+        let signatures = tx.signatures.as_ref()
+            .map(|signatures| format!("<{} redacted>", signatures.iter().filter(|sig| !sig.is_empty()).count()));
+
+        f.debug_struct("Transaction")
+            .field("blockchain_rid", &hex::encode(&tx.blockchain_rid))
+            .field("operations", &tx.operations)
+            .field("signers", &tx.signers.as_ref().map(|signers| signers.iter().map(hex::encode).collect::<Vec<_>>()))
+            .field("signatures", &signatures.as_deref().map(Redacted))
+            .finish()
+    }
+}
+
+/// Text printed verbatim by `Debug`, without quotes.
+struct Redacted<'s>(&'s str);
+
+impl std::fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Fluent builder for [`Transaction`].
+///
+/// By default a unique `nop` operation (see [`Operation::nop_unique`]) is appended on `build`, so that
+/// transactions with the same operations still get distinct transaction RIDs. This is
+/// skipped when the operations already contain a `nop`, or when disabled with `auto_nop(false)`.
+///
+/// # Example
+/// ```
+/// use postchain_client::utils::{operation::{Operation, Params}, transaction::TransactionBuilder};
+///
+/// let tx = TransactionBuilder::new()
+///     .blockchain_rid(vec![0; 32])
+///     .add_operation(Operation::from_list("set_name", vec![Params::Text("foo".to_string())]))
+///     .build();
+///
+/// assert_eq!(tx.operations.unwrap().len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct TransactionBuilder<'a> {
+    blockchain_rid: Vec<u8>,
+    operations: Vec<Operation<'a>>,
+    signers: Vec<Vec<u8>>,
+    auto_nop: bool
+}
+
+impl Default for TransactionBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            blockchain_rid: Vec::new(),
+            operations: Vec::new(),
+            signers: Vec::new(),
+            auto_nop: true
+        }
+    }
+}
+
+impl<'a> TransactionBuilder<'a> {
+    /// Creates an empty builder with automatic `nop` insertion enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the blockchain RID.
+    ///
+    /// # Arguments
+    /// * `blockchain_rid` - Unique identifier of the blockchain, as bytes or a `BlockchainRid`
+    pub fn blockchain_rid(mut self, blockchain_rid: impl Into<Vec<u8>>) -> Self {
+        self.blockchain_rid = blockchain_rid.into();
+        self
+    }
+
+    /// Appends an operation.
+    ///
+    /// # Arguments
+    /// * `operation` - Operation to append
+    pub fn add_operation(mut self, operation: Operation<'a>) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// Appends several operations, in order.
+    ///
+    /// # Arguments
+    /// * `operations` - Operations to append
+    pub fn add_operations(mut self, operations: impl IntoIterator<Item = Operation<'a>>) -> Self {
+        self.operations.extend(operations);
+        self
+    }
+
+    /// Declares a signer by its 33-byte compressed public key.
+    ///
+    /// # Arguments
+    /// * `public_key` - Public key of the signer
+    pub fn add_signer(mut self, public_key: Vec<u8>) -> Self {
+        self.signers.push(public_key);
+        self
+    }
+
+    /// Makes the built transaction invalid once the deadline has passed.
+    ///
+    /// # Arguments
+    /// * `until` - Deadline, in milliseconds since the epoch
+    pub fn valid_until(self, until: i64) -> Self {
+        self.add_operation(Operation::timeb(0, until))
+    }
+
+    /// Makes the built transaction invalid after `ttl` from now.
+    ///
+    /// # Arguments
+    /// * `ttl` - How long the transaction stays valid
+    pub fn ttl(self, ttl: Duration) -> Self {
+        self.valid_until(deadline_after(ttl))
+    }
+
+    /// Enables or disables appending a unique `nop` operation on `build`.
+    ///
+    /// # Arguments
+    /// * `auto_nop` - Whether to append the `nop` operation
+    pub fn auto_nop(mut self, auto_nop: bool) -> Self {
+        self.auto_nop = auto_nop;
+        self
+    }
+
+    /// Builds the unsigned transaction.
+    ///
+    /// # Returns
+    /// A new Transaction instance
+    pub fn build(self) -> Transaction<'a> {
+        let mut operations = self.operations;
+
+        if self.auto_nop && !operations.iter().any(|op| op.name() == Some(NOP_OPERATION)) {
+            operations.push(Operation::nop_unique());
+        }
+
+        let signers = if self.signers.is_empty() { None } else { Some(self.signers) };
+        Transaction::new(self.blockchain_rid, Some(operations), signers, None)
+    }
+}
+
+/// Returns the time `ttl` from now, in milliseconds since the epoch.
+fn deadline_after(ttl: Duration) -> i64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    i64::try_from((now + ttl).as_millis()).unwrap_or(i64::MAX)
+}
+
+/// Signs a message digest using ECDSA with secp256k1.
 /// 
 /// # Arguments
 /// * `digest` - 32-byte message digest to sign
@@ -273,6 +1359,25 @@ fn sign(digest: &[u8; 32], private_key: &[u8; 32]) -> Result<[u8; 64], secp256k1
     Ok(serialized_signature)
 }
 
+/// Verifies a compact ECDSA signature of a message digest.
+///
+/// # Arguments
+/// * `digest` - 32-byte message digest
+/// * `public_key` - 33-byte compressed public key
+/// * `signature` - 64-byte compact signature
+///
+/// # Returns
+/// true if the signature is valid for the digest and public key
+fn verify(digest: &[u8; 32], public_key: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (PublicKey::from_slice(public_key), Signature::from_compact(signature)) else {
+        return false;
+    };
+
+    Secp256k1::verification_only()
+        .verify_ecdsa(&Message::from_digest(*digest), &signature, &public_key)
+        .is_ok()
+}
+
 /// Derives a public key from a private key using secp256k1.
 /// 
 /// # Arguments
@@ -309,4 +1414,464 @@ fn get_public_keys(private_keys: &[&[u8; 32]]) -> Result<Vec<[u8; 33]>, secp256k
     }
 
     Ok(public_keys)
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_transaction_builder_appends_unique_nop() {
+    let build = || TransactionBuilder::new()
+        .blockchain_rid(vec![1; 32])
+        .add_operation(Operation::from_list("set_boolean", vec![Params::Boolean(true)]))
+        .build();
+
+    let tx1 = build();
+    let tx2 = build();
+
+    let operations = tx1.operations.as_ref().unwrap();
+    assert_eq!(operations.len(), 2);
+    assert_eq!(operations[1].name(), Some("nop"));
+    assert_ne!(tx1.tx_rid().unwrap(), tx2.tx_rid().unwrap());
+}
+
+#[test]
+fn test_transaction_builder_keeps_existing_nop() {
+    let tx = TransactionBuilder::new()
+        .add_operations(vec![
+            Operation::from_list("set_boolean", vec![Params::Boolean(true)]),
+            Operation::from_list("nop", vec![Params::Integer(1)])
+        ])
+        .add_signer(vec![2; 33])
+        .build();
+
+    assert_eq!(tx.operations.unwrap().len(), 2);
+    assert_eq!(tx.signers, Some(vec![vec![2; 33]]));
+
+    let tx = TransactionBuilder::new().auto_nop(false).build();
+    assert_eq!(tx.operations.unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_sign_with_matches_sign() {
+    let private_key = [7u8; 32];
+    let operations = || Some(vec![Operation::from_list("set_boolean", vec![Params::Boolean(true)])]);
+
+    let mut tx1 = Transaction::new(vec![1; 32], operations(), None, None);
+    tx1.sign(&private_key).unwrap();
+
+    let mut tx2 = Transaction::new(vec![1; 32], operations(), None, None);
+    tx2.sign_with(&SecretKey::from_byte_array(&private_key).unwrap()).await.unwrap();
+
+    assert_eq!(tx1.signers, tx2.signers);
+    assert_eq!(tx1.signatures, tx2.signatures);
+}
+
+#[test]
+fn test_pre_declared_signers_keep_tx_rid_stable() {
+    let private_key1 = [1u8; 32];
+    let private_key2 = [2u8; 32];
+    let public_key1 = get_public_key(&private_key1).unwrap().to_vec();
+    let public_key2 = get_public_key(&private_key2).unwrap().to_vec();
+
+    let mut tx = Transaction::new(vec![1; 32],
+        Some(vec![Operation::from_list("set_boolean", vec![Params::Boolean(true)])]), None, None);
+    tx.set_signers(vec![public_key1.clone(), public_key2.clone()]);
+
+    let tx_rid = tx.tx_rid().unwrap();
+
+    tx.sign(&private_key2).unwrap();
+    assert_eq!(tx.tx_rid().unwrap(), tx_rid);
+    tx.sign(&private_key1).unwrap();
+    assert_eq!(tx.tx_rid().unwrap(), tx_rid);
+
+    assert_eq!(tx.signers, Some(vec![public_key1, public_key2]));
+    let signatures = tx.signatures.as_ref().unwrap();
+    assert_eq!(signatures[0], sign(&tx_rid, &private_key1).unwrap().to_vec());
+    assert_eq!(signatures[1], sign(&tx_rid, &private_key2).unwrap().to_vec());
+
+    let result = tx.sign(&[3u8; 32]);
+    assert!(matches!(result, Err(TransactionError::UndeclaredSigner(_))));
+}
+
+#[test]
+fn test_multi_sign_declares_signers_once() {
+    let private_key1 = [1u8; 32];
+    let private_key2 = [2u8; 32];
+
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+    tx.multi_sign(&[&private_key1, &private_key2]).unwrap();
+
+    let tx_rid = tx.tx_rid().unwrap();
+    assert_eq!(tx.signers.as_ref().unwrap().len(), 2);
+    assert_eq!(tx.signatures.as_ref().unwrap()[1], sign(&tx_rid, &private_key2).unwrap().to_vec());
+
+    // Re-signing replaces the signature instead of adding a signer
+    tx.sign(&private_key1).unwrap();
+    assert_eq!(tx.signers.as_ref().unwrap().len(), 2);
+    assert_eq!(tx.signatures.as_ref().unwrap().len(), 2);
+}
+
+#[test]
+fn test_offline_multi_party_signing() {
+    let private_key1 = [1u8; 32];
+    let private_key2 = [2u8; 32];
+    let public_key1 = get_public_key(&private_key1).unwrap().to_vec();
+    let public_key2 = get_public_key(&private_key2).unwrap().to_vec();
+
+    let mut tx = Transaction::new(vec![1; 32],
+        Some(vec![Operation::from_list("transfer", vec![Params::Integer(10)])]), None, None);
+    tx.set_signers(vec![public_key1.clone(), public_key2.clone()]);
+
+    let payload = tx.signing_payload().unwrap();
+    assert_eq!(payload.missing_signers().len(), 2);
+
+    let json = serde_json::to_string(&payload).unwrap();
+
+    let mut payload2: SigningPayload = serde_json::from_str(&json).unwrap();
+    payload2.sign(&private_key2).unwrap();
+    assert_eq!(payload2.missing_signers(), vec![hex::encode(&public_key1)]);
+    assert!(matches!(payload2.sign(&[3u8; 32]), Err(TransactionError::UndeclaredSigner(_))));
+
+    tx.add_signatures_from(&payload2).unwrap();
+
+    let signature1 = sign(&tx.tx_rid().unwrap(), &private_key1).unwrap();
+    assert!(matches!(tx.add_signature(&public_key1, &[0u8; 64]), Err(TransactionError::InvalidSignature(_))));
+    tx.add_signature(&public_key1, &signature1).unwrap();
+
+    assert!(tx.signing_payload().unwrap().missing_signers().is_empty());
+
+    let mut other = Transaction::new(vec![2; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+    other.set_signers(vec![public_key1, public_key2]);
+    assert!(matches!(other.add_signatures_from(&payload2), Err(TransactionError::PayloadMismatch)));
+}
+
+#[test]
+fn test_encoded_size() {
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+    assert_eq!(tx.encoded_size().unwrap(), tx.to_bytes().unwrap().len());
+
+    let size = tx.encoded_size().unwrap();
+    tx.operations = Some(vec![Operation::from_list("store", vec![Params::ByteArray(vec![0; 1000])])]);
+    assert!(tx.encoded_size().unwrap() > size + 1000);
+
+    tx.operations = Some(vec![Operation { list: Some(vec![]), ..Default::default() }]);
+    assert!(matches!(tx.encoded_size(), Err(TransactionError::Encode(gtv::EncodeError::MissingOperationName(0)))));
+    assert!(matches!(tx.tx_rid(), Err(hasher::HashError::Encode(gtv::EncodeError::MissingOperationName(0)))));
+}
+
+#[tokio::test]
+async fn test_owned_transaction_moves_across_tasks() {
+    let operation_name = String::from("set_name");
+    let argument_name = String::from("name");
+
+    let tx: Transaction<'static> = Transaction::new(vec![1; 32], Some(vec![
+        Operation::from_dict(operation_name, vec![(argument_name, Params::Text("foo".to_string()))])
+    ]), None, None);
+
+    let encoded = tx.to_bytes().unwrap();
+    let decoded = Transaction::from_bytes(&encoded).unwrap().into_owned();
+    drop(encoded);
+
+    let tx_rid = tokio::spawn(async move { decoded.tx_rid().unwrap() }).await.unwrap();
+    assert_eq!(tx_rid, Transaction::from_bytes(&tx.to_bytes().unwrap()).unwrap().tx_rid().unwrap());
+}
+
+#[test]
+fn test_valid_until() {
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![]), None, None);
+    tx.set_valid_until(1_700_000_000_000).unwrap();
+    assert_eq!(tx.operations.as_ref().unwrap()[0], Operation::timeb(0, 1_700_000_000_000));
+
+    tx.sign(&[1u8; 32]).unwrap();
+    assert!(matches!(tx.set_ttl(Duration::from_secs(60)), Err(TransactionError::AlreadySigned)));
+
+    let before = deadline_after(Duration::ZERO);
+    let tx = TransactionBuilder::new().ttl(Duration::from_secs(3600)).auto_nop(false).build();
+    match &tx.operations.as_ref().unwrap()[0].list.as_ref().unwrap()[..] {
+        [Params::Integer(0), Params::Integer(until)] => assert!(*until >= before + 3_600_000),
+        other => panic!("Unexpected timeb arguments: {:?}", other)
+    }
+}
+
+#[test]
+fn test_clone_eq_and_redacted_debug() {
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop_unique()]), None, None);
+    tx.sign(&[1u8; 32]).unwrap();
+
+    let copy = tx.clone();
+    assert_eq!(copy, tx);
+
+    let signature = hex::encode(&tx.signatures.as_ref().unwrap()[0]);
+    let signature_bytes = format!("{:?}", tx.signatures.as_ref().unwrap()[0]);
+    let output = format!("{:?}", tx.redacted());
+
+    assert!(output.contains("signatures: Some(<1 redacted>)"));
+    assert!(!output.contains(&signature) && !output.contains(&signature_bytes));
+    assert!(output.contains(&hex::encode(&tx.signers.as_ref().unwrap()[0])));
+}
+
+#[test]
+fn test_multi_sign_with_keypairs() {
+    let keypair1 = KeyPair::generate();
+    let keypair2 = KeyPair::generate();
+
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+    tx.multi_sign_with_keypairs(&[&keypair1, &keypair2]).unwrap();
+
+    let mut expected = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("nop", vec![])]), None, None);
+    expected.multi_sign(&[&keypair1.private_key(), &keypair2.private_key()]).unwrap();
+
+    assert_eq!(tx, expected);
+    assert_eq!(tx.signers.unwrap(), vec![keypair1.public_key().to_vec(), keypair2.public_key().to_vec()]);
+}
+
+#[test]
+fn test_mutation_after_signing() {
+    let mut tx = Transaction::new(vec![1; 32], None, None, None);
+    tx.push_operation(Operation::from_list("b", vec![])).unwrap();
+    tx.insert_operation(0, Operation::from_list("a", vec![])).unwrap();
+    assert_eq!(tx.operations.as_ref().unwrap().iter().map(|op| op.name().unwrap()).collect::<Vec<_>>(), vec!["a", "b"]);
+    tx.insert_operation(2, Operation::from_list("c", vec![])).unwrap();
+    assert!(matches!(tx.insert_operation(4, Operation::from_list("d", vec![])), Err(TransactionError::OperationIndexOutOfRange { index: 4, len: 3 })));
+    assert_eq!(tx.operations.as_ref().unwrap().len(), 3);
+
+    let mut empty = Transaction::new(vec![1; 32], None, None, None);
+    let error = empty.insert_operation(1, Operation::nop()).unwrap_err();
+    assert_eq!(error.to_string(), "Operation index 1 is out of range for 0 operations");
+
+    tx.sign(&[1u8; 32]).unwrap();
+    assert!(tx.is_signed());
+    assert!(matches!(tx.push_operation(Operation::from_list("c", vec![])), Err(TransactionError::AlreadySigned)));
+    assert!(matches!(tx.insert_operation(0, Operation::from_list("c", vec![])), Err(TransactionError::AlreadySigned)));
+
+    tx.clear_signatures();
+    assert!(!tx.is_signed());
+    assert_eq!(tx.signers.as_ref().unwrap().len(), 1);
+    tx.push_operation(Operation::from_list("c", vec![])).unwrap();
+    tx.sign(&[1u8; 32]).unwrap();
+    assert_eq!(tx.signatures.as_ref().unwrap().len(), 1);
+}
+
+#[test]
+fn test_signing_reuses_tx_rid_until_changed() {
+    let private_key1 = [1u8; 32];
+    let private_key2 = [2u8; 32];
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("a", vec![])]), None, None);
+    tx.set_signers(vec![get_public_key(&private_key1).unwrap().to_vec(), get_public_key(&private_key2).unwrap().to_vec()]);
+    assert_eq!(tx.signed_rid.0, None);
+
+    tx.sign(&private_key1).unwrap();
+    let tx_rid = tx.tx_rid().unwrap();
+    assert_eq!(tx.signed_rid.0, Some(tx_rid));
+
+    // The cached RID is what the second signature covers
+    tx.signed_rid = SignedRid(Some([0; 32]));
+    tx.sign(&private_key2).unwrap();
+    assert_eq!(tx.signatures.as_ref().unwrap()[1], sign(&[0; 32], &private_key2).unwrap().to_vec());
+    assert!(tx.verify_signatures().is_err());
+
+    tx.clear_signatures();
+    assert_eq!(tx.signed_rid.0, None);
+    tx.push_operation(Operation::from_list("b", vec![])).unwrap();
+    tx.multi_sign(&[&private_key1, &private_key2]).unwrap();
+    assert_ne!(tx.signed_rid.0, Some(tx_rid));
+    assert_eq!(tx.signed_rid.0, Some(tx.tx_rid().unwrap()));
+    assert!(tx.verify_signatures().is_ok());
+
+    tx.set_signers(vec![get_public_key(&private_key1).unwrap().to_vec()]);
+    assert_eq!(tx.signed_rid.0, None);
+    tx.sign(&private_key1).unwrap();
+    assert!(tx.verify_signatures().is_ok());
+
+    // An unsigned transaction never reuses a cached RID
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("a", vec![])]), None, None);
+    tx.signed_rid = SignedRid(Some([0; 32]));
+    tx.sign(&private_key1).unwrap();
+    assert!(tx.verify_signatures().is_ok());
+    assert_eq!(tx, Transaction { signed_rid: SignedRid::default(), ..tx.clone() });
+}
+
+#[test]
+fn test_validate_rejects_malformed_transactions() {
+    let mut tx = Transaction::new(vec![1; 32], None, None, None);
+    assert!(matches!(tx.sign(&[1u8; 32]), Err(TransactionError::NoOperations)));
+    assert!(tx.signers.is_none());
+
+    tx.operations = Some(vec![]);
+    assert!(matches!(tx.validate(), Err(TransactionError::NoOperations)));
+
+    tx.operations = Some(vec![Operation::from_list("a", vec![]), Operation::from_list("", vec![])]);
+    assert!(matches!(tx.validate(), Err(TransactionError::MissingOperationName(1))));
+
+    tx.operations = Some(vec![Operation::from_list("a", vec![])]);
+    tx.blockchain_rid = vec![1; 31];
+    assert!(matches!(tx.multi_sign(&[&[1u8; 32]]), Err(TransactionError::InvalidBlockchainRid(31))));
+
+    tx.blockchain_rid = vec![1; 32];
+    assert!(tx.validate().is_ok());
+}
+
+#[test]
+fn test_report() {
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![
+        Operation::from_list("store", vec![Params::ByteArray(vec![0; 100])]),
+        Operation::nop()
+    ]), None, None);
+    tx.set_signers(vec![get_public_key(&[1u8; 32]).unwrap().to_vec(), get_public_key(&[2u8; 32]).unwrap().to_vec()]);
+    tx.sign(&[1u8; 32]).unwrap();
+
+    let report = tx.report().unwrap();
+
+    assert_eq!(report.operations.len(), 2);
+    assert_eq!(report.operations[0].name, "store");
+    assert!(report.operations[0].encoded_size > 100);
+    assert_eq!(report.operations[1].encoded_size, Operation::nop().to_bytes().unwrap().len());
+    assert_eq!(report.total_size, tx.encoded_size().unwrap());
+    assert_eq!(report.signer_count, 2);
+    assert_eq!(report.signature_count, 1);
+    assert_eq!(report.tx_rid, Some(tx.tx_rid_hex().unwrap()));
+}
+
+#[tokio::test]
+async fn test_multi_sign_mixed() {
+    struct FailingSigner;
+
+    impl TxSigner for FailingSigner {
+        async fn pubkey(&self) -> [u8; 33] {
+            get_public_key(&[9u8; 32]).unwrap()
+        }
+
+        async fn sign(&self, _digest: &[u8; 32]) -> Result<[u8; 64], TransactionError> {
+            Err(TransactionError::Signer("device not connected".to_string()))
+        }
+    }
+
+    let local_key = [1u8; 32];
+    let external = KeyPair::from_private_key(&[2u8; 32]).unwrap();
+
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), None, None);
+    tx.multi_sign_mixed(&[AnySigner::Local(&local_key), AnySigner::External(&external)]).await.unwrap();
+
+    let mut expected = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), None, None);
+    expected.multi_sign(&[&local_key, &[2u8; 32]]).unwrap();
+    assert_eq!(tx, expected);
+
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), None, None);
+    let result = tx.multi_sign_mixed(&[AnySigner::Local(&local_key), AnySigner::External(&FailingSigner)]).await;
+    assert!(matches!(result, Err(TransactionError::Signer(_))));
+    assert!(!tx.is_signed());
+}
+
+#[test]
+fn test_template_instantiate() {
+    let mut tx = TransactionBuilder::new()
+        .blockchain_rid(vec![1; 32])
+        .add_operation(Operation::from_list("mint", vec![Params::Integer(1)]))
+        .build();
+    tx.sign(&[1u8; 32]).unwrap();
+
+    let template = tx.template();
+    assert!(!template.is_signed());
+    assert_eq!(template.signers, tx.signers);
+    assert_eq!(template.operations.as_ref().unwrap().len(), 1);
+
+    let first = template.instantiate();
+    let second = tx.instantiate();
+    for instance in [&first, &second] {
+        let operations = instance.operations.as_ref().unwrap();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].name(), Some("mint"));
+        assert_eq!(operations[1].name(), Some(NOP_OPERATION));
+        assert!(!instance.is_signed());
+    }
+    assert_ne!(first.tx_rid().unwrap(), second.tx_rid().unwrap());
+    assert_ne!(first.tx_rid().unwrap(), tx.tx_rid().unwrap());
+}
+
+#[test]
+fn test_for_chain() {
+    let brid = "FA189BEBA886669CF7DF7DB3D8CFD878D1F80ED360BDCF26B43ABE3D9B3D53CC";
+    let tx = Transaction::for_chain(brid, vec![Operation::nop()]).unwrap();
+
+    assert_eq!(tx, Transaction::new(hex::decode(brid).unwrap(), Some(vec![Operation::nop()]), None, None));
+    assert_eq!(Transaction::for_chain("FA18", vec![]), Err(BlockchainRidError::InvalidLength(2)));
+    assert!(matches!(Transaction::for_chain("not hex", vec![]), Err(BlockchainRidError::InvalidHex(_))));
+}
+
+#[test]
+fn test_verify_signatures() {
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), None, None);
+    assert!(tx.verify_signatures().is_ok());
+
+    tx.multi_sign(&[&[1u8; 32], &[2u8; 32]]).unwrap();
+    assert!(tx.verify_signatures().is_ok());
+
+    tx.operations.as_mut().unwrap().push(Operation::from_list("mint", vec![]));
+    let signer = tx.signers.as_ref().unwrap()[0].clone();
+    assert!(matches!(tx.verify_signatures(), Err(TransactionError::StaleSignature(public_key)) if public_key == signer));
+
+    tx.operations.as_mut().unwrap().pop();
+    tx.signers.as_mut().unwrap().swap(0, 1);
+    assert!(matches!(tx.verify_signatures(), Err(TransactionError::StaleSignature(_))));
+}
+
+#[test]
+fn test_base64_roundtrip() {
+    let mut tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("mint", vec![Params::Integer(5)])]), None, None);
+    tx.sign(&[1u8; 32]).unwrap();
+
+    let encoded = tx.to_base64().unwrap();
+    assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(Transaction::from_base64(&encoded).unwrap(), tx);
+    assert_eq!(Transaction::from_base64(&format!("{}==", encoded)).unwrap().tx_rid().unwrap(), tx.tx_rid().unwrap());
+    assert!(matches!(Transaction::from_base64("not*base64"), Err(TransactionError::Decode(_))));
+}
+
+#[test]
+fn test_tx_rid_matches_draw_gtx() {
+    use crate::{dict, gtv_array};
+
+    let list_args = |count: usize| (0..count)
+        .map(|index| gtv_array![index as i64, dict!{"nested" => gtv_array![vec![index as u8; 3]]}])
+        .collect::<Vec<_>>();
+    let operations = vec![
+        Operation::from_list("empty", vec![]),
+        Operation::from_list("one", list_args(1)),
+        Operation::from_list("seven", list_args(7)),
+        Operation::from_dict("named", vec![("b", Params::Integer(1)), ("a", Params::Null), ("b", Params::Text("later".to_string()))]),
+        Operation::from_dict("no_names", Vec::<(&str, Params)>::new()),
+        Operation { operation_name: Some("unset".into()), ..Default::default() },
+    ];
+
+    let transactions = [
+        Transaction::new(vec![1; 32], None, None, None),
+        Transaction::new(vec![1; 32], Some(vec![]), Some(vec![vec![2; 33]]), None),
+        Transaction::new(vec![1; 32], Some(operations[..1].to_vec()), None, None),
+        Transaction::new(vec![1; 32], Some(operations.clone()), Some(vec![vec![2; 33], vec![3; 33], vec![4; 33]]), Some(vec![vec![5; 64]])),
+    ];
+    for tx in transactions {
+        let draw_gtx = gtv::to_draw_gtx(&tx);
+        let signatures = tx.signatures.iter().flatten().cloned().map(Params::ByteArray).collect();
+        assert_eq!(gtv::encode_tx(&tx).unwrap(), gtv::encode_value(&Params::Array(vec![draw_gtx.clone(), Params::Array(signatures)])).unwrap());
+        assert_eq!(hasher::tx_body_hash(&tx).unwrap(), gtv_hash(&draw_gtx).unwrap());
+        assert_eq!(tx.tx_rid().unwrap(), gtv_hash(&draw_gtx).unwrap());
+    }
+
+    let deep = (0..gtv::DEFAULT_MAX_DEPTH - 4).fold(Params::Null, |inner, _| gtv_array![inner]);
+    let tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("deep", vec![deep.clone()])]), None, None);
+    assert!(tx.tx_rid().is_ok());
+    let tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_list("deep", vec![gtv_array![deep.clone()]])]), None, None);
+    assert!(matches!(tx.tx_rid(), Err(hasher::HashError::MaxDepthExceeded)));
+    let tx = Transaction::new(vec![1; 32], Some(vec![Operation::from_dict("deep", vec![("a", deep)])]), None, None);
+    assert!(matches!(tx.tx_rid(), Err(hasher::HashError::MaxDepthExceeded)));
+}
+
+#[test]
+fn test_tx_rid_hex_case() {
+    let tx = Transaction::new(vec![1; 32], Some(vec![Operation::nop()]), None, None);
+    let lower = tx.tx_rid_hex().unwrap();
+    assert_eq!(tx.tx_rid_hex_case(gtv::HexCase::Lower).unwrap(), lower);
+    assert_eq!(tx.tx_rid_hex_case(gtv::HexCase::Upper).unwrap(), lower.to_uppercase());
+
+    let mut line = String::from("tx=");
+    tx.tx_rid_hex_into(gtv::HexCase::Upper, &mut line).unwrap();
+    assert_eq!(line, format!("tx={}", lower.to_uppercase()));
+}
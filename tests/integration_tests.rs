@@ -1,14 +1,13 @@
 use postchain_client::{
-    transport::client::{self, RestClient, RestResponse},
+    transport::client::{self, RestClient, RestRequestMethod, RestResponse},
     utils::{
+        confirmation_proof::ConfirmationProof,
         operation::{Operation, Params, QueryParams},
-        transaction::Transaction
+        transaction::{Transaction, TransactionStatus}
     }
 };
 
 use std::{collections::BTreeMap, str::FromStr};
-use rand::Rng;
-use tracing_subscriber;
 use ctor::ctor;
 
 #[ctor]
@@ -19,14 +18,14 @@ fn initialize_test_envs() {
 const POSTCHAIN_SINGLE_NODE_API_URL: &str = "http://localhost:7740";
 const POSTCHAIN_MULTI_NODE_API_URL: &str = "https://node0.devnet1.chromia.dev:7740";
 
-async fn assert_roundtrips<'a>(
+async fn assert_roundtrips(
     rc: &RestClient<'_>,
     brid: &str,
     query_type: &str,
-    query_args: Option<&'a mut Vec<(&str, Params)>>,
+    query_args: Option<&mut Vec<(&str, Params)>>,
     expected_value: &str,
 ) {
-    let do_query = rc.query(&brid, None, query_type, None, query_args).await;
+    let do_query = rc.query(brid, None, query_type, None, query_args).await;
 
     print!("test query = {} ... ", query_type);
 
@@ -103,7 +102,7 @@ async fn initialize_rest_client() -> (String, RestClient<'static>) {
         let result = rc.get_nodes_from_directory(&brid).await;
 
         if let Err(ref error) = result {
-            if rc.print_error(&error, false) {
+            if rc.print_error(error, false) {
                 std::process::exit(0);
             }
         }
@@ -146,9 +145,6 @@ fn read_private_key_from_env_var() -> [u8; 32] {
 async fn signed_transactions_integration_test() {
     let client = initialize_rest_client().await;
 
-    let mut rng = rand::thread_rng();
-    let random_integer: i32 = rng.gen_range(1..=100000);
-
     let brid = client.0;
     let rc = client.1;
 
@@ -156,14 +152,10 @@ async fn signed_transactions_integration_test() {
     let params = vec![Params::Boolean(false)];
     let ops = vec![
         Operation::from_list(operation_name, params),
-        Operation::from_list("nop", vec![Params::Integer(random_integer.into())])
+        Operation::nop_unique()
     ];
 
-    let mut tx = Transaction{
-        blockchain_rid: hex::decode(brid.clone()).unwrap(),
-        operations: Some(ops),
-        ..Default::default()
-    };
+    let mut tx = Transaction::new(hex::decode(brid.clone()).unwrap(), Some(ops), None, None);
 
     let private_key_from_env = read_private_key_from_env_var();
 
@@ -182,13 +174,9 @@ async fn signed_transactions_integration_test() {
     ];
     let ops = vec![
         Operation::from_dict(operation_name, params),
-        Operation::from_list("nop", vec![Params::Integer(random_integer.into())])
+        Operation::nop_unique()
     ];
-    let mut tx = Transaction{
-        blockchain_rid: hex::decode(brid.clone()).unwrap(),
-        operations: Some(ops),
-        ..Default::default()
-    };
+    let mut tx = Transaction::new(hex::decode(brid.clone()).unwrap(), Some(ops), None, None);
 
     let result = tx.sign(&private_key_from_env);
 
@@ -199,14 +187,39 @@ async fn signed_transactions_integration_test() {
     }
 }
 
+#[tokio::test]
+async fn confirmation_proof_integration_test() {
+    let (brid, rc) = initialize_rest_client().await;
+
+    let mut tx = Transaction::new(hex::decode(brid.clone()).unwrap(), Some(vec![Operation::from_list("setBoolean", vec![Params::Boolean(true)]), Operation::nop_unique()]), None, None);
+    tx.sign(&read_private_key_from_env_var()).unwrap();
+    let tx_rid = tx.tx_rid_hex().unwrap();
+
+    if let Err(error) = rc.send_transaction(&tx).await {
+        if rc.print_error(&error, false) {
+            std::process::exit(0);
+        }
+    }
+    let detail = rc.get_transaction_status_detail(&brid, &tx_rid).await.unwrap();
+    assert_eq!(detail.status, TransactionStatus::CONFIRMED);
+
+    // The node answers with the GTV proof as hex: {"proof": "..."}
+    let response = rc.raw_request(RestRequestMethod::GET, &["tx", &brid, &tx_rid, "confirmationProof"], None, None).await.unwrap();
+    let RestResponse::Json(response) = response else {
+        panic!("Unexpected confirmation proof response {:?}", response);
+    };
+    let proof = ConfirmationProof::decode(&hex::decode(response["proof"].as_str().unwrap()).unwrap()).unwrap();
+
+    let verified = proof.verify_transaction(&tx, &proof.block_header).unwrap();
+    assert_eq!(Some(hex::encode(verified.block_rid)), detail.block_rid.map(|rid| rid.to_lowercase()));
+    assert_eq!(verified.header.height, detail.block_height.unwrap());
+}
+
 #[allow(unused_assignments)]
 #[tokio::test]
 async fn unsigned_transactions_integration_test() {
     let client = initialize_rest_client().await;
 
-    let mut rng = rand::thread_rng();
-    let random_integer: i32 = rng.gen_range(1..=100000);
-
     let brid = client.0;
     let brid_vec = hex::decode(brid.clone()).unwrap();
     let rc = client.1;
@@ -215,13 +228,9 @@ async fn unsigned_transactions_integration_test() {
     let params = vec![Params::Boolean(true)];
     let ops = vec![
         Operation::from_list(operation_name, params),
-        Operation::from_list("nop", vec![Params::Integer(random_integer.into())])
+        Operation::nop_unique()
     ];
-    let tx = Transaction{
-        blockchain_rid: brid_vec.clone(),
-        operations: Some(ops),
-        ..Default::default()
-    };
+    let tx = Transaction::new(brid_vec.clone(), Some(ops), None, None);
 
     assert_roundtrips_transaction(&rc, &tx, operation_name, &brid).await;
 
@@ -233,13 +242,9 @@ async fn unsigned_transactions_integration_test() {
         ];
     let ops = vec![
         Operation::from_list(operation_name, params),
-        Operation::from_list("nop", vec![Params::Integer(random_integer.into())])
+        Operation::nop_unique()
     ];
-    let tx = Transaction{
-        blockchain_rid: brid_vec.clone(),
-        operations: Some(ops),
-        ..Default::default()
-    };
+    let tx = Transaction::new(brid_vec.clone(), Some(ops), None, None);
 
     assert_roundtrips_transaction(&rc, &tx, operation_name, &brid).await;
 
@@ -251,13 +256,9 @@ async fn unsigned_transactions_integration_test() {
         ];
     let ops = vec![
         Operation::from_dict(operation_name, params),
-        Operation::from_list("nop", vec![Params::Integer(random_integer.into())])
+        Operation::nop_unique()
     ];
-    let tx = Transaction{
-        blockchain_rid: brid_vec.clone(),
-        operations: Some(ops),
-        ..Default::default()
-    };
+    let tx = Transaction::new(brid_vec.clone(), Some(ops), None, None);
 
     assert_roundtrips_transaction(&rc, &tx, operation_name, &brid).await;
 
@@ -268,13 +269,9 @@ async fn unsigned_transactions_integration_test() {
     ];
     let ops = vec![
         Operation::from_dict(operation_name, params),
-        Operation::from_list("nop", vec![Params::Integer(random_integer.into())])
+        Operation::nop_unique()
     ];
-    let tx = Transaction{
-        blockchain_rid: brid_vec.clone(),
-        operations: Some(ops),
-        ..Default::default()
-    };
+    let tx = Transaction::new(brid_vec.clone(), Some(ops), None, None);
 
     assert_roundtrips_transaction(&rc, &tx, operation_name, &brid).await;
 }